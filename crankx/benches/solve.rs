@@ -0,0 +1,149 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use crankx::batch::solve_batch;
+use crankx::equix::{EquiXBuilder, RuntimeOption, SolverMemory};
+use crankx::{solve, solve_with_memory, verify, Solution};
+
+const CHALLENGE: [u8; 32] = [7; 32];
+
+/// Nonces per `solve_batch` call
+const BATCH: u64 = 16;
+
+/// Run `f` once per segment size we care about, from a single hash-sized
+/// segment up to a 4KB page.
+macro_rules! for_segment_sizes {
+    ($f:ident, $c:expr) => {
+        $f::<32>($c);
+        $f::<128>($c);
+        $f::<512>($c);
+        $f::<1024>($c);
+        $f::<4096>($c);
+    };
+}
+
+fn find_solution<const N: usize>(data: &[u8; N]) -> Solution {
+    (0u64..)
+        .find_map(|n| solve(CHALLENGE, data, n.to_le_bytes()).ok())
+        .unwrap()
+}
+
+fn bench_solve<const N: usize>(c: &mut Criterion) {
+    let data = [42u8; N];
+    let mut group = c.benchmark_group("solve");
+    group.throughput(Throughput::Elements(1));
+
+    let mut nonce = 0u64;
+    group.bench_function(BenchmarkId::new("alloc", N), |b| {
+        b.iter(|| {
+            nonce += 1;
+            let _ = black_box(solve(CHALLENGE, &data, nonce.to_le_bytes()));
+        })
+    });
+
+    let mut memory = SolverMemory::new();
+    let mut nonce = 0u64;
+    group.bench_function(BenchmarkId::new("with_memory", N), |b| {
+        b.iter(|| {
+            nonce += 1;
+            let _ = black_box(solve_with_memory(
+                &mut memory,
+                CHALLENGE,
+                &data,
+                nonce.to_le_bytes(),
+            ));
+        })
+    });
+
+    // Per-call overhead amortized over a run of consecutive nonces
+    let mut nonce = 0u64;
+    group.throughput(Throughput::Elements(BATCH));
+    group.bench_function(BenchmarkId::new("batch", N), |b| {
+        b.iter(|| {
+            nonce += BATCH;
+            black_box(solve_batch(CHALLENGE, &data, nonce, BATCH).unwrap());
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_runtime<const N: usize>(c: &mut Criterion) {
+    let data = [42u8; N];
+    let mut group = c.benchmark_group("runtime");
+    group.throughput(Throughput::Elements(1));
+
+    for (name, option) in [
+        ("compiled", RuntimeOption::TryCompile),
+        ("interpreted", RuntimeOption::InterpretOnly),
+    ] {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(option);
+
+        let mut memory = SolverMemory::new();
+        let mut nonce = 0u64;
+        group.bench_function(BenchmarkId::new(name, N), |b| {
+            b.iter(|| {
+                nonce += 1;
+                let seed = [&CHALLENGE[..], &data[..], &nonce.to_le_bytes()].concat();
+                if let Ok(eq) = builder.build(&seed) {
+                    black_box(eq.solve_with_memory(&mut memory));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Seed prep and HashX program build alone: the most a pipeline that
+/// prepares the next seed during the current solve could hide
+fn bench_build<const N: usize>(c: &mut Criterion) {
+    let data = [42u8; N];
+    let mut group = c.benchmark_group("build");
+    group.throughput(Throughput::Elements(1));
+
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(RuntimeOption::TryCompile);
+
+    let mut nonce = 0u64;
+    group.bench_function(BenchmarkId::from_parameter(N), |b| {
+        b.iter(|| {
+            nonce += 1;
+            let seed = [&CHALLENGE[..], &data[..], &nonce.to_le_bytes()].concat();
+            let _ = black_box(builder.build(&seed));
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_verify<const N: usize>(c: &mut Criterion) {
+    let data = [42u8; N];
+    let solution = find_solution(&data);
+
+    let mut group = c.benchmark_group("verify");
+    group.throughput(Throughput::Bytes(N as u64));
+    group.bench_function(BenchmarkId::from_parameter(N), |b| {
+        b.iter(|| verify(CHALLENGE, black_box(&data), solution.n, &solution.d).unwrap())
+    });
+    group.finish();
+}
+
+fn solve_paths(c: &mut Criterion) {
+    for_segment_sizes!(bench_solve, c);
+}
+
+fn runtimes(c: &mut Criterion) {
+    for_segment_sizes!(bench_runtime, c);
+}
+
+fn builds(c: &mut Criterion) {
+    for_segment_sizes!(bench_build, c);
+}
+
+fn verification(c: &mut Criterion) {
+    for_segment_sizes!(bench_verify, c);
+}
+
+criterion_group!(benches, solve_paths, runtimes, builds, verification);
+criterion_main!(benches);
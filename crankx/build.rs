@@ -0,0 +1,15 @@
+// Code generation for the protobuf-based features
+// Uses a vendored protoc unless `PROTOC` points at one, so building needs no
+// system packages.
+
+fn main() {
+    #[cfg(any(feature = "proto", feature = "grpc"))]
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+    }
+    #[cfg(feature = "proto")]
+    prost_build::compile_protos(&["proto/crankx.proto"], &["proto"]).expect("compile crankx.proto");
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/prover.proto").expect("compile prover.proto");
+}
@@ -0,0 +1,96 @@
+// Compute units the reference verifier spends per proof
+// Runs a `crankx_verifier.so` (`cargo build-sbf --manifest-path
+// verifier/Cargo.toml` writes one to `target/deploy`) in `runtime`, a
+// metered SBF VM, submitting one proof per segment size and difficulty
+// check: no check, a check the proof just meets, and one it just misses.
+// Units are what the `SubmitProof` instruction consumed, as a validator
+// would charge it; `cost_model` fits `crankx::cost::CostModel` figures to
+// them.
+
+pub mod runtime;
+
+use crankx::cost::{CostModel, MAX_TRANSACTION_CU};
+use crankx::solana::cpi::submit_proof;
+use crankx::solana::SubmitProof;
+use solana_program::pubkey::Pubkey;
+use solana_sbpf::error::EbpfError;
+
+use crate::runtime::Runtime;
+
+/// Segment sizes measured; 960 bytes is the most one transaction carries
+pub const SEGMENT_LENS: [usize; 5] = [64, 128, 256, 512, 960];
+
+/// Units each submission may spend, the most a transaction can have, so the
+/// measurement is never cut short
+pub const UNIT_LIMIT: u64 = MAX_TRANSACTION_CU;
+
+/// Challenge every measured proof is for
+const CHALLENGE: [u8; 32] = [7; 32];
+
+/// Signer of every submission
+const MINER: Pubkey = Pubkey::new_from_array([9; 32]);
+
+/// One proof submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    pub segment_len: usize,
+    /// `min_difficulty` the instruction asked for
+    pub min_difficulty: u32,
+    /// Difficulty of the submitted proof
+    pub difficulty: u32,
+    /// Compute units the instruction consumed
+    pub units: u64,
+    /// Whether the verifier accepted the proof
+    pub accepted: bool,
+}
+
+/// A solved proof over a segment
+struct Proof {
+    segment: Vec<u8>,
+    /// Packed `digest || nonce`
+    packed: [u8; 24],
+    difficulty: u32,
+}
+
+fn proof<const N: usize>() -> Proof {
+    let data = [N as u8; N];
+    let solution = (0u64..).find_map(|n| crankx::solve(CHALLENGE, &data, n).ok()).unwrap();
+    Proof { segment: data.to_vec(), packed: solution.to_bytes(), difficulty: solution.difficulty() }
+}
+
+fn proofs() -> [Proof; SEGMENT_LENS.len()] {
+    [proof::<64>(), proof::<128>(), proof::<256>(), proof::<512>(), proof::<960>()]
+}
+
+/// Measure the verifier ELF `program` over every size in [`SEGMENT_LENS`]
+pub fn measure(program: &[u8]) -> Result<Vec<Measurement>, EbpfError> {
+    let runtime = Runtime::load(program)?;
+    let mut measurements = Vec::new();
+    for proof in proofs() {
+        for min_difficulty in [0, proof.difficulty, proof.difficulty + 1] {
+            let submit = SubmitProof {
+                challenge: CHALLENGE,
+                min_difficulty,
+                proof: proof.packed,
+                segment: &proof.segment,
+            };
+            let execution = runtime.execute(&submit_proof(&MINER, &submit), UNIT_LIMIT);
+            measurements.push(Measurement {
+                segment_len: proof.segment.len(),
+                min_difficulty,
+                difficulty: proof.difficulty,
+                units: execution.units,
+                accepted: execution.result.is_ok(),
+            });
+        }
+    }
+    Ok(measurements)
+}
+
+/// The default cost model with compute unit figures fitted to the accepted
+/// submissions, `None` if fewer than two sizes were accepted
+pub fn cost_model(measurements: &[Measurement]) -> Option<CostModel> {
+    CostModel::from_cu_measurements(
+        measurements.iter().filter(|m| m.accepted).map(|m| (m.segment_len, m.units)),
+    )
+}
@@ -0,0 +1,55 @@
+// crankx-cu [<path to crankx_verifier.so>]
+// Prints the compute units the reference verifier spends per proof, one row
+// per segment size and difficulty check, then the `CostModel::with_cu`
+// figures they fit; see the library for what is measured. The program
+// defaults to the workspace's `target/deploy`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crankx::cost::MAX_TRANSACTION_CU;
+use crankx_cu::{cost_model, measure, SEGMENT_LENS};
+
+fn main() -> ExitCode {
+    let path = match std::env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/crankx_verifier.so")
+        }
+    };
+    let program = match std::fs::read(&path) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("crankx-cu: {}: {e} (build it with `cargo build-sbf`)", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let measurements = match measure(&program) {
+        Ok(measurements) => measurements,
+        Err(e) => {
+            eprintln!("crankx-cu: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("segment  min_difficulty  difficulty  accepted      units");
+    for m in &measurements {
+        println!(
+            "{:>7}  {:>14}  {:>10}  {:>8}  {:>9}",
+            m.segment_len, m.min_difficulty, m.difficulty, m.accepted, m.units
+        );
+    }
+
+    let Some(model) = cost_model(&measurements) else {
+        eprintln!("crankx-cu: too few proofs accepted to fit a cost model");
+        return ExitCode::FAILURE;
+    };
+    let cu = model.cu.expect("fitted models have compute units");
+    println!();
+    println!("CostModel::DEFAULT.with_cu({}, {})", cu.per_proof, cu.per_byte);
+    let len = SEGMENT_LENS[SEGMENT_LENS.len() - 1];
+    if let Some(proofs) = model.proofs_within(len, MAX_TRANSACTION_CU) {
+        println!("{proofs} proofs over {len} bytes fit a transaction's units");
+    }
+    ExitCode::SUCCESS
+}
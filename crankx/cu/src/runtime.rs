@@ -0,0 +1,457 @@
+// Just enough of the Solana runtime to meter one program instruction
+// Runs an SBF program in solana-sbpf's interpreter with the memory layout,
+// input serialization and `Config` the bpf_loader uses on mainnet, and
+// charges compute units as agave's default compute budget does: one per
+// instruction executed plus each syscall's own cost. Only the syscalls the
+// verifier can reach are registered; a program calling any other fails when
+// it does.
+
+use std::sync::Arc;
+
+use solana_program::instruction::Instruction;
+use solana_sbpf::aligned_memory::AlignedMemory;
+use solana_sbpf::declare_builtin_function;
+use solana_sbpf::ebpf::{HOST_ALIGN, MM_HEAP_START, MM_INPUT_START, MM_STACK_START};
+use solana_sbpf::elf::Executable;
+use solana_sbpf::error::{EbpfError, ProgramResult};
+use solana_sbpf::memory_region::{AccessType, MemoryMapping, MemoryRegion};
+use solana_sbpf::program::{BuiltinProgram, SBPFVersion};
+use solana_sbpf::verifier::RequisiteVerifier;
+use solana_sbpf::vm::{Config, ContextObject, EbpfVm};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Heap the bpf_loader gives a program unless it asks for more
+const HEAP_SIZE: usize = 32 * 1024;
+
+/// Room the loader leaves after each account's data for it to grow into
+const MAX_PERMITTED_DATA_INCREASE: usize = 10 * 1024;
+
+// Compute budget costs, as in agave's `ComputeBudget::default`
+const SYSCALL_BASE_COST: u64 = 100;
+const LOG_64_UNITS: u64 = 100;
+const SHA256_BASE_COST: u64 = 85;
+const SHA256_BYTE_COST: u64 = 1;
+const SHA256_MAX_SLICES: u64 = 20_000;
+const MEM_OP_BASE_COST: u64 = 10;
+const CPI_BYTES_PER_UNIT: u64 = 250;
+
+/// Compute meter and log of one execution
+pub struct Meter {
+    remaining: u64,
+    logs: Vec<String>,
+}
+
+impl Meter {
+    fn charge(&mut self, units: u64) -> Result<(), Error> {
+        if units > self.remaining {
+            self.remaining = 0;
+            return Err("exceeded the compute unit limit".into());
+        }
+        self.remaining -= units;
+        Ok(())
+    }
+}
+
+impl ContextObject for Meter {
+    fn trace(&mut self, _state: [u64; 12]) {}
+
+    fn consume(&mut self, amount: u64) {
+        self.remaining = self.remaining.saturating_sub(amount);
+    }
+
+    fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+/// What running one instruction did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    /// Compute units consumed, at most the limit it ran with
+    pub units: u64,
+    /// `Err` with the program's error code or the runtime's reason
+    pub result: Result<(), String>,
+    /// Messages the program logged, formatted as a validator logs them
+    pub logs: Vec<String>,
+}
+
+/// A loaded program, ready to run instructions
+pub struct Runtime {
+    executable: Executable<Meter>,
+}
+
+impl Runtime {
+    /// Load and verify an SBF ELF, as deploying it would
+    pub fn load(elf: &[u8]) -> Result<Self, EbpfError> {
+        let executable = Executable::from_elf(elf, loader())?;
+        executable.verify::<RequisiteVerifier>()?;
+        Ok(Self { executable })
+    }
+
+    /// Assemble a program from sBPF assembly, for checking the runtime itself
+    pub fn assemble(src: &str) -> Result<Self, String> {
+        let executable = solana_sbpf::assembler::assemble(src, loader())?;
+        executable.verify::<RequisiteVerifier>().map_err(|e| e.to_string())?;
+        Ok(Self { executable })
+    }
+
+    /// Run `ix` against accounts holding nothing, with `unit_limit` compute
+    /// units to spend
+    pub fn execute(&self, ix: &Instruction, unit_limit: u64) -> Execution {
+        let config = self.executable.get_config();
+        let sbpf_version = self.executable.get_sbpf_version();
+        let mut stack = AlignedMemory::<HOST_ALIGN>::zero_filled(config.stack_size());
+        let mut heap = AlignedMemory::<HOST_ALIGN>::zero_filled(HEAP_SIZE);
+        let mut input = AlignedMemory::<HOST_ALIGN>::from_slice(&serialize(ix));
+        let stack_gap = if !sbpf_version.dynamic_stack_frames() && config.enable_stack_frame_gaps {
+            config.stack_frame_size as u64
+        } else {
+            0
+        };
+        let stack_len = stack.len();
+        let regions = vec![
+            self.executable.get_ro_region(),
+            MemoryRegion::new_writable_gapped(stack.as_slice_mut(), MM_STACK_START, stack_gap),
+            MemoryRegion::new_writable(heap.as_slice_mut(), MM_HEAP_START),
+            MemoryRegion::new_writable(input.as_slice_mut(), MM_INPUT_START),
+        ];
+        let mut meter = Meter { remaining: unit_limit, logs: Vec::new() };
+        let result = match MemoryMapping::new(regions, config, sbpf_version) {
+            Ok(mapping) => {
+                let mut vm = EbpfVm::new(
+                    self.executable.get_loader().clone(),
+                    sbpf_version,
+                    &mut meter,
+                    mapping,
+                    stack_len,
+                );
+                match vm.execute_program(&self.executable, true).1 {
+                    ProgramResult::Ok(0) => Ok(()),
+                    ProgramResult::Ok(code) => Err(format!("program error {code:#x}")),
+                    ProgramResult::Err(e) => Err(e.to_string()),
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        Execution { units: unit_limit - meter.remaining, result, logs: meter.logs }
+    }
+}
+
+/// The bpf_loader's VM configuration and the syscalls this runtime has
+fn loader() -> Arc<BuiltinProgram<Meter>> {
+    let config = Config {
+        enabled_sbpf_versions: SBPFVersion::V0..=SBPFVersion::V0,
+        optimize_rodata: false,
+        ..Config::default()
+    };
+    let mut loader = BuiltinProgram::new_loader(config);
+    for (name, syscall) in [
+        ("abort", SyscallAbort::vm as _),
+        ("sol_panic_", SyscallPanic::vm as _),
+        ("sol_log_", SyscallLog::vm as _),
+        ("sol_log_64_", SyscallLogU64::vm as _),
+        ("sol_log_compute_units_", SyscallLogComputeUnits::vm as _),
+        ("sol_log_data", SyscallLogData::vm as _),
+        ("sol_remaining_compute_units", SyscallRemainingComputeUnits::vm as _),
+        ("sol_keccak256", SyscallKeccak256::vm as _),
+        ("sol_memcpy_", SyscallMemcpy::vm as _),
+        ("sol_memmove_", SyscallMemmove::vm as _),
+        ("sol_memset_", SyscallMemset::vm as _),
+        ("sol_memcmp_", SyscallMemcmp::vm as _),
+    ] {
+        loader.register_function(name, syscall).expect("syscall names are distinct");
+    }
+    Arc::new(loader)
+}
+
+/// The bpf_loader's input for `ix`: its accounts, each empty, owned by the
+/// system program and signing or writable as `ix` marks it, then its data
+/// and program id
+fn serialize(ix: &Instruction) -> Vec<u8> {
+    let mut input = Vec::new();
+    input.extend_from_slice(&(ix.accounts.len() as u64).to_le_bytes());
+    for (i, meta) in ix.accounts.iter().enumerate() {
+        if let Some(first) = ix.accounts[..i].iter().position(|m| m.pubkey == meta.pubkey) {
+            input.extend_from_slice(&[first as u8, 0, 0, 0, 0, 0, 0, 0]);
+            continue;
+        }
+        input.extend_from_slice(&[u8::MAX, meta.is_signer as u8, meta.is_writable as u8, 0]);
+        input.extend_from_slice(&[0; 4]);
+        input.extend_from_slice(meta.pubkey.as_ref());
+        input.extend_from_slice(&[0; 32]);
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.resize((input.len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(8), 0);
+        input.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    input.extend_from_slice(&(ix.data.len() as u64).to_le_bytes());
+    input.extend_from_slice(&ix.data);
+    input.extend_from_slice(ix.program_id.as_ref());
+    input
+}
+
+/// Host address of `len` bytes at `addr` in the VM
+fn translate(
+    mapping: &MemoryMapping,
+    access: AccessType,
+    addr: u64,
+    len: u64,
+) -> Result<*mut u8, Error> {
+    if len == 0 {
+        return Ok(std::ptr::NonNull::dangling().as_ptr());
+    }
+    let host: Result<u64, EbpfError> = mapping.map(access, addr, len).into();
+    Ok(host? as *mut u8)
+}
+
+fn load(mapping: &MemoryMapping, addr: u64, len: u64) -> Result<Vec<u8>, Error> {
+    let host = translate(mapping, AccessType::Load, addr, len)?;
+    // SAFETY: the mapping checked `len` bytes at `host` belong to a region
+    Ok(unsafe { std::slice::from_raw_parts(host, len as usize) }.to_vec())
+}
+
+fn store(mapping: &MemoryMapping, addr: u64, bytes: &[u8]) -> Result<(), Error> {
+    let host = translate(mapping, AccessType::Store, addr, bytes.len() as u64)?;
+    // SAFETY: as in `load`, and the region is writable
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), host, bytes.len()) };
+    Ok(())
+}
+
+/// `&[&[u8]]` at `addr`, each slice a pointer and a length
+fn load_slices(mapping: &MemoryMapping, addr: u64, len: u64) -> Result<Vec<Vec<u8>>, Error> {
+    let descriptors = load(mapping, addr, len.checked_mul(16).ok_or("slice list too long")?)?;
+    descriptors
+        .chunks_exact(16)
+        .map(|d| {
+            let (ptr, len) = d.split_at(8);
+            load(mapping, u64::from_le_bytes(ptr.try_into()?), u64::from_le_bytes(len.try_into()?))
+        })
+        .collect()
+}
+
+fn mem_op_cost(len: u64) -> u64 {
+    MEM_OP_BASE_COST.max(len / CPI_BYTES_PER_UNIT)
+}
+
+declare_builtin_function!(
+    SyscallAbort,
+    fn rust(
+        _meter: &mut Meter,
+        _a: u64,
+        _b: u64,
+        _c: u64,
+        _d: u64,
+        _e: u64,
+        _mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        Err("program aborted".into())
+    }
+);
+
+declare_builtin_function!(
+    SyscallPanic,
+    fn rust(
+        meter: &mut Meter,
+        file: u64,
+        len: u64,
+        line: u64,
+        column: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(len)?;
+        let file = load(mapping, file, len)?;
+        Err(format!("program panicked at {}:{line}:{column}", String::from_utf8_lossy(&file))
+            .into())
+    }
+);
+
+declare_builtin_function!(
+    SyscallLog,
+    fn rust(
+        meter: &mut Meter,
+        addr: u64,
+        len: u64,
+        _c: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(SYSCALL_BASE_COST.max(len))?;
+        let message = load(mapping, addr, len)?;
+        meter.logs.push(format!("Program log: {}", String::from_utf8_lossy(&message)));
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallLogU64,
+    fn rust(
+        meter: &mut Meter,
+        a: u64,
+        b: u64,
+        c: u64,
+        d: u64,
+        e: u64,
+        _mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(LOG_64_UNITS)?;
+        meter.logs.push(format!("Program log: {a:#x}, {b:#x}, {c:#x}, {d:#x}, {e:#x}"));
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallLogComputeUnits,
+    fn rust(
+        meter: &mut Meter,
+        _a: u64,
+        _b: u64,
+        _c: u64,
+        _d: u64,
+        _e: u64,
+        _mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(SYSCALL_BASE_COST)?;
+        let remaining = meter.remaining;
+        meter.logs.push(format!("Program consumption: {remaining} units remaining"));
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallLogData,
+    fn rust(
+        meter: &mut Meter,
+        addr: u64,
+        len: u64,
+        _c: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(SYSCALL_BASE_COST)?;
+        meter.charge(SYSCALL_BASE_COST.saturating_mul(len))?;
+        let fields = load_slices(mapping, addr, len)?;
+        meter.charge(fields.iter().map(|f| f.len() as u64).sum())?;
+        let hex: Vec<String> =
+            fields.iter().map(|f| f.iter().map(|b| format!("{b:02x}")).collect()).collect();
+        meter.logs.push(format!("Program data: {}", hex.join(" ")));
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallRemainingComputeUnits,
+    fn rust(
+        meter: &mut Meter,
+        _a: u64,
+        _b: u64,
+        _c: u64,
+        _d: u64,
+        _e: u64,
+        _mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(SYSCALL_BASE_COST)?;
+        Ok(meter.remaining)
+    }
+);
+
+declare_builtin_function!(
+    SyscallKeccak256,
+    fn rust(
+        meter: &mut Meter,
+        vals: u64,
+        len: u64,
+        result: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        if len > SHA256_MAX_SLICES {
+            return Err("too many slices to hash".into());
+        }
+        meter.charge(SHA256_BASE_COST)?;
+        let parts = load_slices(mapping, vals, len)?;
+        for part in &parts {
+            meter.charge(MEM_OP_BASE_COST.max(SHA256_BYTE_COST * (part.len() as u64 / 2)))?;
+        }
+        let parts: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+        store(mapping, result, &crankx::keccak::keccak256(&parts))?;
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallMemcpy,
+    fn rust(
+        meter: &mut Meter,
+        dst: u64,
+        src: u64,
+        len: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(mem_op_cost(len))?;
+        if dst < src.saturating_add(len) && src < dst.saturating_add(len) {
+            return Err("overlapping memcpy".into());
+        }
+        store(mapping, dst, &load(mapping, src, len)?)?;
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallMemmove,
+    fn rust(
+        meter: &mut Meter,
+        dst: u64,
+        src: u64,
+        len: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(mem_op_cost(len))?;
+        store(mapping, dst, &load(mapping, src, len)?)?;
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallMemset,
+    fn rust(
+        meter: &mut Meter,
+        dst: u64,
+        byte: u64,
+        len: u64,
+        _d: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(mem_op_cost(len))?;
+        store(mapping, dst, &vec![byte as u8; len as usize])?;
+        Ok(0)
+    }
+);
+
+declare_builtin_function!(
+    SyscallMemcmp,
+    fn rust(
+        meter: &mut Meter,
+        a: u64,
+        b: u64,
+        len: u64,
+        result: u64,
+        _e: u64,
+        mapping: &mut MemoryMapping,
+    ) -> Result<u64, Error> {
+        meter.charge(mem_op_cost(len))?;
+        let (a, b) = (load(mapping, a, len)?, load(mapping, b, len)?);
+        let cmp = a.iter().zip(&b).find(|(x, y)| x != y).map_or(0, |(&x, &y)| x as i32 - y as i32);
+        store(mapping, result, &cmp.to_le_bytes())?;
+        Ok(0)
+    }
+);
@@ -0,0 +1,73 @@
+use crankx::keccak::keccak256;
+use crankx_cu::runtime::Runtime;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+/// Instruction with no accounts, so its data sits 16 bytes into the input
+fn ix(data: &[u8]) -> Instruction {
+    Instruction::new_with_bytes(Pubkey::new_unique(), data, vec![])
+}
+
+#[test]
+fn instructions_and_syscalls_are_charged() {
+    let log = Runtime::assemble(
+        "
+        ldxdw r2, [r1+8]
+        add64 r1, 16
+        syscall sol_log_
+        mov64 r0, 0
+        exit",
+    )
+    .unwrap();
+
+    // One unit per instruction, and a log costs its length, at least 100
+    let short = log.execute(&ix(b"hello"), 1_000);
+    assert_eq!(short.result, Ok(()));
+    assert_eq!(short.units, 5 + 100);
+    assert_eq!(short.logs, ["Program log: hello"]);
+    let long = log.execute(&ix(&[b'a'; 300]), 1_000);
+    assert_eq!(long.units, 5 + 300);
+
+    // Running out stops the program having spent the whole limit
+    let starved = log.execute(&ix(&[b'a'; 300]), 200);
+    assert!(starved.result.is_err());
+    assert_eq!(starved.units, 200);
+}
+
+#[test]
+fn keccak_hashes_vm_memory() {
+    let hash = Runtime::assemble(
+        "
+        ldxdw r2, [r1+8]
+        add64 r1, 16
+        stxdw [r10-16], r1
+        stxdw [r10-8], r2
+        mov64 r1, r10
+        sub64 r1, 16
+        mov64 r2, 1
+        mov64 r3, r10
+        sub64 r3, 48
+        syscall sol_keccak256
+        ldxdw r1, [r10-48]
+        syscall sol_log_64_
+        mov64 r0, 0
+        exit",
+    )
+    .unwrap();
+
+    let data = [3; 100];
+    let execution = hash.execute(&ix(&data), 1_000);
+    assert_eq!(execution.result, Ok(()));
+    // 85 per hash and one per two bytes hashed, then 100 for the log
+    assert_eq!(execution.units, 14 + 85 + 50 + 100);
+    let word = u64::from_le_bytes(keccak256(&[&data])[..8].try_into().unwrap());
+    assert!(execution.logs[0].starts_with(&format!("Program log: {word:#x}, ")));
+}
+
+#[test]
+fn program_errors_fail_the_instruction() {
+    let fail = Runtime::assemble("mov64 r0, 3\nexit").unwrap();
+    assert_eq!(fail.execute(&ix(&[]), 1_000).result, Err("program error 0x3".into()));
+    let abort = Runtime::assemble("syscall abort\nexit").unwrap();
+    assert!(abort.execute(&ix(&[]), 1_000).result.is_err());
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "sbf")]
+
+use std::path::Path;
+
+use crankx::cost::MAX_TRANSACTION_CU;
+use crankx_cu::{cost_model, measure, SEGMENT_LENS, UNIT_LIMIT};
+
+#[test]
+fn every_size_and_check_is_measured() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/crankx_verifier.so");
+    let program = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("{}: {e} (build it with `cargo build-sbf`)", path.display()));
+    let measurements = measure(&program).unwrap();
+
+    assert_eq!(measurements.len(), 3 * SEGMENT_LENS.len());
+    for (m, check) in measurements.iter().zip((0..3).cycle()) {
+        assert!(m.units > 0 && m.units < UNIT_LIMIT, "{m:?}");
+        // No check and a check the proof meets pass; one more bit fails
+        assert_eq!(m.accepted, check < 2, "{m:?}");
+    }
+
+    // The largest segment a transaction carries verifies within its units
+    let model = cost_model(&measurements).unwrap();
+    let largest = SEGMENT_LENS[SEGMENT_LENS.len() - 1];
+    assert!(model.proofs_within(largest, MAX_TRANSACTION_CU).unwrap() >= 1, "{model:?}");
+}
@@ -0,0 +1,27 @@
+// Conformance suite for other verifier implementations
+//
+//     cargo run --release --example export_vectors -- [json|csv] [random-cases] [seed]
+//
+// Prints the golden vectors, the edge cases and the requested number of
+// random cases (default 16, seed 0). Every case says whether a verifier
+// must accept it.
+
+use std::env;
+use std::error::Error;
+
+use crankx::testing::{export_vectors, vectors_to_csv, vectors_to_json};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let format = args.next().unwrap_or_else(|| "json".into());
+    let random: usize = args.next().map_or(Ok(16), |s| s.parse())?;
+    let seed: u64 = args.next().map_or(Ok(0), |s| s.parse())?;
+
+    let vectors = export_vectors(random, seed);
+    match format.as_str() {
+        "json" => print!("{}", vectors_to_json(&vectors)),
+        "csv" => print!("{}", vectors_to_csv(&vectors)),
+        _ => return Err("usage: export_vectors [json|csv] [random-cases] [seed]".into()),
+    }
+    Ok(())
+}
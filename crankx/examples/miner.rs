@@ -0,0 +1,85 @@
+// Reference integration: crank every segment file in a directory
+//
+//     cargo run --release --example miner -- <segment-dir> [difficulty] [threads] [challenge-hex]
+//
+// Each file is one segment of at most MAX_DATA_LEN bytes, proved in name order
+// on all threads. Press Enter to stop; the segment in progress is abandoned
+// and the stats so far are printed.
+
+use std::error::Error;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::{env, fs, io, thread};
+
+use crankx::batch::{verify_batch, BatchItem};
+use crankx::miner::Miner;
+use crankx::Challenge;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let dir = args.next().ok_or("usage: miner <segment-dir> [difficulty] [threads] [challenge]")?;
+    let difficulty: u32 = args.next().map_or(Ok(8), |s| s.parse())?;
+    let threads = match args.next() {
+        Some(s) => s.parse()?,
+        None => thread::available_parallelism()?.get(),
+    };
+    let challenge = args.next().map_or(Ok(Challenge::default()), |s| Challenge::from_hex(&s))?;
+
+    let mut paths: Vec<_> = fs::read_dir(&dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|p| p.is_file());
+    paths.sort();
+
+    let miner = Miner::new(threads);
+    let stop = miner.stop_flag();
+    thread::spawn(move || {
+        // Closed stdin (e.g. under a service manager) never stops the run
+        if io::stdin().read_line(&mut String::new()).is_ok_and(|n| n > 0) {
+            stop.store(true, Ordering::Relaxed);
+        }
+    });
+
+    println!("challenge {challenge}, difficulty {difficulty}, {threads} threads");
+
+    let mut segments = Vec::new();
+    let mut attempts = 0;
+    let mut elapsed = Duration::ZERO;
+
+    for path in &paths {
+        let data = fs::read(path)?;
+        let report = miner.mine(challenge, &data, difficulty)?;
+        attempts += report.attempts;
+        elapsed += report.elapsed;
+
+        let rate = report.attempts_per_sec();
+        let runtime = report.runtime_used;
+        let Some(solution) = report.solution else {
+            println!("stopped during {}", path.display());
+            break;
+        };
+
+        println!(
+            "{}: nonce {}, difficulty {}, {} attempts in {:.2?} ({:.0} H/s, {:?})",
+            path.display(),
+            u64::from_le_bytes(solution.n),
+            solution.difficulty(),
+            report.attempts,
+            report.elapsed,
+            rate,
+            runtime,
+        );
+        segments.push((data, solution));
+    }
+
+    let items: Vec<_> = segments.iter().map(|(data, s)| BatchItem::new(data, s)).collect();
+    verify_batch(challenge, &items)?;
+
+    println!(
+        "proved {} of {} segments, {attempts} attempts in {elapsed:.2?} ({:.0} H/s), all verified",
+        segments.len(),
+        paths.len(),
+        attempts as f64 / elapsed.as_secs_f64(),
+    );
+    Ok(())
+}
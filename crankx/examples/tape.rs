@@ -0,0 +1,85 @@
+// End to end: from a blob on disk to a verified proof
+//
+//     cargo run --release --example tape -- <input-file> [segment-size] [difficulty] [threads]
+//
+// The input is cut into segments and written as a tape file next to it
+// (`<input-file>.tape`). The challenge is derived from the tape's segment
+// checksums and a round number, and picks the segment the protocol
+// recalls. The scheduler hands that segment out first, the parallel miner
+// proves it, and the proof is verified against the bytes read back.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::{env, fs, thread};
+
+use crankx::batch::{verify_batch, BatchItem};
+use crankx::keccak::keccak256;
+use crankx::miner::Miner;
+use crankx::scheduler::{RecallPriority, Scheduler, StalestFirst};
+use crankx::tape::{Reader, Writer};
+use crankx::{Challenge, SegmentProvider, MAX_DATA_LEN};
+
+const ROUND: u64 = 1;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let usage = "usage: tape <input-file> [segment-size] [difficulty] [threads]";
+    let input = args.next().ok_or(usage)?;
+    let segment_size: u32 = args.next().map_or(Ok(1024), |s| s.parse())?;
+    let difficulty: u32 = args.next().map_or(Ok(8), |s| s.parse())?;
+    let threads = match args.next() {
+        Some(s) => s.parse()?,
+        None => thread::available_parallelism()?.get(),
+    };
+    if segment_size as usize > MAX_DATA_LEN {
+        return Err(format!("segment size is at most {MAX_DATA_LEN} bytes").into());
+    }
+
+    // 1. Lay the blob out as a tape
+    let blob = fs::read(&input)?;
+    let path = format!("{input}.tape");
+    let mut writer = Writer::new(BufWriter::new(File::create(&path)?), segment_size)?;
+    for segment in blob.chunks(segment_size as usize) {
+        writer.append(segment)?;
+    }
+    writer.finish()?;
+    let tape = Reader::open(&path)?;
+    let count = tape.segment_count();
+    println!("{path}: {count} segments of {segment_size} bytes");
+    if count == 0 {
+        return Err("input is empty".into());
+    }
+
+    // 2. Challenge for this round, committing to what the tape holds
+    let checksums: Vec<&[u8]> = tape.entries().iter().map(|e| &e.checksum[..]).collect();
+    let root = keccak256(&checksums);
+    let challenge = Challenge(keccak256(&[&root, &ROUND.to_le_bytes()]));
+
+    // 3. The challenge recalls one segment
+    let recalled = u64::from_le_bytes(challenge.0[..8].try_into()?) % count;
+    let mut scheduler = Scheduler::for_provider(&tape, RecallPriority { fallback: StalestFirst });
+    scheduler.recall(recalled);
+    let index = scheduler.next(ROUND).ok_or("nothing to prove")?;
+    println!("challenge {challenge}, round {ROUND}, recalled segment {index}");
+
+    // 4. Prove it on every thread
+    let data = tape.segment(index)?;
+    let report = Miner::new(threads).mine(challenge, &data, difficulty)?;
+    let solution = report.solution.as_ref().ok_or("mining stopped")?;
+    scheduler.proved(index, ROUND);
+    println!(
+        "nonce {}, difficulty {}, {} attempts in {:.2?} ({:.0} H/s)",
+        u64::from_le_bytes(solution.n),
+        solution.difficulty(),
+        report.attempts,
+        report.elapsed,
+        report.attempts_per_sec(),
+    );
+
+    // 5. Verify against the bytes on disk
+    let stored = tape.read_segment(index)?;
+    verify_batch(challenge, &[BatchItem::new(&stored, solution)])?;
+    println!("proof for segment {index} verified");
+    Ok(())
+}
@@ -0,0 +1,24 @@
+#![no_main]
+
+use crankx::batch::SolutionBatch;
+use crankx::encoding::cbor::{from_cbor, to_cbor};
+use crankx::job::{Job, Share};
+use crankx::Solution;
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Decoding never panics, and only canonical CBOR decodes, so anything
+/// accepted re-encodes to exactly the same bytes.
+fn round_trip<T: Serialize + DeserializeOwned>(data: &[u8]) {
+    if let Ok(value) = from_cbor::<T>(data) {
+        assert_eq!(to_cbor(&value).unwrap(), data);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    round_trip::<Solution>(data);
+    round_trip::<Job>(data);
+    round_trip::<Share>(data);
+    round_trip::<SolutionBatch>(data);
+});
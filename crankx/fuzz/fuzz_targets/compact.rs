@@ -0,0 +1,14 @@
+#![no_main]
+
+use crankx::encoding::compact::{decode, encode, encoded_len};
+use libfuzzer_sys::fuzz_target;
+
+// Compact proof lists come straight from instruction data: decoding must never
+// panic, and the strict grammar means anything accepted is its only encoding.
+fuzz_target!(|data: &[u8]| {
+    let Ok(proofs) = decode(data) else {
+        return;
+    };
+    assert_eq!(encode(&proofs), data);
+    assert_eq!(encoded_len(&proofs), data.len());
+});
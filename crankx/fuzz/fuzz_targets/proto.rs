@@ -0,0 +1,28 @@
+#![no_main]
+
+use crankx::batch::SolutionBatch;
+use crankx::encoding::compact::CompactProof;
+use crankx::encoding::proto::Proto;
+use crankx::job::{Job, Share};
+use crankx::{CrankXError, Solution};
+use libfuzzer_sys::fuzz_target;
+
+/// Decoding never panics, and whatever decodes survives a round trip.
+/// Protobuf allows many encodings of one message, so the bytes may differ.
+fn round_trip<T>(data: &[u8])
+where
+    T: Proto + TryFrom<T::Message, Error = CrankXError> + PartialEq + std::fmt::Debug,
+{
+    if let Ok(value) = T::decode_proto(data) {
+        assert_eq!(T::decode_proto(&value.to_proto()).unwrap(), value);
+    }
+}
+
+// Protobuf messages arrive from peers and orchestrators
+fuzz_target!(|data: &[u8]| {
+    round_trip::<Solution>(data);
+    round_trip::<Job>(data);
+    round_trip::<Share>(data);
+    round_trip::<CompactProof>(data);
+    round_trip::<SolutionBatch>(data);
+});
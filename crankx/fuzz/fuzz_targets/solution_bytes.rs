@@ -0,0 +1,24 @@
+#![no_main]
+
+use crankx::Solution;
+use libfuzzer_sys::fuzz_target;
+
+// Proof bytes arrive straight from untrusted transactions: decoding must never
+// panic, and anything we accept must re-encode to exactly the same bytes.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(solution) = Solution::from_versioned_bytes(data) {
+        assert_eq!(&solution.to_versioned_bytes()[..], data);
+    }
+
+    let Ok(solution) = Solution::try_from_slice(data) else {
+        assert_ne!(data.len(), 24);
+        return;
+    };
+
+    let bytes = solution.to_bytes();
+    assert_eq!(&bytes[..], data);
+
+    let decoded = Solution::from_bytes(&bytes);
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(decoded.to_hash(), solution.to_hash());
+});
@@ -0,0 +1,37 @@
+#![no_main]
+
+use crankx::tape::format::{Header, IndexEntry, TapeView, ENTRY_LEN, HEADER_LEN};
+use libfuzzer_sys::fuzz_target;
+
+/// Tapes this short have their seals checked too; each costs a verification
+const MAX_CHECKED: u64 = 4;
+
+// Tape files come from disk or other provers. Headers and entries must parse
+// without panicking and survive a round trip (reserved bytes are dropped), and
+// a view over arbitrary bytes must only ever hand out in-bounds segments.
+fuzz_target!(|data: &[u8]| {
+    if let Some((head, _)) = data.split_first_chunk::<HEADER_LEN>() {
+        if let Ok(header) = Header::from_bytes(head) {
+            assert_eq!(Header::from_bytes(&header.to_bytes()).unwrap(), header);
+        }
+    }
+    if let Some((entry, _)) = data.split_first_chunk::<ENTRY_LEN>() {
+        if let Ok(entry) = IndexEntry::from_bytes(entry) {
+            assert_eq!(IndexEntry::from_bytes(&entry.to_bytes()).unwrap(), entry);
+        }
+    }
+
+    let Ok(view) = TapeView::new(data) else {
+        return;
+    };
+    let header = *view.header();
+    for index in 0..header.count {
+        assert_eq!(view.segment_bytes(index).unwrap().len(), header.segment_size as usize);
+        let _ = view.entry(index);
+        let _ = view.check(index);
+    }
+    assert!(view.segment_bytes(header.count).is_err());
+    if header.count <= MAX_CHECKED {
+        let _ = view.unsealed();
+    }
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+use crankx::{verify, Solution};
+use libfuzzer_sys::fuzz_target;
+
+const DATA_LEN: usize = 64;
+
+// Layout: challenge (32) || nonce (8) || digest (16) || data (64)
+fuzz_target!(|input: &[u8]| {
+    if input.len() < 32 + 8 + 16 + DATA_LEN {
+        return;
+    }
+
+    let (challenge, rest) = input.split_at(32);
+    let (nonce, rest) = rest.split_at(8);
+    let (digest, rest) = rest.split_at(16);
+
+    let challenge: &[u8; 32] = challenge.try_into().unwrap();
+    let nonce: &[u8; 8] = nonce.try_into().unwrap();
+    let digest: &[u8; 16] = digest.try_into().unwrap();
+    let data: &[u8; DATA_LEN] = rest[..DATA_LEN].try_into().unwrap();
+
+    if verify(challenge, data, nonce, digest).is_ok() {
+        let solution = Solution::new(*digest, *nonce);
+        assert!(solution.is_valid(challenge, data).is_ok());
+    }
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use crankx::wide::WideSolution;
+use libfuzzer_sys::fuzz_target;
+
+// Wide proofs share the transport of regular ones: decoding must never panic,
+// and anything accepted must re-encode to exactly the same bytes.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(solution) = WideSolution::from_versioned_bytes(data) {
+        assert_eq!(&solution.to_versioned_bytes()[..], data);
+    }
+
+    let Some((packed, _)) = data.split_first_chunk::<32>() else {
+        return;
+    };
+    let solution = WideSolution::from_bytes(packed);
+    assert_eq!(&solution.to_bytes(), packed);
+    assert_eq!(WideSolution::from_bytes(&solution.to_bytes()).to_hash(), solution.to_hash());
+});
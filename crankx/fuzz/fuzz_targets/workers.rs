@@ -0,0 +1,27 @@
+#![no_main]
+
+use crankx::workers::{Aggregator, WorkerJob, WorkerReport, REPORT_LEN};
+use libfuzzer_sys::fuzz_target;
+
+/// Workers an aggregator expects reports from
+const WORKERS: u32 = 4;
+
+// Jobs and reports cross a message boundary from code the coordinator doesn't
+// control. Decoding must never panic and must round-trip; an aggregator fed
+// any run of reports must reject bad ones rather than panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(job) = WorkerJob::from_bytes(data) {
+        assert_eq!(job.to_bytes(), data);
+    }
+
+    let mut aggregator = Aggregator::new(WORKERS);
+    for chunk in data.chunks_exact(REPORT_LEN) {
+        let Ok(report) = WorkerReport::from_bytes(chunk) else {
+            continue;
+        };
+        let again = WorkerReport::from_bytes(&report.to_bytes()).unwrap();
+        assert_eq!(again, report);
+        let _ = aggregator.push(report);
+    }
+    let _ = (aggregator.is_done(), aggregator.best(), aggregator.attempts());
+});
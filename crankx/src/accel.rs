@@ -0,0 +1,289 @@
+// HashX program export for external accelerators (feature = "accel")
+// EquiX spends nearly all its time evaluating the per-seed HashX function on
+// inputs 0..65536. Hardware prototypes run that part themselves and hand the
+// results back, so this exports the program in a stable form: the register
+// key and 512 instructions, plus a reference evaluator to test against.
+//
+// hashx 0.1.5 has no accessor for its program ("no stable API for program
+// information", in its own words) and only lists it through `Debug`, so the
+// export builds an interpreted instance and reads that listing back. The
+// listing isn't covered by semver either, which is why the workspace pins
+// hashx to exactly 0.1.5; the test suite checks the listing's shape and the
+// evaluator against hashx itself, so a bump that changes either fails there
+// rather than in the field. Move to an accessor once hashx grows one.
+//
+// Binary form from `HashXProgram::to_bytes` (all words little-endian):
+//
+//   program     := register_key:[u64; 4] instruction{512}
+//   instruction := opcode:u8 dst:u8 src:u8 zero:u8 imm:u32
+//
+// Opcodes and operands follow `Instruction`'s declaration order; unused
+// fields are zero, `src` of `AddShift` carries the register and `imm` the
+// shift.
+
+use hashx::{HashXBuilder, RuntimeOption, SipState};
+
+use crate::CrankXError;
+
+/// Instructions in every HashX program
+pub const PROGRAM_LEN: usize = 512;
+
+/// Bytes in [`HashXProgram::to_bytes`]
+pub const ENCODED_LEN: usize = 32 + 8 * PROGRAM_LEN;
+
+/// One HashX instruction; registers are `0..8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// `dst = dst * src`, wrapping
+    Mul { dst: u8, src: u8 },
+    /// `dst = (dst * src) >> 64`, unsigned
+    UMulH { dst: u8, src: u8 },
+    /// `dst = (dst * src) >> 64`, signed
+    SMulH { dst: u8, src: u8 },
+    /// `dst = dst + (src << shift)`, wrapping
+    AddShift { dst: u8, src: u8, shift: u8 },
+    /// `dst = dst + imm` with `imm` sign-extended, wrapping
+    AddConst { dst: u8, imm: i32 },
+    /// `dst = dst - src`, wrapping
+    Sub { dst: u8, src: u8 },
+    Xor { dst: u8, src: u8 },
+    /// `dst = dst ^ imm` with `imm` sign-extended
+    XorConst { dst: u8, imm: i32 },
+    /// `dst = dst.rotate_right(amount)`
+    Rotate { dst: u8, amount: u8 },
+    /// Where the next taken branch jumps to
+    Target,
+    /// Jump back to the last `Target` if `mask & low32(last mulh) == 0` and
+    /// no branch has been taken yet
+    Branch { mask: u32 },
+}
+
+impl Instruction {
+    fn opcode(&self) -> u8 {
+        match self {
+            Self::Mul { .. } => 0,
+            Self::UMulH { .. } => 1,
+            Self::SMulH { .. } => 2,
+            Self::AddShift { .. } => 3,
+            Self::AddConst { .. } => 4,
+            Self::Sub { .. } => 5,
+            Self::Xor { .. } => 6,
+            Self::XorConst { .. } => 7,
+            Self::Rotate { .. } => 8,
+            Self::Target => 9,
+            Self::Branch { .. } => 10,
+        }
+    }
+
+    /// `opcode, dst, src, 0, imm (4, LE)`
+    pub fn to_bytes(&self) -> [u8; 8] {
+        let (dst, src, imm) = match *self {
+            Self::Mul { dst, src }
+            | Self::UMulH { dst, src }
+            | Self::SMulH { dst, src }
+            | Self::Sub { dst, src }
+            | Self::Xor { dst, src } => (dst, src, 0),
+            Self::AddShift { dst, src, shift } => (dst, src, shift as u32),
+            Self::AddConst { dst, imm } | Self::XorConst { dst, imm } => (dst, 0, imm as u32),
+            Self::Rotate { dst, amount } => (dst, 0, amount as u32),
+            Self::Target => (0, 0, 0),
+            Self::Branch { mask } => (0, 0, mask),
+        };
+        let imm = imm.to_le_bytes();
+        [self.opcode(), dst, src, 0, imm[0], imm[1], imm[2], imm[3]]
+    }
+}
+
+/// The HashX function EquiX builds for one seed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashXProgram {
+    /// SipHash state that expands each input into the initial registers and
+    /// is mixed back in by the final digest
+    pub register_key: [u64; 4],
+    pub instructions: Vec<Instruction>,
+}
+
+impl HashXProgram {
+    /// Export the program for `seed` (`challenge || data || nonce`)
+    ///
+    /// Fails with [`CrankXError::EquiXFailure`] for the rare seeds whose
+    /// program HashX rejects; EquiX has no solutions for those either.
+    ///
+    /// # Panics
+    ///
+    /// If hashx's `Debug` listing isn't the one 0.1.5 prints, which the
+    /// pinned dependency rules out. Reading it as a rejected seed instead
+    /// would have every export quietly fail.
+    pub fn export(seed: &[u8]) -> Result<Self, CrankXError> {
+        let hashx = HashXBuilder::new()
+            .runtime(RuntimeOption::InterpretOnly)
+            .build(seed)
+            .map_err(|_| CrankXError::EquiXFailure)?;
+        let (_, register_key) = SipState::pair_from_seed(seed);
+
+        let instructions = parse_listing(&format!("{hashx:?}"))
+            .expect("hashx's Debug listing changed; crankx::accel reads the hashx 0.1.5 format");
+
+        Ok(Self { register_key: register_key.into(), instructions })
+    }
+
+    /// The documented binary form (see the module header)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(ENCODED_LEN);
+        for word in self.register_key {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        for instruction in &self.instructions {
+            out.extend_from_slice(&instruction.to_bytes());
+        }
+        out
+    }
+
+    /// Reference evaluation: the 64-bit word EquiX uses for `input`
+    pub fn hash_to_u64(&self, input: u64) -> u64 {
+        let mut regs = siphash24_ctr(self.register_key, input);
+        self.run(&mut regs);
+
+        let k = self.register_key;
+        let mut x = [regs[0].wrapping_add(k[0]), regs[1].wrapping_add(k[1]), regs[2], regs[3]];
+        let mut y = [regs[4], regs[5], regs[6].wrapping_add(k[2]), regs[7].wrapping_add(k[3])];
+        sip_round(&mut x);
+        sip_round(&mut y);
+        x[0] ^ y[0]
+    }
+
+    fn run(&self, regs: &mut [u64; 8]) {
+        let mut pc = 0;
+        let mut target = 0;
+        let mut may_branch = true;
+        let mut mulh = 0u32;
+
+        while let Some(&instruction) = self.instructions.get(pc) {
+            pc += 1;
+            match instruction {
+                Instruction::Mul { dst, src } => {
+                    regs[dst as usize] = regs[dst as usize].wrapping_mul(regs[src as usize])
+                }
+                Instruction::UMulH { dst, src } => {
+                    let wide = regs[dst as usize] as u128 * regs[src as usize] as u128;
+                    regs[dst as usize] = (wide >> 64) as u64;
+                    mulh = regs[dst as usize] as u32;
+                }
+                Instruction::SMulH { dst, src } => {
+                    let a = regs[dst as usize] as i64 as i128;
+                    let wide = a.wrapping_mul(regs[src as usize] as i64 as i128);
+                    regs[dst as usize] = (wide >> 64) as u64;
+                    mulh = regs[dst as usize] as u32;
+                }
+                Instruction::AddShift { dst, src, shift } => {
+                    let add = regs[src as usize] << shift;
+                    regs[dst as usize] = regs[dst as usize].wrapping_add(add);
+                }
+                Instruction::AddConst { dst, imm } => {
+                    regs[dst as usize] = regs[dst as usize].wrapping_add(imm as i64 as u64)
+                }
+                Instruction::Sub { dst, src } => {
+                    regs[dst as usize] = regs[dst as usize].wrapping_sub(regs[src as usize])
+                }
+                Instruction::Xor { dst, src } => regs[dst as usize] ^= regs[src as usize],
+                Instruction::XorConst { dst, imm } => regs[dst as usize] ^= imm as i64 as u64,
+                Instruction::Rotate { dst, amount } => {
+                    regs[dst as usize] = regs[dst as usize].rotate_right(amount as u32)
+                }
+                Instruction::Target => target = pc - 1,
+                Instruction::Branch { mask } => {
+                    if may_branch && mask & mulh == 0 {
+                        may_branch = false;
+                        pc = target;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse the instructions out of an interpreted `HashX`'s `Debug` output,
+/// one ` [addr]: Instruction` line each
+fn parse_listing(listing: &str) -> Option<Vec<Instruction>> {
+    listing
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix('['))
+        .map(|line| parse_instruction(line.split_once("]: ")?.1))
+        .collect::<Option<Vec<_>>>()
+        .filter(|i| i.len() == PROGRAM_LEN)
+}
+
+/// Parse one `Debug` line of hashx's interpreter listing
+fn parse_instruction(text: &str) -> Option<Instruction> {
+    let (name, fields) = match text.split_once(" { ") {
+        Some((name, rest)) => (name, rest.strip_suffix(" }")?),
+        None => (text, ""),
+    };
+    let field = |key: &str| {
+        fields.split(", ").find_map(|f| f.strip_prefix(key)?.strip_prefix(": "))
+    };
+    let reg = |key: &str| -> Option<u8> {
+        field(key)?.strip_prefix('R')?.parse().ok().filter(|r| *r < 8)
+    };
+
+    Some(match name {
+        "Mul" => Instruction::Mul { dst: reg("dst")?, src: reg("src")? },
+        "UMulH" => Instruction::UMulH { dst: reg("dst")?, src: reg("src")? },
+        "SMulH" => Instruction::SMulH { dst: reg("dst")?, src: reg("src")? },
+        "AddShift" => Instruction::AddShift {
+            dst: reg("dst")?,
+            src: reg("src")?,
+            shift: field("left_shift")?.parse().ok()?,
+        },
+        "AddConst" => Instruction::AddConst { dst: reg("dst")?, imm: field("src")?.parse().ok()? },
+        "Sub" => Instruction::Sub { dst: reg("dst")?, src: reg("src")? },
+        "Xor" => Instruction::Xor { dst: reg("dst")?, src: reg("src")? },
+        "XorConst" => Instruction::XorConst { dst: reg("dst")?, imm: field("src")?.parse().ok()? },
+        "Rotate" => Instruction::Rotate {
+            dst: reg("dst")?,
+            amount: field("right_rotate")?.parse().ok()?,
+        },
+        "Target" => Instruction::Target,
+        "Branch" => Instruction::Branch { mask: field("mask")?.parse().ok()? },
+        _ => return None,
+    })
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[1] = v[1].rotate_left(13);
+    v[3] = v[3].rotate_left(16);
+    v[1] ^= v[0];
+    v[3] ^= v[2];
+    v[0] = v[0].rotate_left(32);
+
+    v[2] = v[2].wrapping_add(v[1]);
+    v[0] = v[0].wrapping_add(v[3]);
+    v[1] = v[1].rotate_left(17);
+    v[3] = v[3].rotate_left(21);
+    v[1] ^= v[2];
+    v[3] ^= v[0];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// HashX's SipHash-2-4 counter mode: the initial register file for `input`
+fn siphash24_ctr(key: [u64; 4], input: u64) -> [u64; 8] {
+    let mut s = key;
+    s[1] ^= 0xee;
+    s[3] ^= input;
+    sip_round(&mut s);
+    sip_round(&mut s);
+    s[0] ^= input;
+    s[2] ^= 0xee;
+    for _ in 0..4 {
+        sip_round(&mut s);
+    }
+
+    let mut t = s;
+    t[1] ^= 0xdd;
+    for _ in 0..4 {
+        sip_round(&mut t);
+    }
+    [s[0], s[1], s[2], s[3], t[0], t[1], t[2], t[3]]
+}
@@ -0,0 +1,84 @@
+// Zero-copy proof archives (feature = "rkyv")
+// `Solution`, `Challenge`, `Nonce`, `CompactProof` and `SolutionBatch` archive
+// with rkyv 0.7, so a scan over millions of stored proofs reads them in place
+// from an mmapped file instead of decoding each one. Archives are always
+// little-endian (`archive_le`), so a file written on one host maps on any
+// other. Open untrusted bytes with `rkyv::check_archived_root`, which checks
+// every archived solution's hash algorithm id.
+// `Solution` caches its final hash and so can't derive the traits; it
+// archives as its digest, nonce and algorithm id, and the final hash is
+// recomputed on demand like a decoded solution's.
+
+use rkyv::bytecheck::CheckBytes;
+use rkyv::{Archive, Archived, Deserialize, Fallible, Serialize};
+
+use crate::{difficulty_of, CrankXError, HashAlgorithm, Solution};
+
+/// A [`Solution`] in place in an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct ArchivedSolution {
+    /// Raw EquiX digest (16 bytes)
+    pub d: [u8; 16],
+    /// Nonce (8 bytes)
+    pub n: [u8; 8],
+    alg: u8,
+}
+
+impl ArchivedSolution {
+    /// Algorithm used for the final hash
+    pub fn hash_algorithm(&self) -> Result<HashAlgorithm, CrankXError> {
+        HashAlgorithm::from_id(self.alg)
+    }
+
+    /// Final hash(digest || nonce), computed on every call
+    pub fn to_hash(&self) -> Result<[u8; 32], CrankXError> {
+        Ok(self.hash_algorithm()?.hash(&self.d, &self.n))
+    }
+
+    /// Difficulty of the final hash, computed on every call
+    pub fn difficulty(&self) -> Result<u32, CrankXError> {
+        self.to_hash().map(|h| difficulty_of(&h))
+    }
+
+    /// Copy out as an owned [`Solution`]
+    pub fn to_solution(&self) -> Result<Solution, CrankXError> {
+        Ok(Solution::with_hash_algorithm(self.d, self.n, self.hash_algorithm()?))
+    }
+}
+
+impl Archive for Solution {
+    type Archived = ArchivedSolution;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve(&self, _: usize, _: (), out: *mut ArchivedSolution) {
+        out.write(ArchivedSolution { d: self.d, n: self.n, alg: self.hash_algorithm().id() });
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Solution {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+/// Only an archive that skipped validation can hold an unknown algorithm id;
+/// deserializing one panics
+impl<D: Fallible + ?Sized> Deserialize<Solution, D> for Archived<Solution> {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<Solution, D::Error> {
+        Ok(self.to_solution().expect("archived solution with an unknown hash algorithm"))
+    }
+}
+
+impl<C: ?Sized> CheckBytes<C> for ArchivedSolution {
+    type Error = CrankXError;
+
+    unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, CrankXError> {
+        // Every field but `alg` is plain bytes
+        HashAlgorithm::from_id(*core::ptr::addr_of!((*value).alg))?;
+        Ok(&*value)
+    }
+}
@@ -0,0 +1,101 @@
+// Pluggable proof-of-work backends
+// The seed layout, Solution encoding and difficulty rules stay the same; only
+// the puzzle that maps a seed to 16-byte digests is swapped out.
+
+use equix::{EquiXBuilder, RuntimeOption, SolverMemory};
+
+use crate::{
+    build_seed, check_segment_size, Challenge, CrankXError, Nonce, Solution, DEFAULT_RUNTIME,
+};
+
+/// A puzzle that maps a seed to zero or more 16-byte digests
+pub trait PowBackend {
+    /// Scratch space reused across solves
+    type Memory;
+
+    /// Allocate fresh scratch space for [`PowBackend::solve`]
+    fn new_memory(&self) -> Self::Memory;
+
+    /// All candidate digests for `seed`, in the backend's preferred order
+    fn solve(&self, mem: &mut Self::Memory, seed: &[u8]) -> Result<Vec<[u8; 16]>, CrankXError>;
+
+    /// Check a single digest against `seed`
+    fn verify(&self, seed: &[u8], digest: &[u8; 16]) -> Result<(), CrankXError>;
+}
+
+/// The default backend: EquiX, as used by the top-level solve/verify
+#[derive(Debug, Clone)]
+pub struct EquiXBackend {
+    builder: EquiXBuilder,
+}
+
+impl EquiXBackend {
+    /// EquiX backend with a specific HashX runtime
+    pub fn new(runtime: RuntimeOption) -> Self {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(runtime);
+        Self { builder }
+    }
+}
+
+impl Default for EquiXBackend {
+    fn default() -> Self {
+        Self::new(DEFAULT_RUNTIME)
+    }
+}
+
+impl PowBackend for EquiXBackend {
+    type Memory = SolverMemory;
+
+    fn new_memory(&self) -> SolverMemory {
+        SolverMemory::new()
+    }
+
+    fn solve(&self, mem: &mut SolverMemory, seed: &[u8]) -> Result<Vec<[u8; 16]>, CrankXError> {
+        let eq = self.builder
+            .build(seed)
+            .map_err(|_| CrankXError::EquiXFailure)?;
+
+        Ok(eq.solve_with_memory(mem).iter().map(|s| s.to_bytes()).collect())
+    }
+
+    fn verify(&self, seed: &[u8], digest: &[u8; 16]) -> Result<(), CrankXError> {
+        self.builder
+            .verify_bytes(seed, digest)
+            .map_err(|_| CrankXError::EquiXFailure)
+    }
+}
+
+/// Solve PoW over raw `challenge || data || nonce` with a custom backend
+pub fn solve<B: PowBackend, const N: usize>(
+    backend: &B,
+    mem: &mut B::Memory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+
+    let digest = backend
+        .solve(mem, &seed)?
+        .into_iter()
+        .next()
+        .ok_or(CrankXError::NoSolution)?;
+
+    Ok(Solution::new(digest, nonce.to_bytes()))
+}
+
+/// Verify a candidate digest against raw `challenge || data || nonce` with a custom backend
+pub fn verify<B: PowBackend, const N: usize>(
+    backend: &B,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.into().as_bytes())?;
+    backend.verify(&seed, digest)
+}
@@ -0,0 +1,404 @@
+// Batch solving and verification of proofs that share one challenge
+// The challenge is written into the seed buffer once; each proof only rewrites
+// the `data || nonce` tail of the same scratch buffer. Solving a run of
+// consecutive nonces goes further and rewrites just the trailing nonce, as
+// does verifying several proofs over one segment.
+// With the `rayon` feature, verification also shards across cores, each
+// worker thread keeping its own seed buffer.
+// `SolutionBatch` is the transport form of such a batch: the challenge once,
+// then each proof as a compact segment index, nonce and digest (see
+// `encoding::compact`), with the segments looked up on verification.
+// The `_limited` variants check a `VerifyLimits` before building any seed.
+// The `_report` variants check every item instead of stopping at the first
+// failure and return a `VerificationReport` saying how each one fared.
+
+use std::time::Instant;
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::encoding::compact::{self, CompactProof};
+use crate::limits::VerifyLimits;
+use crate::report::{ItemReport, Outcome, VerificationReport};
+use crate::segment::SegmentProvider;
+use crate::{
+    build_seed, check_seed_len, check_segment_size, compute_hash, difficulty_of, search_nonces,
+    split_array, verify_seed, Challenge, CrankXError, SelectionPolicy, Solution,
+    DEFAULT_RUNTIME, MAX_SEED_LEN,
+};
+
+/// One proof in a batch: the segment it covers plus its nonce and digest
+#[derive(Debug, Clone, Copy)]
+pub struct BatchItem<'a> {
+    /// Raw segment bytes
+    pub data: &'a [u8],
+    /// Nonce (8 bytes)
+    pub nonce: [u8; 8],
+    /// Raw EquiX digest (16 bytes)
+    pub digest: [u8; 16],
+}
+
+impl<'a> BatchItem<'a> {
+    /// Batch entry for `solution` over `data`
+    pub fn new(data: &'a [u8], solution: &Solution) -> Self {
+        Self { data, nonce: solution.n, digest: solution.d }
+    }
+}
+
+/// Verify every item against `challenge`, stopping at the first failure
+///
+/// Consecutive items borrowing the same `data` slice share the seed prefix:
+/// only their nonce is rewritten.
+pub fn verify_batch(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+) -> Result<(), CrankXError> {
+    let challenge = challenge.into();
+    let longest = items.iter().map(|i| i.data.len()).max().unwrap_or(0);
+
+    let mut seed = Vec::with_capacity(MAX_SEED_LEN.min(32 + longest + 8));
+    seed.extend_from_slice(challenge.as_bytes());
+
+    let mut prefix: Option<&[u8]> = None;
+    for item in items {
+        if prefix.is_some_and(|data| core::ptr::eq(data, item.data)) {
+            *nonce_slot(&mut seed)? = item.nonce;
+            verify_seed(&seed, &item.digest)?;
+        } else {
+            verify_item(&mut seed, item)?;
+            prefix = Some(item.data);
+        }
+    }
+
+    Ok(())
+}
+
+/// [`verify_batch`] for client `key`, once `items` pass `limits`
+pub fn verify_batch_limited(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+    limits: &VerifyLimits,
+    key: &str,
+) -> Result<(), CrankXError> {
+    limits.check_batch(items)?;
+    limits.admit(key, items.len())?;
+    verify_batch(challenge, items)
+}
+
+/// Check every item against `challenge` and report each outcome
+///
+/// Valid proofs under `min_difficulty` count as below difficulty.
+pub fn verify_batch_report(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+    min_difficulty: u32,
+) -> VerificationReport {
+    let timer = Instant::now();
+    let mut seed = seed_buffer(&challenge.into());
+    let reports = (0..)
+        .zip(items)
+        .map(|(index, item)| check_item(&mut seed, index, item, min_difficulty))
+        .collect();
+    VerificationReport::new(reports, timer.elapsed())
+}
+
+/// Verify packed `digest (16) || nonce (8)` proofs that all cover `data`
+///
+/// `challenge || data` is written once; each proof rewrites only the
+/// trailing nonce.
+pub fn verify_nonces(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    proofs: &[[u8; 24]],
+) -> Result<(), CrankXError> {
+    let mut seed = build_seed(challenge.into().as_bytes(), data, &[0; 8])?;
+
+    for proof in proofs {
+        let (digest, nonce) = split_array(proof);
+        *nonce_slot(&mut seed)? = nonce;
+        verify_seed(&seed, &digest)?;
+    }
+
+    Ok(())
+}
+
+/// The trailing nonce of a built seed
+fn nonce_slot(seed: &mut [u8]) -> Result<&mut [u8; 8], CrankXError> {
+    seed.last_chunk_mut().ok_or(CrankXError::InvalidLength)
+}
+
+/// [`verify_batch`] spread across the rayon thread pool
+///
+/// Each worker thread reuses one seed buffer for all the items it takes.
+/// When several items fail, which error is returned is unspecified.
+#[cfg(feature = "rayon")]
+pub fn verify_batch_parallel(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+) -> Result<(), CrankXError> {
+    use rayon::prelude::*;
+
+    let challenge = challenge.into();
+    items.par_iter().try_for_each_init(
+        || {
+            let mut seed = Vec::with_capacity(MAX_SEED_LEN);
+            seed.extend_from_slice(challenge.as_bytes());
+            seed
+        },
+        |seed, item| verify_item(seed, item),
+    )
+}
+
+/// [`verify_batch_report`] spread across the rayon thread pool
+#[cfg(feature = "rayon")]
+pub fn verify_batch_parallel_report(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+    min_difficulty: u32,
+) -> VerificationReport {
+    use rayon::prelude::*;
+
+    let timer = Instant::now();
+    let challenge = challenge.into();
+    let reports = items
+        .par_iter()
+        .enumerate()
+        .map_init(
+            || seed_buffer(&challenge),
+            |seed, (index, item)| check_item(seed, index, item, min_difficulty),
+        )
+        .collect();
+    VerificationReport::new(reports, timer.elapsed())
+}
+
+fn seed_buffer(challenge: &Challenge) -> Vec<u8> {
+    let mut seed = Vec::with_capacity(MAX_SEED_LEN);
+    seed.extend_from_slice(challenge.as_bytes());
+    seed
+}
+
+/// Classify `item`, timing the check
+fn check_item(
+    seed: &mut Vec<u8>,
+    index: usize,
+    item: &BatchItem,
+    min_difficulty: u32,
+) -> ItemReport {
+    let timer = Instant::now();
+    let outcome = classify(seed, item, min_difficulty);
+    let elapsed_ns = timer.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+    ItemReport { index, outcome, elapsed_ns }
+}
+
+fn classify(seed: &mut Vec<u8>, item: &BatchItem, min_difficulty: u32) -> Outcome {
+    if let Err(e) = check_seed_len(32, item.data.len(), 8) {
+        return Outcome::Malformed { reason: e.to_string() };
+    }
+    if equix::Solution::try_from_bytes(&item.digest).is_err() {
+        let reason = "digest items not in canonical order".to_string();
+        return Outcome::Malformed { reason };
+    }
+    if verify_item(seed, item).is_err() {
+        return Outcome::InvalidSolution;
+    }
+    let difficulty = difficulty_of(&compute_hash(&item.digest, &item.nonce));
+    if difficulty < min_difficulty {
+        return Outcome::BelowDifficulty { difficulty, required: min_difficulty };
+    }
+    Outcome::Valid { difficulty }
+}
+
+/// Rewrite the tail of `seed` (which starts with the challenge) for `item`
+/// and verify it
+fn verify_item(seed: &mut Vec<u8>, item: &BatchItem) -> Result<(), CrankXError> {
+    check_seed_len(32, item.data.len(), 8)?;
+
+    seed.truncate(32);
+    seed.extend_from_slice(item.data);
+    seed.extend_from_slice(&item.nonce);
+
+    verify_seed(seed, &item.digest)
+}
+
+/// Solve `count` consecutive nonces from `start_nonce`, returning a solution
+/// for every nonce whose seed has one
+///
+/// The seed, solver memory and EquiX builder are set up once for the whole
+/// batch. The run stops at nonce `u64::MAX` rather than wrapping.
+pub fn solve_batch<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    start_nonce: u64,
+    count: u64,
+) -> Result<Vec<Solution>, CrankXError> {
+    check_segment_size::<N>();
+    let mut seed = build_seed(challenge.into().as_bytes(), data, &start_nonce.to_le_bytes())?;
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let mut solutions = Vec::new();
+
+    let count = usize::try_from(count).unwrap_or(usize::MAX);
+    search_nonces(
+        &builder,
+        &mut SolverMemory::new(),
+        &mut seed,
+        (start_nonce..=u64::MAX).take(count),
+        SelectionPolicy::First,
+        || false,
+        |solution| {
+            solutions.push(solution);
+            false
+        },
+    )?;
+    Ok(solutions)
+}
+
+/// Proofs for one challenge, addressed by segment index
+///
+/// Encoded as `challenge (32) || compact proof list`: the challenge is paid
+/// once and each proof costs its varint segment index plus 24 bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes), archive_attr(derive(Debug)))]
+pub struct SolutionBatch {
+    pub challenge: Challenge,
+    pub proofs: Vec<CompactProof>,
+}
+
+impl SolutionBatch {
+    /// Empty batch for `challenge`
+    pub fn new(challenge: impl Into<Challenge>) -> Self {
+        Self { challenge: challenge.into(), proofs: Vec::new() }
+    }
+
+    /// Add `solution` as the proof for segment `segment`
+    pub fn push(&mut self, segment: u64, solution: &Solution) -> &mut Self {
+        self.proofs.push(CompactProof::new(segment, solution));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Exact length of [`SolutionBatch::to_bytes`]
+    pub fn encoded_len(&self) -> usize {
+        32 + compact::encoded_len(&self.proofs)
+    }
+
+    /// `challenge (32) || compact proof list`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.encoded_len());
+        out.extend_from_slice(self.challenge.as_bytes());
+        compact::encode_into(&self.proofs, &mut out);
+        out
+    }
+
+    /// Decode [`SolutionBatch::to_bytes`], strictly like [`compact::decode`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let (challenge, rest) =
+            bytes.split_first_chunk::<32>().ok_or(CrankXError::InvalidEncoding)?;
+        Ok(Self { challenge: Challenge(*challenge), proofs: compact::decode(rest)? })
+    }
+
+    /// Verify every proof against its segment from `segments`, stopping at
+    /// the first failure
+    pub fn verify<P>(&self, segments: &P) -> Result<(), P::Error>
+    where
+        P: SegmentProvider + ?Sized,
+        P::Error: From<CrankXError>,
+    {
+        let mut seed = Vec::with_capacity(MAX_SEED_LEN);
+        seed.extend_from_slice(self.challenge.as_bytes());
+
+        for proof in &self.proofs {
+            let data = segments.segment(proof.segment)?;
+            let item = BatchItem { data: &data, nonce: proof.nonce, digest: proof.digest };
+            verify_item(&mut seed, &item)?;
+        }
+        Ok(())
+    }
+
+    /// Check every proof against its segment from `segments` and report each
+    /// outcome
+    ///
+    /// A segment `segments` can't produce makes that proof malformed.
+    pub fn verify_report<P>(&self, segments: &P, min_difficulty: u32) -> VerificationReport
+    where
+        P: SegmentProvider + ?Sized,
+        P::Error: core::fmt::Display,
+    {
+        let timer = Instant::now();
+        let mut seed = seed_buffer(&self.challenge);
+        let reports = (0..)
+            .zip(&self.proofs)
+            .map(|(index, proof)| match segments.segment(proof.segment) {
+                Ok(data) => {
+                    let item = BatchItem { data: &data, nonce: proof.nonce, digest: proof.digest };
+                    check_item(&mut seed, index, &item, min_difficulty)
+                }
+                Err(e) => {
+                    let outcome = Outcome::Malformed { reason: e.to_string() };
+                    ItemReport { index, outcome, elapsed_ns: 0 }
+                }
+            })
+            .collect();
+        VerificationReport::new(reports, timer.elapsed())
+    }
+
+    /// [`SolutionBatch::verify`] for client `key`, once the proof count
+    /// passes `limits`
+    ///
+    /// Segments come from `segments` rather than the client, so only the
+    /// count and the rate are checked.
+    pub fn verify_limited<P>(
+        &self,
+        segments: &P,
+        limits: &VerifyLimits,
+        key: &str,
+    ) -> Result<(), P::Error>
+    where
+        P: SegmentProvider + ?Sized,
+        P::Error: From<CrankXError>,
+    {
+        limits.check_batch_len(self.len())?;
+        limits.admit(key, self.len())?;
+        self.verify(segments)
+    }
+}
+
+/// Hex of [`SolutionBatch::to_bytes`]
+#[cfg(feature = "serde")]
+impl serde::Serialize for SolutionBatch {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::types::serialize_hex(&self.to_bytes(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SolutionBatch {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = crate::types::deserialize_hex_vec(deserializer)?;
+        Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Borsh `Vec<u8>` of [`SolutionBatch::to_bytes`]
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for SolutionBatch {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.to_bytes().serialize(writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for SolutionBatch {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        Self::from_bytes(&bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
@@ -0,0 +1,179 @@
+// Hashrate measurement: how fast can this machine crank a segment of a given size?
+// Runs the same seed → EquiX solve loop as `solve_with_memory`, timed, then
+// verifies a sample of what it found.
+// `MachineReport` adds the host and crate version in a flat, serializable
+// shape, so fleet tooling can collect one JSON object per machine.
+
+use std::time::{Duration, Instant};
+
+use equix::{EquiXBuilder, Runtime, RuntimeOption, SolverMemory};
+
+use crate::{build_seed, DEFAULT_RUNTIME};
+
+/// Most solutions kept from the solve loop for timing verification
+const VERIFY_SAMPLE: usize = 256;
+
+/// Throughput measured by [`measure`]
+#[derive(Debug, Clone, Copy)]
+pub struct BenchReport {
+    /// Segment size measured, in bytes
+    pub segment_size: usize,
+    /// Nonces whose seed yielded at least one EquiX solution, per second
+    pub solves_per_sec: f64,
+    /// Nonces tried, per second
+    pub attempts_per_sec: f64,
+    /// Proofs verified per second, over up to 256 of the solutions found
+    /// (zero if none were)
+    pub verifies_per_sec: f64,
+    /// HashX runtime the puzzles ran on (`None` if no seed built)
+    pub runtime_used: Option<Runtime>,
+}
+
+/// The machine a benchmark ran on
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HostInfo {
+    /// `std::env::consts::OS`
+    pub os: &'static str,
+    /// `std::env::consts::ARCH`
+    pub arch: &'static str,
+    /// Available parallelism
+    pub cpus: usize,
+    /// CPU model name, where the OS reports one (Linux `/proc/cpuinfo`)
+    pub cpu_model: Option<String>,
+}
+
+impl HostInfo {
+    /// Describe the current machine
+    pub fn detect() -> Self {
+        let cpu_model = std::fs::read_to_string("/proc/cpuinfo").ok().and_then(|info| {
+            info.lines()
+                .find_map(|l| l.strip_prefix("model name")?.split_once(':'))
+                .map(|(_, name)| name.trim().to_string())
+        });
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            cpu_model,
+        }
+    }
+}
+
+/// One benchmark with its context, flat for JSON
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MachineReport {
+    /// crankx version that ran the benchmark
+    pub version: &'static str,
+    pub host: HostInfo,
+    /// `"compiled"`, `"interpreted"`, or `None` if no seed built
+    pub runtime: Option<&'static str>,
+    pub segment_size: usize,
+    pub solves_per_sec: f64,
+    pub attempts_per_sec: f64,
+    pub verifies_per_sec: f64,
+}
+
+impl MachineReport {
+    /// `report` as measured on `host`
+    pub fn new(report: &BenchReport, host: HostInfo) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            host,
+            runtime: report.runtime_used.map(runtime_name),
+            segment_size: report.segment_size,
+            solves_per_sec: report.solves_per_sec,
+            attempts_per_sec: report.attempts_per_sec,
+            verifies_per_sec: report.verifies_per_sec,
+        }
+    }
+}
+
+/// Stable name of a HashX runtime for reports
+pub fn runtime_name(runtime: Runtime) -> &'static str {
+    match runtime {
+        Runtime::Compiled => "compiled",
+        _ => "interpreted",
+    }
+}
+
+/// Crank a `segment_size`-byte segment over consecutive nonces for `duration`,
+/// then time verifying a sample of the solutions (on top of `duration`)
+///
+/// Segments larger than [`crate::MAX_DATA_LEN`] can't be cranked and report
+/// zero throughput.
+pub fn measure(segment_size: usize, duration: Duration) -> BenchReport {
+    measure_with(segment_size, duration, DEFAULT_RUNTIME)
+}
+
+/// [`measure`] with an explicit HashX runtime choice
+///
+/// Compare `InterpretOnly` against the default to see what the compiler is
+/// worth on this machine; `runtime_used` shows whether `TryCompile` fell back.
+/// Under `CompileOnly` without a working compiler nothing solves and
+/// `runtime_used` stays `None`.
+pub fn measure_with(
+    segment_size: usize,
+    duration: Duration,
+    runtime: RuntimeOption,
+) -> BenchReport {
+    let challenge = [0u8; 32];
+    let data = vec![42u8; segment_size];
+
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(runtime);
+
+    let mut memory = SolverMemory::new();
+    let mut runtime_used = None;
+    let mut attempts: u64 = 0;
+    let mut solves: u64 = 0;
+    let mut found = Vec::with_capacity(VERIFY_SAMPLE);
+
+    let timer = Instant::now();
+    while timer.elapsed() < duration {
+        let Ok(seed) = build_seed(&challenge, &data, &attempts.to_le_bytes()) else {
+            break;
+        };
+        attempts += 1;
+
+        // A small fraction of seeds can't build a HashX program; those count
+        // as attempts without a solve, same as in the mining loop.
+        let Ok(eq) = builder.build(&seed) else {
+            continue;
+        };
+        runtime_used = Some(eq.runtime());
+
+        if let Some(solution) = eq.solve_with_memory(&mut memory).first() {
+            solves += 1;
+            if found.len() < VERIFY_SAMPLE {
+                found.push((seed, solution.to_bytes()));
+            }
+        }
+    }
+
+    let secs = timer.elapsed().as_secs_f64();
+
+    let timer = Instant::now();
+    // Same builder as the solve loop, so both sides run on `runtime`
+    let verified =
+        found.iter().filter(|(seed, digest)| builder.verify_bytes(seed, digest).is_ok()).count();
+    let verify_secs = timer.elapsed().as_secs_f64();
+
+    BenchReport {
+        segment_size,
+        solves_per_sec: per_sec(solves, secs),
+        attempts_per_sec: per_sec(attempts, secs),
+        verifies_per_sec: per_sec(verified as u64, verify_secs),
+        runtime_used,
+    }
+}
+
+/// `count / secs`, or zero when nothing happened or no time was measured
+pub(crate) fn per_sec(count: u64, secs: f64) -> f64 {
+    if count == 0 || secs <= 0.0 {
+        0.0
+    } else {
+        count as f64 / secs
+    }
+}
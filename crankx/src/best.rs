@@ -0,0 +1,87 @@
+// Top-K solution tracking
+// A min-heap of the K best solutions under the canonical `Ord`: the root is
+// the weakest one kept, so a candidate is compared against it once and, if
+// better, replaces it in O(log K).
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Solution;
+
+/// The `K` best solutions offered so far
+#[derive(Debug)]
+pub struct BestSolutions<const K: usize> {
+    heap: BinaryHeap<Reverse<Solution>>,
+}
+
+impl<const K: usize> Default for BestSolutions<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const K: usize> BestSolutions<K> {
+    pub fn new() -> Self {
+        Self { heap: BinaryHeap::with_capacity(K) }
+    }
+
+    /// Keep `solution` if it ranks among the best `K`; returns whether it did
+    ///
+    /// Offering the same proof twice keeps it twice.
+    pub fn offer(&mut self, solution: Solution) -> bool {
+        if self.heap.len() < K {
+            self.heap.push(Reverse(solution));
+            return true;
+        }
+
+        match self.heap.peek_mut() {
+            Some(mut weakest) if weakest.0 < solution => {
+                *weakest = Reverse(solution);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Weakest solution kept, the one a candidate must beat once full
+    pub fn threshold(&self) -> Option<&Solution> {
+        self.heap.peek().map(|r| &r.0)
+    }
+
+    /// Best solution kept
+    pub fn best(&self) -> Option<&Solution> {
+        self.heap.iter().map(|r| &r.0).max()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Whether `K` solutions are held
+    pub fn is_full(&self) -> bool {
+        self.heap.len() == K
+    }
+
+    /// Solutions kept, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Solution> {
+        self.heap.iter().map(|r| &r.0)
+    }
+
+    /// Solutions kept, best first
+    pub fn into_sorted_vec(self) -> Vec<Solution> {
+        // Ascending by Reverse is descending by Solution
+        self.heap.into_sorted_vec().into_iter().map(|r| r.0).collect()
+    }
+}
+
+impl<const K: usize> Extend<Solution> for BestSolutions<K> {
+    fn extend<I: IntoIterator<Item = Solution>>(&mut self, iter: I) {
+        for solution in iter {
+            self.offer(solution);
+        }
+    }
+}
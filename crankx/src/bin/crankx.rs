@@ -0,0 +1,131 @@
+// crankx daemon [--config <path>]
+// Keeps every tape in the configured directory proved, writing proofs to the
+// archive; see `crankx::config::Config` for the settings file, which
+// defaults to `crankx.toml` in the working directory. Meant to run under
+// systemd: logs go to stderr (journald) or a file, SIGHUP reloads the
+// config and SIGTERM stops.
+//
+// crankx tui [--config <path>] (feature = "tui")
+// The same daemon, quiet, behind a terminal dashboard of its metrics; `q`
+// stops it.
+//
+// crankx bench [<segment_size> [<millis>]]
+// Runs one benchmark locally and prints its JSON report, the same one the
+// service answers `/bench` with.
+
+use std::process::ExitCode;
+#[cfg(feature = "tui")]
+use std::sync::atomic::Ordering::Relaxed;
+#[cfg(feature = "tui")]
+use std::thread;
+use std::time::Duration;
+
+use crankx::bench::{measure, HostInfo, MachineReport};
+use crankx::daemon::Daemon;
+
+const USAGE: &str = "usage: crankx daemon [--config <path>]
+       crankx tui [--config <path>]
+       crankx bench [<segment_size> [<millis>]]";
+
+enum Command {
+    Daemon {
+        config: String,
+    },
+    #[cfg(feature = "tui")]
+    Tui {
+        config: String,
+    },
+    Bench {
+        segment_size: usize,
+        millis: u64,
+    },
+}
+
+fn parse(args: &[&str]) -> Option<Command> {
+    Some(match *args {
+        ["daemon"] => Command::Daemon { config: "crankx.toml".to_string() },
+        ["daemon", "--config", path] => Command::Daemon { config: path.to_string() },
+        #[cfg(feature = "tui")]
+        ["tui"] => Command::Tui { config: "crankx.toml".to_string() },
+        #[cfg(feature = "tui")]
+        ["tui", "--config", path] => Command::Tui { config: path.to_string() },
+        ["bench"] => Command::Bench { segment_size: 128, millis: 2_000 },
+        ["bench", size] => Command::Bench { segment_size: size.parse().ok()?, millis: 2_000 },
+        ["bench", size, millis] => {
+            Command::Bench { segment_size: size.parse().ok()?, millis: millis.parse().ok()? }
+        }
+        _ => return None,
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let config = match parse(&args.iter().map(String::as_str).collect::<Vec<_>>()) {
+        Some(Command::Daemon { config }) => config,
+        #[cfg(feature = "tui")]
+        Some(Command::Tui { config }) => return tui(&config),
+        Some(Command::Bench { segment_size, millis }) => return bench(segment_size, millis),
+        None => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let run = || {
+        let mut daemon = Daemon::load(&config)?;
+        #[cfg(unix)]
+        daemon.handle_signals()?;
+        daemon.run()
+    };
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("crankx: {config}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the daemon on a thread of its own with the dashboard in front
+#[cfg(feature = "tui")]
+fn tui(config: &str) -> ExitCode {
+    let run = || {
+        let mut daemon = Daemon::load(config)?.quiet();
+        #[cfg(unix)]
+        daemon.handle_signals()?;
+        let (stop, metrics) = (daemon.stop_flag(), daemon.metrics_handle());
+        let stopped = stop.clone();
+        let cranking = thread::spawn(move || {
+            let result = daemon.run();
+            // A daemon that gives up takes the dashboard with it
+            stopped.store(true, Relaxed);
+            result
+        });
+        let shown = crankx::tui::run(metrics, &stop);
+        stop.store(true, Relaxed);
+        cranking.join().expect("daemon thread panicked")?;
+        shown
+    };
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("crankx: {config}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn bench(segment_size: usize, millis: u64) -> ExitCode {
+    let report = measure(segment_size, Duration::from_millis(millis));
+    let report = MachineReport::new(&report, HostInfo::detect());
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            println!("{json}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("crankx: bench: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
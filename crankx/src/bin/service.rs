@@ -0,0 +1,20 @@
+// crankx-service [addr] [threads]
+// Serves /solve, /verify and /bench as JSON over HTTP.
+
+use std::thread::available_parallelism;
+
+use crankx::service::Service;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let threads = args
+        .next()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or_else(|| available_parallelism().map_or(1, |n| n.get()));
+
+    let service = Service::bind(&addr)?;
+    println!("crankx-service listening on {addr} with {threads} threads");
+    service.run(threads);
+    Ok(())
+}
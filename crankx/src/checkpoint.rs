@@ -0,0 +1,139 @@
+// Checkpoint and resume of a cranking run
+// Everything a restarted prover needs to carry on is the challenge, the
+// nonce strategy, how far each worker got through its share of the nonces it
+// walks, and the best proof so far. Saved as `version || challenge ||
+// epoch (u64 LE) || strategy || count (u32 LE) || cursors (u64 LE)* || best?`,
+// the strategy a tag byte (0 sequential, 1 random, 2 identity, 3 strided)
+// then its fields as u64 LE. Version 1 checkpoints, which predate the epoch,
+// restore with epoch zero, and versions 1 and 2, which predate strategies,
+// as sequential. `Miner::mine_from` runs from a state and
+// leaves it where it stopped, and the daemon keeps one per tape so a restart
+// picks up the segment it was cranking.
+
+use std::io::{self, Read, Write};
+
+use crate::nonces::NonceStrategy;
+use crate::{Challenge, Solution};
+
+/// Current version of the checkpoint encoding
+pub const CHECKPOINT_VERSION: u8 = 3;
+
+/// Resumable position of a mining run
+#[derive(Debug, Default)]
+pub struct MinerState {
+    pub challenge: Challenge,
+    /// Times the challenge has been rotated under the run
+    pub epoch: u64,
+    /// Order the nonces are walked in; the cursors only mean anything under
+    /// it, since [`NonceStrategy::random`] starts somewhere new every call
+    pub nonce_strategy: NonceStrategy,
+    /// Nonces each worker has got through of its share of the nonce space,
+    /// indexed by worker
+    pub cursors: Vec<u64>,
+    /// Highest-difficulty solution found so far
+    pub best: Option<Solution>,
+}
+
+impl MinerState {
+    /// Fresh state for `workers` workers walking nonces sequentially, each
+    /// at the start of its share; see [`Miner::state`](crate::miner::Miner::state)
+    /// for one matching a miner
+    pub fn new(challenge: impl Into<Challenge>, workers: usize) -> Self {
+        Self {
+            challenge: challenge.into(),
+            epoch: 0,
+            nonce_strategy: NonceStrategy::Sequential,
+            cursors: vec![0; workers],
+            best: None,
+        }
+    }
+
+    /// Keep `solution` if it beats the best so far; returns whether it did
+    pub fn offer(&mut self, solution: Solution) -> bool {
+        if self.best.as_ref().is_some_and(|b| *b >= solution) {
+            return false;
+        }
+        self.best = Some(solution);
+        true
+    }
+
+    /// Write the checkpoint
+    pub fn save(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(&[CHECKPOINT_VERSION])?;
+        w.write_all(self.challenge.as_bytes())?;
+        w.write_all(&self.epoch.to_le_bytes())?;
+        let (tag, fields) = match self.nonce_strategy {
+            NonceStrategy::Sequential => (0, vec![]),
+            NonceStrategy::Random { start } => (1, vec![start]),
+            NonceStrategy::Identity { start } => (2, vec![start]),
+            NonceStrategy::Strided { index, count } => (3, vec![index, count]),
+        };
+        w.write_all(&[tag])?;
+        for field in fields {
+            w.write_all(&field.to_le_bytes())?;
+        }
+        w.write_all(&(self.cursors.len() as u32).to_le_bytes())?;
+        for cursor in &self.cursors {
+            w.write_all(&cursor.to_le_bytes())?;
+        }
+
+        match &self.best {
+            Some(best) => {
+                w.write_all(&[1])?;
+                w.write_all(&best.to_versioned_bytes())?;
+            }
+            None => w.write_all(&[0])?,
+        }
+        w.flush()
+    }
+
+    /// Read a checkpoint written by [`MinerState::save`], this version's or
+    /// an earlier one
+    pub fn restore(r: &mut impl Read) -> io::Result<Self> {
+        let [version] = take(r)?;
+        if !(1..=CHECKPOINT_VERSION).contains(&version) {
+            return Err(malformed());
+        }
+
+        let challenge = Challenge(take(r)?);
+        let epoch = match version {
+            1 => 0,
+            _ => u64::from_le_bytes(take(r)?),
+        };
+        let field = |r: &mut _| take(r).map(u64::from_le_bytes);
+        let nonce_strategy = match version {
+            1 | 2 => NonceStrategy::Sequential,
+            _ => match take(r)? {
+                [0] => NonceStrategy::Sequential,
+                [1] => NonceStrategy::Random { start: field(r)? },
+                [2] => NonceStrategy::Identity { start: field(r)? },
+                [3] => NonceStrategy::Strided { index: field(r)?, count: field(r)? },
+                _ => return Err(malformed()),
+            },
+        };
+        let count = u32::from_le_bytes(take(r)?);
+        let cursors = (0..count)
+            .map(|_| take(r).map(u64::from_le_bytes))
+            .collect::<io::Result<_>>()?;
+
+        let best = match take(r)? {
+            [0] => None,
+            [1] => Some(
+                Solution::from_versioned_bytes(&take::<26>(r)?).map_err(|_| malformed())?,
+            ),
+            _ => return Err(malformed()),
+        };
+
+        Ok(Self { challenge, epoch, nonce_strategy, cursors, best })
+    }
+}
+
+fn take<const L: usize>(r: &mut impl Read) -> io::Result<[u8; L]> {
+    let mut out = [0u8; L];
+    r.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed miner checkpoint")
+}
@@ -0,0 +1,3 @@
+// Interop with other EquiX-based proof formats
+
+pub mod drillx;
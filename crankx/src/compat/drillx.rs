@@ -0,0 +1,100 @@
+// Ore drillx compatibility
+// Drillx seeds EquiX with `challenge || nonce` and hashes the canonical digest
+// exactly like crankx does, so a drillx proof is a crankx proof over empty data.
+// The core API rejects empty segments (`MIN_SEGMENT_SIZE`); this module is the
+// one place the data-less mode is available, plus format conversions.
+
+use equix::SolverMemory;
+
+use crate::{
+    solve_seed, solve_seed_with_memory, verify_seed, Challenge, CrankXError, Nonce, Solution,
+};
+
+/// Drillx solution layout: digest (16 bytes) || nonce (8 bytes)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrillxSolution {
+    /// Raw EquiX digest (16 bytes)
+    pub d: [u8; 16],
+    /// Nonce (8 bytes)
+    pub n: [u8; 8],
+}
+
+impl DrillxSolution {
+    /// Serialize to drillx's 24-byte wire format
+    pub fn to_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0; 24];
+        bytes[..16].copy_from_slice(&self.d);
+        bytes[16..].copy_from_slice(&self.n);
+        bytes
+    }
+
+    /// Deserialize from drillx's 24-byte wire format
+    pub fn from_bytes(bytes: &[u8; 24]) -> Self {
+        let mut d = [0; 16];
+        let mut n = [0; 8];
+
+        d.copy_from_slice(&bytes[..16]);
+        n.copy_from_slice(&bytes[16..]);
+
+        Self { d, n }
+    }
+
+    /// True if both proofs commit to the same canonical digest and nonce
+    ///
+    /// EquiX digests are an unordered set of indices, so two byte-wise
+    /// different digests can be the same proof.
+    pub fn matches(&self, solution: &Solution) -> bool {
+        self.n == solution.n && Solution::from(*self).to_hash() == solution.to_hash()
+    }
+}
+
+impl From<DrillxSolution> for Solution {
+    fn from(s: DrillxSolution) -> Self {
+        Solution::new(s.d, s.n)
+    }
+}
+
+impl From<&Solution> for DrillxSolution {
+    fn from(s: &Solution) -> Self {
+        Self { d: s.d, n: s.n }
+    }
+}
+
+/// Solve PoW over the data-less seed `challenge || nonce`
+#[inline(always)]
+pub fn solve(
+    challenge: impl Into<Challenge>,
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    let nonce = nonce.into();
+    let seed = drillx_seed(challenge.into(), &nonce);
+    solve_seed(&seed, nonce.as_bytes())
+}
+
+/// Solve PoW over the data-less seed with pre‑allocated memory
+#[inline(always)]
+pub fn solve_with_memory(
+    mem: &mut SolverMemory,
+    challenge: impl Into<Challenge>,
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    let nonce = nonce.into();
+    let seed = drillx_seed(challenge.into(), &nonce);
+    solve_seed_with_memory(mem, &seed, nonce.as_bytes())
+}
+
+/// Verify a drillx-style digest against `challenge || nonce`
+#[inline(always)]
+pub fn verify(
+    challenge: impl Into<Challenge>,
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    let seed = drillx_seed(challenge.into(), &nonce.into());
+    verify_seed(&seed, digest)
+}
+
+/// `challenge || nonce`; crankx's own seed builder rejects empty data
+fn drillx_seed(challenge: Challenge, nonce: &Nonce) -> Vec<u8> {
+    [&challenge.as_bytes()[..], nonce.as_bytes()].concat()
+}
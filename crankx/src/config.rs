@@ -0,0 +1,160 @@
+// Solver and verifier settings in one place
+// EquiX fixes its hash function, the eight-item solution size and the
+// canonical item order; the only builder option it has is the HashX
+// runtime. `SolverConfig` gathers that with crankx's own choices (which
+// candidate to keep, how much checking to do) and exposes the candidate
+// types without reaching into `crankx::equix`.
+// `Config` (feature = "toml") is the miner's settings file: threads, tapes,
+// target difficulty and the rest, as read by `crankx daemon --config`.
+
+#[cfg(feature = "toml")]
+mod file;
+
+use equix::{EquiXBuilder, SolverMemory};
+
+pub use equix::{Runtime, RuntimeOption, SolutionItem};
+#[cfg(feature = "toml")]
+pub use file::{Config, LogTarget};
+#[cfg(feature = "toml")]
+pub(crate) use file::runtime_key;
+
+use crate::{
+    build_equix, build_seed, check_segment_size, Challenge, CrankXError, Nonce,
+    SelectionPolicy, Solution, DEFAULT_RUNTIME,
+};
+
+/// A candidate digest as its eight HashX inputs, in canonical order
+pub type SolutionItems = [SolutionItem; 8];
+
+/// How thoroughly [`SolverConfig::verify`] checks a digest
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyLevel {
+    /// Rebuild the seed's HashX program and check the hash sums
+    #[default]
+    Full,
+    /// Only check the digest is eight items in canonical order; no hashing,
+    /// so it's a cheap pre-filter and never a substitute for `Full`
+    Structure,
+}
+
+/// EquiX runtime plus crankx's solving and verification choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverConfig {
+    pub runtime: RuntimeOption,
+    pub policy: SelectionPolicy,
+    pub verify_level: VerifyLevel,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            runtime: DEFAULT_RUNTIME,
+            policy: SelectionPolicy::First,
+            verify_level: VerifyLevel::Full,
+        }
+    }
+}
+
+impl SolverConfig {
+    /// Smallest configuration that fully verifies: interpreted HashX, so no
+    /// JIT and no executable pages
+    ///
+    /// A full verify peaks at about 8 KiB of heap for a `MAX_DATA_LEN`
+    /// segment (the 4136-byte seed plus a 4 KiB HashX program) and never
+    /// allocates the 1.8 MiB solver memory. Solving still works, slowly.
+    pub const EMBEDDED: Self = Self {
+        runtime: RuntimeOption::InterpretOnly,
+        policy: SelectionPolicy::First,
+        verify_level: VerifyLevel::Full,
+    };
+
+    pub fn runtime(mut self, runtime: RuntimeOption) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn policy(mut self, policy: SelectionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn verify_level(mut self, verify_level: VerifyLevel) -> Self {
+        self.verify_level = verify_level;
+        self
+    }
+
+    /// EquiX builder with this runtime, for the `*_with_builder` functions
+    pub fn builder(&self) -> EquiXBuilder {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(self.runtime);
+        builder
+    }
+
+    /// Every candidate for `nonce`, in solver order
+    pub fn candidates<const N: usize>(
+        &self,
+        mem: &mut SolverMemory,
+        challenge: impl Into<Challenge>,
+        data: &[u8; N],
+        nonce: impl Into<Nonce>,
+    ) -> Result<Vec<Solution>, CrankXError> {
+        check_segment_size::<N>();
+        let nonce = nonce.into();
+        let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+        let eq = build_equix(&self.builder(), &seed)?;
+        let candidates = eq.solve_with_memory(mem);
+        Ok(candidates.iter().map(|c| Solution::new(c.to_bytes(), *nonce.as_bytes())).collect())
+    }
+
+    /// The candidate [`SolverConfig::policy`] picks for `nonce`
+    pub fn solve<const N: usize>(
+        &self,
+        mem: &mut SolverMemory,
+        challenge: impl Into<Challenge>,
+        data: &[u8; N],
+        nonce: impl Into<Nonce>,
+    ) -> Result<Solution, CrankXError> {
+        check_segment_size::<N>();
+        let nonce = nonce.into();
+        let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+        let eq = build_equix(&self.builder(), &seed)?;
+        self.policy.select(&eq.solve_with_memory(mem), nonce.as_bytes())
+    }
+
+    /// Check `solution` at [`SolverConfig::verify_level`]
+    pub fn verify<const N: usize>(
+        &self,
+        challenge: impl Into<Challenge>,
+        data: &[u8; N],
+        solution: &Solution,
+    ) -> Result<(), CrankXError> {
+        check_segment_size::<N>();
+        match self.verify_level {
+            VerifyLevel::Structure => solution.items().map(|_| ()),
+            VerifyLevel::Full => {
+                let seed = build_seed(challenge.into().as_bytes(), data, &solution.n)?;
+                self.builder()
+                    .verify_bytes(&seed, &solution.d)
+                    .map_err(|_| CrankXError::EquiXFailure)
+            }
+        }
+    }
+}
+
+impl Solution {
+    /// Proof of `candidate` at `nonce`
+    pub fn from_candidate(candidate: &equix::Solution, nonce: impl Into<Nonce>) -> Self {
+        Self::new(candidate.to_bytes(), *nonce.into().as_bytes())
+    }
+
+    /// The digest as an equix solution, [`CrankXError::InvalidSolution`] if
+    /// its items aren't in canonical order
+    pub fn candidate(&self) -> Result<equix::Solution, CrankXError> {
+        equix::Solution::try_from_bytes(&self.d).map_err(|_| CrankXError::InvalidSolution)
+    }
+
+    /// The digest's eight HashX inputs
+    pub fn items(&self) -> Result<SolutionItems, CrankXError> {
+        Ok(*self.candidate()?.as_ref())
+    }
+}
@@ -0,0 +1,235 @@
+// The miner's TOML settings file
+//
+//   tape_dir = "/var/lib/crankx/tapes"     # required
+//   archive = "/var/lib/crankx/proofs"     # required
+//   threads = 8                            # default: every core
+//   batch_size = 16                        # nonces a thread claims at a time; default 1
+//   runtime = "interpret_only"             # HashX: "try_compile" (default),
+//                                          # "compile_only" or "interpret_only"
+//   affinity = [0, 1, 2, 3]                # cores to run on; default: any
+//   min_difficulty = 8
+//   challenge = "<64 hex chars>"           # default: each tape header's
+//   duty_cycle = 0.6                       # or max_rate = 50000.0; default: flat out
+//   submit_url = "https://rpc.example"     # where proofs are sent
+//   metrics_port = 9100                    # where metrics are served
+//   metrics_addr = "0.0.0.0"               # interface they're served on;
+//                                          # default: "127.0.0.1"
+//   epoch = 0                              # recorded with each proof, for pruning
+//   poll_secs = 30                         # idle wait between passes
+//   log = "journal"                        # "stderr" (default), "journal" or a path
+//
+// Unknown keys are rejected so a typo can't silently fall back to a default.
+// `tune::Tuning::save` writes `threads`, `batch_size` and `runtime` into an
+// existing file, leaving the rest of it, comments included, as it was.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use equix::RuntimeOption;
+use serde::Deserialize;
+
+use crate::miner::Throttle;
+use crate::{Challenge, DEFAULT_RUNTIME};
+
+/// Where log lines go
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogTarget {
+    Stderr,
+    /// stderr with `<N>` priority prefixes, which journald reads as levels
+    Journal,
+    /// Appended to a file, each line stamped with Unix seconds
+    File(PathBuf),
+}
+
+impl From<&str> for LogTarget {
+    fn from(value: &str) -> Self {
+        match value {
+            "stderr" => Self::Stderr,
+            "journal" => Self::Journal,
+            path => Self::File(path.into()),
+        }
+    }
+}
+
+/// Miner settings, usually read with [`Config::from_path`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub tape_dir: PathBuf,
+    /// Directory the proofs are archived in
+    pub archive: PathBuf,
+    pub threads: usize,
+    /// Consecutive nonces each thread claims at a time, see
+    /// [`Miner::batch_size`](crate::miner::Miner::batch_size)
+    pub batch_size: u64,
+    /// HashX runtime the miner asks for
+    pub runtime: RuntimeOption,
+    /// Cores the cranking threads may run on, empty for any
+    pub affinity: Vec<usize>,
+    pub min_difficulty: u32,
+    /// Challenge to prove under; `None` uses each tape header's
+    pub challenge: Option<Challenge>,
+    pub throttle: Throttle,
+    /// Endpoint proofs are submitted to
+    pub submit_url: Option<String>,
+    /// Port metrics are served on
+    pub metrics_port: Option<u16>,
+    /// Address metrics are served on, loopback unless set
+    pub metrics_addr: IpAddr,
+    pub epoch: u64,
+    pub poll: Duration,
+    pub log: LogTarget,
+}
+
+/// The file as written, before defaults and validation
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Raw {
+    tape_dir: PathBuf,
+    archive: PathBuf,
+    threads: Option<usize>,
+    batch_size: Option<u64>,
+    runtime: Option<String>,
+    #[serde(default)]
+    affinity: Vec<usize>,
+    min_difficulty: Option<u32>,
+    challenge: Option<Challenge>,
+    duty_cycle: Option<f64>,
+    max_rate: Option<f64>,
+    submit_url: Option<String>,
+    metrics_port: Option<u16>,
+    metrics_addr: Option<IpAddr>,
+    epoch: Option<u64>,
+    poll_secs: Option<u64>,
+    log: Option<String>,
+}
+
+impl Config {
+    /// Defaults for everything but the two directories
+    pub fn new(tape_dir: impl Into<PathBuf>, archive: impl Into<PathBuf>) -> Self {
+        Self {
+            tape_dir: tape_dir.into(),
+            archive: archive.into(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            batch_size: 1,
+            runtime: DEFAULT_RUNTIME,
+            affinity: Vec::new(),
+            min_difficulty: 8,
+            challenge: None,
+            throttle: Throttle::Off,
+            submit_url: None,
+            metrics_port: None,
+            metrics_addr: Ipv4Addr::LOCALHOST.into(),
+            epoch: 0,
+            poll: Duration::from_secs(30),
+            log: LogTarget::Stderr,
+        }
+    }
+
+    /// Read and validate the file at `path`
+    pub fn from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parse and validate a settings file's contents; `InvalidData` for
+    /// malformed TOML, unknown keys or values [`Config::validate`] rejects
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let raw: Raw = toml::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        let defaults = Self::new(raw.tape_dir, raw.archive);
+        let throttle = match (raw.duty_cycle, raw.max_rate) {
+            (None, None) => Throttle::Off,
+            (Some(duty), None) => Throttle::DutyCycle(duty),
+            (None, Some(rate)) => Throttle::MaxRate(rate),
+            (Some(_), Some(_)) => return Err(invalid("set duty_cycle or max_rate, not both")),
+        };
+        let runtime = match raw.runtime.as_deref() {
+            None => defaults.runtime,
+            Some(key) => runtime_from_key(key)
+                .ok_or_else(|| invalid(format!("unknown runtime {key:?}")))?,
+        };
+        let config = Self {
+            threads: raw.threads.unwrap_or(defaults.threads),
+            batch_size: raw.batch_size.unwrap_or(defaults.batch_size),
+            runtime,
+            affinity: raw.affinity,
+            min_difficulty: raw.min_difficulty.unwrap_or(defaults.min_difficulty),
+            challenge: raw.challenge,
+            throttle,
+            submit_url: raw.submit_url,
+            metrics_port: raw.metrics_port,
+            metrics_addr: raw.metrics_addr.unwrap_or(defaults.metrics_addr),
+            epoch: raw.epoch.unwrap_or(defaults.epoch),
+            poll: raw.poll_secs.map_or(defaults.poll, Duration::from_secs),
+            log: raw.log.as_deref().map_or(defaults.log.clone(), LogTarget::from),
+            ..defaults
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check values a file can express but a miner can't use
+    pub fn validate(&self) -> io::Result<()> {
+        if self.threads == 0 {
+            return Err(invalid("threads must be at least 1"));
+        }
+        if self.batch_size == 0 {
+            return Err(invalid("batch_size must be at least 1"));
+        }
+        let mut cores = HashSet::new();
+        if let Some(core) = self.affinity.iter().find(|&&core| !cores.insert(core)) {
+            return Err(invalid(format!("affinity lists core {core} twice")));
+        }
+        if self.min_difficulty > 256 {
+            return Err(invalid("min_difficulty can't exceed 256, the hash length in bits"));
+        }
+        match self.throttle {
+            Throttle::DutyCycle(duty) if !(duty > 0.0 && duty <= 1.0) => {
+                return Err(invalid("duty_cycle must be in (0, 1]"));
+            }
+            Throttle::MaxRate(rate) if !(rate > 0.0 && rate.is_finite()) => {
+                return Err(invalid("max_rate must be positive"));
+            }
+            _ => {}
+        }
+        if let Some(url) = &self.submit_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(invalid(format!("submit_url {url:?} isn't an http(s) URL")));
+            }
+        }
+        if self.metrics_port == Some(0) {
+            return Err(invalid("metrics_port must be non-zero"));
+        }
+        if self.poll.is_zero() {
+            return Err(invalid("poll_secs must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+/// How `runtime` is spelled in the file
+pub(crate) fn runtime_key(runtime: RuntimeOption) -> &'static str {
+    match runtime {
+        RuntimeOption::TryCompile => "try_compile",
+        RuntimeOption::CompileOnly => "compile_only",
+        RuntimeOption::InterpretOnly => "interpret_only",
+        // Non-exhaustive upstream; a newer option saves as the default
+        _ => "try_compile",
+    }
+}
+
+fn runtime_from_key(key: &str) -> Option<RuntimeOption> {
+    match key {
+        "try_compile" => Some(RuntimeOption::TryCompile),
+        "compile_only" => Some(RuntimeOption::CompileOnly),
+        "interpret_only" => Some(RuntimeOption::InterpretOnly),
+        _ => None,
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
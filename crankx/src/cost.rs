@@ -0,0 +1,171 @@
+// What verifying a proof costs, for budgeting rather than guessing
+// Verification is dominated by HashX program generation, a fixed cost per
+// proof; the Blake2b pass over the seed adds a little per segment byte. So a
+// proof over `len` bytes costs `per_proof + per_byte * len`, both on-chain in
+// compute units and off-chain in wall-clock time.
+// There are no built-in compute unit figures: they depend on the runtime
+// version, so measure the `verifier` program (the `crankx/cu` harness does
+// that) and build the model from its measurements with
+// `CostModel::from_cu_measurements`, or pass figures to `CostModel::with_cu`.
+// Without them, estimates carry no compute units and `proofs_within` has no
+// answer.
+// `CostModel::DEFAULT` carries wall-clock figures only. Per proof, 100us is
+// the middle of `crankx bench 1 5000` runs (83-114us) on a 1-vCPU Xeon VM
+// with the compiled HashX runtime; per byte, the 4096-byte runs differed from
+// the 1-byte ones by less than that run-to-run spread, so 1ns stands in for
+// Blake2b's ~3 cycles per byte. `CostModel::calibrate` redoes the wall-clock
+// side on the machine it runs on, which is what a service setting timeouts
+// wants.
+
+use std::time::Duration;
+
+use crate::bench::measure;
+use crate::{MAX_DATA_LEN, MIN_SEGMENT_SIZE};
+
+/// Compute units a Solana transaction may request
+pub const MAX_TRANSACTION_CU: u64 = 1_400_000;
+
+/// Cost of verifying one or more proofs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CostEstimate {
+    /// Solana compute units, `None` from a model without compute unit figures
+    pub cu: Option<u64>,
+    /// Time on the machine the model describes, in nanoseconds
+    pub wallclock_ns: u64,
+}
+
+impl CostEstimate {
+    pub fn wallclock(&self) -> Duration {
+        Duration::from_nanos(self.wallclock_ns)
+    }
+}
+
+impl core::ops::Add for CostEstimate {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            cu: self.cu.zip(other.cu).map(|(a, b)| a.saturating_add(b)),
+            wallclock_ns: self.wallclock_ns.saturating_add(other.wallclock_ns),
+        }
+    }
+}
+
+/// Compute units verification costs on chain, as measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuCost {
+    pub per_proof: u64,
+    pub per_byte: u64,
+}
+
+/// Linear cost of verification in segment length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostModel {
+    /// Set with [`CostModel::with_cu`]
+    pub cu: Option<CuCost>,
+    pub per_proof_ns: f64,
+    pub per_byte_ns: f64,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl CostModel {
+    /// Built-in wall-clock figures and no compute unit ones, see the module
+    /// notes for where they come from
+    pub const DEFAULT: Self = Self { cu: None, per_proof_ns: 100_000.0, per_byte_ns: 1.0 };
+
+    /// Measure the wall-clock side on this machine, cranking for `duration`
+    /// at the smallest and largest segment sizes to find proofs to time
+    ///
+    /// Has no compute unit figures; add them with [`CostModel::with_cu`].
+    /// Falls back to the default wall-clock figures if a measurement finds
+    /// nothing to verify.
+    pub fn calibrate(duration: Duration) -> Self {
+        let ns_per_verify = |size| {
+            let verifies = measure(size, duration).verifies_per_sec;
+            (verifies > 0.0).then(|| 1e9 / verifies)
+        };
+        let (Some(small), Some(large)) =
+            (ns_per_verify(MIN_SEGMENT_SIZE), ns_per_verify(MAX_DATA_LEN))
+        else {
+            return Self::DEFAULT;
+        };
+        let per_byte_ns = ((large - small) / (MAX_DATA_LEN - MIN_SEGMENT_SIZE) as f64).max(0.0);
+        Self {
+            per_proof_ns: small - per_byte_ns * MIN_SEGMENT_SIZE as f64,
+            per_byte_ns,
+            ..Self::DEFAULT
+        }
+    }
+
+    /// The default wall-clock figures with compute unit ones fitted to
+    /// `(segment_len, units)` measurements of the verifier, as `crankx_cu`
+    /// takes them
+    ///
+    /// The per-byte figure is the least-squares slope, rounded up; the
+    /// per-proof one is then raised until no measurement costs more than the
+    /// model says, so budgets built on it hold for what was measured. `None`
+    /// unless the measurements cover at least two segment lengths.
+    pub fn from_cu_measurements(
+        measurements: impl IntoIterator<Item = (usize, u64)>,
+    ) -> Option<Self> {
+        let points: Vec<(f64, u64)> =
+            measurements.into_iter().map(|(len, units)| (len as f64, units)).collect();
+        let n = points.len() as f64;
+        let mean_len = points.iter().map(|&(len, _)| len).sum::<f64>() / n;
+        let mean_units = points.iter().map(|&(_, units)| units as f64).sum::<f64>() / n;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for &(len, units) in &points {
+            cov += (len - mean_len) * (units as f64 - mean_units);
+            var += (len - mean_len) * (len - mean_len);
+        }
+        if var <= 0.0 {
+            return None;
+        }
+        let per_byte = (cov / var).max(0.0).ceil() as u64;
+        let per_proof = points
+            .iter()
+            .map(|&(len, units)| units.saturating_sub(per_byte.saturating_mul(len as u64)))
+            .max()?;
+        Some(Self::DEFAULT.with_cu(per_proof, per_byte))
+    }
+
+    /// Set the compute unit figures to ones measured on chain
+    pub fn with_cu(mut self, per_proof_cu: u64, per_byte_cu: u64) -> Self {
+        self.cu = Some(CuCost { per_proof: per_proof_cu, per_byte: per_byte_cu });
+        self
+    }
+
+    /// Cost of verifying one proof over `segment_len` bytes
+    pub fn estimate_verify(&self, segment_len: usize) -> CostEstimate {
+        let len = segment_len as u64;
+        let ns = self.per_proof_ns + self.per_byte_ns * segment_len as f64;
+        CostEstimate {
+            cu: self.cu.map(|cu| cu.per_proof.saturating_add(cu.per_byte.saturating_mul(len))),
+            wallclock_ns: ns.max(0.0) as u64,
+        }
+    }
+
+    /// Cost of verifying one proof per entry of `segment_lens`
+    pub fn estimate_batch(&self, segment_lens: impl IntoIterator<Item = usize>) -> CostEstimate {
+        let empty = CostEstimate { cu: self.cu.map(|_| 0), wallclock_ns: 0 };
+        segment_lens.into_iter().map(|len| self.estimate_verify(len)).fold(empty, |t, c| t + c)
+    }
+
+    /// Most proofs over `segment_len` bytes that fit in `cu_budget` compute
+    /// units, `None` without compute unit figures
+    pub fn proofs_within(&self, segment_len: usize, cu_budget: u64) -> Option<u64> {
+        let cu = self.estimate_verify(segment_len).cu?;
+        Some(cu_budget.checked_div(cu).unwrap_or(u64::MAX))
+    }
+}
+
+/// [`CostModel::estimate_verify`] with the default model: wall-clock time
+/// only, `cu` is `None`
+pub fn estimate_verify(segment_len: usize) -> CostEstimate {
+    CostModel::DEFAULT.estimate_verify(segment_len)
+}
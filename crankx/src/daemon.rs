@@ -0,0 +1,503 @@
+// Unattended cranking (feature = "daemon")
+// `crankx daemon --config <path>` keeps every tape in a directory proved: each pass
+// opens the `*.tape` files in name order, schedules the segments without a
+// stored proof under the current challenge, cranks them and appends the
+// proofs to that tape's archive (`<archive>/<tape file name>.proofs`). A pass
+// that finds nothing to do sleeps for `poll_secs` before looking again, so
+// tapes dropped into the directory are picked up without a restart.
+// A segment abandoned on a stop leaves `<archive>/<tape file name>.checkpoint`
+// (`segment (u64 LE) || MinerState`), and the next pass over the tape cranks
+// that segment first, from where the miner stopped, provided the challenge
+// and thread count still match.
+// SIGHUP re-reads the config and reopens the log file (for logrotate) between
+// segments; SIGTERM and SIGINT stop the segment in progress and exit.
+// Settings come from a `Config` file. Throttle, runtime and thread count go
+// to the miner; on Linux the daemon pins itself to `affinity` before
+// cranking, and the miner's threads inherit that mask. Submission settings
+// are for the tools around it: the daemon only archives.
+// With `metrics_port` set, `GET /metrics` on that port of `metrics_addr`
+// (loopback unless configured) serves the miner's counters and the
+// staleness of every segment of every tape in the directory: seconds since
+// the daemon last proved it, with a segment it hasn't proved counting as
+// proved just before it started.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::checkpoint::MinerState;
+use crate::config::{Config, LogTarget};
+use crate::metrics::Metrics;
+use crate::miner::Miner;
+use crate::scheduler::{RecallPriority, Scheduler, SegmentInfo, StalestFirst};
+use crate::store::ProofStore;
+use crate::tape::Reader;
+use crate::SegmentProvider;
+
+/// Proves tapes until stopped, see the module notes
+pub struct Daemon {
+    config_path: Option<PathBuf>,
+    config: Config,
+    miner: Miner,
+    log: Log,
+    stop: Arc<AtomicBool>,
+    reload: Arc<AtomicBool>,
+    metrics: Arc<Metrics>,
+    exporter: Option<Exporter>,
+    started: Instant,
+    /// When each `(tape name, segment)` was last proved, in seconds since
+    /// `started`
+    proved_at: HashMap<(String, u64), u64>,
+    /// Tapes whose staleness is being reported
+    tapes: BTreeSet<String>,
+}
+
+impl Daemon {
+    /// Daemon running `config`; [`Daemon::reload_flag`] only reopens the log
+    pub fn new(config: Config) -> io::Result<Self> {
+        config.validate()?;
+        let stop = Arc::<AtomicBool>::default();
+        let metrics = Arc::<Metrics>::default();
+        let addr = config.metrics_port.map(|p| (config.metrics_addr, p));
+        let exporter = addr.map(|a| Exporter::bind(a, &metrics)).transpose()?;
+        Ok(Self {
+            config_path: None,
+            miner: miner(&config, &stop, &metrics)?,
+            log: Log::open(&config.log, &metrics)?,
+            config,
+            stop,
+            reload: Arc::default(),
+            metrics,
+            exporter,
+            started: Instant::now(),
+            proved_at: HashMap::new(),
+            tapes: BTreeSet::new(),
+        })
+    }
+
+    /// Daemon running the config at `path`, re-read on every reload
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut daemon = Self::new(Config::from_path(&path)?)?;
+        daemon.config_path = Some(path);
+        Ok(daemon)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Counters served at `/metrics`
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Shared handle on [`Daemon::metrics`], for a dashboard on another thread
+    pub fn metrics_handle(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    /// Drop log lines bound for stderr, e.g. while a dashboard owns the
+    /// terminal; warnings and errors still reach [`Metrics::recent_errors`]
+    pub fn quiet(mut self) -> Self {
+        self.log.quiet = true;
+        self
+    }
+
+    /// Flag that stops the daemon, abandoning the segment in progress
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Flag that reloads the config before the next segment
+    pub fn reload_flag(&self) -> Arc<AtomicBool> {
+        self.reload.clone()
+    }
+
+    /// Route SIGHUP to [`Daemon::reload_flag`] and SIGTERM and SIGINT to
+    /// [`Daemon::stop_flag`]
+    ///
+    /// Only one daemon per process can have the signals; later calls fail
+    /// with `AlreadyExists`.
+    #[cfg(unix)]
+    pub fn handle_signals(&self) -> io::Result<()> {
+        signals::install(self.stop.clone(), self.reload.clone())
+    }
+
+    /// Run passes until stopped
+    pub fn run(&mut self) -> io::Result<()> {
+        let dir = self.config.tape_dir.display().to_string();
+        self.log.info(format_args!("started, tapes in {dir}"));
+        while !self.stop.load(Relaxed) {
+            if self.reload.swap(false, Relaxed) {
+                self.reload();
+            }
+            if self.run_once()? > 0 {
+                continue;
+            }
+            // Nothing to do: wait for new tapes, a reload or a stop
+            let mut idle = Duration::ZERO;
+            while idle < self.config.poll && !self.stop.load(Relaxed) && !self.reload.load(Relaxed)
+            {
+                thread::sleep(POLL_STEP);
+                idle += POLL_STEP;
+            }
+        }
+        self.log.info(format_args!("stopped"));
+        Ok(())
+    }
+
+    /// One pass over the tape directory; returns the proofs written
+    ///
+    /// A tape that can't be read, or that has no challenge to prove under in
+    /// either the config or its header, is logged and skipped. Only a
+    /// missing or unreadable tape directory, or an archive that can't be
+    /// written, is an error.
+    pub fn run_once(&mut self) -> io::Result<u64> {
+        let mut tapes: Vec<_> = fs::read_dir(&self.config.tape_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<_>>()?;
+        tapes.retain(|p| p.is_file() && p.extension().is_some_and(|e| e == "tape"));
+        tapes.sort();
+        fs::create_dir_all(&self.config.archive)?;
+
+        // Tapes gone from the directory stop being reported
+        let names: BTreeSet<_> = tapes.iter().map(|t| tape_name(t)).collect();
+        for gone in self.tapes.difference(&names) {
+            self.metrics.clear_source(gone);
+        }
+        self.proved_at.retain(|(name, _), _| names.contains(name));
+        self.tapes = names;
+
+        let mut proved = 0;
+        for tape in &tapes {
+            if self.stop.load(Relaxed) || self.reload.load(Relaxed) {
+                break;
+            }
+            let reader = match Reader::open(tape) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    self.log.warn(format_args!("skipping {}: {e}", tape.display()));
+                    continue;
+                }
+            };
+            proved += self.crank_tape(tape, &reader)?;
+        }
+        Ok(proved)
+    }
+
+    fn crank_tape(&mut self, tape: &Path, reader: &Reader) -> io::Result<u64> {
+        let name = tape_name(tape);
+        let Some(challenge) = self.config.challenge.or(reader.header().challenge) else {
+            self.log.warn(format_args!("skipping {name}: no challenge in the config or the tape"));
+            return Ok(0);
+        };
+        let mut store = ProofStore::open(self.config.archive.join(format!("{name}.proofs")))?;
+
+        let (mut missing, mut held) = (Vec::new(), Vec::new());
+        for index in 0..reader.segment_count() {
+            let last_proved = self.proved_at.get(&(name.clone(), index)).copied();
+            match store.get(challenge, index) {
+                Some(_) => held.push((index, last_proved.unwrap_or(0))),
+                None => {
+                    missing.push(SegmentInfo { index, last_proved, reward: 1.0, recalled: false })
+                }
+            }
+        }
+        let now = self.started.elapsed().as_secs();
+        let done = held.len() as u64;
+        for (index, at) in held {
+            self.metrics.set_staleness(&name, index, now.saturating_sub(at));
+        }
+        let todo = missing.len();
+        self.metrics.set_segments(&name, done, todo as u64);
+
+        // A checkpoint only helps if its segment still needs proving under
+        // the same challenge by as many threads
+        let checkpoint = self.config.archive.join(format!("{name}.checkpoint"));
+        let mut resume = load_checkpoint(&checkpoint).unwrap_or_else(|e| {
+            self.log.warn(format_args!("{name}: ignoring checkpoint: {e}"));
+            None
+        });
+        resume = resume.filter(|(index, state)| {
+            missing.iter().any(|s| s.index == *index)
+                && state.challenge == challenge
+                && state.cursors.len() == self.miner.threads()
+        });
+        let strategy = RecallPriority { fallback: StalestFirst };
+        let mut scheduler =
+            Scheduler::with_segments(missing, strategy).metrics(self.metrics.clone(), name.clone());
+        match &resume {
+            Some((index, _)) => scheduler.recall(*index),
+            None => remove_checkpoint(&checkpoint)?,
+        }
+
+        let mut proved = 0;
+        for _ in 0..todo {
+            if self.stop.load(Relaxed) || self.reload.load(Relaxed) {
+                break;
+            }
+            let now = self.started.elapsed().as_secs();
+            let Some(index) = scheduler.next(now) else {
+                break;
+            };
+            scheduler.proved(index, now);
+
+            let mut state = match resume.take() {
+                Some((at, state)) if at == index => state,
+                _ => self.miner.state(challenge),
+            };
+            let min_difficulty = self.config.min_difficulty;
+            let mined = self.miner.mine_segment_from(&mut state, reader, index, min_difficulty);
+            let report = match mined {
+                Ok(report) => report,
+                Err(e) => {
+                    self.log.warn(format_args!("{name} segment {index}: {e}"));
+                    continue;
+                }
+            };
+            // No solution means the stop flag cut the search short
+            let Some(solution) = report.solution else {
+                save_checkpoint(&checkpoint, index, &state)?;
+                let tried = state.cursors.iter().sum::<u64>();
+                self.log.info(format_args!("{name} segment {index}: stopped, {tried} tried"));
+                break;
+            };
+            let difficulty = solution.difficulty();
+            store.put(report.challenge, index, self.config.epoch, solution)?;
+            remove_checkpoint(&checkpoint)?;
+            self.proved_at.insert((name.clone(), index), now);
+            proved += 1;
+            self.metrics.set_segments(&name, done + proved, todo as u64 - proved);
+            self.log.info(format_args!(
+                "{name} segment {index}: difficulty {difficulty} after {} attempts in {:.1?}",
+                report.attempts, report.elapsed
+            ));
+        }
+        Ok(proved)
+    }
+
+    /// Re-read the config file and reopen the log, keeping the old settings
+    /// if the file no longer parses
+    fn reload(&mut self) {
+        if let Some(path) = &self.config_path {
+            let metrics = &self.metrics;
+            match Config::from_path(path).and_then(|c| Ok((miner(&c, &self.stop, metrics)?, c))) {
+                Ok((miner, config)) => {
+                    self.miner = miner;
+                    self.config = config;
+                }
+                Err(e) => {
+                    self.log.error(format_args!("reload failed, keeping old config: {e}"));
+                    return;
+                }
+            }
+        }
+        match Log::open(&self.config.log, &self.metrics) {
+            Ok(log) => self.log = Log { quiet: self.log.quiet, ..log },
+            Err(e) => self.log.error(format_args!("reopening log failed: {e}")),
+        }
+        let addr = self.config.metrics_port.map(|p| (self.config.metrics_addr, p));
+        if self.exporter.as_ref().map(|e| e.addr) != addr {
+            // The old listener has to go first in case the address is the same
+            self.exporter = None;
+            match addr.map(|a| Exporter::bind(a, &self.metrics)).transpose() {
+                Ok(exporter) => self.exporter = exporter,
+                Err(e) => self.log.error(format_args!("serving metrics failed: {e}")),
+            }
+        }
+        self.log.info(format_args!("config reloaded"));
+    }
+}
+
+/// Segment the checkpoint at `path` is for and the miner's state in it,
+/// `None` if there is none
+fn load_checkpoint(path: &Path) -> io::Result<Option<(u64, MinerState)>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let (index, mut state) = bytes
+        .split_first_chunk::<8>()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "truncated checkpoint"))?;
+    Ok(Some((u64::from_le_bytes(*index), MinerState::restore(&mut state)?)))
+}
+
+/// Write the checkpoint for segment `index` aside and rename it into place,
+/// so a crash never leaves half of one
+fn save_checkpoint(path: &Path, index: u64, state: &MinerState) -> io::Result<()> {
+    let mut bytes = index.to_le_bytes().to_vec();
+    state.save(&mut bytes)?;
+    let partial = path.with_extension("checkpoint.partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(partial, path)
+}
+
+fn remove_checkpoint(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Name a tape's proofs and metrics go under
+fn tape_name(tape: &Path) -> String {
+    tape.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+/// Miner for `config`, with the calling thread pinned to its affinity
+fn miner(config: &Config, stop: &Arc<AtomicBool>, metrics: &Arc<Metrics>) -> io::Result<Miner> {
+    #[cfg(target_os = "linux")]
+    if !config.affinity.is_empty() {
+        pin(&config.affinity)?;
+    }
+    let miner =
+        Miner::new(config.threads).batch_size(config.batch_size).runtime(config.runtime);
+    let miner = miner.throttle(config.throttle).map_err(io::Error::other)?;
+    Ok(miner.with_stop_flag(stop.clone()).metrics(metrics.clone()))
+}
+
+/// `GET /metrics` on a thread of its own, until dropped
+struct Exporter {
+    server: Arc<Server>,
+    addr: (IpAddr, u16),
+}
+
+impl Exporter {
+    /// Serve `metrics` at `addr`
+    fn bind(addr: (IpAddr, u16), metrics: &Arc<Metrics>) -> io::Result<Self> {
+        let server = Server::http(addr).map_err(|e| io::Error::other(e.to_string()))?;
+        let server = Arc::new(server);
+        let (serving, metrics) = (server.clone(), metrics.clone());
+        thread::spawn(move || {
+            for request in serving.incoming_requests() {
+                let response = match (request.method(), request.url()) {
+                    (Method::Get, "/metrics") => {
+                        let content_type = "text/plain; version=0.0.4";
+                        let header = Header::from_bytes("Content-Type", content_type).unwrap();
+                        Response::from_string(metrics.render()).with_header(header)
+                    }
+                    _ => Response::from_string("not found").with_status_code(404),
+                };
+                let _ = request.respond(response);
+            }
+        });
+        Ok(Self { server, addr })
+    }
+}
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Restrict the calling thread, and threads it spawns later, to `cores`
+#[cfg(target_os = "linux")]
+fn pin(cores: &[usize]) -> io::Result<()> {
+    // SAFETY: an all-zero cpu_set_t is the empty set
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core >= libc::CPU_SETSIZE as usize {
+            let message = format!("affinity core {core} out of range");
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+        // SAFETY: `core` is within the set
+        unsafe { libc::CPU_SET(core, &mut set) };
+    }
+    // SAFETY: `set` is a valid cpu_set_t of the size passed
+    match unsafe { libc::sched_setaffinity(0, std::mem::size_of_val(&set), &set) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// How often an idle daemon checks its flags
+const POLL_STEP: Duration = Duration::from_millis(100);
+
+struct Log {
+    target: LogTarget,
+    file: Option<File>,
+    /// Where warnings and errors are also recorded
+    metrics: Arc<Metrics>,
+    /// Drop lines bound for stderr
+    quiet: bool,
+}
+
+impl Log {
+    fn open(target: &LogTarget, metrics: &Arc<Metrics>) -> io::Result<Self> {
+        let file = match target {
+            LogTarget::File(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+            _ => None,
+        };
+        Ok(Self { target: target.clone(), file, metrics: metrics.clone(), quiet: false })
+    }
+
+    fn info(&mut self, message: fmt::Arguments) {
+        self.write(6, "info", message)
+    }
+
+    fn warn(&mut self, message: fmt::Arguments) {
+        self.metrics.record_error(message.to_string());
+        self.write(4, "warn", message)
+    }
+
+    fn error(&mut self, message: fmt::Arguments) {
+        self.metrics.record_error(message.to_string());
+        self.write(3, "error", message)
+    }
+
+    /// One line at syslog `priority`; a log that can't be written is ignored
+    fn write(&mut self, priority: u8, level: &str, message: fmt::Arguments) {
+        let _ = match (&self.target, &mut self.file) {
+            (LogTarget::Journal, _) | (_, None) if self.quiet => Ok(()),
+            (LogTarget::Journal, _) => writeln!(io::stderr(), "<{priority}>{message}"),
+            (LogTarget::File(_), Some(file)) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                writeln!(file, "{} {level} {message}", now.as_secs())
+            }
+            _ => writeln!(io::stderr(), "crankx: {level}: {message}"),
+        };
+    }
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::io;
+    use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+    use std::sync::{Arc, OnceLock};
+
+    /// `(stop, reload)`, set once before any handler is installed
+    static FLAGS: OnceLock<(Arc<AtomicBool>, Arc<AtomicBool>)> = OnceLock::new();
+
+    pub(super) fn install(stop: Arc<AtomicBool>, reload: Arc<AtomicBool>) -> io::Result<()> {
+        FLAGS.set((stop, reload)).map_err(|_| io::Error::from(io::ErrorKind::AlreadyExists))?;
+        for signal in [libc::SIGHUP, libc::SIGTERM, libc::SIGINT] {
+            let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            // SAFETY: the handler only does atomic loads and stores
+            if unsafe { libc::signal(signal, handler) } == libc::SIG_ERR {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    extern "C" fn on_signal(signal: libc::c_int) {
+        if let Some((stop, reload)) = FLAGS.get() {
+            match signal {
+                libc::SIGHUP => reload.store(true, Relaxed),
+                _ => stop.store(true, Relaxed),
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Replay tracking for validators
+// A proof may be accepted once per epoch. Recent keys live in an exact set;
+// once that fills, the oldest spill into a fixed-size Bloom filter, so memory
+// stays bounded however many proofs an epoch sees. A Bloom hit can be a false
+// positive, which is reported separately so callers choose how strict to be.
+// Keys are final hashes: keccak output is uniform, so the filter indexes
+// straight off the key bytes and every validator computes the same bits.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::Solution;
+
+/// Bits probed per key in the Bloom filter
+const BLOOM_HASHES: u64 = 4;
+
+/// Identity of a proof: its final hash, which commits to digest and nonce
+pub type DedupKey = [u8; 32];
+
+/// Outcome of [`DedupTracker::check`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seen {
+    /// First sighting this epoch; now recorded
+    New,
+    /// Certainly seen before this epoch
+    Duplicate,
+    /// Only the Bloom filter matched; a replay or a false positive
+    Probable,
+    /// Belongs to an epoch the tracker has moved past
+    Stale,
+}
+
+/// Memory-bounded set of proofs accepted in the current epoch
+#[derive(Debug, Clone)]
+pub struct DedupTracker {
+    epoch: u64,
+    capacity: usize,
+    recent: HashSet<DedupKey>,
+    order: VecDeque<DedupKey>,
+    bloom: Vec<u64>,
+}
+
+impl DedupTracker {
+    /// Keep up to `capacity` keys exactly and the rest in a `bloom_bits`
+    /// filter (rounded up to a multiple of 64, at least 64)
+    pub fn new(capacity: usize, bloom_bits: usize) -> Self {
+        Self {
+            epoch: 0,
+            capacity,
+            recent: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            bloom: vec![0; bloom_bits.div_ceil(64).max(1)],
+        }
+    }
+
+    /// Epoch currently tracked
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Keys held exactly
+    pub fn len(&self) -> usize {
+        self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty() && self.bloom.iter().all(|&w| w == 0)
+    }
+
+    /// Forget everything and start tracking `epoch`
+    pub fn reset(&mut self, epoch: u64) {
+        self.epoch = epoch;
+        self.recent.clear();
+        self.order.clear();
+        self.bloom.fill(0);
+    }
+
+    /// Record `key` for `epoch`, reporting whether it was already seen
+    ///
+    /// A later epoch resets the tracker first; an earlier one is [`Seen::Stale`]
+    /// and leaves the tracker untouched.
+    pub fn check(&mut self, epoch: u64, key: DedupKey) -> Seen {
+        if epoch < self.epoch {
+            return Seen::Stale;
+        }
+        if epoch > self.epoch {
+            self.reset(epoch);
+        }
+
+        if self.recent.contains(&key) {
+            return Seen::Duplicate;
+        }
+        if self.bloom_contains(&key) {
+            return Seen::Probable;
+        }
+
+        if self.order.len() >= self.capacity {
+            match self.order.pop_front() {
+                Some(old) => {
+                    self.recent.remove(&old);
+                    self.bloom_insert(&old);
+                }
+                None => {
+                    // Zero capacity: everything goes straight to the filter
+                    self.bloom_insert(&key);
+                    return Seen::New;
+                }
+            }
+        }
+        self.recent.insert(key);
+        self.order.push_back(key);
+        Seen::New
+    }
+
+    /// [`check`](Self::check) keyed by the solution's final hash
+    pub fn check_solution(&mut self, epoch: u64, solution: &Solution) -> Seen {
+        self.check(epoch, solution.to_hash())
+    }
+
+    fn bit_indices(&self, key: &DedupKey) -> impl Iterator<Item = usize> {
+        let word = |i: usize| u64::from_le_bytes(key[i..i + 8].try_into().unwrap());
+        let (h1, h2) = (word(0), word(8) | 1);
+        let bits = self.bloom.len() as u64 * 64;
+        (0..BLOOM_HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits) as usize)
+    }
+
+    fn bloom_contains(&self, key: &DedupKey) -> bool {
+        self.bit_indices(key).all(|b| self.bloom[b / 64] & (1 << (b % 64)) != 0)
+    }
+
+    fn bloom_insert(&mut self, key: &DedupKey) {
+        let indices: Vec<_> = self.bit_indices(key).collect();
+        for b in indices {
+            self.bloom[b / 64] |= 1 << (b % 64);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+// One mining loop over mixed devices
+// CPU threads, GPUs and other accelerators differ by orders of magnitude in
+// throughput and in how much work they want per call, so a fixed split of the
+// nonce space leaves the fast ones idle. Instead every device pulls
+// contiguous chunks from a shared cursor, each sized to keep it busy for
+// about one `slice` at its measured rate. Rates are re-measured after every
+// chunk (an exponential moving average), so the split rebalances by itself
+// when a device throttles or the system gets busy. A device's first chunk is
+// sized from its `rate_hint`, or is a small probe without one.
+// Anything implementing `Device` joins the loop; `CpuDevice` is one CPU
+// thread, and GPU backends plug in the same way.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering::Relaxed};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use equix::{EquiXBuilder, RuntimeOption, SolverMemory};
+
+use crate::{
+    build_seed, search_nonces, Challenge, CrankXError, SelectionPolicy, Solution,
+    DEFAULT_RUNTIME,
+};
+
+/// Nonces in a device's first chunk when it gives no rate hint
+pub const PROBE_CHUNK: u64 = 16;
+
+/// Weight of the newest measurement in a device's rate
+const RATE_SMOOTHING: f64 = 0.5;
+
+/// The segment a [`DeviceScheduler`] is mining
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceWork<'a> {
+    pub challenge: Challenge,
+    pub data: &'a [u8],
+    pub min_difficulty: u32,
+}
+
+/// What one [`Device::search`] call did
+#[derive(Debug, Default)]
+pub struct DeviceResult {
+    /// Best solution in the chunk reaching the minimum difficulty
+    pub solution: Option<Solution>,
+    /// Nonces tried; fewer than the chunk holds only if a solution turned up
+    pub attempts: u64,
+}
+
+/// Anything that can crank a contiguous range of nonces
+pub trait Device: Send {
+    /// Label for reports, e.g. `"cpu0"` or a GPU's model name
+    fn name(&self) -> String;
+
+    /// Expected nonces per second, if known before measuring
+    fn rate_hint(&self) -> Option<f64> {
+        None
+    }
+
+    /// Try the nonces in `nonces` against `work`, stopping early at a
+    /// qualifying solution
+    fn search(
+        &mut self,
+        work: &DeviceWork,
+        nonces: Range<u64>,
+    ) -> Result<DeviceResult, CrankXError>;
+}
+
+/// One CPU thread's worth of cranking
+pub struct CpuDevice {
+    index: usize,
+    builder: EquiXBuilder,
+    memory: SolverMemory,
+}
+
+impl CpuDevice {
+    /// CPU device `index` (used in its name) with `runtime`
+    pub fn new(index: usize, runtime: RuntimeOption) -> Self {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(runtime);
+        Self { index, builder, memory: SolverMemory::new() }
+    }
+}
+
+impl Device for CpuDevice {
+    fn name(&self) -> String {
+        format!("cpu{}", self.index)
+    }
+
+    fn search(
+        &mut self,
+        work: &DeviceWork,
+        nonces: Range<u64>,
+    ) -> Result<DeviceResult, CrankXError> {
+        let mut seed = build_seed(work.challenge.as_bytes(), work.data, &[0; 8])?;
+        let mut result = DeviceResult::default();
+        result.attempts = search_nonces(
+            &self.builder,
+            &mut self.memory,
+            &mut seed,
+            nonces,
+            SelectionPolicy::HighestDifficulty,
+            || false,
+            |solution| {
+                let qualifies = solution.difficulty() >= work.min_difficulty;
+                if qualifies {
+                    result.solution = Some(solution);
+                }
+                qualifies
+            },
+        )?;
+        Ok(result)
+    }
+}
+
+/// Per-device totals from one [`DeviceScheduler::mine`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceReport {
+    pub name: String,
+    pub attempts: u64,
+    pub chunks: u64,
+    /// Smoothed nonces per second at the end of the run
+    pub rate: f64,
+}
+
+/// Outcome of [`DeviceScheduler::mine`]
+#[derive(Debug)]
+pub struct FleetReport {
+    /// Qualifying solution, `None` if stopped or the nonce space ran out
+    pub solution: Option<Solution>,
+    pub elapsed: Duration,
+    /// One entry per device, in the order they were added
+    pub devices: Vec<DeviceReport>,
+}
+
+impl FleetReport {
+    /// Nonces tried across all devices
+    pub fn attempts(&self) -> u64 {
+        self.devices.iter().map(|d| d.attempts).sum()
+    }
+}
+
+/// Splits one segment's nonce space across heterogeneous devices
+pub struct DeviceScheduler {
+    devices: Vec<Box<dyn Device>>,
+    slice: Duration,
+    stop: Arc<AtomicBool>,
+}
+
+impl DeviceScheduler {
+    /// No devices yet; each chunk aims to keep its device busy for `slice`
+    pub fn new(slice: Duration) -> Self {
+        Self { devices: Vec::new(), slice, stop: Arc::default() }
+    }
+
+    /// Add `count` [`CpuDevice`]s on [`DEFAULT_RUNTIME`]
+    pub fn with_cpus(mut self, count: usize) -> Self {
+        let first = self.devices.len();
+        for i in first..first + count {
+            self.devices.push(Box::new(CpuDevice::new(i, DEFAULT_RUNTIME)));
+        }
+        self
+    }
+
+    pub fn add(&mut self, device: impl Device + 'static) -> &mut Self {
+        self.devices.push(Box::new(device));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Flag that makes [`DeviceScheduler::mine`] return after the chunks in
+    /// flight; stays set until cleared
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Crank `data` on every device until one finds a solution of at least
+    /// `min_difficulty`
+    ///
+    /// When several devices qualify in the same round the higher difficulty
+    /// wins. The first device error ends the run and is returned.
+    pub fn mine(
+        &mut self,
+        challenge: impl Into<Challenge>,
+        data: &[u8],
+        min_difficulty: u32,
+    ) -> Result<FleetReport, CrankXError> {
+        let work = DeviceWork { challenge: challenge.into(), data, min_difficulty };
+        build_seed(work.challenge.as_bytes(), data, &[0; 8])?;
+
+        let cursor = AtomicU64::new(0);
+        let found = AtomicBool::new(false);
+        let best: Mutex<Option<Solution>> = Mutex::new(None);
+        let timer = Instant::now();
+
+        let outcomes: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = self
+                .devices
+                .iter_mut()
+                .map(|device| {
+                    let (work, cursor, found, best) = (&work, &cursor, &found, &best);
+                    let (slice, stop) = (self.slice, &*self.stop);
+                    s.spawn(move || {
+                        let mut report = DeviceReport {
+                            name: device.name(),
+                            attempts: 0,
+                            chunks: 0,
+                            rate: device.rate_hint().unwrap_or(0.0),
+                        };
+                        while !found.load(Relaxed) && !stop.load(Relaxed) {
+                            let size = chunk_size(report.rate, slice);
+                            let start = cursor.fetch_add(size, Relaxed);
+                            let Some(end) = start.checked_add(size) else {
+                                break;
+                            };
+
+                            let started = Instant::now();
+                            let result = match device.search(work, start..end) {
+                                Ok(result) => result,
+                                Err(e) => {
+                                    found.store(true, Relaxed);
+                                    return Err(e);
+                                }
+                            };
+                            let secs = started.elapsed().as_secs_f64();
+                            report.attempts += result.attempts;
+                            report.chunks += 1;
+                            if secs > 0.0 && result.attempts > 0 {
+                                let measured = result.attempts as f64 / secs;
+                                report.rate = if report.rate > 0.0 {
+                                    RATE_SMOOTHING * measured + (1.0 - RATE_SMOOTHING) * report.rate
+                                } else {
+                                    measured
+                                };
+                            }
+
+                            if let Some(solution) = result.solution {
+                                let mut best = best.lock().unwrap();
+                                if best.as_ref().is_none_or(|b| *b < solution) {
+                                    *best = Some(solution);
+                                }
+                                found.store(true, Relaxed);
+                            }
+                        }
+                        Ok(report)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        Ok(FleetReport {
+            solution: best.into_inner().unwrap(),
+            elapsed: timer.elapsed(),
+            devices: outcomes.into_iter().collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Nonces that keep a device running at `rate` busy for `slice`
+fn chunk_size(rate: f64, slice: Duration) -> u64 {
+    if rate <= 0.0 {
+        return PROBE_CHUNK;
+    }
+    (rate * slice.as_secs_f64()).clamp(1.0, u32::MAX as f64) as u64
+}
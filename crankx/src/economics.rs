@@ -0,0 +1,63 @@
+// Mining economics
+// Expected proofs follow directly from the target: each final hash meets it
+// with probability p, so a rig checking `hashrate` hashes a second finds
+// `hashrate * p * 86400` proofs a day on average. Revenue and power cost
+// are linear on top of that. All amounts are in whatever unit the caller
+// prices rewards and power in.
+
+use crate::Target;
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// What an operator knows about one rig
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rig {
+    /// Final hashes checked per second
+    pub hashrate: f64,
+    /// Target a proof must meet
+    pub target: Target,
+    /// Paid per accepted proof
+    pub reward_per_proof: f64,
+    /// Average draw while cranking
+    pub power_watts: f64,
+    /// Price of one kilowatt-hour
+    pub power_cost_per_kwh: f64,
+}
+
+/// Daily averages for a [`Rig`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub proofs_per_day: f64,
+    pub revenue_per_day: f64,
+    pub cost_per_day: f64,
+    /// Revenue minus power cost
+    pub margin_per_day: f64,
+}
+
+impl Rig {
+    /// Expected daily proofs, revenue, cost and margin
+    pub fn estimate(&self) -> Estimate {
+        let proofs_per_day = self.hashrate * self.target.probability() * SECS_PER_DAY;
+        let revenue_per_day = proofs_per_day * self.reward_per_proof;
+        let cost_per_day = self.power_watts / 1000.0 * 24.0 * self.power_cost_per_kwh;
+
+        Estimate {
+            proofs_per_day,
+            revenue_per_day,
+            cost_per_day,
+            margin_per_day: revenue_per_day - cost_per_day,
+        }
+    }
+
+    /// Reward per proof at which the rig exactly covers its power
+    pub fn break_even_reward(&self) -> f64 {
+        let Estimate { proofs_per_day, cost_per_day, .. } = self.estimate();
+        cost_per_day / proofs_per_day
+    }
+
+    /// Power price per kWh at which the margin reaches zero
+    pub fn break_even_power_cost(&self) -> f64 {
+        let kwh_per_day = self.power_watts / 1000.0 * 24.0;
+        self.estimate().revenue_per_day / kwh_per_day
+    }
+}
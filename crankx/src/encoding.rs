@@ -0,0 +1,7 @@
+// Byte encodings of proofs beyond the fixed-size Solution formats
+
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod compact;
+#[cfg(feature = "proto")]
+pub mod proto;
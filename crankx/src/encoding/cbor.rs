@@ -0,0 +1,59 @@
+// Canonical CBOR (feature = "cbor")
+// Anything with a serde representation (solutions, batches, jobs, shares,
+// envelopes) goes through CBOR in that same shape, byte fields included as
+// their hex strings. Encoding follows RFC 8949 core deterministic encoding:
+// shortest integer and length heads, definite lengths, and map entries
+// sorted by the bytes of their encoded keys. Decoding accepts only that
+// form, so a value has exactly one encoding and signatures or hashes over it
+// are stable.
+
+use ciborium::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::CrankXError;
+
+/// Canonical CBOR encoding of `value`'s serde representation
+pub fn to_cbor<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, CrankXError> {
+    let value = Value::serialized(value).map_err(|_| CrankXError::InvalidEncoding)?;
+    encode(canonical(value))
+}
+
+/// Decode canonical CBOR produced by [`to_cbor`]
+///
+/// [`CrankXError::InvalidEncoding`] for malformed or trailing bytes and for
+/// any valid CBOR not in canonical form, such as unsorted map keys.
+pub fn from_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, CrankXError> {
+    let value: Value = ciborium::from_reader(bytes).map_err(|_| CrankXError::InvalidEncoding)?;
+    let value = canonical(value);
+    if encode(value.clone())? != bytes {
+        return Err(CrankXError::InvalidEncoding);
+    }
+    value.deserialized().map_err(|_| CrankXError::InvalidEncoding)
+}
+
+fn encode(value: Value) -> Result<Vec<u8>, CrankXError> {
+    let mut out = Vec::new();
+    ciborium::into_writer(&value, &mut out).map_err(|_| CrankXError::InvalidEncoding)?;
+    Ok(out)
+}
+
+/// `value` with every map's entries sorted by encoded key
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical).collect()),
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(canonical(*inner))),
+        Value::Map(entries) => {
+            let mut keyed: Vec<_> = entries
+                .into_iter()
+                .map(|(k, v)| {
+                    let (k, v) = (canonical(k), canonical(v));
+                    (encode(k.clone()).unwrap_or_default(), k, v)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Map(keyed.into_iter().map(|(_, k, v)| (k, v)).collect())
+        }
+        other => other,
+    }
+}
@@ -0,0 +1,126 @@
+// Compact multi-proof codec for instruction data
+// Grammar (all multi-byte fixed fields little-endian):
+//
+//   proofs  := count:varint proof{count}
+//   proof   := segment:varint nonce:[u8; 8] digest:[u8; 16]
+//   varint  := unsigned LEB128 u64, minimal, at most 10 bytes
+//
+// The decoder is strict: a non-minimal or overflowing varint, a count the
+// remaining bytes can't hold, a truncated proof or trailing bytes are all
+// `InvalidEncoding`, so every proof list has exactly one encoding.
+
+use crate::{split_array, CrankXError, Solution};
+
+/// Bytes per proof after its segment index
+const FIXED_LEN: usize = 8 + 16;
+
+/// Longest LEB128 encoding of a u64
+const MAX_VARINT_LEN: usize = 10;
+
+/// One proof addressed by segment index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes), archive_attr(derive(Debug)))]
+pub struct CompactProof {
+    pub segment: u64,
+    pub nonce: [u8; 8],
+    pub digest: [u8; 16],
+}
+
+impl CompactProof {
+    /// Proof of `solution` for segment `segment`
+    pub fn new(segment: u64, solution: &Solution) -> Self {
+        Self { segment, nonce: solution.n, digest: solution.d }
+    }
+
+    /// The proof as a [`Solution`] (computes its final hash)
+    pub fn solution(&self) -> Solution {
+        Solution::new(self.digest, self.nonce)
+    }
+}
+
+/// Exact length of `encode(proofs)`
+pub fn encoded_len(proofs: &[CompactProof]) -> usize {
+    let body: usize = proofs.iter().map(|p| varint_len(p.segment) + FIXED_LEN).sum();
+    varint_len(proofs.len() as u64) + body
+}
+
+/// Encode `proofs`
+pub fn encode(proofs: &[CompactProof]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(encoded_len(proofs));
+    encode_into(proofs, &mut out);
+    out
+}
+
+/// Append the encoding of `proofs` to `out`
+pub fn encode_into(proofs: &[CompactProof], out: &mut Vec<u8>) {
+    write_varint(proofs.len() as u64, out);
+    for proof in proofs {
+        write_varint(proof.segment, out);
+        out.extend_from_slice(&proof.nonce);
+        out.extend_from_slice(&proof.digest);
+    }
+}
+
+/// Decode exactly one proof list spanning all of `bytes`
+pub fn decode(bytes: &[u8]) -> Result<Vec<CompactProof>, CrankXError> {
+    let mut rest = bytes;
+    let count = read_varint(&mut rest)?;
+
+    // Each proof takes at least 1 + FIXED_LEN bytes; bound the allocation
+    if count > (rest.len() / (1 + FIXED_LEN)) as u64 {
+        return Err(CrankXError::InvalidEncoding);
+    }
+
+    let mut proofs = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let segment = read_varint(&mut rest)?;
+        let (fixed, tail) =
+            rest.split_first_chunk::<FIXED_LEN>().ok_or(CrankXError::InvalidEncoding)?;
+        rest = tail;
+
+        let (nonce, digest) = split_array(fixed);
+        proofs.push(CompactProof { segment, nonce, digest });
+    }
+
+    if !rest.is_empty() {
+        return Err(CrankXError::InvalidEncoding);
+    }
+    Ok(proofs)
+}
+
+pub(crate) fn varint_len(value: u64) -> usize {
+    (64 - value.leading_zeros() as usize).div_ceil(7).max(1)
+}
+
+pub(crate) fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn read_varint(bytes: &mut &[u8]) -> Result<u64, CrankXError> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(MAX_VARINT_LEN) {
+        let bits = (byte & 0x7f) as u64;
+        let shift = 7 * i as u32;
+
+        // The tenth byte may only carry the top bit of a u64
+        if shift == 63 && bits > 1 {
+            return Err(CrankXError::InvalidEncoding);
+        }
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            // A zero final byte means a shorter encoding existed
+            if i > 0 && byte == 0 {
+                return Err(CrankXError::InvalidEncoding);
+            }
+            *bytes = bytes.get(i + 1..).unwrap_or_default();
+            return Ok(value);
+        }
+    }
+    Err(CrankXError::InvalidEncoding)
+}
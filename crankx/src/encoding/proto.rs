@@ -0,0 +1,187 @@
+// Protobuf codec for proof transport (feature = "proto")
+// The message types in `pb` are generated by prost from `proto/crankx.proto`
+// (shipped as `SCHEMA`) at build time, the schema consumers in other
+// languages generate theirs from too. The crate's types convert to and from
+// them, and `Proto` encodes and decodes through prost, so the wire format is
+// protobuf's own: fields in number order with zero scalars and empty bytes
+// left out; on decode, any field order, the last occurrence winning, unknown
+// fields skipped and varints of any length. The conversions add what the
+// schema can't say: fixed-size bytes fields must have their exact length,
+// or be empty for all zeros.
+
+use prost::Message;
+
+use crate::batch::SolutionBatch;
+use crate::encoding::compact::CompactProof;
+use crate::job::{Job, Share};
+use crate::{Challenge, CrankXError, Solution};
+
+/// Types generated from [`SCHEMA`]
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/crankx.v1.rs"));
+}
+
+/// The schema these encodings follow
+pub const SCHEMA: &str = include_str!("../../proto/crankx.proto");
+
+/// A crate type with a protobuf message in [`SCHEMA`]
+pub trait Proto: Sized {
+    /// The generated message it converts to and from
+    type Message: Message + Default + for<'a> From<&'a Self>;
+
+    fn encode_proto(&self, out: &mut Vec<u8>) {
+        Self::Message::from(self).encode(out).expect("a Vec grows to fit")
+    }
+
+    /// [`CrankXError::InvalidEncoding`] for malformed protobuf or a missing
+    /// required message, [`CrankXError::InvalidLength`] for a fixed-size
+    /// field of the wrong length
+    fn decode_proto(bytes: &[u8]) -> Result<Self, CrankXError>
+    where
+        Self: TryFrom<Self::Message, Error = CrankXError>,
+    {
+        Self::Message::decode(bytes).map_err(|_| CrankXError::InvalidEncoding)?.try_into()
+    }
+
+    fn to_proto(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_proto(&mut out);
+        out
+    }
+}
+
+impl Proto for Solution {
+    type Message = pb::Solution;
+}
+
+impl From<&Solution> for pb::Solution {
+    fn from(solution: &Solution) -> Self {
+        Self { digest: solution.d.to_vec(), nonce: solution.n.to_vec() }
+    }
+}
+
+impl TryFrom<pb::Solution> for Solution {
+    type Error = CrankXError;
+
+    fn try_from(message: pb::Solution) -> Result<Self, CrankXError> {
+        Ok(Solution::new(fixed(&message.digest)?, fixed(&message.nonce)?))
+    }
+}
+
+impl Proto for Job {
+    type Message = pb::Job;
+}
+
+impl From<&Job> for pb::Job {
+    fn from(job: &Job) -> Self {
+        Self {
+            id: job.id,
+            challenge: job.challenge.as_bytes().to_vec(),
+            segment: job.segment,
+            target: job.target,
+            nonce_start: job.nonce_start,
+            nonce_end: job.nonce_end,
+            expires_at: job.expires_at,
+        }
+    }
+}
+
+impl TryFrom<pb::Job> for Job {
+    type Error = CrankXError;
+
+    fn try_from(message: pb::Job) -> Result<Self, CrankXError> {
+        let job = Job {
+            id: message.id,
+            challenge: Challenge(fixed(&message.challenge)?),
+            segment: message.segment,
+            target: message.target,
+            nonce_start: message.nonce_start,
+            nonce_end: message.nonce_end,
+            expires_at: message.expires_at,
+        };
+        job.validate()?;
+        Ok(job)
+    }
+}
+
+impl Proto for Share {
+    type Message = pb::Share;
+}
+
+impl From<&Share> for pb::Share {
+    fn from(share: &Share) -> Self {
+        Self {
+            job_id: share.job_id,
+            worker_id: share.worker_id,
+            solution: Some((&share.solution).into()),
+        }
+    }
+}
+
+impl TryFrom<pb::Share> for Share {
+    type Error = CrankXError;
+
+    fn try_from(message: pb::Share) -> Result<Self, CrankXError> {
+        let solution = message.solution.ok_or(CrankXError::InvalidEncoding)?;
+        Ok(Share {
+            job_id: message.job_id,
+            worker_id: message.worker_id,
+            solution: solution.try_into()?,
+        })
+    }
+}
+
+impl Proto for CompactProof {
+    type Message = pb::Proof;
+}
+
+impl From<&CompactProof> for pb::Proof {
+    fn from(proof: &CompactProof) -> Self {
+        Self { segment: proof.segment, nonce: proof.nonce.to_vec(), digest: proof.digest.to_vec() }
+    }
+}
+
+impl TryFrom<pb::Proof> for CompactProof {
+    type Error = CrankXError;
+
+    fn try_from(message: pb::Proof) -> Result<Self, CrankXError> {
+        Ok(CompactProof {
+            segment: message.segment,
+            nonce: fixed(&message.nonce)?,
+            digest: fixed(&message.digest)?,
+        })
+    }
+}
+
+impl Proto for SolutionBatch {
+    type Message = pb::Batch;
+}
+
+impl From<&SolutionBatch> for pb::Batch {
+    fn from(batch: &SolutionBatch) -> Self {
+        Self {
+            challenge: batch.challenge.as_bytes().to_vec(),
+            proofs: batch.proofs.iter().map(pb::Proof::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::Batch> for SolutionBatch {
+    type Error = CrankXError;
+
+    fn try_from(message: pb::Batch) -> Result<Self, CrankXError> {
+        let mut batch = SolutionBatch::new(fixed(&message.challenge)?);
+        batch.proofs =
+            message.proofs.into_iter().map(CompactProof::try_from).collect::<Result<_, _>>()?;
+        Ok(batch)
+    }
+}
+
+/// A fixed-size bytes field; an unset one decodes as empty and stands for
+/// all zeros, like any proto3 default
+fn fixed<const N: usize>(bytes: &[u8]) -> Result<[u8; N], CrankXError> {
+    if bytes.is_empty() {
+        return Ok([0; N]);
+    }
+    bytes.try_into().map_err(|_| CrankXError::InvalidLength)
+}
@@ -0,0 +1,175 @@
+// Signed proof envelopes (feature = "envelope")
+// A relay passing proofs towards the chain needs to know who produced each
+// one. The envelope carries the proof with what it was for and who made it,
+// signed by the miner with Ed25519 (RFC 8032) over a fixed-layout encoding:
+//
+//   signed   := "crankx:envelope:v1" || body
+//   body     := challenge (32) || segment:u64 || miner (32) || timestamp:u64
+//               || digest (16) || nonce (8)
+//   envelope := body || signature (64)
+//
+// Integers are little-endian; `miner` is the Ed25519 public key, the same
+// 32 bytes as a Solana pubkey. Signing and verification are ed25519-dalek's,
+// with signatures checked by `verify_strict`: `S` must be reduced and neither
+// the key nor `R` may have small order, so an envelope has exactly one valid
+// encoding.
+
+use ed25519_dalek::{Signature, Signer, VerifyingKey};
+
+use crate::{build_seed, verify_seed, Challenge, CrankXError, Solution};
+
+/// Domain tag signed in front of the body
+pub const DOMAIN: &[u8] = b"crankx:envelope:v1";
+
+/// Bytes in the signed body
+pub const BODY_LEN: usize = 104;
+
+/// Bytes in an encoded envelope
+pub const ENVELOPE_LEN: usize = BODY_LEN + 64;
+
+/// Ed25519 key a miner signs envelopes with; the secret is zeroized on drop
+pub struct SigningKey(ed25519_dalek::SigningKey);
+
+impl SigningKey {
+    /// Key for a 32-byte secret seed
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self(ed25519_dalek::SigningKey::from_bytes(seed))
+    }
+
+    /// Key from a Solana keypair, `seed (32) || public key (32)`
+    ///
+    /// [`CrankXError::InvalidEncoding`] if the public half doesn't belong to
+    /// the seed.
+    pub fn from_keypair_bytes(bytes: &[u8; 64]) -> Result<Self, CrankXError> {
+        ed25519_dalek::SigningKey::from_keypair_bytes(bytes)
+            .map(Self)
+            .map_err(|_| CrankXError::InvalidEncoding)
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.0.verifying_key().to_bytes()
+    }
+
+    /// Ed25519 signature over `message`
+    pub fn sign(&self, message: &[&[u8]]) -> [u8; 64] {
+        self.0.sign(&message.concat()).to_bytes()
+    }
+}
+
+impl core::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("SigningKey").field("public", &self.public_key()).finish_non_exhaustive()
+    }
+}
+
+/// Check an Ed25519 `signature` by `public` over `message`
+pub fn verify_signature(
+    public: &[u8; 32],
+    message: &[&[u8]],
+    signature: &[u8; 64],
+) -> Result<(), CrankXError> {
+    let public = VerifyingKey::from_bytes(public).map_err(|_| CrankXError::InvalidSignature)?;
+    public
+        .verify_strict(&message.concat(), &Signature::from_bytes(signature))
+        .map_err(|_| CrankXError::InvalidSignature)
+}
+
+/// A proof, what it proves, who produced it, and their signature
+///
+/// With the `serde` feature byte fields serialize as hex strings.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofEnvelope {
+    /// Challenge the proof was solved against
+    pub challenge: Challenge,
+    /// Segment the proof covers
+    pub segment: u64,
+    /// Ed25519 public key of the miner
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::types::serialize_hex",
+            deserialize_with = "crate::types::deserialize_hex"
+        )
+    )]
+    pub miner: [u8; 32],
+    /// When the miner signed, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub solution: Solution,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::types::serialize_hex",
+            deserialize_with = "crate::types::deserialize_hex"
+        )
+    )]
+    pub signature: [u8; 64],
+}
+
+impl ProofEnvelope {
+    /// Envelope for `solution`, signed by `key`
+    pub fn sign(
+        key: &SigningKey,
+        challenge: impl Into<Challenge>,
+        segment: u64,
+        timestamp: u64,
+        solution: Solution,
+    ) -> Self {
+        let mut envelope = Self {
+            challenge: challenge.into(),
+            segment,
+            miner: key.public_key(),
+            timestamp,
+            solution,
+            signature: [0; 64],
+        };
+        envelope.signature = key.sign(&[DOMAIN, &envelope.body()]);
+        envelope
+    }
+
+    /// The signed fields in their canonical layout
+    pub fn body(&self) -> [u8; BODY_LEN] {
+        let mut body = [0; BODY_LEN];
+        body[..32].copy_from_slice(self.challenge.as_bytes());
+        body[32..40].copy_from_slice(&self.segment.to_le_bytes());
+        body[40..72].copy_from_slice(&self.miner);
+        body[72..80].copy_from_slice(&self.timestamp.to_le_bytes());
+        body[80..].copy_from_slice(&self.solution.to_bytes());
+        body
+    }
+
+    /// Check the miner's signature, not the proof itself
+    pub fn verify_signature(&self) -> Result<(), CrankXError> {
+        verify_signature(&self.miner, &[DOMAIN, &self.body()], &self.signature)
+    }
+
+    /// Check the signature, then the proof against `data`, the segment's
+    /// bytes
+    pub fn verify(&self, data: &[u8]) -> Result<(), CrankXError> {
+        self.verify_signature()?;
+        let seed = build_seed(self.challenge.as_bytes(), data, &self.solution.n)?;
+        verify_seed(&seed, &self.solution.d)
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENVELOPE_LEN] {
+        let mut bytes = [0; ENVELOPE_LEN];
+        bytes[..BODY_LEN].copy_from_slice(&self.body());
+        bytes[BODY_LEN..].copy_from_slice(&self.signature);
+        bytes
+    }
+
+    /// Inverse of [`ProofEnvelope::to_bytes`]; checks only the length
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let bytes: &[u8; ENVELOPE_LEN] =
+            bytes.try_into().map_err(|_| CrankXError::InvalidLength)?;
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        Ok(Self {
+            challenge: Challenge(bytes[..32].try_into().unwrap()),
+            segment: u64_at(32),
+            miner: bytes[40..72].try_into().unwrap(),
+            timestamp: u64_at(72),
+            solution: Solution::from_bytes(bytes[80..BODY_LEN].try_into().unwrap()),
+            signature: bytes[BODY_LEN..].try_into().unwrap(),
+        })
+    }
+}
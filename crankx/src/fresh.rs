@@ -0,0 +1,107 @@
+// Proof freshness
+// A proof that commits to nothing time-bound can be mined long before its
+// challenge window and stockpiled. Here the proof commits to a recency
+// anchor (a slot or a unix timestamp) through the aux field, so the seed is
+// `challenge || data || anchor || nonce`, and the verifier rejects anchors
+// more than `max_age` behind its own clock before doing any hashing.
+//
+//   anchor := kind:u8 (0 = slot, 1 = timestamp) value:u64 (LE)
+
+use equix::SolverMemory;
+
+use crate::{
+    solve_with_aux, split_array, verify_with_aux, Challenge, CrankXError, Nonce, Solution,
+};
+
+/// Point in time a proof commits to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    /// Solana slot
+    Slot(u64),
+    /// Unix time in seconds
+    Timestamp(u64),
+}
+
+impl Anchor {
+    /// Bytes in [`Anchor::to_bytes`]
+    pub const LEN: usize = 9;
+
+    /// Slot or timestamp value
+    pub fn value(&self) -> u64 {
+        match *self {
+            Self::Slot(v) | Self::Timestamp(v) => v,
+        }
+    }
+
+    /// `kind (1) || value (8, LE)`, as bound into the seed
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let kind = match self {
+            Self::Slot(_) => 0,
+            Self::Timestamp(_) => 1,
+        };
+        let mut bytes = [kind; Self::LEN];
+        bytes[1..].copy_from_slice(&self.value().to_le_bytes());
+        bytes
+    }
+
+    /// Inverse of [`Anchor::to_bytes`]
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Result<Self, CrankXError> {
+        let ([kind], value): ([u8; 1], [u8; 8]) = split_array(bytes);
+        let value = u64::from_le_bytes(value);
+        match kind {
+            0 => Ok(Self::Slot(value)),
+            1 => Ok(Self::Timestamp(value)),
+            _ => Err(CrankXError::InvalidEncoding),
+        }
+    }
+
+    /// How far this anchor is behind `now`
+    ///
+    /// [`CrankXError::InvalidAnchor`] if the kinds differ or this anchor is
+    /// ahead of `now`.
+    pub fn age(&self, now: &Anchor) -> Result<u64, CrankXError> {
+        match (self, now) {
+            (Self::Slot(a), Self::Slot(n)) | (Self::Timestamp(a), Self::Timestamp(n)) => {
+                n.checked_sub(*a).ok_or(CrankXError::InvalidAnchor)
+            }
+            _ => Err(CrankXError::InvalidAnchor),
+        }
+    }
+
+    /// Ok if this anchor is at most `max_age` behind `now`
+    pub fn check_fresh(&self, now: &Anchor, max_age: u64) -> Result<(), CrankXError> {
+        let age = self.age(now)?;
+        if age > max_age {
+            return Err(CrankXError::StaleProof { age, max_age });
+        }
+        Ok(())
+    }
+}
+
+/// [`solve_with_aux`] with `anchor` as the aux field
+pub fn solve_fresh<const N: usize>(
+    mem: &mut SolverMemory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    anchor: Anchor,
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    solve_with_aux(mem, challenge, data, &anchor.to_bytes(), nonce)
+}
+
+/// Verify a proof from [`solve_fresh`], rejecting it if `anchor` is more
+/// than `max_age` behind `now`
+///
+/// The age check runs first, so stale proofs cost no hashing.
+pub fn verify_fresh<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    anchor: Anchor,
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+    now: Anchor,
+    max_age: u64,
+) -> Result<(), CrankXError> {
+    anchor.check_fresh(&now, max_age)?;
+    verify_with_aux(challenge, data, &anchor.to_bytes(), nonce, digest)
+}
@@ -0,0 +1,75 @@
+// Structured fuzz inputs (feature = "arbitrary")
+// `Challenge` and `Nonce` derive `arbitrary::Arbitrary`; `Solution` caches
+// its final hash and implements it by hand, drawing the digest, the nonce and
+// one of the hash algorithms compiled in. Those digests are valid-shaped but
+// almost never in EquiX's canonical item order, so handlers stop at
+// `Solution::candidate`; `CanonicalSolution` sorts them into that order so a
+// fuzzer also reaches everything past the structure check.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{HashAlgorithm, Solution};
+
+/// Hash algorithms an arbitrary solution picks from
+const ALGORITHMS: &[HashAlgorithm] = &[
+    HashAlgorithm::Keccak256,
+    #[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+    HashAlgorithm::Sha3_256,
+    #[cfg(feature = "blake3")]
+    HashAlgorithm::Blake3,
+];
+
+impl<'a> Arbitrary<'a> for Solution {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (digest, nonce) = Arbitrary::arbitrary(u)?;
+        Ok(Self::with_hash_algorithm(digest, nonce, *u.choose(ALGORITHMS)?))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (24, Some(25))
+    }
+}
+
+/// A [`Solution`] whose digest items are in EquiX's canonical tree order,
+/// so [`Solution::candidate`] accepts it
+#[derive(Debug)]
+pub struct CanonicalSolution(pub Solution);
+
+impl<'a> Arbitrary<'a> for CanonicalSolution {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut solution = Solution::arbitrary(u)?;
+        let mut items = [0u16; 8];
+        for (item, pair) in items.iter_mut().zip(solution.d.chunks_exact(2)) {
+            *item = u16::from_le_bytes([pair[0], pair[1]]);
+        }
+        sort_into_tree_order(&mut items);
+        for (pair, item) in solution.d.chunks_exact_mut(2).zip(items) {
+            pair.copy_from_slice(&item.to_le_bytes());
+        }
+        Ok(Self(solution))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Solution::size_hint(depth)
+    }
+}
+
+impl From<CanonicalSolution> for Solution {
+    fn from(canonical: CanonicalSolution) -> Self {
+        canonical.0
+    }
+}
+
+/// Order every pair of sibling branches the way EquiX requires: the left
+/// branch no greater than the right, comparing from the last item back
+fn sort_into_tree_order(items: &mut [u16]) {
+    let len = items.len();
+    let (left, right) = items.split_at_mut(len / 2);
+    if len > 2 {
+        sort_into_tree_order(left);
+        sort_into_tree_order(right);
+    }
+    if left.iter().rev().cmp(right.iter().rev()).is_gt() {
+        left.swap_with_slice(right);
+    }
+}
@@ -0,0 +1,240 @@
+// gRPC front end for prover orchestration (feature = "grpc")
+// Serves `proto/prover.proto` over tonic. The prover holds a `SegmentProvider`
+// and one `Miner`; `SubmitJob` queues segments under a challenge, and a
+// cranking thread takes jobs in order, runs each through a `Scheduler` and
+// mines its segments one by one. Every qualifying solution goes out to all
+// `StreamShares` subscribers; a subscriber that falls more than
+// `SHARE_BUFFER` shares behind skips the ones it missed. `GetStats` reports
+// the same counters as `crate::metrics` plus the queue depth.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering::Relaxed};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::metrics::Metrics;
+use crate::miner::Miner;
+use crate::scheduler::{Scheduler, SegmentInfo, StalestFirst};
+use crate::{Challenge, CrankXError, SegmentProvider};
+
+/// Types and stubs generated from `proto/prover.proto`
+pub mod pb {
+    tonic::include_proto!("crankx.grpc.v1");
+}
+
+pub use pb::prover_client::ProverClient;
+pub use pb::prover_server::ProverServer;
+
+/// Shares buffered per `StreamShares` subscriber
+const SHARE_BUFFER: usize = 1024;
+
+/// How often an idle cranking thread checks the stop flag
+const IDLE_POLL: Duration = Duration::from_millis(100);
+
+/// Cranks submitted jobs over `P`'s segments, see the module notes
+pub struct ProverService<P> {
+    inner: Arc<Inner<P>>,
+}
+
+struct Inner<P> {
+    provider: P,
+    miner: Miner,
+    jobs: Mutex<VecDeque<QueuedJob>>,
+    queued: Condvar,
+    shares: broadcast::Sender<pb::Share>,
+    metrics: Metrics,
+    /// Jobs queued or in progress
+    jobs_pending: AtomicU64,
+    segments_pending: AtomicU64,
+    errors: AtomicU64,
+}
+
+struct QueuedJob {
+    id: u64,
+    challenge: Challenge,
+    segments: Vec<u64>,
+    min_difficulty: u32,
+}
+
+impl<P> ProverService<P>
+where
+    P: SegmentProvider + Send + Sync + 'static,
+    P::Error: From<CrankXError>,
+{
+    /// Serve `provider`'s segments, cranking them with `miner` on a thread
+    /// of its own
+    ///
+    /// Setting the miner's stop flag ends the thread once the segment in
+    /// hand is abandoned.
+    pub fn new(provider: P, miner: Miner) -> Self {
+        let inner = Arc::new(Inner {
+            provider,
+            miner,
+            jobs: Mutex::default(),
+            queued: Condvar::new(),
+            shares: broadcast::channel(SHARE_BUFFER).0,
+            metrics: Metrics::default(),
+            jobs_pending: AtomicU64::new(0),
+            segments_pending: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+        });
+        let cranking = inner.clone();
+        thread::spawn(move || cranking.crank());
+        Self { inner }
+    }
+
+    /// Flag that stops the cranking thread, the miner's
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.inner.miner.stop_flag()
+    }
+
+    /// Counters reported by `GetStats`
+    pub fn metrics(&self) -> &Metrics {
+        &self.inner.metrics
+    }
+
+    /// The service ready to add to a `tonic::transport::Server`
+    pub fn into_server(self) -> ProverServer<Self> {
+        ProverServer::new(self)
+    }
+}
+
+impl<P> Inner<P>
+where
+    P: SegmentProvider,
+    P::Error: From<CrankXError>,
+{
+    /// Run jobs in submission order until the stop flag is set
+    fn crank(&self) {
+        let stop = self.miner.stop_flag();
+        while !stop.load(Relaxed) {
+            let job = {
+                let jobs = self.jobs.lock().unwrap();
+                let (mut jobs, _) =
+                    self.queued.wait_timeout_while(jobs, IDLE_POLL, |j| j.is_empty()).unwrap();
+                jobs.pop_front()
+            };
+            if let Some(job) = job {
+                self.run(&job, &stop);
+                self.jobs_pending.fetch_sub(1, Relaxed);
+            }
+        }
+    }
+
+    fn run(&self, job: &QueuedJob, stop: &AtomicBool) {
+        let segments = job
+            .segments
+            .iter()
+            .map(|&index| SegmentInfo { index, last_proved: None, reward: 1.0, recalled: false })
+            .collect();
+        let mut scheduler = Scheduler::with_segments(segments, StalestFirst);
+
+        let mut left = job.segments.len() as u64;
+        for now in 0..job.segments.len() as u64 {
+            let Some(index) = scheduler.next(now).filter(|_| !stop.load(Relaxed)) else {
+                break;
+            };
+            scheduler.proved(index, now);
+
+            let mined =
+                self.miner.mine_segment(job.challenge, &self.provider, index, job.min_difficulty);
+            left -= 1;
+            self.segments_pending.fetch_sub(1, Relaxed);
+            let Ok(report) = mined else {
+                self.errors.fetch_add(1, Relaxed);
+                continue;
+            };
+            self.metrics.add_attempts(report.attempts);
+            // No solution means the stop flag cut the search short
+            let Some(solution) = report.solution else {
+                break;
+            };
+            self.metrics.record_solution(solution.difficulty());
+            // Nobody listening is fine: the counters still move
+            let _ = self.shares.send(pb::Share {
+                job_id: job.id,
+                segment: index,
+                challenge: report.challenge.as_bytes().to_vec(),
+                digest: solution.d.to_vec(),
+                nonce: solution.n.to_vec(),
+                difficulty: solution.difficulty(),
+            });
+        }
+        self.segments_pending.fetch_sub(left, Relaxed);
+    }
+}
+
+type ShareStream = Pin<Box<dyn Stream<Item = Result<pb::Share, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl<P> pb::prover_server::Prover for ProverService<P>
+where
+    P: SegmentProvider + Send + Sync + 'static,
+    P::Error: From<CrankXError>,
+{
+    async fn submit_job(
+        &self,
+        request: Request<pb::SubmitJobRequest>,
+    ) -> Result<Response<pb::SubmitJobResponse>, Status> {
+        let req = request.into_inner();
+        let challenge: [u8; 32] = req
+            .challenge
+            .try_into()
+            .map_err(|_| Status::invalid_argument("challenge must be 32 bytes"))?;
+
+        let count = self.inner.provider.segment_count();
+        let segments = if req.segments.is_empty() { (0..count).collect() } else { req.segments };
+        if let Some(index) = segments.iter().find(|&&i| i >= count) {
+            let e = CrankXError::SegmentOutOfRange { index: *index, count };
+            return Err(Status::invalid_argument(e.to_string()));
+        }
+
+        let queued = segments.len() as u64;
+        let job = QueuedJob {
+            id: req.job_id,
+            challenge: Challenge(challenge),
+            segments,
+            min_difficulty: req.min_difficulty,
+        };
+        let inner = &self.inner;
+        inner.jobs_pending.fetch_add(1, Relaxed);
+        inner.segments_pending.fetch_add(queued, Relaxed);
+        inner.jobs.lock().unwrap().push_back(job);
+        inner.queued.notify_one();
+        Ok(Response::new(pb::SubmitJobResponse { queued }))
+    }
+
+    type StreamSharesStream = ShareStream;
+
+    async fn stream_shares(
+        &self,
+        _request: Request<pb::StreamSharesRequest>,
+    ) -> Result<Response<ShareStream>, Status> {
+        // A lagging subscriber skips what it missed rather than ending
+        let shares = BroadcastStream::new(self.inner.shares.subscribe())
+            .filter_map(|share| share.ok().map(Ok));
+        Ok(Response::new(Box::pin(shares)))
+    }
+
+    async fn get_stats(
+        &self,
+        _request: Request<pb::GetStatsRequest>,
+    ) -> Result<Response<pb::Stats>, Status> {
+        let inner = &self.inner;
+        Ok(Response::new(pb::Stats {
+            attempts: inner.metrics.attempts(),
+            solutions: inner.metrics.solutions(),
+            best_difficulty: inner.metrics.best_difficulty(),
+            jobs_pending: inner.jobs_pending.load(Relaxed),
+            segments_pending: inner.segments_pending.load(Relaxed),
+            errors: inner.errors.load(Relaxed),
+        }))
+    }
+}
@@ -0,0 +1,140 @@
+// Puzzle hardness beyond difficulty
+// Difficulty only filters finished proofs; the cost of one attempt is fixed
+// by EquiX. `Hardness` multiplies that cost by chaining `rounds` EquiX
+// puzzles per nonce, each seeded with the previous round's digest:
+//
+//   seed_0     := challenge || data || nonce
+//   seed_i     := challenge || data || nonce || digest_{i-1}     (i >= 1)
+//   final hash := hash(canonical digest_{rounds-1} || nonce)
+//
+// An attempt succeeds only if every round has a solution, and the rounds
+// can't run in parallel. One round is exactly the plain crankx proof.
+
+use equix::SolverMemory;
+
+use crate::{
+    build_seed, check_segment_size, difficulty_of, keccak, solve_seed_with_memory, to_canonical,
+    verify_seed, Challenge, CrankXError, Nonce,
+};
+
+/// Most rounds a [`Hardness`] can ask for
+pub const MAX_ROUNDS: u8 = 16;
+
+/// EquiX puzzles chained per nonce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hardness {
+    rounds: u8,
+}
+
+impl Default for Hardness {
+    fn default() -> Self {
+        Self::BASE
+    }
+}
+
+impl Hardness {
+    /// One round: plain crankx
+    pub const BASE: Self = Self { rounds: 1 };
+
+    /// `rounds` chained puzzles, clamped to `1..=MAX_ROUNDS`
+    pub const fn new(rounds: u8) -> Self {
+        let rounds = if rounds > MAX_ROUNDS { MAX_ROUNDS } else { rounds };
+        Self { rounds: if rounds == 0 { 1 } else { rounds } }
+    }
+
+    pub const fn rounds(&self) -> u8 {
+        self.rounds
+    }
+}
+
+/// A nonce and the digest of every round at that nonce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardSolution {
+    /// Nonce (8 bytes)
+    pub n: [u8; 8],
+    /// Raw EquiX digest of each round, in order
+    pub d: Vec<[u8; 16]>,
+}
+
+impl HardSolution {
+    /// Final hash over the last round's digest and the nonce; for one round
+    /// the same as [`crate::Solution::to_hash`]
+    pub fn to_hash(&self) -> [u8; 32] {
+        let mut last = self.d.last().copied().unwrap_or_default();
+        to_canonical(&mut last);
+        keccak(&[&last, &self.n])
+    }
+
+    /// Compute the difficulty of the solution
+    pub fn difficulty(&self) -> u32 {
+        difficulty_of(&self.to_hash())
+    }
+
+    /// `nonce (8) || digest (16) * rounds`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + 16 * self.d.len());
+        bytes.extend_from_slice(&self.n);
+        self.d.iter().for_each(|d| bytes.extend_from_slice(d));
+        bytes
+    }
+
+    /// Inverse of [`HardSolution::to_bytes`] for `hardness`
+    pub fn from_bytes(bytes: &[u8], hardness: Hardness) -> Result<Self, CrankXError> {
+        if bytes.len() != 8 + 16 * hardness.rounds as usize {
+            return Err(CrankXError::InvalidLength);
+        }
+        let (n, digests) = bytes.split_first_chunk::<8>().ok_or(CrankXError::InvalidLength)?;
+        let (d, _) = digests.as_chunks::<16>();
+        Ok(Self { n: *n, d: d.to_vec() })
+    }
+}
+
+/// Solve every round for `nonce`, taking EquiX's first solution each time
+///
+/// [`CrankXError::NoSolution`] (or `EquiXFailure`) if any round's seed has
+/// none; move on to the next nonce.
+pub fn solve_hard<const N: usize>(
+    mem: &mut SolverMemory,
+    hardness: Hardness,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<HardSolution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let mut seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+    let base = seed.len();
+
+    let mut digests = Vec::with_capacity(hardness.rounds as usize);
+    for _ in 0..hardness.rounds {
+        let digest = solve_seed_with_memory(mem, &seed, nonce.as_bytes())?.d;
+        seed.truncate(base);
+        seed.extend_from_slice(&digest);
+        digests.push(digest);
+    }
+
+    Ok(HardSolution { n: nonce.to_bytes(), d: digests })
+}
+
+/// Verify every round of `solution` under `hardness`
+pub fn verify_hard<const N: usize>(
+    hardness: Hardness,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    solution: &HardSolution,
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    let expected = hardness.rounds as usize;
+    if solution.d.len() != expected {
+        return Err(CrankXError::ProofCount { expected, got: solution.d.len() });
+    }
+
+    let mut seed = build_seed(challenge.into().as_bytes(), data, &solution.n)?;
+    let base = seed.len();
+    for digest in &solution.d {
+        verify_seed(&seed, digest)?;
+        seed.truncate(base);
+        seed.extend_from_slice(digest);
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// Final hash over the canonical digest and nonce
+// Keccak256 is the default and what Solana programs expect; the others are for
+// deployments that prefer a different primitive. The algorithm's id travels in
+// the versioned wire format so a verifier never has to guess.
+
+use crate::{keccak, to_canonical, CrankXError};
+
+/// Hash function for the final `hash(canonical digest || nonce)` step
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HashAlgorithm {
+    /// Keccak256, as used by Solana's `keccak::hashv` (default)
+    #[default]
+    Keccak256 = 0,
+    /// FIPS-202 SHA3-256
+    #[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+    Sha3_256 = 1,
+    /// BLAKE3 with 32-byte output
+    #[cfg(feature = "blake3")]
+    Blake3 = 2,
+}
+
+impl HashAlgorithm {
+    /// Wire identifier for this algorithm
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Look up an algorithm by wire identifier
+    pub fn from_id(id: u8) -> Result<Self, CrankXError> {
+        match id {
+            0 => Ok(Self::Keccak256),
+            #[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+            1 => Ok(Self::Sha3_256),
+            #[cfg(feature = "blake3")]
+            2 => Ok(Self::Blake3),
+            _ => Err(CrankXError::UnsupportedHash),
+        }
+    }
+
+    /// Compute the final 32‑byte hash of the canonical digest and nonce
+    ///
+    /// `nonce` is 8 bytes for [`crate::Solution`] and 16 for
+    /// [`crate::wide::WideSolution`].
+    pub fn hash(self, digest: &[u8; 16], nonce: &[u8]) -> [u8; 32] {
+        let mut d = *digest;
+        to_canonical(&mut d);
+
+        match self {
+            Self::Keccak256 => keccak(&[&d, nonce]),
+            #[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+            Self::Sha3_256 => crate::keccak::sha3_256(&[&d, nonce]),
+            #[cfg(feature = "blake3")]
+            Self::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(&d);
+                hasher.update(nonce);
+                hasher.finalize().into()
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// Miner-identity-bound proofs
+// A proof seen in the mempool must not be resubmittable under another
+// signer. The miner's 32-byte public key is folded into the challenge, so
+// every verifier has to apply exactly this binding for the proof to check:
+//
+//   bound challenge := keccak256("crankx:miner:v1" || challenge || pubkey)
+//
+// The bound challenge is an ordinary 32-byte challenge, so it also works
+// with the batch, buffer and Solana verifiers unchanged.
+
+use equix::SolverMemory;
+
+use crate::{keccak, solve_with_memory, verify, Challenge, CrankXError, Nonce, Solution};
+
+/// Domain tag hashed in front of the challenge
+pub const DOMAIN: &[u8] = b"crankx:miner:v1";
+
+/// Challenge for proofs by the holder of `miner`
+pub fn bind_challenge(challenge: impl Into<Challenge>, miner: &[u8; 32]) -> Challenge {
+    Challenge(keccak(&[DOMAIN, challenge.into().as_bytes(), miner]))
+}
+
+/// [`solve_with_memory`] under the challenge bound to `miner`
+pub fn solve_for<const N: usize>(
+    mem: &mut SolverMemory,
+    miner: &[u8; 32],
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    solve_with_memory(mem, bind_challenge(challenge, miner), data, nonce)
+}
+
+/// [`verify`] under the challenge bound to `miner`, the proof's submitter
+pub fn verify_for<const N: usize>(
+    miner: &[u8; 32],
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    verify(bind_challenge(challenge, miner), data, nonce, digest)
+}
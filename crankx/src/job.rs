@@ -0,0 +1,103 @@
+// Work units and submissions
+// The shared vocabulary for anything that hands out work and takes proofs
+// back: the TCP pool, the HTTP service and external RPC layers. With the
+// `serde` feature, challenges and solutions serialize as hex strings.
+
+use core::ops::RangeInclusive;
+
+use crate::{Challenge, CrankXError, Solution};
+
+/// Unit of work: crank `segment` against `challenge` over `nonce_start..=nonce_end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Job {
+    pub id: u64,
+    pub challenge: Challenge,
+    /// Index of the segment to prove, resolved by the worker's provider
+    pub segment: u64,
+    /// Minimum difficulty for a solution to count as a share
+    pub target: u32,
+    pub nonce_start: u64,
+    /// Last nonce of the range, inclusive, so a range can reach `u64::MAX`
+    pub nonce_end: u64,
+    /// Unix time (seconds) after which shares are no longer accepted
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub expires_at: Option<u64>,
+}
+
+impl Job {
+    /// Check the job could have been handed out: [`CrankXError::InvalidNonceRange`]
+    /// if `nonce_start` is past `nonce_end`
+    pub fn validate(&self) -> Result<(), CrankXError> {
+        if self.nonce_start > self.nonce_end {
+            return Err(CrankXError::InvalidNonceRange {
+                start: self.nonce_start,
+                end: self.nonce_end,
+            });
+        }
+        Ok(())
+    }
+
+    /// The job's nonce range
+    pub fn nonces(&self) -> RangeInclusive<u64> {
+        self.nonce_start..=self.nonce_end
+    }
+
+    /// Whether `nonce` lies in the job's range
+    pub fn contains(&self, nonce: u64) -> bool {
+        self.nonces().contains(&nonce)
+    }
+
+    /// Whether the job has expired at unix time `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|t| now > t)
+    }
+}
+
+/// A solution submitted against a job
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Share {
+    pub job_id: u64,
+    pub worker_id: u64,
+    pub solution: Solution,
+}
+
+/// Why a share was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Submitted against a different job
+    WrongJob,
+    /// Submitted after the job expired
+    Expired,
+    /// Nonce outside the job's range
+    NonceOutOfRange,
+    /// Final hash short of the required target
+    BelowTarget,
+    /// EquiX proof doesn't verify against the segment
+    BadProof,
+    /// Already accepted for this job
+    Duplicate,
+}
+
+impl Share {
+    /// Cheap checks against `job` at unix time `now`: job id, expiry, nonce
+    /// range and the job's difficulty target
+    ///
+    /// Doesn't verify the EquiX proof, which needs the segment bytes.
+    pub fn check(&self, job: &Job, now: u64) -> Result<(), Rejection> {
+        if self.job_id != job.id {
+            return Err(Rejection::WrongJob);
+        }
+        if job.is_expired(now) {
+            return Err(Rejection::Expired);
+        }
+        if !job.contains(u64::from_le_bytes(self.solution.n)) {
+            return Err(Rejection::NonceOutOfRange);
+        }
+        if self.solution.difficulty() < job.target {
+            return Err(Rejection::BelowTarget);
+        }
+        Ok(())
+    }
+}
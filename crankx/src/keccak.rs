@@ -0,0 +1,86 @@
+// Keccak backends for the final hash
+// One is used, picked by feature; when several are enabled (features unify
+// across a dependency graph) the first in this list wins:
+//   solana-hash   Solana's hasher (the sol_keccak256 syscall on-chain),
+//                 implied by `solana`
+//   keccak-f1600  tiny-keccak, for targets that can't build the RustCrypto
+//                 `digest` stack
+//   sha3          the RustCrypto sha3 crate (default)
+// All of them must agree bit for bit; the known-answer tests and `self_test`
+// check whichever one is in the build.
+// SHA3-256 needs `keccak-f1600` or `sha3`; a `solana-hash` build has no
+// `HashAlgorithm::Sha3_256`.
+
+#[cfg(not(any(feature = "sha3", feature = "keccak-f1600", feature = "solana-hash")))]
+compile_error!(
+    "crankx needs a Keccak backend: enable `sha3`, `keccak-f1600` or `solana-hash`"
+);
+
+/// Name of the backend behind [`keccak256`]
+pub const BACKEND: &str = if cfg!(feature = "solana-hash") {
+    "solana"
+} else if cfg!(feature = "keccak-f1600") {
+    "keccak-f1600"
+} else {
+    "sha3"
+};
+
+/// Keccak256 over the concatenation of `parts`
+#[cfg(feature = "solana-hash")]
+#[inline(always)]
+pub fn keccak256(parts: &[&[u8]]) -> [u8; 32] {
+    solana_keccak_hasher::hashv(parts).to_bytes()
+}
+
+/// Keccak256 over the concatenation of `parts`
+#[cfg(all(feature = "keccak-f1600", not(feature = "solana-hash")))]
+#[inline(always)]
+pub fn keccak256(parts: &[&[u8]]) -> [u8; 32] {
+    tiny(tiny_keccak::Keccak::v256(), parts)
+}
+
+/// Keccak256 over the concatenation of `parts`
+#[cfg(all(feature = "sha3", not(any(feature = "keccak-f1600", feature = "solana-hash"))))]
+#[inline(always)]
+pub fn keccak256(parts: &[&[u8]]) -> [u8; 32] {
+    use sha3::Digest;
+    let mut hasher = sha3::Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// FIPS-202 SHA3-256 over the concatenation of `parts`, from the same crate
+/// as [`keccak256`]
+///
+/// Solana has no SHA3 syscall, so there is none with `solana-hash`.
+#[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+#[inline(always)]
+pub fn sha3_256(parts: &[&[u8]]) -> [u8; 32] {
+    #[cfg(feature = "keccak-f1600")]
+    {
+        tiny(tiny_keccak::Sha3::v256(), parts)
+    }
+    #[cfg(not(feature = "keccak-f1600"))]
+    {
+        use sha3::Digest;
+        let mut hasher = sha3::Sha3_256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Run `parts` through a tiny-keccak hasher
+#[cfg(feature = "keccak-f1600")]
+#[inline(always)]
+fn tiny(mut hasher: impl tiny_keccak::Hasher, parts: &[&[u8]]) -> [u8; 32] {
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    out
+}
@@ -5,69 +5,385 @@
 
 pub use equix;
 
-#[cfg(not(feature = "solana"))]
-use sha3::Digest;
+#[cfg(feature = "accel")]
+pub mod accel;
+#[cfg(feature = "rkyv")]
+pub mod archive;
+pub mod backend;
+pub mod batch;
+pub mod bench;
+pub mod best;
+pub mod checkpoint;
+pub mod compat;
+pub mod config;
+pub mod cost;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod dedup;
+pub mod devices;
+pub mod economics;
+pub mod encoding;
+#[cfg(feature = "envelope")]
+pub mod envelope;
+pub mod fresh;
+#[cfg(feature = "arbitrary")]
+pub mod fuzzing;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod hardness;
+pub mod hash;
+pub mod identity;
+pub mod job;
+pub mod keccak;
+pub mod limits;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod miner;
+pub mod multi;
+pub mod nonces;
+pub mod pipeline;
+pub mod policy;
+#[cfg(feature = "pool")]
+pub mod pool;
+pub mod report;
+pub mod retarget;
+#[cfg(any(feature = "sim", feature = "testing"))]
+mod rng;
+pub mod sampled;
+pub mod scheduler;
+pub mod score;
+pub mod seed;
+pub mod selection;
+pub mod segment;
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(feature = "service")]
+pub mod service;
+#[cfg(feature = "solana")]
+pub mod solana;
+pub mod stats;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod tape;
+pub mod target;
+pub mod test_vectors;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tune;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wide;
+pub mod workers;
+
+pub use hash::HashAlgorithm;
+pub use multi::{solve_k, verify_k};
+pub use policy::SelectionPolicy;
+pub use segment::SegmentProvider;
+pub use target::Target;
+pub use test_vectors::{self_test, self_test_with_runtime};
+pub use types::{Challenge, Nonce, WideNonce};
+
+use std::sync::OnceLock;
+
+/// Current version of the self-describing solution encoding
+pub const WIRE_VERSION: u8 = 1;
+
+/// Version of the self-describing encoding for 16-byte nonces, see
+/// [`wide::WideSolution`]
+pub const WIDE_WIRE_VERSION: u8 = 2;
+
+/// HashX runtime used wherever the caller doesn't pick one
+///
+/// `TryCompile` where the JIT exists (the `compiler` feature on x86_64 or
+/// aarch64), `InterpretOnly` everywhere else, so targets without it never
+/// attempt a compile.
+pub const DEFAULT_RUNTIME: equix::RuntimeOption =
+    if cfg!(all(feature = "compiler", any(target_arch = "x86_64", target_arch = "aarch64"))) {
+        equix::RuntimeOption::TryCompile
+    } else {
+        equix::RuntimeOption::InterpretOnly
+    };
+
+/// Largest segment (in bytes) accepted by solve and verify
+///
+/// HashX absorbs the seed through Blake2b, so EquiX itself takes any length;
+/// the cap bounds the seed-building work a verifier can be forced to do.
+pub const MAX_DATA_LEN: usize = 4096;
+
+/// Largest seed: `challenge (32) || data (MAX_DATA_LEN) || nonce (8)`
+pub const MAX_SEED_LEN: usize = 32 + MAX_DATA_LEN + 8;
+
+/// Largest segment `N` the const-generic solve and verify functions accept
+pub const MAX_SEGMENT_SIZE: usize = MAX_DATA_LEN;
+
+/// Smallest segment accepted by solve and verify
+///
+/// An empty segment leaves the seed as `challenge || nonce`, which is the
+/// drillx seed and proves possession of nothing; use [`compat::drillx`] for
+/// that on purpose. Any non-empty segment is supported, though a segment of a
+/// few bytes can be guessed rather than stored, so the proof-of-access
+/// guarantee only holds for segments too large to brute-force.
+pub const MIN_SEGMENT_SIZE: usize = 1;
+
+/// Fails the build when instantiated with `N` outside
+/// `MIN_SEGMENT_SIZE..=MAX_SEGMENT_SIZE`
+///
+/// Every const-generic entry point calls this, so a bad `solve::<N>` is
+/// rejected at compile time rather than erroring inside a mining loop.
+/// Slice-based paths (batches, the service) still check at runtime.
+///
+/// ```compile_fail
+/// crankx::check_segment_size::<{ crankx::MAX_SEGMENT_SIZE + 1 }>();
+/// ```
+///
+/// ```compile_fail
+/// crankx::check_segment_size::<0>();
+/// ```
+#[inline(always)]
+pub const fn check_segment_size<const N: usize>() {
+    const { assert!(N <= MAX_SEGMENT_SIZE, "segment larger than MAX_SEGMENT_SIZE") }
+    const { assert!(N >= MIN_SEGMENT_SIZE, "segment smaller than MIN_SEGMENT_SIZE") }
+}
+
+/// Longest challenge [`solve_with_challenge`] and [`verify_with_challenge`]
+/// accept; every other function takes exactly 32 bytes
+pub const MAX_CHALLENGE_LEN: usize = 128;
+
+/// Fails the build when instantiated with `C` outside
+/// `1..=MAX_CHALLENGE_LEN`
+///
+/// ```compile_fail
+/// crankx::check_challenge_size::<{ crankx::MAX_CHALLENGE_LEN + 1 }>();
+/// ```
+#[inline(always)]
+pub const fn check_challenge_size<const C: usize>() {
+    const { assert!(C >= 1 && C <= MAX_CHALLENGE_LEN, "challenge length out of range") }
+}
+
+/// Longest auxiliary field [`solve_with_aux`] and [`verify_with_aux`] accept
+pub const MAX_AUX_LEN: usize = 128;
+
+/// Fails the build when instantiated with `M` above [`MAX_AUX_LEN`]
+///
+/// ```compile_fail
+/// crankx::check_aux_size::<{ crankx::MAX_AUX_LEN + 1 }>();
+/// ```
+#[inline(always)]
+pub const fn check_aux_size<const M: usize>() {
+    const { assert!(M <= MAX_AUX_LEN, "aux field larger than MAX_AUX_LEN") }
+}
 
 /// Errors for PoW operations
+///
+/// Verification never panics on untrusted input: the `verify*` functions, the
+/// Solana helpers, proof decoding and seed assembly take no unwraps and index
+/// no slices, so malformed bytes always come back as one of these. Allocation
+/// can still fail, and equix's internal expects are on fixed-size arrays.
 #[derive(Debug)]
 pub enum CrankXError {
     /// Failed to build or solve the EquiX puzzle
     EquiXFailure,
+    /// `RuntimeOption::CompileOnly` was requested but HashX can't compile here
+    CompilerUnavailable,
     /// No solution found for the given seed
     NoSolution,
     /// Invalid solution
     InvalidSolution,
+    /// Serialized solution has the wrong length
+    InvalidLength,
+    /// Unknown wire format version
+    UnsupportedVersion,
+    /// Unknown or disabled final hash algorithm
+    UnsupportedHash,
+    /// Malformed hex string
+    InvalidHex,
+    /// Segment index past the end of the provider
+    SegmentOutOfRange { index: u64, count: u64 },
+    /// Wrong number of proofs submitted
+    ProofCount { expected: usize, got: usize },
+    /// The same nonce was used by more than one proof
+    DuplicateNonce,
+    /// Sampling parameters don't fit the segment
+    InvalidSampling,
+    /// Solution is valid but below the required difficulty
+    InsufficientDifficulty { required: u32, actual: u32 },
+    /// Seed would exceed [`MAX_SEED_LEN`]
+    SeedTooLarge { max: usize, got: usize },
+    /// Segment shorter than [`MIN_SEGMENT_SIZE`]
+    SegmentTooSmall { min: usize, got: usize },
+    /// Encoded bytes don't follow the format's grammar
+    InvalidEncoding,
+    /// Proof's recency anchor is more than `max_age` behind the verifier's
+    StaleProof { age: u64, max_age: u64 },
+    /// Proof's recency anchor is of a different kind than the verifier's, or
+    /// ahead of it
+    InvalidAnchor,
+    /// Segment longer than a [`limits::VerifyLimits`] allows
+    DataTooLarge { max: usize, got: usize },
+    /// More proofs in one request than a [`limits::VerifyLimits`] allows
+    BatchTooLarge { max: usize, got: usize },
+    /// Client key has used up its verification rate
+    RateLimited,
+    /// Signature doesn't match the signed bytes and public key
+    InvalidSignature,
+    /// Throttle rate not finite and positive, or duty cycle outside `(0, 1]`
+    InvalidThrottle,
+    /// Worker report from an unknown worker, or one that overflows the totals
+    InvalidReport,
+    /// Checkpoint saved by a miner with a different number of threads or a
+    /// different nonce strategy
+    CheckpointMismatch {
+        threads: usize,
+        cursors: usize,
+        strategy: nonces::NonceStrategy,
+        saved_strategy: nonces::NonceStrategy,
+    },
+    /// Job whose nonce range ends before it starts
+    InvalidNonceRange { start: u64, end: u64 },
+    /// Rate limit that refills at a negative or non-finite rate, or allows no
+    /// burst
+    InvalidRate,
 }
 
 impl core::fmt::Display for CrankXError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        write!(f, "{}", match self {
-            CrankXError::EquiXFailure => "EquiX build/solve failed",
-            CrankXError::NoSolution   => "No EquiX solution found",
-            CrankXError::InvalidSolution => "Invalid EquiX solution",
-        })
+        match self {
+            CrankXError::EquiXFailure => f.write_str("EquiX build/solve failed"),
+            CrankXError::CompilerUnavailable => f.write_str("HashX compiler unavailable"),
+            CrankXError::NoSolution   => f.write_str("No EquiX solution found"),
+            CrankXError::InvalidSolution => f.write_str("Invalid EquiX solution"),
+            CrankXError::InvalidLength => f.write_str("Invalid solution length"),
+            CrankXError::UnsupportedVersion => f.write_str("Unsupported wire format version"),
+            CrankXError::UnsupportedHash => f.write_str("Unsupported hash algorithm"),
+            CrankXError::InvalidHex => f.write_str("Invalid hex string"),
+            CrankXError::InvalidEncoding => f.write_str("Malformed encoding"),
+            CrankXError::InvalidSampling => f.write_str("Invalid sampling parameters"),
+            CrankXError::DuplicateNonce => f.write_str("Duplicate nonce across proofs"),
+            CrankXError::ProofCount { expected, got } => {
+                write!(f, "Expected {expected} proofs, got {got}")
+            }
+            CrankXError::InsufficientDifficulty { required, actual } => {
+                write!(f, "Difficulty {actual} below required {required}")
+            }
+            CrankXError::SegmentOutOfRange { index, count } => {
+                write!(f, "Segment {index} out of range ({count} segments)")
+            }
+            CrankXError::SeedTooLarge { max, got } => {
+                write!(f, "Seed too large: {got} bytes (max {max})")
+            }
+            CrankXError::SegmentTooSmall { min, got } => {
+                write!(f, "Segment too small: {got} bytes (min {min})")
+            }
+            CrankXError::StaleProof { age, max_age } => {
+                write!(f, "Proof anchor {age} old (max {max_age})")
+            }
+            CrankXError::InvalidAnchor => f.write_str("Proof anchor mismatched or in the future"),
+            CrankXError::DataTooLarge { max, got } => {
+                write!(f, "Segment too large: {got} bytes (max {max})")
+            }
+            CrankXError::BatchTooLarge { max, got } => {
+                write!(f, "Too many proofs: {got} (max {max})")
+            }
+            CrankXError::RateLimited => f.write_str("Verification rate limit exceeded"),
+            CrankXError::InvalidSignature => f.write_str("Invalid signature"),
+            CrankXError::InvalidThrottle => f.write_str("Invalid throttle"),
+            CrankXError::InvalidReport => f.write_str("Invalid worker report"),
+            CrankXError::CheckpointMismatch { threads, cursors, .. } if threads != cursors => {
+                write!(f, "Checkpoint has {cursors} cursors for {threads} threads")
+            }
+            CrankXError::CheckpointMismatch { strategy, saved_strategy, .. } => {
+                write!(f, "Checkpoint walks nonces {saved_strategy:?}, the miner {strategy:?}")
+            }
+            CrankXError::InvalidNonceRange { start, end } => {
+                write!(f, "Nonce range {start}..={end} is empty")
+            }
+            CrankXError::InvalidRate => f.write_str("Invalid rate limit"),
+        }
     }
 }
 
 impl std::error::Error for CrankXError {}
 
-#[derive(Default)]
+impl From<CrankXError> for std::io::Error {
+    fn from(e: CrankXError) -> Self {
+        std::io::Error::other(e)
+    }
+}
+
+/// An EquiX digest and the nonce it was found at
+///
+/// The final hash is computed on first use and cached, so decoding and
+/// relaying proofs never pays for the Keccak.
+#[derive(Debug, Default)]
 pub struct Solution {
     /// Raw EquiX digest (16 bytes)
     pub d: [u8; 16],
     /// Nonce (8 bytes)
     pub n: [u8; 8],
-    /// Final keccak(digest || nonce) hash (32 bytes)
-    h: [u8; 32],
+    /// Final hash(digest || nonce), keccak unless `alg` says otherwise (32 bytes)
+    h: OnceLock<[u8; 32]>,
+    /// Algorithm used for `h`
+    alg: HashAlgorithm,
 }
 
 impl Solution {
     /// Create a new solution
     pub fn new(digest: [u8; 16], nonce: [u8; 8]) -> Self {
-        Self {
-            d: digest,
-            n: nonce,
-            h: compute_hash(&digest, &nonce),
-        }
+        Self::with_hash_algorithm(digest, nonce, HashAlgorithm::Keccak256)
+    }
+
+    /// Create a solution only if it verifies against `challenge || data || nonce`
+    ///
+    /// A digest that fails verification is reported as `InvalidSolution`.
+    pub fn try_new<const N: usize>(
+        digest: [u8; 16],
+        nonce: [u8; 8],
+        challenge: impl Into<Challenge>,
+        data: &[u8; N],
+    ) -> Result<Self, CrankXError> {
+        verify(challenge, data, nonce, &digest).map_err(|e| match e {
+            CrankXError::EquiXFailure => CrankXError::InvalidSolution,
+            e => e,
+        })?;
+
+        Ok(Self::new(digest, nonce))
+    }
+
+    /// Create a new solution whose final hash uses `alg` instead of Keccak256
+    pub fn with_hash_algorithm(digest: [u8; 16], nonce: [u8; 8], alg: HashAlgorithm) -> Self {
+        Self { d: digest, n: nonce, h: OnceLock::new(), alg }
+    }
+
+    /// Algorithm used for the final hash
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.alg
     }
 
     /// Verify the solution against the raw `challenge || data || nonce`
     pub fn is_valid<const N: usize>(
         &self,
-        challenge: &[u8; 32],
+        challenge: impl Into<Challenge>,
         data: &[u8; N],
     ) -> Result<(), CrankXError> {
-        verify(challenge, data, &self.n, &self.d)
+        verify(challenge, data, self.n, &self.d)
     }
 
-    /// Final keccak(digest || nonce) hash (32 bytes)
+    /// Final hash(digest || nonce) (32 bytes)
     pub fn to_hash(&self) -> [u8; 32] {
-        self.h
+        *self.h.get_or_init(|| self.alg.hash(&self.d, &self.n))
     }
 
     /// Compute the difficulty of the solution
     pub fn difficulty(&self) -> u32 {
-        difficulty(self.h)
+        difficulty_of(&self.to_hash())
+    }
+
+    /// Whether the final hash is at or below `target`
+    pub fn meets(&self, target: &Target) -> bool {
+        target.is_met_by(&self.to_hash())
     }
 
     /// Serialize the solution to a byte array
@@ -80,26 +396,164 @@ impl Solution {
 
     /// Deserialize a byte array into a solution
     pub fn from_bytes(bytes: &[u8; 24]) -> Self {
+        let (d, n) = split_array(bytes);
+        Self::new(d, n)
+    }
+
+    /// Serialize with the final hash: `digest (16) || nonce (8) || hash (32)`
+    pub fn to_bytes_full(&self) -> [u8; 56] {
+        let mut bytes = [0; 56];
+        bytes[..24].copy_from_slice(&self.to_bytes());
+        bytes[24..].copy_from_slice(&self.to_hash());
+        bytes
+    }
+
+    /// Deserialize the 56-byte form, checking the carried hash
+    ///
+    /// The hash is recomputed with Keccak256; a mismatch is `InvalidSolution`.
+    pub fn from_bytes_full(bytes: &[u8; 56]) -> Result<Self, CrankXError> {
+        let (packed, hash): ([u8; 24], [u8; 32]) = split_array(bytes);
+        let solution = Self::from_bytes(&packed);
+        if solution.to_hash() != hash {
+            return Err(CrankXError::InvalidSolution);
+        }
+        Ok(solution)
+    }
+
+    /// Deserialize the 56-byte form, trusting the carried hash
+    ///
+    /// Skips the Keccak entirely. Only for bytes from a trusted source (or
+    /// already checked); a forged hash passes straight through to
+    /// [`difficulty`](Self::difficulty).
+    pub fn from_bytes_full_unchecked(bytes: &[u8; 56]) -> Self {
+        let (packed, hash): ([u8; 24], [u8; 32]) = split_array(bytes);
+        let (d, n) = split_array(&packed);
+        Self { d, n, h: OnceLock::from(hash), alg: HashAlgorithm::Keccak256 }
+    }
+
+    /// Deserialize a byte slice into a solution, checking its length
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let bytes: &[u8; 24] = bytes
+            .try_into()
+            .map_err(|_| CrankXError::InvalidLength)?;
+
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Serialize to the self-describing wire format:
+    /// `version (1) || hash algorithm (1) || digest (16) || nonce (8)`
+    pub fn to_versioned_bytes(&self) -> [u8; 26] {
+        let mut bytes = [0; 26];
+        bytes[0] = WIRE_VERSION;
+        bytes[1] = self.alg.id();
+        bytes[2..].copy_from_slice(&self.to_bytes());
+        bytes
+    }
+
+    /// Deserialize the self-describing wire format
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let bytes: &[u8; 26] = bytes
+            .try_into()
+            .map_err(|_| CrankXError::InvalidLength)?;
+
+        if bytes[0] != WIRE_VERSION {
+            return Err(CrankXError::UnsupportedVersion);
+        }
+
+        let alg = HashAlgorithm::from_id(bytes[1])?;
+
         let mut d = [0; 16];
         let mut n = [0; 8];
 
-        d.copy_from_slice(&bytes[..16]);
-        n.copy_from_slice(&bytes[16..]);
+        d.copy_from_slice(&bytes[2..18]);
+        n.copy_from_slice(&bytes[18..]);
 
-        Self::new(d, n)
+        Ok(Self::with_hash_algorithm(d, n, alg))
+    }
+}
+
+/// Hex of the packed `digest || nonce` bytes
+#[cfg(feature = "serde")]
+impl serde::Serialize for Solution {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        types::serialize_hex(&self.to_bytes(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Solution {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        types::deserialize_hex(deserializer).map(|bytes| Self::from_bytes(&bytes))
+    }
+}
+
+/// Canonical comparison: solutions are equal when their final hashes are
+///
+/// A digest's word order doesn't change its final hash, so permutations of
+/// one proof compare equal.
+impl PartialEq for Solution {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_hash() == other.to_hash()
+    }
+}
+
+impl Eq for Solution {}
+
+impl PartialOrd for Solution {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Canonical ranking: higher difficulty is greater, and at equal difficulty
+/// the lower final hash (compared as big-endian bytes) is greater
+///
+/// Every pool, leader election and on-chain comparison of "the best proof"
+/// should use this order so they all pick the same winner.
+impl Ord for Solution {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.difficulty()
+            .cmp(&other.difficulty())
+            .then_with(|| other.to_hash().cmp(&self.to_hash()))
     }
 }
 
 /// Solve PoW over raw `challenge || data || nonce`
 #[inline(always)]
 pub fn solve<const N: usize>(
-    challenge: &[u8; 32],
+    challenge: impl Into<Challenge>,
     data: &[u8; N],
-    nonce: &[u8; 8],
+    nonce: impl Into<Nonce>,
 ) -> Result<Solution, CrankXError> {
-    let seed = build_seed(challenge, data, nonce);
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+    solve_seed(&seed, nonce.as_bytes())
+}
 
-    let solutions = equix::solve(&seed)
+/// Solve PoW with pre‑allocated memory (for on‑chain performance)
+///
+/// `SolverMemory` only holds a pointer to its ~1.8 MB heap buffer, so it is
+/// safe to create and keep on small worker-thread stacks.
+#[inline(always)]
+pub fn solve_with_memory<const N: usize>(
+    mem: &mut equix::SolverMemory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+    solve_seed_with_memory(mem, &seed, nonce.as_bytes())
+}
+
+/// Solve an already-built seed
+#[inline(always)]
+pub(crate) fn solve_seed(seed: &[u8], nonce: &[u8; 8]) -> Result<Solution, CrankXError> {
+    let solutions = equix::EquiXBuilder::new()
+        .runtime(DEFAULT_RUNTIME)
+        .solve(seed)
         .map_err(|_| CrankXError::EquiXFailure)?;
 
     if solutions.is_empty() {
@@ -114,101 +568,515 @@ pub fn solve<const N: usize>(
     Ok(Solution::new(digest, *nonce))
 }
 
-/// Solve PoW with pre‑allocated memory (for on‑chain performance)
+/// Solve PoW with pre‑allocated memory, choosing among the seed's solutions
+/// with `policy` instead of taking the first
 #[inline(always)]
-pub fn solve_with_memory<const N: usize>(
+pub fn solve_with_policy<const N: usize>(
     mem: &mut equix::SolverMemory,
-    challenge: &[u8; 32],
+    challenge: impl Into<Challenge>,
     data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    policy: SelectionPolicy,
+) -> Result<Solution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+    solve_seed_with_policy(mem, &seed, nonce.as_bytes(), policy)
+}
+
+/// Solve an already-built seed with pre‑allocated memory
+#[inline(always)]
+pub(crate) fn solve_seed_with_memory(
+    mem: &mut equix::SolverMemory,
+    seed: &[u8],
     nonce: &[u8; 8],
 ) -> Result<Solution, CrankXError> {
+    solve_seed_with_policy(mem, seed, nonce, SelectionPolicy::First)
+}
 
-    let seed = build_seed(challenge, data, nonce);
+/// Number of EquiX solutions the seed for `nonce` has, without hashing any
+///
+/// Between 0 and 8. Fails with [`CrankXError::EquiXFailure`] for the small
+/// fraction of seeds whose HashX program can't be built.
+pub fn count_solutions<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<usize, CrankXError> {
+    check_segment_size::<N>();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.into().as_bytes())?;
+    let mut builder = equix::EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    Ok(build_equix(&builder, &seed)?.solve().len())
+}
 
-    let eq = equix::EquiXBuilder::new()
-        .runtime(equix::RuntimeOption::TryCompile)
-        .build(&seed)
-        .map_err(|_| CrankXError::EquiXFailure)?;
+/// Solve PoW with pre‑allocated memory and a caller-configured EquiX builder
+///
+/// Build the `EquiXBuilder` once (runtime and any other equix options) and
+/// reuse it across the nonce loop instead of [`DEFAULT_RUNTIME`].
+#[inline(always)]
+pub fn solve_with_builder<const N: usize>(
+    builder: &equix::EquiXBuilder,
+    mem: &mut equix::SolverMemory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+    solve_seed_with_builder(builder, mem, &seed, nonce.as_bytes(), SelectionPolicy::First)
+}
 
-    let solutions = eq.solve_with_memory(mem);
-    if solutions.is_empty() {
-        return Err(CrankXError::NoSolution);
+/// Solve an already-built seed with pre‑allocated memory and a selection policy
+#[inline(always)]
+pub(crate) fn solve_seed_with_policy(
+    mem: &mut equix::SolverMemory,
+    seed: &[u8],
+    nonce: &[u8; 8],
+    policy: SelectionPolicy,
+) -> Result<Solution, CrankXError> {
+    let mut builder = equix::EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    solve_seed_with_builder(&builder, mem, seed, nonce, policy)
+}
+
+/// Solve an already-built seed with a given builder, memory and selection policy
+///
+/// With the `tracing` feature each call is a trace-level `solve` span
+/// recording the nonce, runtime and difficulty.
+#[inline(always)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "solve",
+        level = "trace",
+        skip_all,
+        fields(nonce = u64::from_le_bytes(*nonce), runtime, difficulty)
+    )
+)]
+pub(crate) fn solve_seed_with_builder(
+    builder: &equix::EquiXBuilder,
+    mem: &mut equix::SolverMemory,
+    seed: &[u8],
+    nonce: &[u8; 8],
+    policy: SelectionPolicy,
+) -> Result<Solution, CrankXError> {
+    let eq = build_equix(builder, seed)?;
+    let solution = policy.select(&eq.solve_with_memory(mem), nonce);
+
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        span.record("runtime", tracing::field::debug(eq.runtime()));
+        if let Ok(solution) = &solution {
+            span.record("difficulty", solution.difficulty());
+        }
     }
+    solution
+}
 
-    let digest = unsafe { solutions.get_unchecked(0) }.to_bytes();
+/// Crank `seed` (`challenge || data || nonce`) at each of `nonces` in turn,
+/// rewriting its nonce field, and hand each nonce's pick under `policy` to
+/// `found`
+///
+/// The loop behind every nonce search. Ends when the nonces run out, `stop`
+/// returns true before an attempt, or `found` returns true; returns the
+/// attempts made. A seed whose puzzle can't be built or has no solution is
+/// an attempt without a find; a missing compiler ends the search with
+/// [`CrankXError::CompilerUnavailable`]. With the `tracing` feature each
+/// search is a debug-level `search` span recording the nonces it tried and
+/// the best difficulty it saw.
+pub(crate) fn search_nonces(
+    builder: &equix::EquiXBuilder,
+    memory: &mut equix::SolverMemory,
+    seed: &mut [u8],
+    nonces: impl IntoIterator<Item = u64>,
+    policy: SelectionPolicy,
+    mut stop: impl FnMut() -> bool,
+    mut found: impl FnMut(Solution) -> bool,
+) -> Result<u64, CrankXError> {
+    let nonce_at = seed.len() - 8;
+    let mut nonces = nonces.into_iter();
+    let mut attempts = 0;
+    #[cfg(feature = "tracing")]
+    let (span, mut first, mut last, mut best) = (
+        tracing::debug_span!(
+            "search",
+            seed_len = seed.len(),
+            nonce_start = tracing::field::Empty,
+            nonce_end = tracing::field::Empty,
+            attempts = tracing::field::Empty,
+            difficulty = tracing::field::Empty,
+        ),
+        None,
+        0,
+        None,
+    );
+    #[cfg(feature = "tracing")]
+    let _entered = span.enter();
 
-    Ok(Solution::new(digest, *nonce))
+    // `stop` goes first so a stopped search never draws a nonce it won't try
+    while !stop() {
+        let Some(nonce) = nonces.next() else {
+            break;
+        };
+        #[cfg(feature = "tracing")]
+        {
+            first = first.or(Some(nonce));
+            last = nonce;
+        }
+        let nonce = nonce.to_le_bytes();
+        attempts += 1;
+        seed[nonce_at..].copy_from_slice(&nonce);
+        match solve_seed_with_builder(builder, memory, seed, &nonce, policy) {
+            Ok(solution) => {
+                #[cfg(feature = "tracing")]
+                {
+                    best = best.max(Some(solution.difficulty()));
+                }
+                if found(solution) {
+                    break;
+                }
+            }
+            Err(e @ CrankXError::CompilerUnavailable) => return Err(e),
+            Err(_) => {}
+        }
+    }
+
+    // The range tried, inclusive; a search that tried nothing leaves it out
+    #[cfg(feature = "tracing")]
+    {
+        if let Some(first) = first {
+            span.record("nonce_start", first);
+            span.record("nonce_end", last);
+        }
+        span.record("attempts", attempts);
+        if let Some(best) = best {
+            span.record("difficulty", best);
+        }
+    }
+    Ok(attempts)
+}
+
+/// Build the EquiX puzzle for `seed`, telling a missing compiler apart from
+/// an unusable seed
+#[inline(always)]
+pub(crate) fn build_equix(
+    builder: &equix::EquiXBuilder,
+    seed: &[u8],
+) -> Result<equix::EquiX, CrankXError> {
+    builder.build(seed).map_err(|e| match e {
+        equix::Error::Hash(equix::HashError::Compiler(_)) => CrankXError::CompilerUnavailable,
+        _ => CrankXError::EquiXFailure,
+    })
 }
 
 /// Verify a candidate digest against raw `challenge || data || nonce`
 #[inline(always)]
 pub fn verify<const N: usize>(
-    challenge: &[u8; 32],
+    challenge: impl Into<Challenge>,
     data: &[u8; N],
-    nonce: &[u8; 8],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.into().as_bytes())?;
+    verify_seed(&seed, digest)
+}
+
+/// Verify a packed `digest (16) || nonce (8)` proof, as produced by
+/// [`Solution::to_bytes`], without building a `Solution` or its final hash
+#[inline(always)]
+pub fn verify_raw<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    proof: &[u8; 24],
+) -> Result<(), CrankXError> {
+    let (digest, nonce): ([u8; 16], [u8; 8]) = split_array(proof);
+    verify(challenge, data, nonce, &digest)
+}
+
+/// [`verify`] for a proof whose final hash the caller already holds, returning
+/// the difficulty of `hash`
+///
+/// With `strict` the hash is recomputed and must match, as in
+/// [`Solution::from_bytes_full`]; a mismatch is `InvalidSolution`. Without
+/// it the Keccak is skipped and `hash` is trusted, so only pass `false` for
+/// hashes from a trusted source, e.g. ones the validator stored itself.
+#[inline(always)]
+pub fn verify_with_hash<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+    hash: &[u8; 32],
+    strict: bool,
+) -> Result<u32, CrankXError> {
+    let nonce = nonce.into();
+    verify(challenge, data, nonce, digest)?;
+    if strict && compute_hash(digest, nonce.as_bytes()) != *hash {
+        return Err(CrankXError::InvalidSolution);
+    }
+    Ok(difficulty_of(hash))
+}
+
+/// [`solve_with_memory`] under a `C`-byte challenge
+///
+/// The seed is `challenge (C) || data || nonce`, so a longer challenge (e.g.
+/// slot hash, epoch and miner tag) is bound as-is instead of pre-hashed.
+/// At `C = 32` this is exactly `solve_with_memory`. The final hash covers
+/// only digest and nonce, so solutions look the same for every `C`.
+#[inline(always)]
+pub fn solve_with_challenge<const C: usize, const N: usize>(
+    mem: &mut equix::SolverMemory,
+    challenge: &[u8; C],
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    check_challenge_size::<C>();
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge, data, nonce.as_bytes())?;
+    solve_seed_with_memory(mem, &seed, nonce.as_bytes())
+}
+
+/// [`verify`] under a `C`-byte challenge, see [`solve_with_challenge`]
+#[inline(always)]
+pub fn verify_with_challenge<const C: usize, const N: usize>(
+    challenge: &[u8; C],
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    check_challenge_size::<C>();
+    check_segment_size::<N>();
+    let seed = build_seed(challenge, data, nonce.into().as_bytes())?;
+    verify_seed(&seed, digest)
+}
+
+/// [`solve_with_memory`] with `aux` bound into the seed:
+/// `challenge || data || aux || nonce`
+///
+/// For metadata the proof must commit to without being part of the segment,
+/// such as the submitter's pubkey or the segment index, so a relay can't
+/// claim someone else's proof. An empty `aux` gives the plain seed.
+#[inline(always)]
+pub fn solve_with_aux<const N: usize, const M: usize>(
+    mem: &mut equix::SolverMemory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    aux: &[u8; M],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    check_segment_size::<N>();
+    check_aux_size::<M>();
+    let nonce = nonce.into();
+    let seed = build_aux_seed(challenge.into().as_bytes(), data, aux, nonce.as_bytes())?;
+    solve_seed_with_memory(mem, &seed, nonce.as_bytes())
+}
+
+/// [`verify`] with `aux` bound into the seed, see [`solve_with_aux`]
+#[inline(always)]
+pub fn verify_with_aux<const N: usize, const M: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    aux: &[u8; M],
+    nonce: impl Into<Nonce>,
     digest: &[u8; 16],
 ) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    check_aux_size::<M>();
+    let seed = build_aux_seed(challenge.into().as_bytes(), data, aux, nonce.into().as_bytes())?;
+    verify_seed(&seed, digest)
+}
 
-    let seed = build_seed(challenge, data, nonce);
+/// Verify without allocating: the seed is assembled in `buf` instead of a `Vec`
+///
+/// `buf` needs `32 + N + 8` bytes; a `[u8; MAX_SEED_LEN]` fits every segment.
+/// On Solana keep it off the 4 KiB stack frame (e.g. in account data), since
+/// `MAX_SEED_LEN` doesn't fit there. The HashX program EquiX builds for the
+/// seed is still heap-allocated inside equix.
+#[inline(always)]
+pub fn verify_in_buffer<const N: usize>(
+    buf: &mut [u8],
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    let seed = write_seed(buf, challenge.into().as_bytes(), data, nonce.into().as_bytes())?;
+    verify_seed(seed, digest)
+}
 
-    equix::verify_bytes(&seed, digest)
+/// Verify a candidate digest against an already-built seed
+///
+/// With the `tracing` feature each call is a trace-level `verify` span.
+#[inline(always)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "verify",
+        level = "trace",
+        skip_all,
+        fields(seed_len = seed.len()),
+        err(level = "debug")
+    )
+)]
+pub(crate) fn verify_seed(seed: &[u8], digest: &[u8; 16]) -> Result<(), CrankXError> {
+    equix::EquiXBuilder::new()
+        .runtime(DEFAULT_RUNTIME)
+        .verify_bytes(seed, digest)
         .map_err(|_| CrankXError::EquiXFailure)?;
 
     Ok(())
 }
 
-/// Count leading zeros in a 32‑byte hash
-fn difficulty(hash: [u8; 32]) -> u32 {
+/// Difficulty of any 32-byte hash: its leading zero bits, reading the bytes
+/// in order and each byte from its most significant bit
+///
+/// This is what [`Solution::difficulty`] reports for the final hash, so a
+/// validator holding only the hash (e.g. from [`Solution::to_bytes_full`])
+/// gets the same number without the digest and nonce.
+pub const fn difficulty_of(hash: &[u8; 32]) -> u32 {
     let mut count = 0;
-    for &b in &hash {
-        let lz = b.leading_zeros();
+    let mut rest: &[u8] = hash;
+    while let [byte, tail @ ..] = rest {
+        let lz = byte.leading_zeros();
         count += lz;
         if lz < 8 {
             break;
         }
+        rest = tail;
     }
     count
 }
 
+/// Whether `hash` has at least `difficulty` leading zero bits
+///
+/// Same as `hash <= Target::from_difficulty(difficulty)` (see
+/// [`Target::is_met_by`]), without building the target.
+pub const fn meets_difficulty(hash: &[u8; 32], difficulty: u32) -> bool {
+    difficulty_of(hash) >= difficulty
+}
+
 /// Build the seed: `challenge || data || nonce`
 /// Includes full raw data to prove possession; no pre‑hash needed.
 #[inline(always)]
-fn build_seed<const N: usize>(
+pub(crate) fn build_seed(
+    challenge: &[u8],
+    data: &[u8],
+    nonce: &[u8],
+) -> Result<Vec<u8>, CrankXError> {
+    let len = check_seed_len(challenge.len(), data.len(), nonce.len())?;
+
+    let mut seed = Vec::with_capacity(len);
+    seed.extend_from_slice(challenge);
+    seed.extend_from_slice(data);
+    seed.extend_from_slice(nonce);
+    Ok(seed)
+}
+
+/// Build the seed with an auxiliary field: `challenge || data || aux || nonce`
+#[inline(always)]
+fn build_aux_seed(
     challenge: &[u8; 32],
-    data: &[u8; N],
+    data: &[u8],
+    aux: &[u8],
     nonce: &[u8; 8],
-) -> Vec<u8> {
-    let mut seed = Vec::with_capacity(32 + N + 8);
+) -> Result<Vec<u8>, CrankXError> {
+    let len = check_seed_len(challenge.len() + aux.len(), data.len(), nonce.len())?;
+
+    let mut seed = Vec::with_capacity(len);
     seed.extend_from_slice(challenge);
     seed.extend_from_slice(data);
+    seed.extend_from_slice(aux);
     seed.extend_from_slice(nonce);
-    seed
+    Ok(seed)
+}
+
+/// Length of the seed for a `data_len`-byte segment under a
+/// `challenge_len`-byte challenge and `nonce_len`-byte nonce, if that segment
+/// size is allowed
+#[inline(always)]
+pub(crate) fn check_seed_len(
+    challenge_len: usize,
+    data_len: usize,
+    nonce_len: usize,
+) -> Result<usize, CrankXError> {
+    if data_len < MIN_SEGMENT_SIZE {
+        return Err(CrankXError::SegmentTooSmall { min: MIN_SEGMENT_SIZE, got: data_len });
+    }
+    let prefix = challenge_len + nonce_len;
+    let (len, max) = (prefix + data_len, prefix + MAX_DATA_LEN);
+    if len > max {
+        return Err(CrankXError::SeedTooLarge { max, got: len });
+    }
+    Ok(len)
+}
+
+/// Write the seed `challenge || data || nonce` into the front of `buf`
+#[inline(always)]
+pub(crate) fn write_seed<'a>(
+    buf: &'a mut [u8],
+    challenge: &[u8; 32],
+    data: &[u8],
+    nonce: &[u8; 8],
+) -> Result<&'a [u8], CrankXError> {
+    let len = check_seed_len(challenge.len(), data.len(), nonce.len())?;
+    let max = MAX_SEED_LEN.min(buf.len());
+    let seed = buf.get_mut(..len).ok_or(CrankXError::SeedTooLarge { max, got: len })?;
+
+    let bytes = challenge.iter().chain(data).chain(nonce);
+    for (dst, src) in seed.iter_mut().zip(bytes) {
+        *dst = *src;
+    }
+    Ok(seed)
+}
+
+/// Split `bytes` into its first `A` and last `B` bytes, without any panic
+/// path; `A + B == N` is checked at compile time
+#[inline(always)]
+pub(crate) fn split_array<const N: usize, const A: usize, const B: usize>(
+    bytes: &[u8; N],
+) -> ([u8; A], [u8; B]) {
+    const { assert!(A + B == N, "split sizes must add up") }
+    let (mut head, mut tail) = ([0; A], [0; B]);
+    for (dst, src) in head.iter_mut().chain(tail.iter_mut()).zip(bytes) {
+        *dst = *src;
+    }
+    (head, tail)
 }
 
 /// Sort 16‑byte digest as u16 words to prevent malleability
 #[inline(always)]
-fn to_canonical(digest: &mut [u8; 16]) {
-    unsafe {
-        let words: &mut [u16; 8] = core::mem::transmute(digest);
-        words.sort_unstable();
+pub(crate) fn to_canonical(digest: &mut [u8; 16]) {
+    // Native-endian words, as a `[u16; 8]` view would read them; copied out
+    // because `digest` needn't be 2-byte aligned
+    let (pairs, _) = digest.as_chunks_mut::<2>();
+    let mut words = [0u16; 8];
+    for (word, pair) in words.iter_mut().zip(pairs.iter()) {
+        *word = u16::from_ne_bytes(*pair);
+    }
+    words.sort_unstable();
+    for (pair, word) in pairs.iter_mut().zip(words) {
+        *pair = word.to_ne_bytes();
     }
 }
 
 /// Compute the final 32‑byte Keccak hash of the canonical digest and nonce
 #[inline(always)]
-fn compute_hash(digest: &[u8; 16], nonce: &[u8; 8]) -> [u8; 32] {
+pub(crate) fn compute_hash(digest: &[u8; 16], nonce: &[u8; 8]) -> [u8; 32] {
     let mut d = *digest;
     to_canonical(&mut d);
 
-    #[cfg(feature = "solana")]
-    {
-        solana_program::keccak::hashv(&[&d, nonce]).to_bytes()
-    }
-    #[cfg(not(feature = "solana"))]
-    {
-        let mut hasher = sha3::Keccak256::new();
-        hasher.update(&d);
-        hasher.update(nonce);
-        hasher.finalize().into()
-    }
+    keccak(&[&d, nonce])
+}
+
+/// Keccak256 over the concatenation of `parts`, on the configured backend
+#[inline(always)]
+pub(crate) fn keccak(parts: &[&[u8]]) -> [u8; 32] {
+    keccak::keccak256(parts)
 }
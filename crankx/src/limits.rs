@@ -0,0 +1,170 @@
+// Cost guards for verification exposed to untrusted clients
+// Every proof costs a Blake2b pass over its seed plus HashX program
+// generation, so a public endpoint has to bound what one request can ask for
+// before any of that work starts. `VerifyLimits` caps segment length and
+// proofs per request, and optionally rate limits each client key (an IP, an
+// API key) with a token bucket charged one token per proof. Size checks come
+// first and cost nothing, so malformed requests never spend a client's tokens.
+// Buckets that have refilled are forgotten once too many keys are tracked;
+// if none have, unknown keys are refused until some do. Dropping live
+// buckets instead would let a flood of fresh keys reset every client's limit.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::batch::BatchItem;
+use crate::{CrankXError, MAX_DATA_LEN};
+
+/// Proofs per request unless [`VerifyLimits::max_batch`] says otherwise
+pub const DEFAULT_MAX_BATCH: usize = 1024;
+
+/// Keys tracked unless [`VerifyLimits::max_keys`] says otherwise
+pub const DEFAULT_MAX_KEYS: usize = 1 << 16;
+
+/// Token bucket parameters for one client key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate {
+    per_sec: f64,
+    burst: u32,
+}
+
+impl Rate {
+    /// Keys earn back `per_sec` proofs a second, finite and not negative (`0`
+    /// never refills), and may verify up to `burst` at once after idling, at
+    /// least one; [`CrankXError::InvalidRate`] otherwise
+    pub fn new(per_sec: f64, burst: u32) -> Result<Self, CrankXError> {
+        if !(per_sec >= 0.0 && per_sec.is_finite()) || burst == 0 {
+            return Err(CrankXError::InvalidRate);
+        }
+        Ok(Self { per_sec, burst })
+    }
+
+    /// Proofs per second a key earns back
+    pub fn per_sec(&self) -> f64 {
+        self.per_sec
+    }
+
+    /// Proofs a key may verify at once after idling
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+}
+
+/// Limits checked before any proof is verified
+#[derive(Debug)]
+pub struct VerifyLimits {
+    max_data_len: usize,
+    max_batch: usize,
+    per_key_rate: Option<Rate>,
+    max_keys: usize,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Default for VerifyLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerifyLimits {
+    /// Segments up to [`MAX_DATA_LEN`], [`DEFAULT_MAX_BATCH`] proofs per
+    /// request and no rate limit; a rate tracks up to [`DEFAULT_MAX_KEYS`] keys
+    pub fn new() -> Self {
+        Self {
+            max_data_len: MAX_DATA_LEN,
+            max_batch: DEFAULT_MAX_BATCH,
+            per_key_rate: None,
+            max_keys: DEFAULT_MAX_KEYS,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Reject segments longer than `len` bytes
+    pub fn max_data_len(mut self, len: usize) -> Self {
+        self.max_data_len = len;
+        self
+    }
+
+    /// Reject requests carrying more than `count` proofs
+    pub fn max_batch(mut self, count: usize) -> Self {
+        self.max_batch = count;
+        self
+    }
+
+    /// Limit each key to `rate` proofs
+    pub fn per_key_rate(mut self, rate: Rate) -> Self {
+        self.per_key_rate = Some(rate);
+        self
+    }
+
+    /// Track at most `count` keys' buckets
+    pub fn max_keys(mut self, count: usize) -> Self {
+        self.max_keys = count;
+        self
+    }
+
+    /// [`CrankXError::DataTooLarge`] for a segment of `len` bytes over the limit
+    pub fn check_data_len(&self, len: usize) -> Result<(), CrankXError> {
+        if len > self.max_data_len {
+            return Err(CrankXError::DataTooLarge { max: self.max_data_len, got: len });
+        }
+        Ok(())
+    }
+
+    /// [`CrankXError::BatchTooLarge`] for more than `count` proofs
+    pub fn check_batch_len(&self, count: usize) -> Result<(), CrankXError> {
+        if count > self.max_batch {
+            return Err(CrankXError::BatchTooLarge { max: self.max_batch, got: count });
+        }
+        Ok(())
+    }
+
+    /// Check the proof count and every segment's length
+    pub fn check_batch(&self, items: &[BatchItem]) -> Result<(), CrankXError> {
+        self.check_batch_len(items.len())?;
+        items.iter().try_for_each(|item| self.check_data_len(item.data.len()))
+    }
+
+    /// Charge `key` for `proofs` proofs, or [`CrankXError::RateLimited`] if
+    /// its bucket can't cover them
+    ///
+    /// A refused request costs nothing. A key not yet tracked is refused
+    /// while every tracked bucket is still draining. Always succeeds without
+    /// a rate.
+    pub fn admit(&self, key: &str, proofs: usize) -> Result<(), CrankXError> {
+        let Some(rate) = self.per_key_rate else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let burst = rate.burst as f64;
+        let refilled = |b: &Bucket| {
+            let earned = now.duration_since(b.updated).as_secs_f64() * rate.per_sec;
+            (b.tokens + earned).min(burst)
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= self.max_keys && !buckets.contains_key(key) {
+            buckets.retain(|_, b| refilled(b) < burst);
+            if buckets.len() >= self.max_keys {
+                return Err(CrankXError::RateLimited);
+            }
+        }
+        let bucket = buckets
+            .entry(key.to_owned())
+            .or_insert(Bucket { tokens: burst, updated: now });
+        let tokens = refilled(bucket);
+        let cost = proofs as f64;
+        if tokens < cost {
+            return Err(CrankXError::RateLimited);
+        }
+        *bucket = Bucket { tokens: tokens - cost, updated: now };
+        Ok(())
+    }
+}
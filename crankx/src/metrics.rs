@@ -0,0 +1,205 @@
+// Counters for long-running cranking front ends (feature = "metrics")
+// Plain atomics rendered in the Prometheus text exposition format, so
+// operators can scrape them without pulling a metrics crate into the core.
+// A `Miner` or `Scheduler` given a `Metrics` feeds it directly; the service
+// and the daemon serve it at `/metrics`. Staleness is one gauge per segment,
+// labelled with the segment's source (a tape name, say) and index, in
+// whatever time unit its scheduler runs on. Per-worker attempts, per-source
+// segment counts and the last few errors are kept for the terminal
+// dashboard as much as for Prometheus.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+use std::sync::Mutex;
+
+/// Process-wide cranking counters, safe to share between threads
+#[derive(Debug, Default)]
+pub struct Metrics {
+    attempts: AtomicU64,
+    solutions: AtomicU64,
+    best_difficulty: AtomicU64,
+    verifications: AtomicU64,
+    verify_failures: AtomicU64,
+    memory_pool_size: AtomicU64,
+    /// Time since each `(source, segment)` was last proved
+    staleness: Mutex<BTreeMap<(String, u64), u64>>,
+    /// Nonces tried by each `Miner` thread, indexed by thread
+    worker_attempts: Mutex<Vec<u64>>,
+    /// Segments proved and still pending, per source
+    segments: Mutex<BTreeMap<String, (u64, u64)>>,
+    errors: AtomicU64,
+    /// The last [`RECENT_ERRORS`] errors, oldest first
+    recent_errors: Mutex<VecDeque<String>>,
+}
+
+/// Errors [`Metrics::recent_errors`] keeps
+pub const RECENT_ERRORS: usize = 8;
+
+impl Metrics {
+    /// Record `n` nonces tried
+    pub fn add_attempts(&self, n: u64) {
+        self.attempts.fetch_add(n, Relaxed);
+    }
+
+    /// Record a found solution of `difficulty`
+    pub fn record_solution(&self, difficulty: u32) {
+        self.solutions.fetch_add(1, Relaxed);
+        self.best_difficulty.fetch_max(difficulty as u64, Relaxed);
+    }
+
+    /// Record a verification and whether it passed
+    pub fn record_verification(&self, valid: bool) {
+        self.verifications.fetch_add(1, Relaxed);
+        if !valid {
+            self.verify_failures.fetch_add(1, Relaxed);
+        }
+    }
+
+    /// Set the number of idle solver memories held for reuse
+    pub fn set_memory_pool_size(&self, size: usize) {
+        self.memory_pool_size.store(size as u64, Relaxed);
+    }
+
+    /// Set how long segment `index` of `source` has gone unproved
+    pub fn set_staleness(&self, source: &str, index: u64, age: u64) {
+        self.staleness.lock().unwrap().insert((source.to_owned(), index), age);
+    }
+
+    /// Stop reporting anything about `source`, e.g. once a tape is gone
+    pub fn clear_source(&self, source: &str) {
+        self.staleness.lock().unwrap().retain(|(s, _), _| s != source);
+        self.segments.lock().unwrap().remove(source);
+    }
+
+    /// Record `n` nonces tried by worker thread `worker`
+    pub fn add_worker_attempts(&self, worker: usize, n: u64) {
+        let mut workers = self.worker_attempts.lock().unwrap();
+        if workers.len() <= worker {
+            workers.resize(worker + 1, 0);
+        }
+        workers[worker] += n;
+    }
+
+    /// Set how many of `source`'s segments are proved and how many pending
+    pub fn set_segments(&self, source: &str, proved: u64, pending: u64) {
+        self.segments.lock().unwrap().insert(source.to_owned(), (proved, pending));
+    }
+
+    /// Record an error, keeping its message among the recent ones
+    pub fn record_error(&self, message: impl Into<String>) {
+        self.errors.fetch_add(1, Relaxed);
+        let mut recent = self.recent_errors.lock().unwrap();
+        if recent.len() == RECENT_ERRORS {
+            recent.pop_front();
+        }
+        recent.push_back(message.into());
+    }
+
+    /// Last staleness set for segment `index` of `source`
+    pub fn staleness(&self, source: &str, index: u64) -> Option<u64> {
+        self.staleness.lock().unwrap().get(&(source.to_owned(), index)).copied()
+    }
+
+    /// Nonces tried so far
+    pub fn attempts(&self) -> u64 {
+        self.attempts.load(Relaxed)
+    }
+
+    /// Solutions found so far
+    pub fn solutions(&self) -> u64 {
+        self.solutions.load(Relaxed)
+    }
+
+    /// Highest difficulty found so far
+    pub fn best_difficulty(&self) -> u32 {
+        self.best_difficulty.load(Relaxed) as u32
+    }
+
+    /// Nonces tried so far by each worker thread
+    pub fn worker_attempts(&self) -> Vec<u64> {
+        self.worker_attempts.lock().unwrap().clone()
+    }
+
+    /// `(source, proved, pending)` for every source reported
+    pub fn segments(&self) -> Vec<(String, u64, u64)> {
+        let segments = self.segments.lock().unwrap();
+        segments
+            .iter()
+            .map(|(source, &(proved, pending))| (source.clone(), proved, pending))
+            .collect()
+    }
+
+    /// Errors recorded so far
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Relaxed)
+    }
+
+    /// The last few errors, oldest first
+    pub fn recent_errors(&self) -> Vec<String> {
+        self.recent_errors.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Render all counters in the Prometheus text format
+    pub fn render(&self) -> String {
+        let metrics = [
+            ("crankx_attempts_total", "counter", "Nonces tried", &self.attempts),
+            ("crankx_solutions_total", "counter", "Solutions found", &self.solutions),
+            ("crankx_best_difficulty", "gauge", "Highest difficulty found", &self.best_difficulty),
+            ("crankx_verifications_total", "counter", "Proofs verified", &self.verifications),
+            ("crankx_verify_failures_total", "counter", "Proofs rejected", &self.verify_failures),
+            ("crankx_memory_pool_size", "gauge", "Idle solver memories", &self.memory_pool_size),
+            ("crankx_errors_total", "counter", "Errors recorded", &self.errors),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {}", value.load(Relaxed));
+        }
+
+        let workers = self.worker_attempts.lock().unwrap();
+        if !workers.is_empty() {
+            let name = "crankx_worker_attempts_total";
+            let _ = writeln!(out, "# HELP {name} Nonces tried by the worker thread");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (worker, attempts) in workers.iter().enumerate() {
+                let _ = writeln!(out, "{name}{{worker=\"{worker}\"}} {attempts}");
+            }
+        }
+
+        let segments = self.segments.lock().unwrap();
+        for (name, help, pick) in [
+            ("crankx_segments_proved", "Segments with a proof", 0),
+            ("crankx_segments_pending", "Segments still to prove", 1),
+        ] {
+            if segments.is_empty() {
+                break;
+            }
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            for (source, &(proved, pending)) in segments.iter() {
+                let count = [proved, pending][pick];
+                let _ = writeln!(out, "{name}{{source=\"{}\"}} {count}", escape(source));
+            }
+        }
+
+        let staleness = self.staleness.lock().unwrap();
+        if !staleness.is_empty() {
+            let name = "crankx_segment_staleness";
+            let _ = writeln!(out, "# HELP {name} Time since the segment was last proved");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            for ((source, index), age) in staleness.iter() {
+                let source = escape(source);
+                let _ = writeln!(out, "{name}{{source=\"{source}\",segment=\"{index}\"}} {age}");
+            }
+        }
+        out
+    }
+}
+
+/// `value` escaped for a Prometheus label
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
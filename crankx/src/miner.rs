@@ -0,0 +1,895 @@
+// Parallel cranking of one segment
+// Threads split the nonce space by stride: thread `i` of `T` tries batches
+// `i, i + T, i + 2T, ...` of the miner's nonces, so no two threads ever build
+// the same seed. The first qualifying solution stops every thread.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering::Relaxed};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use equix::{EquiXBuilder, Runtime, RuntimeOption, SolverMemory};
+
+use crate::bench::per_sec;
+use crate::checkpoint::MinerState;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::nonces::NonceStrategy;
+use crate::stats::Stats;
+use crate::{
+    build_equix, build_seed, search_nonces, Challenge, CrankXError, SegmentProvider,
+    SelectionPolicy, Solution, DEFAULT_RUNTIME,
+};
+
+/// Multi-threaded solver for a single segment
+#[derive(Debug)]
+pub struct Miner {
+    threads: usize,
+    batch_size: u64,
+    runtime: RuntimeOption,
+    collect_stats: bool,
+    nonce_strategy: NonceStrategy,
+    throttle: Throttle,
+    auto_scale: Option<AutoScale>,
+    stop: Arc<AtomicBool>,
+    /// One per [`Miner::mine`] call in progress
+    rotations: Mutex<Vec<Arc<Rotation>>>,
+    control: Control,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+}
+
+/// Pause state and the number of [`Miner::mine`] and [`Miner::solve_many`]
+/// calls in progress
+#[derive(Debug, Default)]
+struct Control {
+    paused: AtomicBool,
+    running: Mutex<usize>,
+    idle: Condvar,
+    /// Cuts short throttle waits on shutdown
+    wake: Condvar,
+}
+
+/// Counts one call as running until dropped
+struct Running<'a>(&'a Control);
+
+impl<'a> Running<'a> {
+    fn start(control: &'a Control) -> Self {
+        *control.running.lock().unwrap() += 1;
+        Self(control)
+    }
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        *self.0.running.lock().unwrap() -= 1;
+        self.0.idle.notify_all();
+    }
+}
+
+/// Challenge a running [`Miner::mine`] cranks, and how often it has changed
+#[derive(Debug, Default)]
+struct Rotation {
+    epoch: AtomicU64,
+    challenge: Mutex<Challenge>,
+}
+
+impl Rotation {
+    fn new(challenge: Challenge, epoch: u64) -> Self {
+        Self { epoch: AtomicU64::new(epoch), challenge: Mutex::new(challenge) }
+    }
+
+    fn set(&self, challenge: Challenge) {
+        let mut current = self.challenge.lock().unwrap();
+        *current = challenge;
+        self.epoch.fetch_add(1, Relaxed);
+    }
+
+    fn get(&self) -> (u64, Challenge) {
+        let current = self.challenge.lock().unwrap();
+        (self.epoch.load(Relaxed), *current)
+    }
+}
+
+/// Limit on how hard [`Miner::mine`] works
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Throttle {
+    /// Crank flat out
+    #[default]
+    Off,
+    /// Fraction of wall time each thread spends cranking, in `(0, 1]` and
+    /// raised to at least `0.01`; e.g. `0.6` for roughly 60% CPU
+    DutyCycle(f64),
+    /// Nonces per second across all threads, split evenly between them;
+    /// finite and positive
+    MaxRate(f64),
+}
+
+impl Throttle {
+    /// [`CrankXError::InvalidThrottle`] unless the rate or duty cycle is in
+    /// range
+    pub fn check(&self) -> Result<(), CrankXError> {
+        match *self {
+            Throttle::DutyCycle(duty) if !(duty > 0.0 && duty <= 1.0) => {
+                Err(CrankXError::InvalidThrottle)
+            }
+            Throttle::MaxRate(rate) if !(rate > 0.0 && rate.is_finite()) => {
+                Err(CrankXError::InvalidThrottle)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Grow and shrink the number of cranking threads with system load
+///
+/// Each `interval` the miner runs as many threads as fit in
+/// `cpu_budget * cores` once everyone else's load is subtracted, between
+/// `min_threads` and [`Miner::threads`].
+#[derive(Debug, Clone, Copy)]
+pub struct AutoScale {
+    pub min_threads: usize,
+    /// Fraction of the machine's cores that may be busy, ours included
+    pub cpu_budget: f64,
+    pub interval: Duration,
+    /// Runnable threads system-wide, `None` if unknown (scaling then holds)
+    pub load: fn() -> Option<f64>,
+}
+
+impl AutoScale {
+    /// Keep total load under `cpu_budget` of the cores, sampling
+    /// [`system_load`] every five seconds
+    pub fn new(cpu_budget: f64) -> Self {
+        Self {
+            min_threads: 1,
+            cpu_budget,
+            interval: Duration::from_secs(5),
+            load: system_load,
+        }
+    }
+
+    /// Threads to run out of `max` when `ours` are running now
+    fn target(&self, ours: usize, max: usize) -> Option<usize> {
+        let cores = thread::available_parallelism().map_or(1, |n| n.get()) as f64;
+        let others = ((self.load)()? - ours as f64).max(0.0);
+        let room = (self.cpu_budget * cores - others).floor().max(0.0) as usize;
+        Some(room.clamp(self.min_threads.clamp(1, max), max))
+    }
+}
+
+/// One-minute load average from `/proc/loadavg`, `None` off Linux
+pub fn system_load() -> Option<f64> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+    loadavg.split_whitespace().next()?.parse().ok()
+}
+
+/// Outcome of [`Miner::mine`]
+#[derive(Debug, Default)]
+pub struct MineReport {
+    /// Qualifying solution, `None` if mining was stopped first
+    pub solution: Option<Solution>,
+    /// Challenge `solution` proves, which can predate an
+    /// [`Miner::update_challenge`] that landed as it was found; with no
+    /// solution, the challenge in force when mining ended
+    pub challenge: Challenge,
+    /// Nonces tried across all threads
+    pub attempts: u64,
+    pub elapsed: Duration,
+    /// Time an average thread spent cranking rather than throttled
+    pub busy: Duration,
+    /// Threads cranking when mining ended (less than [`Miner::threads`]
+    /// only under [`AutoScale`])
+    pub active_threads: usize,
+    /// HashX runtime the puzzles ran on (`None` if none built)
+    ///
+    /// `Interpret` under `TryCompile` means the compiler failed
+    /// and hashrate is roughly a tenth of what this machine could do.
+    pub runtime_used: Option<Runtime>,
+    /// Order the nonces were searched in
+    pub nonce_strategy: NonceStrategy,
+    /// Per-candidate statistics, if [`Miner::collect_stats`] is on
+    pub stats: Option<Stats>,
+}
+
+impl MineReport {
+    /// Nonces tried per second, zero if no time was measured
+    pub fn attempts_per_sec(&self) -> f64 {
+        per_sec(self.attempts, self.elapsed.as_secs_f64())
+    }
+
+    /// Nonces per second while actually cranking: the unthrottled hashrate,
+    /// zero if no busy time was measured
+    pub fn busy_attempts_per_sec(&self) -> f64 {
+        per_sec(self.attempts, self.busy.as_secs_f64())
+    }
+}
+
+impl Miner {
+    /// Miner running `threads` threads (at least one)
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            batch_size: 1,
+            runtime: DEFAULT_RUNTIME,
+            collect_stats: false,
+            nonce_strategy: NonceStrategy::Sequential,
+            throttle: Throttle::Off,
+            auto_scale: None,
+            stop: Arc::default(),
+            rotations: Mutex::default(),
+            control: Control::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Choose the HashX runtime instead of [`DEFAULT_RUNTIME`]
+    ///
+    /// `CompileOnly` makes [`Miner::mine`] fail with
+    /// [`CrankXError::CompilerUnavailable`] rather than silently interpreting.
+    pub fn runtime(mut self, runtime: RuntimeOption) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Number of threads [`Miner::mine`] spawns
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Fresh [`MinerState`] for [`Miner::mine_from`]: a cursor per thread and
+    /// this miner's nonce strategy
+    pub fn state(&self, challenge: impl Into<Challenge>) -> MinerState {
+        let state = MinerState::new(challenge, self.threads);
+        MinerState { nonce_strategy: self.nonce_strategy, ..state }
+    }
+
+    /// Have each thread claim `batch_size` consecutive nonces (at least one)
+    /// at a time instead of one
+    ///
+    /// Threads check for a stop, pause, new challenge or another thread's
+    /// solution only between batches, so a larger batch reacts that much
+    /// later to each but touches shared state less;
+    /// [`autotune`](crate::tune::autotune) measures which wins on a machine.
+    pub fn batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Record [`Stats`] over every candidate evaluated
+    ///
+    /// Costs a final hash per extra candidate, so it's off by default.
+    pub fn collect_stats(mut self, on: bool) -> Self {
+        self.collect_stats = on;
+        self
+    }
+
+    /// Search [`Miner::mine`]'s nonces in the order `strategy` gives instead
+    /// of from zero, so miners that don't coordinate rarely overlap
+    pub fn nonce_strategy(mut self, strategy: NonceStrategy) -> Self {
+        self.nonce_strategy = strategy;
+        self
+    }
+
+    /// Sleep between attempts to stay under `throttle`
+    ///
+    /// Time spent cranking is tracked apart from the sleeps, so
+    /// [`MineReport::busy_attempts_per_sec`] still shows the real hashrate.
+    /// [`CrankXError::InvalidThrottle`] for a rate or duty cycle out of
+    /// range, see [`Throttle::check`].
+    pub fn throttle(mut self, throttle: Throttle) -> Result<Self, CrankXError> {
+        throttle.check()?;
+        self.throttle = throttle;
+        Ok(self)
+    }
+
+    /// Vary the number of cranking threads with system load, never
+    /// exceeding [`Miner::threads`]
+    ///
+    /// Every thread is still spawned up front. A controller re-derives how
+    /// many may crank each [`AutoScale::interval`]; the rest park between
+    /// batches until it grows again.
+    pub fn auto_scale(mut self, auto_scale: AutoScale) -> Self {
+        self.auto_scale = Some(auto_scale);
+        self
+    }
+
+    /// Flag that makes [`Miner::mine`] return early when set
+    ///
+    /// Stays set until cleared, so every later call also returns immediately.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Use `stop` as the stop flag, so one flag (a signal handler's, say)
+    /// stops several miners
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Count every call's attempts and solutions in `metrics`
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Switch every running [`Miner::mine`] to `challenge` without
+    /// restarting its threads
+    ///
+    /// Every thread moves to the new challenge before its next batch and
+    /// starts its nonces over. An attempt already in flight that qualifies
+    /// still ends the call, reported with the old challenge; when finds
+    /// under several challenges are in, the newest wins. With nothing
+    /// running it has no effect, since `mine` installs its own challenge
+    /// when it starts.
+    pub fn update_challenge(&self, challenge: impl Into<Challenge>) {
+        let challenge = challenge.into();
+        for rotation in self.rotations.lock().unwrap().iter() {
+            rotation.set(challenge);
+        }
+    }
+
+    /// Park every thread once its current batch is done
+    ///
+    /// Anything that batch finds is kept, and so is each thread's solver
+    /// memory, so [`Miner::resume`] picks up where it left off. Also holds
+    /// calls started while paused.
+    pub fn pause(&self) {
+        self.control.paused.store(true, Relaxed);
+    }
+
+    /// Let paused threads crank again
+    pub fn resume(&self) {
+        self.control.paused.store(false, Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.paused.load(Relaxed)
+    }
+
+    /// Set the stop flag and wait up to `timeout` for running calls to
+    /// return; true if they all did
+    ///
+    /// Paused threads wake to stop. Each call still reports the best solution
+    /// found before it stopped. The flag stays set, as with
+    /// [`Miner::stop_flag`].
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        self.stop.store(true, Relaxed);
+        self.control.wake.notify_all();
+        let running = self.control.running.lock().unwrap();
+        let idle = self.control.idle.wait_timeout_while(running, timeout, |n| *n > 0);
+        !idle.unwrap().1.timed_out()
+    }
+
+    /// Crank `data` until a solution of at least `min_difficulty` turns up
+    ///
+    /// When several threads qualify at once the highest difficulty wins.
+    /// With the `tracing` feature the call is an info-level `mine` span
+    /// recording its attempts, the difficulty found and the runtime used,
+    /// and each thread a debug-level `crank` span recording where its stride
+    /// starts and how many nonces it tried.
+    pub fn mine(
+        &self,
+        challenge: impl Into<Challenge>,
+        data: &[u8],
+        min_difficulty: u32,
+    ) -> Result<MineReport, CrankXError> {
+        let mut state = self.state(challenge);
+        self.mine_from(&mut state, data, min_difficulty)
+    }
+
+    /// [`Miner::mine`] `state.challenge`, each thread carrying on from its
+    /// cursor in `state`, and leave `state` where the call stopped
+    ///
+    /// On return the cursors, challenge and epoch are where the threads got
+    /// to and any solution found is offered to `state.best`; save it, and a
+    /// later call with the restored state, on a miner with the same thread
+    /// count, batch size and nonce strategy, tries no nonce twice.
+    /// [`CrankXError::CheckpointMismatch`] unless `state` has a cursor per
+    /// thread and was saved under this miner's nonce strategy. On an error
+    /// `state` is left as it was.
+    pub fn mine_from(
+        &self,
+        state: &mut MinerState,
+        data: &[u8],
+        min_difficulty: u32,
+    ) -> Result<MineReport, CrankXError> {
+        let challenge = state.challenge;
+        if state.cursors.len() != self.threads || state.nonce_strategy != self.nonce_strategy {
+            return Err(CrankXError::CheckpointMismatch {
+                threads: self.threads,
+                cursors: state.cursors.len(),
+                strategy: self.nonce_strategy,
+                saved_strategy: state.nonce_strategy,
+            });
+        }
+
+        // Reject oversized segments up front rather than once per thread
+        build_seed(challenge.as_bytes(), data, &[0; 8])?;
+        let _running = Running::start(&self.control);
+
+        let rotation = Arc::new(Rotation::new(challenge, state.epoch));
+        let shared = Shared { rotation, ..Shared::default() };
+        self.rotations.lock().unwrap().push(shared.rotation.clone());
+        let initial = self.auto_scale.and_then(|a| a.target(0, self.threads));
+        shared.active.store(initial.unwrap_or(self.threads), Relaxed);
+        let timer = Instant::now();
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "mine",
+            threads = self.threads,
+            batch_size = self.batch_size,
+            min_difficulty,
+            attempts = tracing::field::Empty,
+            difficulty = tracing::field::Empty,
+            runtime = tracing::field::Empty,
+            elapsed_ms = tracing::field::Empty,
+        );
+
+        let results: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = (0..self.threads as u64)
+                .zip(&state.cursors)
+                .map(|(i, &cursor)| {
+                    let shared = &shared;
+                    #[cfg(feature = "tracing")]
+                    let span = &span;
+                    s.spawn(move || {
+                        #[cfg(feature = "tracing")]
+                        let _entered = span.enter();
+                        self.crank(data, i, cursor, min_difficulty, shared)
+                    })
+                })
+                .collect();
+            if let Some(auto_scale) = self.auto_scale {
+                let shared = &shared;
+                s.spawn(move || self.scale(auto_scale, shared));
+            }
+            let results = handles.into_iter().map(|h| h.join().unwrap()).collect();
+            shared.done.store(true, Relaxed);
+            results
+        });
+        self.rotations.lock().unwrap().retain(|r| !Arc::ptr_eq(r, &shared.rotation));
+        let cursors = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        (state.epoch, state.challenge) = shared.rotation.get();
+        state.cursors = cursors;
+        let (solution, challenge) = match shared.best.into_inner().unwrap() {
+            Some((_, challenge, solution)) => {
+                state.offer(Solution::new(solution.d, solution.n));
+                (Some(solution), challenge)
+            }
+            None => (None, state.challenge),
+        };
+        let attempts = shared.attempts.into_inner();
+        let elapsed = timer.elapsed();
+        let runtime_used = shared.runtime.into_inner().unwrap();
+        #[cfg(feature = "tracing")]
+        {
+            span.record("attempts", attempts);
+            if let Some(solution) = &solution {
+                span.record("difficulty", solution.difficulty());
+            }
+            if let Some(runtime) = runtime_used {
+                span.record("runtime", tracing::field::debug(runtime));
+            }
+            span.record("elapsed_ms", elapsed.as_millis() as u64);
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.add_attempts(attempts);
+            if let Some(solution) = &solution {
+                metrics.record_solution(solution.difficulty());
+            }
+        }
+        Ok(MineReport {
+            solution,
+            challenge,
+            attempts,
+            elapsed,
+            busy: Duration::from_nanos(shared.busy_nanos.into_inner() / self.threads as u64),
+            active_threads: shared.active.into_inner(),
+            runtime_used,
+            nonce_strategy: self.nonce_strategy,
+            stats: self.collect_stats.then(|| {
+                let mut stats = shared.stats.into_inner().unwrap();
+                stats.nonce_strategy = self.nonce_strategy;
+                stats
+            }),
+        })
+    }
+
+    /// [`Miner::mine`] segment `index` of `provider`, fetched once up front
+    ///
+    /// Cranking a tape this way never holds more than one segment in hand.
+    pub fn mine_segment<P>(
+        &self,
+        challenge: impl Into<Challenge>,
+        provider: &P,
+        index: u64,
+        min_difficulty: u32,
+    ) -> Result<MineReport, P::Error>
+    where
+        P: SegmentProvider + ?Sized,
+        P::Error: From<CrankXError>,
+    {
+        let mut state = self.state(challenge);
+        self.mine_segment_from(&mut state, provider, index, min_difficulty)
+    }
+
+    /// [`Miner::mine_from`] segment `index` of `provider`, fetched once up
+    /// front
+    pub fn mine_segment_from<P>(
+        &self,
+        state: &mut MinerState,
+        provider: &P,
+        index: u64,
+        min_difficulty: u32,
+    ) -> Result<MineReport, P::Error>
+    where
+        P: SegmentProvider + ?Sized,
+        P::Error: From<CrankXError>,
+    {
+        let data = provider.segment(index)?;
+        Ok(self.mine_from(state, &data, min_difficulty)?)
+    }
+
+    /// Solve every segment to at least `min_difficulty`, one segment per
+    /// thread at a time
+    ///
+    /// Threads take segments from a shared queue and search each from nonce
+    /// zero.
+    /// Results line up with `segments`; an entry is `None` only if the stop
+    /// flag was set before that segment was solved. Every segment is size
+    /// checked before any work starts.
+    pub fn solve_many<S: AsRef<[u8]> + Sync>(
+        &self,
+        challenge: impl Into<Challenge>,
+        segments: &[S],
+        min_difficulty: u32,
+    ) -> Result<Vec<Option<Solution>>, CrankXError> {
+        let challenge = challenge.into();
+        for segment in segments {
+            build_seed(challenge.as_bytes(), segment.as_ref(), &[0; 8])?;
+        }
+        let _running = Running::start(&self.control);
+
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let results: Vec<_> = segments.iter().map(|_| Mutex::new(None)).collect();
+
+        let outcomes: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = (0..self.threads.min(segments.len()))
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut builder = EquiXBuilder::new();
+                        builder.runtime(self.runtime);
+                        let mut memory = SolverMemory::new();
+                        loop {
+                            let i = next.fetch_add(1, Relaxed);
+                            let Some(segment) = segments.get(i) else {
+                                return Ok(());
+                            };
+                            let solved = solve_from_zero(
+                                &builder,
+                                &mut memory,
+                                &challenge,
+                                segment.as_ref(),
+                                min_difficulty,
+                                || self.held() || failed.load(Relaxed),
+                            );
+                            if solved.is_err() {
+                                failed.store(true, Relaxed);
+                            }
+                            *results[i].lock().unwrap() = solved?;
+                        }
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        outcomes.into_iter().collect::<Result<(), _>>()?;
+
+        let solutions: Vec<_> = results.into_iter().map(|r| r.into_inner().unwrap()).collect();
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            for solution in solutions.iter().flatten() {
+                metrics.record_solution(solution.difficulty());
+            }
+        }
+        Ok(solutions)
+    }
+
+    /// One thread's share of [`Miner::mine`], starting `cursor` positions
+    /// into its stride; returns how far into the stride it got
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                thread = first,
+                batch = self.batch_size,
+                stride = self.threads as u64 * self.batch_size,
+                attempts = tracing::field::Empty,
+            )
+        )
+    )]
+    fn crank(
+        &self,
+        data: &[u8],
+        first: u64,
+        cursor: u64,
+        min_difficulty: u32,
+        shared: &Shared,
+    ) -> Result<u64, CrankXError> {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(self.runtime);
+        let mut memory = SolverMemory::new();
+        let mut tried = 0;
+        let mut runtime = None;
+        let mut stats = Stats::default();
+        let mut result = Ok(());
+        let started = Instant::now();
+        let mut busy = Duration::ZERO;
+        let mut attempt_start: Option<Instant> = None;
+
+        let rotation = &shared.rotation;
+        let (mut epoch, mut challenge) = rotation.get();
+        let mut seed = build_seed(challenge.as_bytes(), data, &[0; 8])?;
+        let nonce_at = seed.len() - 8;
+        let (threads, batch) = (self.threads as u64, self.batch_size);
+        let mut positions = Walk::resume(first, threads, batch, cursor);
+        let mut cursor = cursor;
+
+        while let Some(position) = positions.next() {
+            if let Some(start) = attempt_start.take() {
+                let spent = start.elapsed();
+                busy += spent;
+                self.throttle_wait(spent, tried, started, shared);
+            }
+            if position % batch == 0 {
+                if self.parked(first as usize, shared) {
+                    break;
+                }
+                if rotation.epoch.load(Relaxed) != epoch {
+                    (epoch, challenge) = rotation.get();
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(epoch, "challenge rotated, restarting nonces");
+                    seed[..32].copy_from_slice(challenge.as_bytes());
+                    positions = Walk::new(first, threads, batch);
+                    cursor = 0;
+                    continue;
+                }
+            }
+            let Some(nonce) = self.nonce_strategy.nonce(position).map(u64::to_le_bytes) else {
+                break;
+            };
+            tried += 1;
+            cursor += 1;
+            attempt_start = Some(Instant::now());
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = &self.metrics {
+                metrics.add_worker_attempts(first as usize, 1);
+            }
+
+            seed[nonce_at..].copy_from_slice(&nonce);
+            let eq = match build_equix(&builder, &seed) {
+                Ok(eq) => eq,
+                Err(CrankXError::CompilerUnavailable) => {
+                    // Don't leave the other threads cranking a doomed run
+                    shared.found.store(true, Relaxed);
+                    result = Err(CrankXError::CompilerUnavailable);
+                    break;
+                }
+                Err(_) => {
+                    if self.collect_stats {
+                        stats.record(&[]);
+                    }
+                    continue;
+                }
+            };
+            runtime = Some(eq.runtime());
+
+            let candidates = eq.solve_with_memory(&mut memory);
+            if self.collect_stats {
+                let difficulties: Vec<_> = candidates
+                    .iter()
+                    .map(|c| Solution::new(c.to_bytes(), nonce).difficulty())
+                    .collect();
+                stats.record(&difficulties);
+            }
+
+            let policy = SelectionPolicy::HighestDifficulty;
+            let Ok(solution) = policy.select(&candidates, &nonce) else {
+                continue;
+            };
+
+            if solution.difficulty() >= min_difficulty {
+                // Published whatever the epoch is now: it proves `challenge`
+                let mut best = shared.best.lock().unwrap();
+                let better = |(e, _, b): &(u64, Challenge, Solution)| {
+                    *e < epoch || (*e == epoch && *b < solution)
+                };
+                if best.as_ref().is_none_or(better) {
+                    *best = Some((epoch, challenge, solution));
+                }
+                shared.found.store(true, Relaxed);
+                break;
+            }
+        }
+
+        if let Some(start) = attempt_start {
+            busy += start.elapsed();
+        }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("attempts", tried);
+        shared.attempts.fetch_add(tried, Relaxed);
+        shared.busy_nanos.fetch_add(busy.as_nanos() as u64, Relaxed);
+        shared.stats.lock().unwrap().merge(&stats);
+        if runtime.is_some() {
+            *shared.runtime.lock().unwrap() = runtime;
+        }
+
+        result.map(|()| cursor)
+    }
+
+    /// Wait while thread `index` is scaled out; true once mining should end
+    fn parked(&self, index: usize, shared: &Shared) -> bool {
+        loop {
+            if shared.found.load(Relaxed) || self.held() {
+                return true;
+            }
+            if index < shared.active.load(Relaxed) {
+                return false;
+            }
+            thread::sleep(PARK_POLL);
+        }
+    }
+
+    /// Wait while paused; true once the stop flag is set
+    fn held(&self) -> bool {
+        while self.control.paused.load(Relaxed) && !self.stop.load(Relaxed) {
+            thread::sleep(PARK_POLL);
+        }
+        self.stop.load(Relaxed)
+    }
+
+    /// Auto-scaling controller, runs until the cranking threads finish
+    fn scale(&self, auto_scale: AutoScale, shared: &Shared) {
+        let mut next = Instant::now() + auto_scale.interval;
+        while !shared.done.load(Relaxed) {
+            if Instant::now() < next {
+                thread::sleep(PARK_POLL);
+                continue;
+            }
+            next += auto_scale.interval;
+            let ours = shared.active.load(Relaxed);
+            if let Some(target) = auto_scale.target(ours, self.threads) {
+                shared.active.store(target, Relaxed);
+            }
+        }
+    }
+
+    /// Throttle one thread after an attempt that took `spent`, its `tried`th
+    /// since `started`
+    fn throttle_wait(&self, spent: Duration, tried: u64, started: Instant, shared: &Shared) {
+        let wait = match self.throttle {
+            Throttle::Off => return,
+            Throttle::DutyCycle(duty) => {
+                let duty = duty.clamp(0.01, 1.0);
+                spent.mul_f64((1.0 - duty) / duty)
+            }
+            Throttle::MaxRate(rate) => {
+                let per_thread = rate / self.threads as f64;
+                let due = Duration::try_from_secs_f64(tried as f64 / per_thread)
+                    .unwrap_or(Duration::MAX);
+                due.saturating_sub(started.elapsed())
+            }
+        };
+
+        // Waits end early on a stop, a shutdown or another thread's find
+        let deadline = Instant::now().checked_add(wait);
+        let mut running = self.control.running.lock().unwrap();
+        while !shared.found.load(Relaxed) && !self.stop.load(Relaxed) {
+            let left = deadline.map_or(PARK_POLL, |d| d.saturating_duration_since(Instant::now()));
+            if left.is_zero() {
+                break;
+            }
+            running = self.control.wake.wait_timeout(running, left.min(PARK_POLL)).unwrap().0;
+        }
+    }
+}
+
+/// Search `data` upward from nonce zero until a solution reaches
+/// `min_difficulty` or `halt` returns true
+pub(crate) fn solve_from_zero(
+    builder: &EquiXBuilder,
+    memory: &mut SolverMemory,
+    challenge: &Challenge,
+    data: &[u8],
+    min_difficulty: u32,
+    halt: impl Fn() -> bool,
+) -> Result<Option<Solution>, CrankXError> {
+    let mut seed = build_seed(challenge.as_bytes(), data, &[0; 8])?;
+    let mut found = None;
+    search_nonces(
+        builder,
+        memory,
+        &mut seed,
+        0..=u64::MAX,
+        SelectionPolicy::HighestDifficulty,
+        halt,
+        |solution| {
+            let qualifies = solution.difficulty() >= min_difficulty;
+            if qualifies {
+                found = Some(solution);
+            }
+            qualifies
+        },
+    )?;
+    Ok(found)
+}
+
+/// Search positions one thread of [`Miner::mine`] walks: batches `first`,
+/// `first + threads`, ... of `batch` consecutive positions each, ending
+/// past `u64::MAX`
+struct Walk {
+    threads: u64,
+    batch: u64,
+    next: Option<u64>,
+}
+
+impl Walk {
+    fn new(first: u64, threads: u64, batch: u64) -> Self {
+        Self::resume(first, threads, batch, 0)
+    }
+
+    /// The same walk with its first `skip` positions already taken
+    fn resume(first: u64, threads: u64, batch: u64, skip: u64) -> Self {
+        let batch_index = (skip / batch).checked_mul(threads).and_then(|b| b.checked_add(first));
+        let next = batch_index
+            .and_then(|b| b.checked_mul(batch))
+            .and_then(|start| start.checked_add(skip % batch));
+        Self { threads, batch, next }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let position = self.next?;
+        let step = if position % self.batch == self.batch - 1 {
+            // Over the other threads' batches to this thread's next one
+            (self.threads - 1).checked_mul(self.batch).and_then(|s| s.checked_add(1))
+        } else {
+            Some(1)
+        };
+        self.next = step.and_then(|s| position.checked_add(s));
+        Some(position)
+    }
+}
+
+/// How often parked threads and the scaling controller check for changes
+const PARK_POLL: Duration = Duration::from_millis(10);
+
+/// State the threads of one [`Miner::mine`] call share
+#[derive(Default)]
+struct Shared {
+    found: AtomicBool,
+    /// Every cranking thread has returned
+    done: AtomicBool,
+    /// Threads allowed to crank; the rest park
+    active: AtomicUsize,
+    attempts: AtomicU64,
+    busy_nanos: AtomicU64,
+    /// Challenge the threads crank, swapped by [`Miner::update_challenge`]
+    rotation: Arc<Rotation>,
+    /// Best solution so far, with the rotation epoch and challenge it was
+    /// found under
+    best: Mutex<Option<(u64, Challenge, Solution)>>,
+    runtime: Mutex<Option<Runtime>>,
+    stats: Mutex<Stats>,
+}
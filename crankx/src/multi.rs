@@ -0,0 +1,80 @@
+// Multiple independent proofs per segment
+// Requiring K solutions under distinct nonces multiplies the work needed per
+// (challenge, segment), which raises the cost of outsourcing the proof.
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::{
+    build_seed, check_segment_size, search_nonces, verify_seed, Challenge, CrankXError,
+    SelectionPolicy, Solution, DEFAULT_RUNTIME,
+};
+
+/// Find `k` solutions with distinct nonces, each at least `min_difficulty`
+///
+/// Nonces are searched upward from zero. This loops until `k` qualifying
+/// solutions are found, so `min_difficulty` must be reachable.
+pub fn solve_k<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    k: usize,
+    min_difficulty: u32,
+) -> Result<Vec<Solution>, CrankXError> {
+    check_segment_size::<N>();
+    let mut seed = build_seed(challenge.into().as_bytes(), data, &[0; 8])?;
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let mut solutions = Vec::with_capacity(k);
+    if k == 0 {
+        return Ok(solutions);
+    }
+
+    // Any of a seed's solutions verifies, so keep the best one
+    search_nonces(
+        &builder,
+        &mut SolverMemory::new(),
+        &mut seed,
+        0..=u64::MAX,
+        SelectionPolicy::HighestDifficulty,
+        || false,
+        |solution| {
+            if solution.difficulty() >= min_difficulty {
+                solutions.push(solution);
+            }
+            solutions.len() == k
+        },
+    )?;
+    Ok(solutions)
+}
+
+/// Verify exactly `k` solutions with distinct nonces, each at least `min_difficulty`
+pub fn verify_k<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    solutions: &[Solution],
+    k: usize,
+    min_difficulty: u32,
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    if solutions.len() != k {
+        return Err(CrankXError::ProofCount { expected: k, got: solutions.len() });
+    }
+
+    let challenge = challenge.into();
+    for (i, solution) in solutions.iter().enumerate() {
+        if solutions[..i].iter().any(|s| s.n == solution.n) {
+            return Err(CrankXError::DuplicateNonce);
+        }
+
+        let difficulty = solution.difficulty();
+        if difficulty < min_difficulty {
+            return Err(CrankXError::InsufficientDifficulty {
+                required: min_difficulty,
+                actual: difficulty,
+            });
+        }
+
+        verify_seed(&build_seed(challenge.as_bytes(), data, &solution.n)?, &solution.d)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+// Lazy nonce search as an iterator
+// Walks nonces upward, rewriting the nonce in one seed buffer and solving it
+// with one reused SolverMemory, and yields only the solutions that qualify,
+// so callers compose the search with `take`, `take_while`, `find` and friends
+// instead of hand-written loops.
+// Fleets split the 64-bit nonce space with `partition_nonces` (contiguous
+// slices) or `strided_nonces` (interleaved), so no two workers overlap.
+// Miners that don't coordinate pick a `NonceStrategy` instead: starting at a
+// random or identity-derived offset makes two of them on the same segment
+// unlikely to crank the same nonces. A strategy maps search positions
+// 0, 1, 2, ... to nonces, so threads inside one miner still split positions
+// by stride on top of it.
+
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::RangeInclusive;
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::{
+    build_seed, check_segment_size, keccak, search_nonces, solve_seed_with_builder, Challenge,
+    Nonce, SelectionPolicy, Solution, DEFAULT_RUNTIME,
+};
+
+/// Domain tag hashed in front of the identity by [`NonceStrategy::for_identity`]
+pub const IDENTITY_DOMAIN: &[u8] = b"crankx:nonce-start:v1";
+
+/// Where a search starts in the nonce space and how it walks from there
+///
+/// Offsets hold the start they were built with, so a strategy recorded in
+/// [`Stats`](crate::stats::Stats) says exactly which nonces were covered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonceStrategy {
+    /// Nonce zero upward
+    #[default]
+    Sequential,
+    /// Upward from a random start, wrapping past `u64::MAX`; see
+    /// [`NonceStrategy::random`]
+    Random { start: u64 },
+    /// Upward from an offset derived from a miner's identity, wrapping; see
+    /// [`NonceStrategy::for_identity`]
+    Identity { start: u64 },
+    /// Every `count`-th nonce from `index`, as worker `index` of `count`
+    Strided { index: u64, count: u64 },
+}
+
+impl NonceStrategy {
+    /// Start at a fresh random 64-bit nonce
+    ///
+    /// Drawn from the standard library's per-process hash seed: unpredictable
+    /// enough to spread miners apart, not for anything secret.
+    pub fn random() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        let start = std::collections::hash_map::RandomState::new().build_hasher().finish();
+        Self::Random { start }
+    }
+
+    /// Start at an offset fixed by `identity`, such as the miner's public
+    /// key: `u64::from_le_bytes(keccak256(IDENTITY_DOMAIN || identity)[..8])`
+    pub fn for_identity(identity: &[u8]) -> Self {
+        let hash = keccak(&[IDENTITY_DOMAIN, identity]);
+        Self::Identity { start: u64::from_le_bytes(hash[..8].try_into().unwrap()) }
+    }
+
+    /// Nonce at search position `position`, `None` past the end of a stride
+    pub fn nonce(&self, position: u64) -> Option<u64> {
+        match *self {
+            Self::Sequential => Some(position),
+            Self::Random { start } | Self::Identity { start } => Some(start.wrapping_add(position)),
+            Self::Strided { index, count } => {
+                position.checked_mul(count.max(1))?.checked_add(index)
+            }
+        }
+    }
+
+    /// Nonces for thread `index` of `count` sharing this strategy, each
+    /// taking every `count`-th position
+    ///
+    /// With [`NonceStrategy::Sequential`] this is [`strided_nonces`]. Panics
+    /// if `index >= count`.
+    pub fn walk(self, index: u64, count: u64) -> impl Iterator<Item = u64> {
+        strided_nonces(index, count).map_while(move |position| self.nonce(position))
+    }
+}
+
+/// Iterator over `(nonce, solution)` pairs for one segment
+///
+/// Seeds without a solution, or whose solution falls short of the minimum
+/// difficulty, are skipped. Iteration ends after nonce `u64::MAX`.
+pub struct Nonces<'a> {
+    /// `challenge || data || nonce`, with only the nonce rewritten per step;
+    /// empty if it couldn't be built, which ends the iteration
+    seed: Vec<u8>,
+    data: PhantomData<&'a [u8]>,
+    next: Option<u64>,
+    min_difficulty: u32,
+    policy: SelectionPolicy,
+    builder: EquiXBuilder,
+    memory: SolverMemory,
+}
+
+impl<'a> Nonces<'a> {
+    /// Every solvable nonce from zero upward, first solution per seed
+    pub fn new<const N: usize>(challenge: impl Into<Challenge>, data: &'a [u8; N]) -> Self {
+        check_segment_size::<N>();
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(DEFAULT_RUNTIME);
+
+        Self {
+            seed: build_seed(challenge.into().as_bytes(), data, &[0; 8]).unwrap_or_default(),
+            data: PhantomData,
+            next: Some(0),
+            min_difficulty: 0,
+            policy: SelectionPolicy::First,
+            builder,
+            memory: SolverMemory::new(),
+        }
+    }
+
+    /// Start the search at `nonce` instead of zero
+    pub fn starting_at(mut self, nonce: impl Into<Nonce>) -> Self {
+        self.next = Some(nonce.into().to_u64());
+        self
+    }
+
+    /// Only yield solutions of at least `min_difficulty`
+    pub fn filter_difficulty(mut self, min_difficulty: u32) -> Self {
+        self.min_difficulty = min_difficulty;
+        self
+    }
+
+    /// Pick among each seed's solutions with `policy`
+    pub fn policy(mut self, policy: SelectionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl Iterator for Nonces<'_> {
+    type Item = (Nonce, Solution);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(nonce_at) = self.seed.len().checked_sub(8) else {
+            self.next = None;
+            return None;
+        };
+        loop {
+            let nonce = self.next?;
+            self.next = nonce.checked_add(1);
+
+            let nonce = nonce.to_le_bytes();
+            self.seed[nonce_at..].copy_from_slice(&nonce);
+
+            let (builder, memory) = (&self.builder, &mut self.memory);
+            let solution =
+                solve_seed_with_builder(builder, memory, &self.seed, &nonce, self.policy);
+            if let Ok(solution) = solution {
+                if solution.difficulty() >= self.min_difficulty {
+                    return Some((Nonce(nonce), solution));
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Nonces<'_> {}
+
+/// Contiguous slice of the nonce space for worker `index` of `count`
+///
+/// Slices are disjoint, differ in size by at most one, and together cover
+/// every nonce. Panics if `index >= count`.
+pub fn partition_nonces(index: u64, count: u64) -> RangeInclusive<u64> {
+    assert!(index < count, "worker index {index} out of range for {count} workers");
+
+    let bound = |i: u64| ((i as u128) << 64) / count as u128;
+    let start = bound(index) as u64;
+    let end = (bound(index + 1) - 1) as u64;
+    start..=end
+}
+
+/// Every `count`-th nonce starting at `index`, for worker `index` of `count`
+///
+/// Panics if `index >= count`.
+pub fn strided_nonces(index: u64, count: u64) -> impl Iterator<Item = u64> {
+    assert!(index < count, "worker index {index} out of range for {count} workers");
+    (index..=u64::MAX).step_by(usize::try_from(count).unwrap_or(usize::MAX))
+}
+
+/// First solution of at least `min_difficulty` among the first `attempts`
+/// nonces `strategy` visits
+pub fn solve_range<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    strategy: NonceStrategy,
+    attempts: u64,
+    min_difficulty: u32,
+) -> Option<(Nonce, Solution)> {
+    check_segment_size::<N>();
+    let mut seed = build_seed(challenge.into().as_bytes(), data, &[0; 8]).ok()?;
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let mut found = None;
+
+    let nonces = strategy.walk(0, 1).take(usize::try_from(attempts).unwrap_or(usize::MAX));
+    search_nonces(
+        &builder,
+        &mut SolverMemory::new(),
+        &mut seed,
+        nonces,
+        SelectionPolicy::First,
+        || false,
+        |solution| {
+            let qualifies = solution.difficulty() >= min_difficulty;
+            if qualifies {
+                found = Some(solution);
+            }
+            qualifies
+        },
+    )
+    .ok()?;
+    found.map(|solution| (Nonce(solution.n), solution))
+}
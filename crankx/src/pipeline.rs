@@ -0,0 +1,152 @@
+// Reader -> solver -> submitter pipeline over bounded channels
+// Each stage is a plain function over channel ends so callers can mix their
+// own stages in; `run` wires the usual shape. Channels are bounded, so a slow
+// submitter blocks the solvers, which block the reader, instead of proofs
+// piling up in memory. Dropping a stage's receiver makes the stage feeding
+// it stop at its next send. The channels are crossbeam's, whose receivers
+// are multi-consumer: solver threads share one without taking a lock per item.
+
+use std::thread;
+
+pub use crossbeam_channel::{bounded, Receiver, Sender};
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::miner::solve_from_zero;
+use crate::segment::SegmentProvider;
+use crate::{Challenge, CrankXError, Solution, DEFAULT_RUNTIME};
+
+/// Segment bytes on their way to a solver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentWork {
+    pub index: u64,
+    pub data: Vec<u8>,
+}
+
+/// A solved segment on its way to the submitter
+#[derive(Debug, PartialEq, Eq)]
+pub struct Proof {
+    pub index: u64,
+    pub solution: Solution,
+}
+
+/// Failure of one pipeline stage
+#[derive(Debug)]
+pub enum PipelineError<R, S> {
+    Read(R),
+    Solve(CrankXError),
+    Submit(S),
+}
+
+/// Reader stage: send segments `indices` of `provider` to `out`
+pub fn read_segments<P: SegmentProvider>(
+    provider: &P,
+    indices: impl IntoIterator<Item = u64>,
+    out: &Sender<SegmentWork>,
+) -> Result<(), P::Error> {
+    for index in indices {
+        let data = provider.segment(index)?.into_owned();
+        if out.send(SegmentWork { index, data }).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Solver stage: crank each segment from nonce zero to `min_difficulty`
+pub fn solve_segments(
+    challenge: impl Into<Challenge>,
+    min_difficulty: u32,
+    input: &Receiver<SegmentWork>,
+    out: &Sender<Proof>,
+) -> Result<(), CrankXError> {
+    let challenge = challenge.into();
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let mut memory = SolverMemory::new();
+
+    while let Ok(work) = input.recv() {
+        let never = || false;
+        let solved =
+            solve_from_zero(&builder, &mut memory, &challenge, &work.data, min_difficulty, never);
+        let Some(solution) = solved? else { continue };
+        if out.send(Proof { index: work.index, solution }).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Submitter stage: hand each proof to `submit`, returning how many went out
+pub fn submit_proofs<E>(
+    input: &Receiver<Proof>,
+    mut submit: impl FnMut(Proof) -> Result<(), E>,
+) -> Result<u64, E> {
+    let mut submitted = 0;
+    while let Ok(proof) = input.recv() {
+        submit(proof)?;
+        submitted += 1;
+    }
+    Ok(submitted)
+}
+
+/// Shape of a [`run`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineConfig {
+    pub solvers: usize,
+    /// Capacity of each channel between stages; zero hands each item
+    /// straight from sender to receiver
+    pub capacity: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        let solvers = thread::available_parallelism().map_or(1, |n| n.get());
+        Self { solvers, capacity: 2 * solvers }
+    }
+}
+
+/// Read, solve and submit every segment in `indices`, returning how many
+/// proofs were submitted
+///
+/// A failed stage stops taking input, so the stages feeding it stop at
+/// their next send. Errors are reported reader first, then solver, then
+/// submitter.
+pub fn run<P, E>(
+    provider: &P,
+    indices: impl IntoIterator<Item = u64> + Send,
+    challenge: impl Into<Challenge>,
+    min_difficulty: u32,
+    config: PipelineConfig,
+    submit: impl FnMut(Proof) -> Result<(), E> + Send,
+) -> Result<u64, PipelineError<P::Error, E>>
+where
+    P: SegmentProvider + Sync,
+    P::Error: Send,
+    E: Send,
+{
+    let challenge = challenge.into();
+    let (segment_tx, segment_rx) = bounded(config.capacity);
+    let (proof_tx, proof_rx) = bounded(config.capacity);
+
+    thread::scope(|s| {
+        let reader = s.spawn(move || read_segments(provider, indices, &segment_tx));
+        let solvers: Vec<_> = (0..config.solvers.max(1))
+            .map(|_| {
+                let (input, out) = (segment_rx.clone(), proof_tx.clone());
+                s.spawn(move || solve_segments(challenge, min_difficulty, &input, &out))
+            })
+            .collect();
+        // Only the stages hold channel ends from here on
+        drop((segment_rx, proof_tx));
+
+        let submitted = submit_proofs(&proof_rx, submit);
+        // A failed submitter must unblock solvers waiting to send
+        drop(proof_rx);
+
+        let solved = solvers.into_iter().try_for_each(|h| h.join().unwrap());
+        reader.join().unwrap().map_err(PipelineError::Read)?;
+        solved.map_err(PipelineError::Solve)?;
+        submitted.map_err(PipelineError::Submit)
+    })
+}
@@ -0,0 +1,42 @@
+// Choosing among the several EquiX solutions a single seed can have
+// Verification accepts any of them, so this is purely a prover-side choice.
+
+use crate::{CrankXError, Solution};
+
+/// Rule for picking one of a seed's EquiX solutions
+///
+/// [`SelectionPolicy::First`] is canonical for consensus: it is what
+/// [`crate::solve`] and [`crate::solve_with_memory`] use and what the golden
+/// test vectors encode. Verifiers never enforce a policy, so protocols that
+/// reward difficulty may let provers use [`SelectionPolicy::HighestDifficulty`]
+/// without any change on the verifying side.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionPolicy {
+    /// The first solution the solver returns (canonical)
+    #[default]
+    First,
+    /// The solution with the lexicographically lowest raw digest
+    LowestDigest,
+    /// The solution whose final hash has the most leading zeros, ties going
+    /// to the lowest hash
+    HighestDifficulty,
+}
+
+impl SelectionPolicy {
+    /// Pick one of `solutions` for `nonce`
+    pub(crate) fn select(
+        self,
+        solutions: &[equix::Solution],
+        nonce: &[u8; 8],
+    ) -> Result<Solution, CrankXError> {
+        let mut digests = solutions.iter().map(|s| s.to_bytes());
+
+        let best = match self {
+            Self::First => digests.next().map(|d| Solution::new(d, *nonce)),
+            Self::LowestDigest => digests.min().map(|d| Solution::new(d, *nonce)),
+            Self::HighestDifficulty => digests.map(|d| Solution::new(d, *nonce)).max(),
+        };
+
+        best.ok_or(CrankXError::NoSolution)
+    }
+}
@@ -0,0 +1,25 @@
+// Work distribution across machines (feature = "pool")
+// A coordinator hands out jobs (challenge + segment index + target + nonce
+// range) over TCP; workers crank their slice of the nonce space and submit
+// shares back. Frames are `u32 LE length || tag || payload`.
+// On one large host the same protocol runs over a unix socket, so prover
+// processes pinned to separate NUMA nodes or containers share a challenge,
+// get disjoint nonce ranges and funnel every share to one submitter.
+
+mod coordinator;
+mod protocol;
+pub mod shares;
+mod transport;
+mod worker;
+
+pub use coordinator::Coordinator;
+pub use crate::job::{Job, Share};
+pub use protocol::{read_message, write_message, Message, MAX_FRAME_LEN};
+pub use worker::Worker;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Unix time in seconds, as [`Job::expires_at`] counts it
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
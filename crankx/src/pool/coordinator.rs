@@ -0,0 +1,333 @@
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::protocol::{read_message, write_message, Message};
+use super::transport::Conn;
+use super::unix_now;
+use crate::job::{Job, Share};
+use crate::nonces::partition_nonces;
+use crate::Challenge;
+
+type Workers = Arc<Mutex<Registry>>;
+
+/// Workers that have said hello and not yet disconnected
+#[derive(Default)]
+struct Registry {
+    next_conn: u64,
+    connected: Vec<Connected>,
+    /// Set by [`Coordinator::shutdown`]; late handshakes are turned away
+    closed: bool,
+}
+
+/// One worker's connection
+struct Connected {
+    /// Unique per connection, unlike the id the worker chose
+    conn: u64,
+    /// Queue the connection's writer thread sends from
+    jobs: Sender<Job>,
+    /// Handle for shutting the socket down
+    stream: Box<dyn Conn>,
+    writer: JoinHandle<()>,
+}
+
+/// Drop connection `conn`, closing its socket so both its threads end
+fn reap(workers: &Workers, conn: u64) {
+    let mut registry = workers.lock().unwrap();
+    if let Some(i) = registry.connected.iter().position(|c| c.conn == conn) {
+        let _ = registry.connected.swap_remove(i).stream.shutdown_conn();
+    }
+}
+
+/// How long a new connection has to say hello before it is dropped
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// First pause after a failed accept, doubled per failure up to the max
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(10);
+const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Accepts workers, splits jobs across them and collects their shares
+///
+/// A worker's slice of the nonce space is freed as soon as its connection
+/// closes or fails. Dropping the coordinator does what
+/// [`Coordinator::shutdown`] does.
+pub struct Coordinator {
+    addr: Option<SocketAddr>,
+    workers: Workers,
+    shares: Receiver<Share>,
+    stop: Arc<AtomicBool>,
+    accept_thread: Option<JoinHandle<()>>,
+    /// How long dispatched jobs stay open, `None` for no expiry
+    job_ttl: Option<Duration>,
+    /// Wakes the accept thread out of a blocking accept; false if it couldn't
+    wake: Box<dyn Fn() -> bool + Send>,
+    /// Socket file to remove on shutdown
+    #[cfg(unix)]
+    path: Option<PathBuf>,
+}
+
+impl Coordinator {
+    /// Listen on `addr` and start accepting workers in the background
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let wake = move || TcpStream::connect(reachable(addr)).is_ok();
+        let mut coordinator =
+            Self::start(move || listener.accept().map(|(stream, _)| stream), Box::new(wake));
+        coordinator.addr = Some(addr);
+        Ok(coordinator)
+    }
+
+    /// Listen on a unix socket at `path`, for workers in other processes on
+    /// this host
+    ///
+    /// A socket file left behind by a coordinator that has exited is
+    /// replaced; one that still accepts connections, or anything at `path`
+    /// that isn't a socket, is `AddrInUse`. The socket file is removed on
+    /// shutdown.
+    #[cfg(unix)]
+    pub fn bind_unix(path: impl AsRef<Path>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = path.as_ref();
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if !meta.file_type().is_socket() => {
+                let message = format!("{} exists and is not a socket", path.display());
+                return Err(io::Error::new(io::ErrorKind::AddrInUse, message));
+            }
+            Ok(_) if UnixStream::connect(path).is_ok() => {
+                return Err(io::ErrorKind::AddrInUse.into());
+            }
+            Ok(_) => std::fs::remove_file(path)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        let listener = UnixListener::bind(path)?;
+        let wake_path = path.to_path_buf();
+        let wake = move || UnixStream::connect(&wake_path).is_ok();
+        let mut coordinator =
+            Self::start(move || listener.accept().map(|(stream, _)| stream), Box::new(wake));
+        coordinator.path = Some(path.to_path_buf());
+        Ok(coordinator)
+    }
+
+    /// Accept workers from `next` on a background thread
+    fn start<C: Conn>(
+        mut next: impl FnMut() -> io::Result<C> + Send + 'static,
+        wake: Box<dyn Fn() -> bool + Send>,
+    ) -> Self {
+        let workers = Workers::default();
+        let stop = Arc::<AtomicBool>::default();
+        let (tx, shares) = mpsc::channel();
+
+        let (accepted, stopped) = (workers.clone(), stop.clone());
+        let accept_thread = thread::spawn(move || {
+            let mut backoff = ACCEPT_BACKOFF;
+            while !stopped.load(Relaxed) {
+                // Accept errors (out of file descriptors, say) tend to
+                // repeat, so wait a little longer after each one
+                let Ok(stream) = next() else {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                    continue;
+                };
+                backoff = ACCEPT_BACKOFF;
+                if stopped.load(Relaxed) {
+                    break;
+                }
+
+                // Handshakes run apart from the accept loop so a silent
+                // client can't hold up the workers behind it; one that
+                // can't complete the handshake is just dropped
+                let (accepted, tx) = (accepted.clone(), tx.clone());
+                thread::spawn(move || accept(Box::new(stream), &accepted, tx));
+            }
+        });
+
+        Self {
+            addr: None,
+            workers,
+            shares,
+            stop,
+            accept_thread: Some(accept_thread),
+            job_ttl: None,
+            wake,
+            #[cfg(unix)]
+            path: None,
+        }
+    }
+
+    /// Expire each dispatched job `ttl` after it is sent, rounded down to
+    /// whole seconds; workers stop searching it then
+    ///
+    /// Jobs never expire unless this is set.
+    pub fn job_ttl(mut self, ttl: Duration) -> Self {
+        self.job_ttl = Some(ttl);
+        self
+    }
+
+    /// Address workers should connect to, `None` for a unix socket
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.addr
+    }
+
+    /// Workers currently connected
+    pub fn worker_count(&self) -> usize {
+        self.workers.lock().unwrap().connected.len()
+    }
+
+    /// Send job `id` to every worker, each with a disjoint slice of the nonce space
+    ///
+    /// Jobs are queued for each worker's own writer thread, so a stalled
+    /// connection doesn't hold up the others. Returns how many workers the
+    /// job was queued for.
+    pub fn dispatch(
+        &self,
+        id: u64,
+        challenge: impl Into<Challenge>,
+        segment: u64,
+        target: u32,
+    ) -> usize {
+        let challenge = challenge.into();
+        let expires_at = self.job_ttl.map(|ttl| unix_now().saturating_add(ttl.as_secs()));
+        let mut registry = self.workers.lock().unwrap();
+
+        // A writer thread that has exited is reaped along with its
+        // connection; until then, leave its slice out
+        registry.connected.retain(|c| !c.writer.is_finished());
+        let count = registry.connected.len() as u64;
+
+        for (i, worker) in (0..).zip(&registry.connected) {
+            let (nonce_start, nonce_end) = partition_nonces(i, count).into_inner();
+            let job = Job { id, challenge, segment, target, nonce_start, nonce_end, expires_at };
+            // Can't fail: the writer holds the receiver until it finishes
+            let _ = worker.jobs.send(job);
+        }
+        registry.connected.len()
+    }
+
+    /// Shares submitted by any worker, in arrival order
+    pub fn shares(&self) -> &Receiver<Share> {
+        &self.shares
+    }
+
+    /// Stop accepting, close every worker connection and wait for the
+    /// accept and writer threads to finish
+    pub fn shutdown(mut self) {
+        self.close();
+    }
+
+    fn close(&mut self) {
+        self.stop.store(true, Relaxed);
+        if let Some(accept_thread) = self.accept_thread.take() {
+            // Left running if it can't be woken, rather than hanging here
+            if (self.wake)() {
+                let _ = accept_thread.join();
+            }
+        }
+
+        let connected = {
+            let mut registry = self.workers.lock().unwrap();
+            registry.closed = true;
+            std::mem::take(&mut registry.connected)
+        };
+        for worker in connected {
+            let _ = worker.stream.shutdown_conn();
+            drop(worker.jobs);
+            let _ = worker.writer.join();
+        }
+
+        // Only if it is still a socket: someone else may have replaced it
+        #[cfg(unix)]
+        if let Some(path) = self.path.take() {
+            use std::os::unix::fs::FileTypeExt;
+
+            if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+impl Drop for Coordinator {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// `addr` with an unspecified IP replaced by loopback, so the coordinator
+/// can connect to its own listener
+fn reachable(mut addr: SocketAddr) -> SocketAddr {
+    match addr.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => addr.set_ip(Ipv4Addr::LOCALHOST.into()),
+        IpAddr::V6(ip) if ip.is_unspecified() => addr.set_ip(Ipv6Addr::LOCALHOST.into()),
+        _ => {}
+    }
+    addr
+}
+
+fn accept(mut stream: Box<dyn Conn>, workers: &Workers, tx: Sender<Share>) -> io::Result<()> {
+    stream.set_read_timeout_conn(Some(HELLO_TIMEOUT))?;
+    let Message::Hello { worker_id } = read_message(&mut stream)? else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected hello"));
+    };
+    stream.set_read_timeout_conn(None)?;
+
+    let mut reader = stream.try_clone_conn()?;
+    let handle = stream.try_clone_conn()?;
+    let (jobs_tx, jobs) = mpsc::channel();
+
+    let conn = {
+        let mut registry = workers.lock().unwrap();
+        if registry.closed {
+            return Err(io::ErrorKind::ConnectionAborted.into());
+        }
+        let conn = registry.next_conn;
+        registry.next_conn += 1;
+
+        // A new job replaces any the connection hasn't been sent yet, so a
+        // slow worker skips straight to the latest. A failed write closes
+        // the socket, which ends the reader below and reaps the worker.
+        let writer = thread::spawn(move || {
+            while let Ok(mut job) = jobs.recv() {
+                while let Ok(newer) = jobs.try_recv() {
+                    job = newer;
+                }
+                if write_message(&mut stream, &Message::Job(job)).is_err() {
+                    let _ = stream.shutdown_conn();
+                    break;
+                }
+            }
+        });
+        registry.connected.push(Connected { conn, jobs: jobs_tx, stream: handle, writer });
+        conn
+    };
+
+    // Shares count for the worker that said hello on this connection; one
+    // claiming another worker's id is dropped. The worker is reaped as
+    // soon as its connection ends.
+    let workers = workers.clone();
+    thread::spawn(move || {
+        while let Ok(msg) = read_message(&mut reader) {
+            if let Message::Share(share) = msg {
+                if share.worker_id != worker_id {
+                    continue;
+                }
+                if tx.send(share).is_err() {
+                    break;
+                }
+            }
+        }
+        reap(&workers, conn);
+    });
+
+    Ok(())
+}
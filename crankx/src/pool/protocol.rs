@@ -0,0 +1,130 @@
+use std::io::{self, Read, Write};
+
+use crate::job::{Job, Share};
+use crate::{Challenge, Solution};
+
+/// Largest frame either side will read
+pub const MAX_FRAME_LEN: usize = 1024;
+
+const TAG_HELLO: u8 = 0;
+const TAG_JOB: u8 = 1;
+const TAG_SHARE: u8 = 2;
+
+/// Everything that goes over the wire
+#[derive(Debug)]
+pub enum Message {
+    /// Worker → coordinator, first frame on a connection
+    Hello { worker_id: u64 },
+    /// Coordinator → worker; replaces any job in progress
+    Job(Job),
+    /// Worker → coordinator
+    Share(Share),
+}
+
+impl Message {
+    /// Tag and payload, without the length prefix
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64);
+        match self {
+            Message::Hello { worker_id } => {
+                out.push(TAG_HELLO);
+                out.extend_from_slice(&worker_id.to_le_bytes());
+            }
+            Message::Job(job) => {
+                out.push(TAG_JOB);
+                out.extend_from_slice(&job.id.to_le_bytes());
+                out.extend_from_slice(job.challenge.as_bytes());
+                out.extend_from_slice(&job.segment.to_le_bytes());
+                out.extend_from_slice(&job.target.to_le_bytes());
+                out.extend_from_slice(&job.nonce_start.to_le_bytes());
+                out.extend_from_slice(&job.nonce_end.to_le_bytes());
+                out.push(job.expires_at.is_some() as u8);
+                out.extend_from_slice(&job.expires_at.unwrap_or(0).to_le_bytes());
+            }
+            Message::Share(share) => {
+                out.push(TAG_SHARE);
+                out.extend_from_slice(&share.job_id.to_le_bytes());
+                out.extend_from_slice(&share.worker_id.to_le_bytes());
+                out.extend_from_slice(&share.solution.to_bytes());
+            }
+        }
+        out
+    }
+
+    /// Parse a tag and payload
+    pub fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let (&tag, mut rest) = bytes.split_first().ok_or_else(malformed)?;
+
+        let msg = match tag {
+            TAG_HELLO => Message::Hello { worker_id: u64::from_le_bytes(take(&mut rest)?) },
+            TAG_JOB => {
+                let job = Job {
+                    id: u64::from_le_bytes(take(&mut rest)?),
+                    challenge: Challenge(take(&mut rest)?),
+                    segment: u64::from_le_bytes(take(&mut rest)?),
+                    target: u32::from_le_bytes(take(&mut rest)?),
+                    nonce_start: u64::from_le_bytes(take(&mut rest)?),
+                    nonce_end: u64::from_le_bytes(take(&mut rest)?),
+                    expires_at: match (take(&mut rest)?, take(&mut rest)?) {
+                        // Absent is zero padded, so every job has one encoding
+                        ([0], [0, 0, 0, 0, 0, 0, 0, 0]) => None,
+                        ([1], t) => Some(u64::from_le_bytes(t)),
+                        _ => return Err(malformed()),
+                    },
+                };
+                job.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                Message::Job(job)
+            }
+            TAG_SHARE => Message::Share(Share {
+                job_id: u64::from_le_bytes(take(&mut rest)?),
+                worker_id: u64::from_le_bytes(take(&mut rest)?),
+                solution: Solution::from_bytes(&take(&mut rest)?),
+            }),
+            _ => return Err(malformed()),
+        };
+
+        if !rest.is_empty() {
+            return Err(malformed());
+        }
+        Ok(msg)
+    }
+}
+
+/// Write one length-prefixed frame
+pub fn write_message(w: &mut impl Write, msg: &Message) -> io::Result<()> {
+    let body = msg.encode();
+    w.write_all(&(body.len() as u32).to_le_bytes())?;
+    w.write_all(&body)?;
+    w.flush()
+}
+
+/// Read one length-prefixed frame, rejecting anything over [`MAX_FRAME_LEN`]
+pub fn read_message(r: &mut impl Read) -> io::Result<Message> {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(malformed());
+    }
+
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+    Message::decode(&body)
+}
+
+fn take<const L: usize>(rest: &mut &[u8]) -> io::Result<[u8; L]> {
+    if rest.len() < L {
+        return Err(malformed());
+    }
+    let (head, tail) = rest.split_at(L);
+    *rest = tail;
+
+    let mut out = [0u8; L];
+    out.copy_from_slice(head);
+    Ok(out)
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed pool frame")
+}
@@ -0,0 +1,175 @@
+// Share validation and work accounting for pool operators
+// Checks run cheapest first: job id and nonce range, then the final hash
+// against the share target, and only then the EquiX proof itself, so junk
+// shares never cost a HashX program build. Accepted shares are credited by
+// the expected attempts their target represents, so workers on different
+// share targets are paid for the same amount of work. `SeenShares` remembers
+// each job's accepted shares by final hash, so a resubmitted share is turned
+// away instead of credited again.
+
+use std::collections::HashMap;
+
+use crate::dedup::{DedupTracker, Seen};
+pub use crate::job::Rejection;
+use crate::job::{Job, Share};
+use crate::{build_seed, verify_seed, Solution, Target};
+
+/// Outcome of [`validate_share`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareClass {
+    Invalid(Rejection),
+    /// Meets the share target only
+    Share,
+    /// Also meets the network target
+    Block,
+}
+
+/// Classify `share` submitted for `job`, whose segment bytes are `data`
+///
+/// Expiry isn't checked here; use [`Share::check`] when jobs carry one.
+pub fn validate_share(
+    job: &Job,
+    share: &Share,
+    data: &[u8],
+    share_target: &Target,
+    network_target: &Target,
+) -> ShareClass {
+    let solution = &share.solution;
+    if share.job_id != job.id {
+        return ShareClass::Invalid(Rejection::WrongJob);
+    }
+    if !job.contains(u64::from_le_bytes(solution.n)) {
+        return ShareClass::Invalid(Rejection::NonceOutOfRange);
+    }
+    if !solution.meets(share_target) {
+        return ShareClass::Invalid(Rejection::BelowTarget);
+    }
+
+    let valid = build_seed(job.challenge.as_bytes(), data, &solution.n)
+        .and_then(|seed| verify_seed(&seed, &solution.d));
+    if valid.is_err() {
+        return ShareClass::Invalid(Rejection::BadProof);
+    }
+
+    if solution.meets(network_target) {
+        ShareClass::Block
+    } else {
+        ShareClass::Share
+    }
+}
+
+/// [`validate_share`], then [`Rejection::Duplicate`] for a share `seen`
+/// already accepted for the job
+///
+/// Only valid shares are recorded, so junk never crowds out real ones.
+pub fn validate_new_share(
+    job: &Job,
+    share: &Share,
+    data: &[u8],
+    share_target: &Target,
+    network_target: &Target,
+    seen: &mut SeenShares,
+) -> ShareClass {
+    let class = validate_share(job, share, data, share_target, network_target);
+    if matches!(class, ShareClass::Invalid(_)) || seen.insert(job.id, &share.solution) {
+        class
+    } else {
+        ShareClass::Invalid(Rejection::Duplicate)
+    }
+}
+
+/// Shares accepted so far, per job
+#[derive(Debug, Clone)]
+pub struct SeenShares {
+    capacity: usize,
+    bloom_bits: usize,
+    jobs: HashMap<u64, DedupTracker>,
+}
+
+impl SeenShares {
+    /// Per job, hold up to `capacity` shares exactly and the rest in a
+    /// `bloom_bits` filter, as [`DedupTracker::new`]
+    ///
+    /// Past `capacity` a fresh share can hit the filter and be refused as a
+    /// duplicate; size it for the shares one job should see.
+    pub fn new(capacity: usize, bloom_bits: usize) -> Self {
+        Self { capacity, bloom_bits, jobs: HashMap::new() }
+    }
+
+    /// Record `solution` for `job_id`; `false` if it was already there
+    pub fn insert(&mut self, job_id: u64, solution: &Solution) -> bool {
+        let (capacity, bloom_bits) = (self.capacity, self.bloom_bits);
+        let tracker =
+            self.jobs.entry(job_id).or_insert_with(|| DedupTracker::new(capacity, bloom_bits));
+        tracker.check_solution(0, solution) == Seen::New
+    }
+
+    /// Drop `job_id`'s shares once it no longer takes submissions
+    pub fn forget(&mut self, job_id: u64) {
+        self.jobs.remove(&job_id);
+    }
+}
+
+/// Credited work for one worker
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WorkerWork {
+    pub shares: u64,
+    pub blocks: u64,
+    pub invalid: u64,
+    /// Sum of expected attempts behind each accepted share
+    pub work: f64,
+}
+
+/// Per-worker work accounting over a payout window
+#[derive(Debug, Clone, Default)]
+pub struct WorkLedger {
+    workers: HashMap<u64, WorkerWork>,
+}
+
+impl WorkLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credit `worker_id` for a share classified as `class` at `share_target`
+    pub fn record(&mut self, worker_id: u64, class: ShareClass, share_target: &Target) {
+        let entry = self.workers.entry(worker_id).or_default();
+        match class {
+            ShareClass::Invalid(_) => entry.invalid += 1,
+            ShareClass::Share | ShareClass::Block => {
+                entry.shares += 1;
+                entry.blocks += (class == ShareClass::Block) as u64;
+                entry.work += share_target.expected_attempts();
+            }
+        }
+    }
+
+    /// Counts for `worker_id`, if it submitted anything
+    pub fn worker(&self, worker_id: u64) -> Option<&WorkerWork> {
+        self.workers.get(&worker_id)
+    }
+
+    /// Every worker's counts, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &WorkerWork)> {
+        self.workers.iter().map(|(&id, work)| (id, work))
+    }
+
+    /// Work credited across all workers
+    pub fn total_work(&self) -> f64 {
+        self.workers.values().map(|w| w.work).sum()
+    }
+
+    /// Fraction of the window's work done by `worker_id`
+    pub fn work_share(&self, worker_id: u64) -> f64 {
+        let total = self.total_work();
+        match self.workers.get(&worker_id) {
+            Some(w) if total > 0.0 => w.work / total,
+            _ => 0.0,
+        }
+    }
+
+    /// Close the window: return its counts and start empty
+    pub fn drain(&mut self) -> HashMap<u64, WorkerWork> {
+        std::mem::take(&mut self.workers)
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// A connected stream the pool can speak over
+pub(crate) trait Conn: Read + Write + Send + 'static {
+    /// Second handle to the same stream, for a reader thread
+    fn try_clone_conn(&self) -> io::Result<Box<dyn Conn>>;
+
+    /// Fail reads that wait longer than `timeout`; `None` waits forever
+    fn set_read_timeout_conn(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Close both directions, waking any thread blocked on the stream
+    fn shutdown_conn(&self) -> io::Result<()>;
+}
+
+impl Conn for TcpStream {
+    fn try_clone_conn(&self) -> io::Result<Box<dyn Conn>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_read_timeout_conn(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)
+    }
+
+    fn shutdown_conn(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+}
+
+#[cfg(unix)]
+impl Conn for UnixStream {
+    fn try_clone_conn(&self) -> io::Result<Box<dyn Conn>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_read_timeout_conn(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)
+    }
+
+    fn shutdown_conn(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+}
@@ -0,0 +1,136 @@
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use super::protocol::{read_message, write_message, Message};
+use super::transport::Conn;
+use super::unix_now;
+use crate::job::{Job, Share};
+use crate::{build_seed, search_nonces, SegmentProvider, SelectionPolicy, DEFAULT_RUNTIME};
+
+/// Cranks jobs from a coordinator over segments from `provider`
+pub struct Worker<P> {
+    worker_id: u64,
+    provider: P,
+    stream: Box<dyn Conn>,
+}
+
+impl<P: SegmentProvider> Worker<P> {
+    /// Connect to a coordinator and introduce ourselves as `worker_id`
+    pub fn connect(addr: impl ToSocketAddrs, worker_id: u64, provider: P) -> io::Result<Self> {
+        Self::hello(Box::new(TcpStream::connect(addr)?), worker_id, provider)
+    }
+
+    /// Connect to a coordinator on this host listening at unix socket `path`
+    #[cfg(unix)]
+    pub fn connect_unix(path: impl AsRef<Path>, worker_id: u64, provider: P) -> io::Result<Self> {
+        Self::hello(Box::new(UnixStream::connect(path)?), worker_id, provider)
+    }
+
+    fn hello(mut stream: Box<dyn Conn>, worker_id: u64, provider: P) -> io::Result<Self> {
+        write_message(&mut stream, &Message::Hello { worker_id })?;
+        Ok(Self { worker_id, provider, stream })
+    }
+
+    /// Crank jobs until the coordinator disconnects
+    ///
+    /// A new job preempts the current one immediately, and an expired one is
+    /// dropped, as is one whose segment the provider can't supply or that
+    /// can't be seeded. Every solution at or above the job's target is
+    /// submitted as a share.
+    pub fn run(mut self) -> io::Result<()> {
+        let jobs = spawn_reader(self.stream.try_clone_conn()?);
+        let mut memory = SolverMemory::new();
+
+        let Ok(mut job) = jobs.recv() else {
+            return Ok(());
+        };
+
+        loop {
+            match self.crank(&mut memory, &job, &jobs)? {
+                Some(next) => job = next,
+                None => match jobs.recv() {
+                    Ok(next) => job = next,
+                    Err(_) => return Ok(()),
+                },
+            }
+        }
+    }
+
+    /// Work through `job`'s nonce range; returns a newer job if one preempted it
+    ///
+    /// A job this worker can't crank is skipped, leaving it for the others.
+    fn crank(
+        &mut self,
+        memory: &mut SolverMemory,
+        job: &Job,
+        jobs: &Receiver<Job>,
+    ) -> io::Result<Option<Job>> {
+        let Ok(data) = self.provider.segment(job.segment) else {
+            return Ok(None);
+        };
+        let Ok(mut seed) = build_seed(job.challenge.as_bytes(), &data, &[0; 8]) else {
+            return Ok(None);
+        };
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(DEFAULT_RUNTIME);
+
+        // What ended the search early, if anything did
+        let mut preempted = None;
+        let mut failed = None;
+        let stream = &mut self.stream;
+        search_nonces(
+            &builder,
+            memory,
+            &mut seed,
+            job.nonces(),
+            SelectionPolicy::First,
+            || {
+                if job.is_expired(unix_now()) {
+                    return true;
+                }
+                match jobs.try_recv() {
+                    Ok(next) => preempted = Some(Some(next)),
+                    Err(TryRecvError::Disconnected) => preempted = Some(None),
+                    Err(TryRecvError::Empty) => {}
+                }
+                preempted.is_some()
+            },
+            |solution| {
+                if solution.difficulty() < job.target {
+                    return false;
+                }
+                let share = Share { job_id: job.id, worker_id: self.worker_id, solution };
+                failed = write_message(stream, &Message::Share(share)).err();
+                failed.is_some()
+            },
+        )
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+        match failed {
+            Some(e) => Err(e),
+            None => Ok(preempted.flatten()),
+        }
+    }
+}
+
+fn spawn_reader(mut stream: Box<dyn Conn>) -> Receiver<Job> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        while let Ok(msg) = read_message(&mut stream) {
+            if let Message::Job(job) = msg {
+                if tx.send(job).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
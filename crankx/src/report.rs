@@ -0,0 +1,105 @@
+// What a batch verification found, item by item
+// The fail-fast batch verifiers answer "is all of it good?"; an auditor
+// replaying an epoch needs to know which proofs failed and why. A
+// `VerificationReport` keeps one outcome per item in input order, with the
+// time each took, plus totals. Malformed items never reached the EquiX
+// check (a segment that can't be seeded, a digest whose items aren't in
+// canonical order, a segment the provider couldn't produce); invalid ones
+// reached it and failed; below-difficulty ones are genuine proofs that
+// don't meet the minimum asked for.
+
+use std::time::Duration;
+
+/// How one proof fared
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "outcome", rename_all = "snake_case")
+)]
+pub enum Outcome {
+    /// Verified, and at least the minimum difficulty
+    Valid { difficulty: u32 },
+    /// Well-formed, but EquiX rejects the digest for this seed
+    InvalidSolution,
+    /// Couldn't be checked at all
+    Malformed { reason: String },
+    /// Valid, but short of the minimum difficulty
+    BelowDifficulty { difficulty: u32, required: u32 },
+}
+
+impl Outcome {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid { .. })
+    }
+}
+
+/// One item's outcome and how long checking it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemReport {
+    /// Position in the input
+    pub index: usize,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub outcome: Outcome,
+    pub elapsed_ns: u64,
+}
+
+/// Items per outcome
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutcomeCounts {
+    pub valid: usize,
+    pub invalid_solution: usize,
+    pub malformed: usize,
+    pub below_difficulty: usize,
+}
+
+impl OutcomeCounts {
+    pub fn total(&self) -> usize {
+        self.valid + self.invalid_solution + self.malformed + self.below_difficulty
+    }
+}
+
+/// Outcome of every item in a batch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerificationReport {
+    /// In input order
+    pub items: Vec<ItemReport>,
+    pub counts: OutcomeCounts,
+    /// Wall-clock time for the whole batch; with parallel verification less
+    /// than the items' sum
+    pub elapsed_ns: u64,
+}
+
+impl VerificationReport {
+    /// Report over `items` (in any order), tallying the counts
+    pub fn new(mut items: Vec<ItemReport>, elapsed: Duration) -> Self {
+        items.sort_by_key(|item| item.index);
+        let mut counts = OutcomeCounts::default();
+        for item in &items {
+            match item.outcome {
+                Outcome::Valid { .. } => counts.valid += 1,
+                Outcome::InvalidSolution => counts.invalid_solution += 1,
+                Outcome::Malformed { .. } => counts.malformed += 1,
+                Outcome::BelowDifficulty { .. } => counts.below_difficulty += 1,
+            }
+        }
+        Self { items, counts, elapsed_ns: elapsed.as_nanos().try_into().unwrap_or(u64::MAX) }
+    }
+
+    /// Every item was valid
+    pub fn all_valid(&self) -> bool {
+        self.counts.valid == self.items.len()
+    }
+
+    /// The items that weren't valid, in input order
+    pub fn failures(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items.iter().filter(|item| !item.outcome.is_valid())
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_ns)
+    }
+}
@@ -0,0 +1,96 @@
+// Difficulty retargeting
+// Difficulty counts leading zero bits, so each step doubles the expected work.
+// After every window of solutions the average interval is compared with the
+// target and difficulty moves by the number of doublings between them, rounded
+// in log space and capped per window. Integer-only, so every platform agrees.
+
+use std::time::Duration;
+
+/// Tuning for [`Retarget`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetargetParams {
+    /// Desired time between solutions
+    pub target_interval: Duration,
+    /// Solutions per adjustment
+    pub window: usize,
+    /// Largest change, in bits, applied in one adjustment
+    pub max_step: u32,
+    pub min_difficulty: u32,
+    pub max_difficulty: u32,
+}
+
+impl Default for RetargetParams {
+    fn default() -> Self {
+        Self {
+            target_interval: Duration::from_secs(60),
+            window: 16,
+            max_step: 2,
+            min_difficulty: 0,
+            max_difficulty: 256,
+        }
+    }
+}
+
+/// Difficulty after observing an average interval of `observed` at `current`
+pub fn adjust(current: u32, observed: Duration, params: &RetargetParams) -> u32 {
+    let observed = observed.as_nanos();
+    let target = params.target_interval.as_nanos();
+
+    let next = if observed < target {
+        current.saturating_add(doublings(observed, target, params.max_step))
+    } else {
+        current.saturating_sub(doublings(target, observed, params.max_step))
+    };
+
+    next.clamp(params.min_difficulty, params.max_difficulty)
+}
+
+/// Times `low` can double before passing `high`, rounded at the geometric
+/// midpoint (`low * 2^k < high / sqrt(2)`), at most `max`
+fn doublings(low: u128, high: u128, max: u32) -> u32 {
+    let high_sq = high.saturating_mul(high);
+    (0..max)
+        .take_while(|&k| {
+            let scaled = low.checked_shl(k).unwrap_or(u128::MAX);
+            scaled.saturating_mul(scaled).saturating_mul(2) < high_sq
+        })
+        .count() as u32
+}
+
+/// Running retarget over a stream of solution intervals
+#[derive(Debug, Clone)]
+pub struct Retarget {
+    params: RetargetParams,
+    difficulty: u32,
+    elapsed: Duration,
+    count: usize,
+}
+
+impl Retarget {
+    /// Start at `difficulty`
+    pub fn new(params: RetargetParams, difficulty: u32) -> Self {
+        let difficulty = difficulty.clamp(params.min_difficulty, params.max_difficulty);
+        Self { params, difficulty, elapsed: Duration::ZERO, count: 0 }
+    }
+
+    /// Difficulty currently in force
+    pub fn difficulty(&self) -> u32 {
+        self.difficulty
+    }
+
+    /// Record the time since the previous solution; returns the difficulty
+    /// in force afterwards
+    pub fn record(&mut self, interval: Duration) -> u32 {
+        self.elapsed = self.elapsed.saturating_add(interval);
+        self.count += 1;
+
+        if self.count >= self.params.window.max(1) {
+            let average = self.elapsed / self.count as u32;
+            self.difficulty = adjust(self.difficulty, average, &self.params);
+            self.elapsed = Duration::ZERO;
+            self.count = 0;
+        }
+
+        self.difficulty
+    }
+}
@@ -0,0 +1,147 @@
+// Sampled-bytes proving mode for large segments
+// Instead of the whole segment, the seed holds M byte ranges picked by the
+// nonce: `challenge || sampled bytes || offsets || nonce`. Seeds stay small, but
+// a prover still can't know which bytes it needs until it picks a nonce, so it
+// has to keep the whole segment around. A verifier holding only Merkle openings
+// for the sampled ranges can check the proof without the full segment.
+
+use crate::{
+    keccak, solve_seed, verify_seed, Challenge, CrankXError, Nonce, Solution, MAX_SEED_LEN,
+};
+
+/// Number and length of the byte ranges sampled from a segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleParams {
+    /// Ranges per seed (M)
+    pub count: usize,
+    /// Bytes per range
+    pub len: usize,
+}
+
+impl SampleParams {
+    /// Seed length for these parameters
+    ///
+    /// [`CrankXError::InvalidSampling`] if it doesn't fit in a `usize`.
+    pub fn seed_len(&self) -> Result<usize, CrankXError> {
+        self.len
+            .checked_add(8)
+            .and_then(|range| range.checked_mul(self.count))
+            .and_then(|ranges| ranges.checked_add(32 + 8))
+            .ok_or(CrankXError::InvalidSampling)
+    }
+}
+
+/// Start offsets of the ranges sampled for `nonce` from a `data_len`-byte segment
+///
+/// Offset `i` is `keccak(challenge || nonce || i as u32) mod (data_len - len + 1)`,
+/// reading the hash's first 8 bytes as a little-endian u64.
+pub fn sample_offsets(
+    challenge: impl Into<Challenge>,
+    nonce: impl Into<Nonce>,
+    data_len: u64,
+    params: SampleParams,
+) -> Result<Vec<u64>, CrankXError> {
+    let len = params.len as u64;
+    if params.count == 0 || len == 0 || data_len < len {
+        return Err(CrankXError::InvalidSampling);
+    }
+    let seed_len = params.seed_len()?;
+    if seed_len > MAX_SEED_LEN {
+        return Err(CrankXError::SeedTooLarge { max: MAX_SEED_LEN, got: seed_len });
+    }
+
+    let (challenge, nonce) = (challenge.into(), nonce.into());
+    let span = data_len - len + 1;
+
+    Ok((0..params.count as u32)
+        .map(|i| {
+            let h = keccak(&[challenge.as_bytes(), nonce.as_bytes(), &i.to_le_bytes()]);
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&h[..8]);
+            u64::from_le_bytes(word) % span
+        })
+        .collect())
+}
+
+/// Solve PoW over the sampled seed of `data`
+pub fn solve_sampled(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    nonce: impl Into<Nonce>,
+    params: SampleParams,
+) -> Result<Solution, CrankXError> {
+    let (challenge, nonce) = (challenge.into(), nonce.into());
+    let seed = sampled_seed_from_data(&challenge, data, &nonce, params)?;
+    solve_seed(&seed, nonce.as_bytes())
+}
+
+/// Verify a digest against the sampled seed of `data`
+pub fn verify_sampled(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+    params: SampleParams,
+) -> Result<(), CrankXError> {
+    let (challenge, nonce) = (challenge.into(), nonce.into());
+    let seed = sampled_seed_from_data(&challenge, data, &nonce, params)?;
+    verify_seed(&seed, digest)
+}
+
+/// Verify a digest from just the sampled ranges of a `data_len`-byte segment
+///
+/// `samples` must be the ranges at [`sample_offsets`], in order. Checking that
+/// they really belong to the segment (e.g. Merkle openings) is up to the caller.
+pub fn verify_sampled_openings(
+    challenge: impl Into<Challenge>,
+    data_len: u64,
+    samples: &[&[u8]],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+    params: SampleParams,
+) -> Result<(), CrankXError> {
+    let (challenge, nonce) = (challenge.into(), nonce.into());
+    let offsets = sample_offsets(challenge, nonce, data_len, params)?;
+
+    if samples.len() != offsets.len() || samples.iter().any(|s| s.len() != params.len) {
+        return Err(CrankXError::InvalidSampling);
+    }
+
+    verify_seed(&build_sampled_seed(&challenge, samples, &offsets, &nonce), digest)
+}
+
+fn sampled_seed_from_data(
+    challenge: &Challenge,
+    data: &[u8],
+    nonce: &Nonce,
+    params: SampleParams,
+) -> Result<Vec<u8>, CrankXError> {
+    let offsets = sample_offsets(challenge, nonce, data.len() as u64, params)?;
+    let samples: Vec<&[u8]> = offsets
+        .iter()
+        .map(|&o| &data[o as usize..o as usize + params.len])
+        .collect();
+
+    Ok(build_sampled_seed(challenge, &samples, &offsets, nonce))
+}
+
+/// `challenge || samples || offsets (u64 LE) || nonce`
+fn build_sampled_seed(
+    challenge: &Challenge,
+    samples: &[&[u8]],
+    offsets: &[u64],
+    nonce: &Nonce,
+) -> Vec<u8> {
+    let sampled: usize = samples.iter().map(|s| s.len()).sum();
+    let mut seed = Vec::with_capacity(32 + sampled + offsets.len() * 8 + 8);
+
+    seed.extend_from_slice(challenge.as_bytes());
+    for sample in samples {
+        seed.extend_from_slice(sample);
+    }
+    for offset in offsets {
+        seed.extend_from_slice(&offset.to_le_bytes());
+    }
+    seed.extend_from_slice(nonce.as_bytes());
+    seed
+}
@@ -0,0 +1,193 @@
+// Choosing which segment to crank next
+// A prover holding many segments has to decide which one to prove next.
+// Deployments weigh deadlines, rewards and recall requests differently, so
+// the choice is a `SelectionStrategy` the `Scheduler` is generic over. Times
+// are caller-defined units (slots, seconds) that only need to increase.
+// Given a `Metrics`, the scheduler reports every segment's age as its
+// staleness whenever it picks the next one.
+
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::SegmentProvider;
+
+/// What the scheduler knows about one segment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentInfo {
+    pub index: u64,
+    /// When the segment was last proved, `None` if never
+    pub last_proved: Option<u64>,
+    /// Reward for proving it, in whatever unit the protocol pays
+    pub reward: f64,
+    /// The protocol has asked for this segment specifically
+    pub recalled: bool,
+}
+
+impl SegmentInfo {
+    /// Time since the segment was last proved; never proved counts as
+    /// proved just before time zero
+    pub fn age(&self, now: u64) -> u64 {
+        match self.last_proved {
+            Some(at) => now.saturating_sub(at),
+            None => now.saturating_add(1),
+        }
+    }
+}
+
+/// Rule for picking the next segment to prove
+pub trait SelectionStrategy {
+    /// Position in `segments` to prove next, `None` to idle
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize>;
+}
+
+impl<F: FnMut(&[SegmentInfo], u64) -> Option<usize>> SelectionStrategy for F {
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize> {
+        self(segments, now)
+    }
+}
+
+impl SelectionStrategy for Box<dyn SelectionStrategy + Send> {
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize> {
+        (**self).select(segments, now)
+    }
+}
+
+/// The segment proved longest ago, ties going to the lowest index
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StalestFirst;
+
+impl SelectionStrategy for StalestFirst {
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize> {
+        stalest(segments.iter().enumerate(), now)
+    }
+}
+
+/// The segment with the highest `reward * (1 + age)`, so well-paid segments
+/// come round more often without starving the rest
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RewardWeighted;
+
+impl SelectionStrategy for RewardWeighted {
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize> {
+        let score = |s: &SegmentInfo| s.reward * (1.0 + s.age(now) as f64);
+        segments
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.reward > 0.0)
+            .max_by(|(i, a), (j, b)| score(a).total_cmp(&score(b)).then(j.cmp(i)))
+            .map(|(i, _)| i)
+    }
+}
+
+/// Recalled segments first (stalest among them), then `fallback`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RecallPriority<S = StalestFirst> {
+    pub fallback: S,
+}
+
+impl<S: SelectionStrategy> SelectionStrategy for RecallPriority<S> {
+    fn select(&mut self, segments: &[SegmentInfo], now: u64) -> Option<usize> {
+        let recalled = segments.iter().enumerate().filter(|(_, s)| s.recalled);
+        stalest(recalled, now).or_else(|| self.fallback.select(segments, now))
+    }
+}
+
+fn stalest<'a>(
+    segments: impl Iterator<Item = (usize, &'a SegmentInfo)>,
+    now: u64,
+) -> Option<usize> {
+    // `max_by_key` keeps the last maximum; reverse the index to keep the first
+    segments.max_by_key(|(i, s)| (s.age(now), usize::MAX - i)).map(|(i, _)| i)
+}
+
+/// Segments a prover holds plus the strategy that orders them
+#[derive(Debug)]
+pub struct Scheduler<S> {
+    segments: Vec<SegmentInfo>,
+    strategy: S,
+    /// Where staleness goes, and the source it is labelled with
+    #[cfg(feature = "metrics")]
+    metrics: Option<(Arc<Metrics>, String)>,
+}
+
+impl<S: SelectionStrategy> Scheduler<S> {
+    /// Schedule segments `0..count`, none yet proved, each with reward 1
+    pub fn new(count: u64, strategy: S) -> Self {
+        let segments = (0..count)
+            .map(|index| SegmentInfo { index, last_proved: None, reward: 1.0, recalled: false })
+            .collect();
+        Self::with_segments(segments, strategy)
+    }
+
+    /// Schedule every segment `provider` holds, such as a tape
+    /// [`Reader`](crate::tape::Reader) or [`TapeView`](crate::tape::TapeView)
+    pub fn for_provider(provider: &impl SegmentProvider, strategy: S) -> Self {
+        Self::new(provider.segment_count(), strategy)
+    }
+
+    /// Schedule exactly `segments`
+    pub fn with_segments(segments: Vec<SegmentInfo>, strategy: S) -> Self {
+        Self {
+            segments,
+            strategy,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Report each segment's [`SegmentInfo::age`] to `metrics` as the
+    /// staleness of segment `index` of `source`, on every
+    /// [`Scheduler::next`] and [`Scheduler::proved`]
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: Arc<Metrics>, source: impl Into<String>) -> Self {
+        self.metrics = Some((metrics, source.into()));
+        self
+    }
+
+    pub fn segments(&self) -> &[SegmentInfo] {
+        &self.segments
+    }
+
+    /// Index of the segment to prove next
+    pub fn next(&mut self, now: u64) -> Option<u64> {
+        #[cfg(feature = "metrics")]
+        if let Some((metrics, source)) = &self.metrics {
+            for s in &self.segments {
+                metrics.set_staleness(source, s.index, s.age(now));
+            }
+        }
+        let pos = self.strategy.select(&self.segments, now)?;
+        self.segments.get(pos).map(|s| s.index)
+    }
+
+    /// Record that segment `index` was proved at `now`, clearing any recall
+    pub fn proved(&mut self, index: u64, now: u64) {
+        if let Some(s) = self.get_mut(index) {
+            s.last_proved = Some(now);
+            s.recalled = false;
+        }
+        #[cfg(feature = "metrics")]
+        if let Some((metrics, source)) = &self.metrics {
+            metrics.set_staleness(source, index, 0);
+        }
+    }
+
+    /// Mark segment `index` as recalled by the protocol
+    pub fn recall(&mut self, index: u64) {
+        if let Some(s) = self.get_mut(index) {
+            s.recalled = true;
+        }
+    }
+
+    pub fn set_reward(&mut self, index: u64, reward: f64) {
+        if let Some(s) = self.get_mut(index) {
+            s.reward = reward;
+        }
+    }
+
+    fn get_mut(&mut self, index: u64) -> Option<&mut SegmentInfo> {
+        self.segments.iter_mut().find(|s| s.index == index)
+    }
+}
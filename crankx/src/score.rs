@@ -0,0 +1,97 @@
+// Final hash as a number
+// Leading zeros bucket hashes into whole bits, so two proofs of equal
+// difficulty tie. A `Score` keeps the whole hash as a 256-bit big-endian
+// number (lower is better) and converts it to the work it represents:
+// `2^256 / (hash + 1)` expected attempts, the same quantity `Target` uses.
+// Summing work over proofs gives fork-choice style cumulative weight.
+
+use core::cmp::Ordering;
+
+use crate::target::Target;
+use crate::{difficulty_of, Solution};
+
+/// A final hash ranked as a number, lower hash is the better (greater) score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Score(pub [u8; 32]);
+
+impl Score {
+    /// Score of a raw final hash
+    pub fn from_hash(hash: [u8; 32]) -> Self {
+        Self(hash)
+    }
+
+    /// Worst score with at least `difficulty` leading zero bits
+    pub fn from_difficulty(difficulty: u32) -> Self {
+        Self(Target::from_difficulty(difficulty).0)
+    }
+
+    /// Leading zero bits of the hash
+    pub fn difficulty(&self) -> u32 {
+        difficulty_of(&self.0)
+    }
+
+    /// Expected final hashes to do this well: `2^256 / (hash + 1)`
+    pub fn work(&self) -> f64 {
+        Target(self.0).expected_attempts()
+    }
+
+    /// `log2(work)`: the difficulty with the fractional part kept, so
+    /// `difficulty() <= fractional_difficulty() < difficulty() + 1`
+    pub fn fractional_difficulty(&self) -> f64 {
+        self.work().log2()
+    }
+}
+
+impl From<&Solution> for Score {
+    fn from(solution: &Solution) -> Self {
+        Self(solution.to_hash())
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Agrees with [`Solution`]'s ordering: any higher difficulty is a lower
+/// hash, and at equal difficulty the lower hash wins
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+/// Work summed over many proofs
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CumulativeWork {
+    /// Sum of [`Score::work`]
+    pub total: f64,
+    /// Proofs counted
+    pub proofs: u64,
+}
+
+impl CumulativeWork {
+    /// Count one proof
+    pub fn add(&mut self, score: &Score) {
+        self.total += score.work();
+        self.proofs += 1;
+    }
+
+    /// Fold in another tally
+    pub fn merge(&mut self, other: &CumulativeWork) {
+        self.total += other.total;
+        self.proofs += other.proofs;
+    }
+
+    /// Difficulty of a single proof carrying the same work
+    pub fn equivalent_difficulty(&self) -> f64 {
+        self.total.log2()
+    }
+}
+
+impl<'a> Extend<&'a Score> for CumulativeWork {
+    fn extend<I: IntoIterator<Item = &'a Score>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|score| self.add(score));
+    }
+}
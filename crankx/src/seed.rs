@@ -0,0 +1,154 @@
+// Incremental seed assembly
+// A segment spread over several buffers (header, payload, padding) is
+// written straight into the seed after the challenge, so there is one copy
+// instead of a user-side concatenation followed by crankx's own. The nonce
+// is appended for each solve or verify and dropped again afterwards, so one
+// builder serves a whole nonce loop.
+// The vectored functions take the segment as a list of slices instead and
+// gather them into a caller-owned buffer. EquiX only accepts one contiguous
+// seed (HashX absorbs it in a single Blake2b call inside equix), so that one
+// copy is the floor; reusing the buffer removes the allocation.
+
+use std::io;
+
+use equix::SolverMemory;
+
+use crate::{
+    check_seed_len, solve_seed_with_memory, verify_seed, Challenge, CrankXError, Nonce, Solution,
+    MAX_SEED_LEN,
+};
+
+/// Write `challenge || parts.. || nonce` into the front of `buf`
+///
+/// `buf` needs `32 + total + 8` bytes; a `[u8; MAX_SEED_LEN]` fits every
+/// segment.
+pub fn gather_seed<'a>(
+    buf: &'a mut [u8],
+    challenge: impl Into<Challenge>,
+    parts: &[&[u8]],
+    nonce: impl Into<Nonce>,
+) -> Result<&'a [u8], CrankXError> {
+    let data_len = parts.iter().map(|p| p.len()).sum();
+    let len = check_seed_len(32, data_len, 8)?;
+    let max = MAX_SEED_LEN.min(buf.len());
+    let seed = buf.get_mut(..len).ok_or(CrankXError::SeedTooLarge { max, got: len })?;
+
+    let (challenge, nonce) = (challenge.into(), nonce.into());
+    let data = parts.iter().flat_map(|p| p.iter());
+    let bytes = challenge.as_bytes().iter().chain(data).chain(nonce.as_bytes());
+    for (dst, src) in seed.iter_mut().zip(bytes) {
+        *dst = *src;
+    }
+    Ok(seed)
+}
+
+/// [`crate::solve_with_memory`] over a segment split into `parts`, gathered
+/// in `buf`
+pub fn solve_vectored(
+    buf: &mut [u8],
+    mem: &mut SolverMemory,
+    challenge: impl Into<Challenge>,
+    parts: &[&[u8]],
+    nonce: impl Into<Nonce>,
+) -> Result<Solution, CrankXError> {
+    let nonce = nonce.into();
+    let seed = gather_seed(buf, challenge, parts, nonce)?;
+    solve_seed_with_memory(mem, seed, nonce.as_bytes())
+}
+
+/// [`crate::verify`] over a segment split into `parts`, gathered in `buf`
+pub fn verify_vectored(
+    buf: &mut [u8],
+    challenge: impl Into<Challenge>,
+    parts: &[&[u8]],
+    nonce: impl Into<Nonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    verify_seed(gather_seed(buf, challenge, parts, nonce)?, digest)
+}
+
+/// `challenge || data` built up part by part
+///
+/// Parts are accepted without a size check; the segment is checked against
+/// [`crate::MIN_SEGMENT_SIZE`] and [`crate::MAX_DATA_LEN`] when it is used.
+#[derive(Debug, Clone)]
+pub struct SeedBuilder {
+    seed: Vec<u8>,
+}
+
+impl SeedBuilder {
+    /// Empty segment under `challenge`
+    pub fn new(challenge: impl Into<Challenge>) -> Self {
+        let mut seed = Vec::with_capacity(MAX_SEED_LEN);
+        seed.extend_from_slice(challenge.into().as_bytes());
+        Self { seed }
+    }
+
+    /// Append `part` to the segment
+    pub fn push(&mut self, part: &[u8]) -> &mut Self {
+        self.seed.extend_from_slice(part);
+        self
+    }
+
+    /// The segment written so far
+    pub fn data(&self) -> &[u8] {
+        self.seed.get(32..).unwrap_or_default()
+    }
+
+    /// Drop the segment, keeping the challenge
+    pub fn clear(&mut self) {
+        self.seed.truncate(32);
+    }
+
+    /// Solve the full seed for `nonce`, taking EquiX's first solution like
+    /// [`crate::solve_with_memory`]
+    pub fn solve(
+        &mut self,
+        mem: &mut SolverMemory,
+        nonce: impl Into<Nonce>,
+    ) -> Result<Solution, CrankXError> {
+        let nonce = nonce.into();
+        self.with_nonce(&nonce, |seed| solve_seed_with_memory(mem, seed, nonce.as_bytes()))
+    }
+
+    /// Verify `digest` against the full seed for `nonce`
+    pub fn verify(&mut self, nonce: impl Into<Nonce>, digest: &[u8; 16]) -> Result<(), CrankXError> {
+        self.with_nonce(&nonce.into(), |seed| verify_seed(seed, digest))
+    }
+
+    /// Run `f` on `challenge || data || nonce`, then strip the nonce again
+    fn with_nonce<T>(
+        &mut self,
+        nonce: &Nonce,
+        f: impl FnOnce(&[u8]) -> Result<T, CrankXError>,
+    ) -> Result<T, CrankXError> {
+        check_seed_len(32, self.data().len(), 8)?;
+        self.seed.extend_from_slice(nonce.as_bytes());
+        let result = f(&self.seed);
+        self.seed.truncate(self.seed.len() - 8);
+        result
+    }
+}
+
+impl io::Write for SeedBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Extend<u8> for SeedBuilder {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.seed.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a u8> for SeedBuilder {
+    fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+        self.seed.extend(iter);
+    }
+}
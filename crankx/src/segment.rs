@@ -0,0 +1,107 @@
+// Lazy access to segment bytes
+// Provers rarely hold a whole tape in memory, so anything that cranks over many
+// segments asks a SegmentProvider for bytes on demand instead of taking slices.
+
+use std::borrow::Cow;
+
+use crate::CrankXError;
+
+/// Source of segment bytes, addressed by segment index
+pub trait SegmentProvider {
+    /// Error returned when a segment can't be produced
+    type Error;
+
+    /// Number of segments available
+    fn segment_count(&self) -> u64;
+
+    /// Bytes of segment `index`, borrowed when possible
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, Self::Error>;
+}
+
+impl<P: SegmentProvider + ?Sized> SegmentProvider for &P {
+    type Error = P::Error;
+
+    fn segment_count(&self) -> u64 {
+        (**self).segment_count()
+    }
+
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, P::Error> {
+        (**self).segment(index)
+    }
+}
+
+impl<T: AsRef<[u8]>> SegmentProvider for [T] {
+    type Error = CrankXError;
+
+    fn segment_count(&self) -> u64 {
+        self.len() as u64
+    }
+
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, CrankXError> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.get(i))
+            .map(|s| Cow::Borrowed(s.as_ref()))
+            .ok_or(CrankXError::SegmentOutOfRange { index, count: self.segment_count() })
+    }
+}
+
+impl<T: AsRef<[u8]>> SegmentProvider for Vec<T> {
+    type Error = CrankXError;
+
+    fn segment_count(&self) -> u64 {
+        self.as_slice().segment_count()
+    }
+
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, CrankXError> {
+        self.as_slice().segment(index)
+    }
+}
+
+/// One contiguous buffer split into fixed-size segments
+///
+/// A short final segment is zero-padded to `segment_size`, the same way a
+/// tape pads its last segment.
+#[derive(Debug, Clone)]
+pub struct ChunkedSegments<B> {
+    bytes: B,
+    segment_size: usize,
+}
+
+impl<B: AsRef<[u8]>> ChunkedSegments<B> {
+    /// Split `bytes` into `segment_size`-byte segments
+    ///
+    /// Panics if `segment_size` is zero.
+    pub fn new(bytes: B, segment_size: usize) -> Self {
+        assert!(segment_size > 0, "segment_size must be non-zero");
+        Self { bytes, segment_size }
+    }
+
+    /// Size of every segment, in bytes
+    pub fn segment_size(&self) -> usize {
+        self.segment_size
+    }
+}
+
+impl<B: AsRef<[u8]>> SegmentProvider for ChunkedSegments<B> {
+    type Error = CrankXError;
+
+    fn segment_count(&self) -> u64 {
+        self.bytes.as_ref().len().div_ceil(self.segment_size) as u64
+    }
+
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, CrankXError> {
+        let chunk = usize::try_from(index)
+            .ok()
+            .and_then(|i| self.bytes.as_ref().chunks(self.segment_size).nth(i))
+            .ok_or(CrankXError::SegmentOutOfRange { index, count: self.segment_count() })?;
+
+        if chunk.len() == self.segment_size {
+            return Ok(Cow::Borrowed(chunk));
+        }
+
+        let mut padded = chunk.to_vec();
+        padded.resize(self.segment_size, 0);
+        Ok(Cow::Owned(padded))
+    }
+}
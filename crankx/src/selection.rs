@@ -0,0 +1,110 @@
+// Choosing an epoch's winners
+// Coordinators and on-chain programs must rank the same submissions the same
+// way, so the rule lives here and uses integers only:
+//
+//   key := difficulty + weight bonus (0, or floor(log2(segments)))
+//
+// Higher key ranks first; ties go to the lower final hash, then the lower
+// miner key, then the earlier submission. A final hash submitted more than
+// once counts only for its earliest submission, so a copied proof never
+// outranks its original.
+
+use std::collections::HashSet;
+
+use crate::Solution;
+
+/// One miner's proof for the epoch
+#[derive(Debug)]
+pub struct Submission {
+    /// Submitter's public key
+    pub miner: [u8; 32],
+    pub solution: Solution,
+    /// Segments the miner stores, used by [`Weighting::SegmentCount`]
+    pub segments: u64,
+}
+
+/// Whether storage counts toward rank
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    /// Difficulty alone
+    #[default]
+    None,
+    /// Add `floor(log2(segments))` bits; miners storing no segments are
+    /// left out of the ranking
+    SegmentCount,
+}
+
+impl Weighting {
+    /// Bits added to a submission's difficulty, `None` if it's ineligible
+    pub fn bonus(&self, segments: u64) -> Option<u32> {
+        match self {
+            Self::None => Some(0),
+            Self::SegmentCount => segments.checked_ilog2(),
+        }
+    }
+}
+
+/// A ranked submission with everything needed to recheck its place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked {
+    /// Position in the ranking, 0 is the winner
+    pub rank: usize,
+    /// Index into the submissions passed in
+    pub index: usize,
+    pub miner: [u8; 32],
+    pub difficulty: u32,
+    /// Bits from [`Weighting::bonus`]
+    pub bonus: u32,
+    pub hash: [u8; 32],
+}
+
+impl Ranked {
+    /// The key submissions are ranked by
+    pub fn key(&self) -> u32 {
+        self.difficulty.saturating_add(self.bonus)
+    }
+}
+
+/// Every eligible submission, best first
+pub fn rank(submissions: &[Submission], weighting: Weighting) -> Vec<Ranked> {
+    let mut seen = HashSet::new();
+    let mut ranked: Vec<Ranked> = submissions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, s)| {
+            let bonus = weighting.bonus(s.segments)?;
+            let hash = s.solution.to_hash();
+            seen.insert(hash).then_some(Ranked {
+                rank: 0,
+                index,
+                miner: s.miner,
+                difficulty: s.solution.difficulty(),
+                bonus,
+                hash,
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.key()
+            .cmp(&a.key())
+            .then_with(|| a.hash.cmp(&b.hash))
+            .then_with(|| a.miner.cmp(&b.miner))
+            .then_with(|| a.index.cmp(&b.index))
+    });
+    for (rank, entry) in ranked.iter_mut().enumerate() {
+        entry.rank = rank;
+    }
+    ranked
+}
+
+/// The best `count` submissions, see [`rank`]
+pub fn select_winners(
+    submissions: &[Submission],
+    count: usize,
+    weighting: Weighting,
+) -> Vec<Ranked> {
+    let mut ranked = rank(submissions, weighting);
+    ranked.truncate(count);
+    ranked
+}
@@ -0,0 +1,346 @@
+// HTTP JSON front end for cranking (feature = "service")
+// Lets non-Rust infrastructure offload solve/verify/bench to dedicated boxes.
+// Byte fields travel as hex strings; nonces are raw 8-byte hex, never integers,
+// so there is no endianness to get wrong. Solver memory comes from a shared
+// pool, so requests never allocate the ~2MB EquiX scratch space. GET /metrics
+// exposes the counters in `crate::metrics` for Prometheus. Every endpoint that
+// does work runs behind one `VerifyLimits`, keyed by client IP: /verify is
+// charged one token per proof, /solve and /bench the verifications their
+// worst case is worth, so no client can hold every handler thread.
+
+use std::io::{self, Read};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use equix::{EquiXBuilder, SolverMemory};
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::bench::{measure, HostInfo, MachineReport};
+use crate::limits::VerifyLimits;
+use crate::metrics::Metrics;
+use crate::types::decode_hex_vec;
+use crate::{
+    build_seed, search_nonces, verify_seed, Challenge, CrankXError, Nonce, SelectionPolicy,
+    DEFAULT_RUNTIME, MAX_DATA_LEN, MIN_SEGMENT_SIZE,
+};
+
+/// Largest request body accepted (a max-size segment in hex plus headroom)
+const MAX_BODY_LEN: usize = 2 * MAX_DATA_LEN + 1024;
+
+/// Longest `/bench` run a client may ask for
+const MAX_BENCH_MILLIS: u64 = 10_000;
+
+/// Most nonces one `/solve` may ask for
+const MAX_SOLVE_ATTEMPTS: u64 = 1 << 20;
+
+/// Longest a `/solve` searches before giving up on its range, however many
+/// attempts it asked for
+const MAX_SOLVE_MILLIS: u64 = 10_000;
+
+/// Rate limit tokens (proofs verified) one `/solve` attempt is charged: an
+/// EquiX solve does about a hundred verifications' work
+const SOLVE_ATTEMPT_COST: u64 = 100;
+
+/// Tokens charged per millisecond a request may crank for, about the
+/// verifications a core gets through in that time
+const CRANK_MILLI_COST: u64 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct SolveRequest {
+    pub challenge: String,
+    pub data: String,
+    #[serde(default)]
+    pub min_difficulty: u32,
+    #[serde(default)]
+    pub nonce_start: u64,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SolveResponse {
+    pub nonce: String,
+    pub digest: String,
+    pub hash: String,
+    pub difficulty: u32,
+    pub attempts: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyRequest {
+    pub challenge: String,
+    pub data: String,
+    pub nonce: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyResponse {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BenchRequest {
+    pub segment_size: usize,
+    pub millis: u64,
+}
+
+/// `/bench` answers with the same report as `crankx bench`
+pub type BenchResponse = MachineReport;
+
+fn default_max_attempts() -> u64 {
+    1 << 16
+}
+
+/// HTTP server exposing `/solve`, `/verify`, `/bench` and `/metrics`
+pub struct Service {
+    server: Arc<Server>,
+    state: Arc<State>,
+}
+
+/// Shared between the handler threads
+#[derive(Default)]
+struct State {
+    memory: MemoryPool,
+    metrics: Metrics,
+    limits: VerifyLimits,
+}
+
+impl Service {
+    /// Listen on `addr` (e.g. `"0.0.0.0:8080"`)
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let server = Server::http(addr).map_err(|e| io::Error::other(e.to_string()))?;
+        Ok(Self { server: Arc::new(server), state: Arc::default() })
+    }
+
+    /// Guard `/verify`, `/solve` and `/bench` with `limits` instead of
+    /// [`VerifyLimits::new`]
+    ///
+    /// A rate is in proofs verified; `/solve` and `/bench` are charged the
+    /// verifications their longest run is worth, up to 100,000 tokens, so a
+    /// `burst` below that refuses them outright.
+    pub fn limits(mut self, limits: VerifyLimits) -> Self {
+        // Handler threads only take their clones in `run`
+        Arc::get_mut(&mut self.state).unwrap().limits = limits;
+        self
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Counters served at `/metrics`
+    pub fn metrics(&self) -> &Metrics {
+        &self.state.metrics
+    }
+
+    /// Serve requests on `threads` handler threads, blocking forever
+    pub fn run(self, threads: usize) {
+        let handlers: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                let server = self.server.clone();
+                let state = self.state.clone();
+                thread::spawn(move || {
+                    for request in server.incoming_requests() {
+                        handle(request, &state);
+                    }
+                })
+            })
+            .collect();
+
+        for handler in handlers {
+            let _ = handler.join();
+        }
+    }
+}
+
+fn handle(mut request: Request, state: &State) {
+    let mut body = Vec::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_LEN as u64 + 1)
+        .read_to_end(&mut body);
+
+    let mut content_type = "application/json";
+    let (status, body) = match (read, request.method(), request.url()) {
+        (Err(e), _, _) => error(400, e.to_string()),
+        _ if body.len() > MAX_BODY_LEN => error(413, "request body too large".into()),
+        (_, Method::Post, "/solve") => route(&body, |r| solve(r, state, &client(&request))),
+        (_, Method::Post, "/verify") => route(&body, |r| verify(r, state, &client(&request))),
+        (_, Method::Post, "/bench") => route(&body, |r| bench(r, state, &client(&request))),
+        (_, Method::Get, "/metrics") => {
+            content_type = "text/plain; version=0.0.4";
+            (200, state.metrics.render())
+        }
+        _ => error(404, "not found".into()),
+    };
+
+    let header = Header::from_bytes("Content-Type", content_type).unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Rate limit key for the client that sent `request`
+fn client(request: &Request) -> String {
+    request.remote_addr().map(|a| a.ip().to_string()).unwrap_or_default()
+}
+
+fn route<Req, Res>(body: &[u8], f: impl FnOnce(Req) -> Result<Res, (u16, String)>) -> (u16, String)
+where
+    Req: for<'de> Deserialize<'de>,
+    Res: Serialize,
+{
+    let result = serde_json::from_slice(body)
+        .map_err(|e| (400, e.to_string()))
+        .and_then(f);
+
+    match result {
+        Ok(res) => (200, serde_json::to_string(&res).unwrap()),
+        Err((status, msg)) => error(status, msg),
+    }
+}
+
+fn error(status: u16, msg: String) -> (u16, String) {
+    (status, serde_json::json!({ "error": msg }).to_string())
+}
+
+fn solve(req: SolveRequest, state: &State, key: &str) -> Result<SolveResponse, (u16, String)> {
+    if req.max_attempts > MAX_SOLVE_ATTEMPTS {
+        return Err((400, format!("max_attempts above {MAX_SOLVE_ATTEMPTS}")));
+    }
+    let challenge = Challenge::from_hex(&req.challenge).map_err(bad_request)?;
+    let data = decode_hex(&req.data)?;
+    state.limits.check_data_len(data.len()).map_err(refused)?;
+    let mut seed = build_seed(challenge.as_bytes(), &data, &[0; 8]).map_err(bad_request)?;
+    // Whichever runs out first, the attempts asked for or the time limit
+    let cost = (req.max_attempts.saturating_mul(SOLVE_ATTEMPT_COST))
+        .min(MAX_SOLVE_MILLIS * CRANK_MILLI_COST);
+    state.limits.admit(key, cost as usize).map_err(refused)?;
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+
+    let mut result = None;
+    let mut mem = state.memory.take();
+    let deadline = Instant::now() + Duration::from_millis(MAX_SOLVE_MILLIS);
+    let end = req.nonce_start.saturating_add(req.max_attempts);
+    let searched = search_nonces(
+        &builder,
+        &mut mem,
+        &mut seed,
+        req.nonce_start..end,
+        SelectionPolicy::HighestDifficulty,
+        || Instant::now() >= deadline,
+        |s| {
+            let qualifies = s.difficulty() >= req.min_difficulty;
+            if qualifies {
+                result = Some(s);
+            }
+            qualifies
+        },
+    );
+    state.memory.give(mem);
+    let attempts = searched.map_err(|e| (500, e.to_string()))?;
+
+    let metrics = &state.metrics;
+    metrics.add_attempts(attempts);
+    metrics.set_memory_pool_size(state.memory.len());
+
+    match result {
+        Some(s) => {
+            metrics.record_solution(s.difficulty());
+            Ok(SolveResponse {
+                nonce: hex(&s.n),
+                digest: hex(&s.d),
+                hash: hex(&s.to_hash()),
+                difficulty: s.difficulty(),
+                attempts,
+            })
+        }
+        None => Err((422, "no qualifying solution in nonce range".into())),
+    }
+}
+
+fn verify(req: VerifyRequest, state: &State, key: &str) -> Result<VerifyResponse, (u16, String)> {
+    let challenge = Challenge::from_hex(&req.challenge).map_err(bad_request)?;
+    let nonce = Nonce::from_hex(&req.nonce).map_err(bad_request)?;
+    let data = decode_hex(&req.data)?;
+    let digest: [u8; 16] = decode_hex(&req.digest)?
+        .try_into()
+        .map_err(|_| bad_request(CrankXError::InvalidLength))?;
+    state.limits.check_data_len(data.len()).map_err(refused)?;
+    state.limits.admit(key, 1).map_err(refused)?;
+
+    let metrics = &state.metrics;
+
+    let result = build_seed(challenge.as_bytes(), &data, nonce.as_bytes())
+        .and_then(|seed| verify_seed(&seed, &digest));
+
+    metrics.record_verification(result.is_ok());
+    Ok(VerifyResponse { valid: result.is_ok(), error: result.err().map(|e| e.to_string()) })
+}
+
+fn bench(req: BenchRequest, state: &State, key: &str) -> Result<BenchResponse, (u16, String)> {
+    if req.segment_size > MAX_DATA_LEN {
+        return Err(bad_request(CrankXError::SeedTooLarge {
+            max: MAX_DATA_LEN,
+            got: req.segment_size,
+        }));
+    }
+    if req.segment_size < MIN_SEGMENT_SIZE {
+        return Err(bad_request(CrankXError::SegmentTooSmall {
+            min: MIN_SEGMENT_SIZE,
+            got: req.segment_size,
+        }));
+    }
+
+    let millis = req.millis.min(MAX_BENCH_MILLIS);
+    state.limits.admit(key, (millis * CRANK_MILLI_COST) as usize).map_err(refused)?;
+    let report = measure(req.segment_size, Duration::from_millis(millis));
+    Ok(MachineReport::new(&report, HostInfo::detect()))
+}
+
+fn bad_request(e: CrankXError) -> (u16, String) {
+    (400, e.to_string())
+}
+
+/// Status for a request a [`VerifyLimits`] turned away
+fn refused(e: CrankXError) -> (u16, String) {
+    match e {
+        CrankXError::RateLimited => (429, e.to_string()),
+        _ => (413, e.to_string()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, (u16, String)> {
+    decode_hex_vec(s).map_err(bad_request)
+}
+
+/// Solver memory shared by the handler threads
+#[derive(Default)]
+struct MemoryPool(Mutex<Vec<SolverMemory>>);
+
+impl MemoryPool {
+    fn take(&self) -> SolverMemory {
+        self.0.lock().unwrap().pop().unwrap_or_default()
+    }
+
+    fn give(&self, mem: SolverMemory) {
+        self.0.lock().unwrap().push(mem);
+    }
+
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+}
@@ -0,0 +1,177 @@
+// Network simulation for picking protocol parameters (feature = "sim")
+// No hashing happens here. Each round the challenge picks a segment, the
+// miners storing it race, and the wait for the first final hash meeting the
+// difficulty is drawn from its exponential law at their combined rate. The
+// winner is drawn in proportion to hashrate, the latency goes to the
+// retargeting rule, and the round's difficulty, latency and winner are
+// recorded. A fixed seed makes every run reproducible.
+
+use std::time::Duration;
+
+use crate::retarget::Retarget;
+use crate::rng::SplitMix64;
+use crate::target::Target;
+
+/// Difficulty rule the simulated network runs under
+pub trait RetargetRule {
+    /// Difficulty currently in force
+    fn difficulty(&self) -> u32;
+
+    /// Record the latency of one proof; returns the difficulty in force
+    /// afterwards
+    fn record(&mut self, latency: Duration) -> u32;
+}
+
+impl RetargetRule for Retarget {
+    fn difficulty(&self) -> u32 {
+        Retarget::difficulty(self)
+    }
+
+    fn record(&mut self, latency: Duration) -> u32 {
+        Retarget::record(self, latency)
+    }
+}
+
+/// Difficulty that never moves, as a baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed(pub u32);
+
+impl RetargetRule for Fixed {
+    fn difficulty(&self) -> u32 {
+        self.0
+    }
+
+    fn record(&mut self, _: Duration) -> u32 {
+        self.0
+    }
+}
+
+/// One simulated miner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimMiner {
+    /// Final hashes per second
+    pub hashrate: f64,
+    /// Fraction of the segment set this miner stores, in `0.0..=1.0`
+    pub coverage: f64,
+}
+
+impl SimMiner {
+    /// Miner storing every segment
+    pub fn new(hashrate: f64) -> Self {
+        Self { hashrate, coverage: 1.0 }
+    }
+}
+
+/// Network to simulate
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimConfig {
+    pub miners: Vec<SimMiner>,
+    /// Challenges to play out
+    pub rounds: usize,
+    /// PRNG seed
+    pub seed: u64,
+}
+
+/// What a simulation produced, one entry per proved round
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimReport {
+    /// Time from challenge to first qualifying proof
+    pub latencies: Vec<Duration>,
+    /// Difficulty each proof was found at
+    pub difficulty: Vec<u32>,
+    /// Proofs won by each miner, indexed like [`SimConfig::miners`]
+    pub wins: Vec<u64>,
+    /// Rounds whose segment no miner stored
+    pub unprovable: u64,
+}
+
+impl SimReport {
+    /// Proofs found
+    pub fn proofs(&self) -> usize {
+        self.latencies.len()
+    }
+
+    /// Mean proof latency, zero before any proof; a total past
+    /// `Duration::MAX` saturates rather than panicking
+    pub fn mean_latency(&self) -> Duration {
+        let total = self.latencies.iter().fold(Duration::ZERO, |sum, &l| sum.saturating_add(l));
+        match u32::try_from(self.proofs()) {
+            Ok(proofs) => total.checked_div(proofs).unwrap_or_default(),
+            Err(_) => total.div_f64(self.proofs() as f64),
+        }
+    }
+
+    /// Latency at or below which a `q` fraction of proofs landed; `None`
+    /// before any proof or for `q` outside `0.0..=1.0`
+    pub fn latency_percentile(&self, q: f64) -> Option<Duration> {
+        if !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        let rank = ((q * sorted.len() as f64).ceil() as usize).max(1);
+        sorted.get(rank - 1).copied()
+    }
+
+    /// Mean difficulty over all proofs, zero before any proof
+    pub fn mean_difficulty(&self) -> f64 {
+        if self.difficulty.is_empty() {
+            return 0.0;
+        }
+        self.difficulty.iter().map(|&d| d as f64).sum::<f64>() / self.difficulty.len() as f64
+    }
+
+    /// Last proof's difficulty minus the first's
+    pub fn difficulty_drift(&self) -> i64 {
+        match (self.difficulty.first(), self.difficulty.last()) {
+            (Some(&first), Some(&last)) => last as i64 - first as i64,
+            _ => 0,
+        }
+    }
+}
+
+/// Play `config` out under `rule`
+pub fn run(config: &SimConfig, rule: &mut impl RetargetRule) -> SimReport {
+    let mut rng = SplitMix64(config.seed);
+    let mut report = SimReport { wins: vec![0; config.miners.len()], ..Default::default() };
+
+    for _ in 0..config.rounds {
+        let racing: Vec<(usize, f64)> = config
+            .miners
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| rng.next_f64() < m.coverage)
+            .map(|(i, m)| (i, m.hashrate.max(0.0)))
+            .collect();
+
+        let rate: f64 = racing.iter().map(|(_, h)| h).sum();
+        let difficulty = rule.difficulty();
+        let hit_rate = rate * Target::from_difficulty(difficulty).probability();
+        if hit_rate <= 0.0 {
+            report.unprovable += 1;
+            continue;
+        }
+
+        // Exponential wait, then the winner in proportion to hashrate
+        let wait = -(-rng.next_f64()).ln_1p() / hit_rate;
+        let latency = Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX);
+        let mut pick = rng.next_f64() * rate;
+        let winner = racing
+            .iter()
+            .find(|(_, h)| {
+                pick -= h;
+                pick < 0.0
+            })
+            .or(racing.last())
+            .map(|(i, _)| *i);
+
+        if let Some(winner) = winner {
+            report.wins[winner] += 1;
+        }
+        report.latencies.push(latency);
+        report.difficulty.push(difficulty);
+        rule.record(latency);
+    }
+
+    report
+}
@@ -0,0 +1,146 @@
+// On-chain helpers (feature = "solana")
+
+pub mod cpi;
+
+use solana_program::program_error::ProgramError;
+
+use crate::batch::{verify_batch, verify_nonces, BatchItem};
+use crate::{build_seed, split_array, verify_seed, Challenge, CrankXError, Solution};
+
+impl From<CrankXError> for ProgramError {
+    fn from(e: CrankXError) -> Self {
+        ProgramError::Custom(match e {
+            CrankXError::EquiXFailure => 0,
+            CrankXError::NoSolution => 1,
+            CrankXError::InvalidSolution => 2,
+            CrankXError::InvalidLength => 3,
+            CrankXError::UnsupportedVersion => 4,
+            CrankXError::UnsupportedHash => 5,
+            CrankXError::InvalidHex => 6,
+            CrankXError::SegmentOutOfRange { .. } => 7,
+            CrankXError::ProofCount { .. } => 8,
+            CrankXError::DuplicateNonce => 9,
+            CrankXError::InvalidSampling => 10,
+            CrankXError::InsufficientDifficulty { .. } => 11,
+            CrankXError::SeedTooLarge { .. } => 12,
+            CrankXError::CompilerUnavailable => 13,
+            CrankXError::InvalidEncoding => 14,
+            CrankXError::SegmentTooSmall { .. } => 15,
+            CrankXError::StaleProof { .. } => 16,
+            CrankXError::InvalidAnchor => 17,
+            CrankXError::DataTooLarge { .. } => 18,
+            CrankXError::BatchTooLarge { .. } => 19,
+            CrankXError::RateLimited => 20,
+            CrankXError::InvalidSignature => 21,
+            CrankXError::InvalidThrottle => 22,
+            CrankXError::InvalidReport => 23,
+            CrankXError::CheckpointMismatch { .. } => 24,
+            CrankXError::InvalidNonceRange { .. } => 25,
+            CrankXError::InvalidRate => 26,
+        })
+    }
+}
+
+/// Verify N proofs sharing one challenge inside a single instruction
+///
+/// Cost model: one seed buffer of `32 + max(data) + 8` bytes is allocated once
+/// and the challenge is copied into it once. Each proof then pays
+///
+/// - a copy of its `data || nonce` into the buffer (linear in segment size),
+///   or of the nonce alone when it borrows the previous item's `data`,
+/// - one Blake2b pass over the seed plus HashX program generation, and
+/// - eight HashX evaluations to check the EquiX tree sums,
+///
+/// with program generation dominating for typical segment sizes. Total cost is
+/// therefore roughly `base + N * (per_proof + per_byte * data_len)`; the
+/// constants depend on the runtime version and should be measured for the
+/// target cluster rather than assumed; [`crate::cost::CostModel`] holds them.
+pub fn verify_batch_ix(
+    challenge: impl Into<Challenge>,
+    items: &[BatchItem],
+) -> Result<(), ProgramError> {
+    verify_batch(challenge, items).map_err(ProgramError::from)
+}
+
+/// Verify K packed proofs over one segment inside a single instruction
+///
+/// Same cost model as [`verify_batch_ix`] with the segment copied only once.
+pub fn verify_nonces_ix(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    proofs: &[[u8; 24]],
+) -> Result<(), ProgramError> {
+    verify_nonces(challenge, data, proofs).map_err(ProgramError::from)
+}
+
+/// Verify one proof over a runtime-sized segment and require `min_difficulty`
+///
+/// Takes the packed `digest (16) || nonce (8)` proof as it arrives in
+/// instruction data and returns the proof's difficulty.
+pub fn verify_with_difficulty(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    proof: &[u8; 24],
+    min_difficulty: u32,
+) -> Result<u32, ProgramError> {
+    let solution = Solution::from_bytes(proof);
+    let seed = build_seed(challenge.into().as_bytes(), data, &solution.n)?;
+    verify_seed(&seed, &solution.d)?;
+
+    let difficulty = solution.difficulty();
+    if difficulty < min_difficulty {
+        return Err(CrankXError::InsufficientDifficulty {
+            required: min_difficulty,
+            actual: difficulty,
+        }
+        .into());
+    }
+
+    Ok(difficulty)
+}
+
+/// Instruction data for the reference verifier's proof submission:
+/// `tag (1) || challenge (32) || min_difficulty (4, LE) || proof (24) || segment`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmitProof<'a> {
+    pub challenge: [u8; 32],
+    pub min_difficulty: u32,
+    /// Packed `digest || nonce`, as from [`Solution::to_bytes`]
+    pub proof: [u8; 24],
+    /// Raw segment bytes
+    pub segment: &'a [u8],
+}
+
+impl<'a> SubmitProof<'a> {
+    /// Leading instruction byte
+    pub const TAG: u8 = 0;
+
+    /// Bytes before the segment
+    pub const HEADER_LEN: usize = 1 + 32 + 4 + 24;
+
+    /// Encode as instruction data
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::HEADER_LEN + self.segment.len());
+        data.push(Self::TAG);
+        data.extend_from_slice(&self.challenge);
+        data.extend_from_slice(&self.min_difficulty.to_le_bytes());
+        data.extend_from_slice(&self.proof);
+        data.extend_from_slice(self.segment);
+        data
+    }
+
+    /// Decode instruction data, borrowing the segment
+    pub fn unpack(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let Some((header, segment)) = data.split_first_chunk::<{ SubmitProof::HEADER_LEN }>() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let ([tag], rest): ([u8; 1], [u8; 60]) = split_array(header);
+        if tag != Self::TAG {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (challenge, rest): (_, [u8; 28]) = split_array(&rest);
+        let (min_difficulty, proof) = split_array(&rest);
+        Ok(Self { challenge, min_difficulty: u32::from_le_bytes(min_difficulty), proof, segment })
+    }
+}
@@ -0,0 +1,48 @@
+// Instruction builders for the reference verifier program
+// Composing programs call into the verifier with `invoke_submit_proof`;
+// off-chain clients build the same instruction with `submit_proof`.
+
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke_signed;
+use solana_program::pubkey::Pubkey;
+
+use super::SubmitProof;
+
+/// Program id of the reference verifier (`crankx-verifier`)
+pub const VERIFIER_ID: Pubkey =
+    solana_program::pubkey!("BwR6tPD811KikQzCR9rXEWBMvRy9w2m7aisf748QGXzi");
+
+/// Accounts for `SubmitProof`: `[signer] miner`
+pub fn submit_proof_accounts(miner: &Pubkey) -> Vec<AccountMeta> {
+    vec![AccountMeta::new_readonly(*miner, true)]
+}
+
+/// `SubmitProof` instruction for the verifier at [`VERIFIER_ID`]
+pub fn submit_proof(miner: &Pubkey, proof: &SubmitProof) -> Instruction {
+    submit_proof_with_program(&VERIFIER_ID, miner, proof)
+}
+
+/// `SubmitProof` instruction for a verifier deployed at `program_id`
+pub fn submit_proof_with_program(
+    program_id: &Pubkey,
+    miner: &Pubkey,
+    proof: &SubmitProof,
+) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &proof.pack(), submit_proof_accounts(miner))
+}
+
+/// CPI into `verifier` with `miner` as the signer
+///
+/// `signer_seeds` sign for a PDA miner; pass `&[]` when the miner signed
+/// the outer transaction.
+pub fn invoke_submit_proof<'a>(
+    verifier: &AccountInfo<'a>,
+    miner: &AccountInfo<'a>,
+    proof: &SubmitProof,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = submit_proof_with_program(verifier.key, miner.key, proof);
+    invoke_signed(&ix, &[miner.clone(), verifier.clone()], signer_seeds)
+}
@@ -0,0 +1,150 @@
+// Per-session solve statistics
+// What protocol tuning needs from a mining run: how often a seed yields
+// nothing, how many EquiX candidates the rest yield, and how the candidates'
+// difficulties are spread. Every candidate counts, not only the one a
+// selection policy keeps, so the histogram reflects the puzzle itself.
+// Percentiles and tail counts read off that histogram are what a deployed
+// target is checked against: the share of candidates at or above it, times
+// the measured rate, gives the actual proving interval.
+
+use crate::nonces::NonceStrategy;
+
+/// Most EquiX solutions a single seed can produce
+pub const MAX_CANDIDATES: usize = 8;
+
+/// Candidate difficulty at a few fixed percentiles, see
+/// [`Stats::percentiles`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Percentiles {
+    pub p50: u32,
+    pub p90: u32,
+    pub p99: u32,
+    pub p999: u32,
+    pub max: u32,
+}
+
+/// Counts collected over a mining session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Nonces tried
+    pub attempts: u64,
+    /// Seeds that produced no candidate (unbuildable HashX program or no
+    /// EquiX solution)
+    pub no_solution: u64,
+    /// `candidates[k]` is the number of seeds that produced exactly `k`
+    /// candidates
+    pub candidates: [u64; MAX_CANDIDATES + 1],
+    /// `difficulty[d]` is the number of candidates with difficulty `d`
+    pub difficulty: Vec<u64>,
+    /// Order the session searched nonces in, so an auditor can tell which
+    /// nonces it covered
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nonce_strategy: NonceStrategy,
+}
+
+impl Stats {
+    /// Record one nonce given the difficulty of each of its seed's candidates
+    pub fn record(&mut self, difficulties: &[u32]) {
+        self.attempts += 1;
+
+        let count = difficulties.len().min(MAX_CANDIDATES);
+        self.candidates[count] += 1;
+        if count == 0 {
+            self.no_solution += 1;
+        }
+
+        for &d in difficulties {
+            let d = d as usize;
+            if self.difficulty.len() <= d {
+                self.difficulty.resize(d + 1, 0);
+            }
+            self.difficulty[d] += 1;
+        }
+    }
+
+    /// Fold another session's counts into this one, keeping this one's
+    /// nonce strategy
+    pub fn merge(&mut self, other: &Stats) {
+        self.attempts += other.attempts;
+        self.no_solution += other.no_solution;
+        for (a, b) in self.candidates.iter_mut().zip(other.candidates) {
+            *a += b;
+        }
+
+        if self.difficulty.len() < other.difficulty.len() {
+            self.difficulty.resize(other.difficulty.len(), 0);
+        }
+        for (a, b) in self.difficulty.iter_mut().zip(&other.difficulty) {
+            *a += b;
+        }
+    }
+
+    /// Fraction of nonces whose seed produced no candidate
+    pub fn no_solution_rate(&self) -> f64 {
+        self.no_solution as f64 / self.attempts as f64
+    }
+
+    /// Total candidates seen
+    pub fn total_candidates(&self) -> u64 {
+        self.difficulty.iter().sum()
+    }
+
+    /// Mean candidates per nonce
+    pub fn mean_candidates(&self) -> f64 {
+        self.total_candidates() as f64 / self.attempts as f64
+    }
+
+    /// Highest candidate difficulty seen
+    pub fn max_difficulty(&self) -> Option<u32> {
+        self.difficulty.iter().rposition(|&n| n > 0).map(|d| d as u32)
+    }
+
+    /// Smallest difficulty `d` such that at least a `q` fraction of
+    /// candidates have difficulty `<= d`; `None` before any candidate or for
+    /// `q` outside `0.0..=1.0`
+    pub fn percentile(&self, q: f64) -> Option<u32> {
+        if !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let total = self.total_candidates();
+        if total == 0 {
+            return None;
+        }
+        let rank = ((q * total as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        let d = self.difficulty.iter().position(|&n| {
+            seen += n;
+            seen >= rank
+        });
+        d.map(|d| d as u32)
+    }
+
+    /// Median, 90th, 99th and 99.9th percentile and maximum candidate
+    /// difficulty
+    pub fn percentiles(&self) -> Option<Percentiles> {
+        Some(Percentiles {
+            p50: self.percentile(0.5)?,
+            p90: self.percentile(0.9)?,
+            p99: self.percentile(0.99)?,
+            p999: self.percentile(0.999)?,
+            max: self.max_difficulty()?,
+        })
+    }
+
+    /// Candidates with difficulty at least `difficulty`
+    pub fn candidates_at_least(&self, difficulty: u32) -> u64 {
+        self.difficulty.iter().skip(difficulty as usize).sum()
+    }
+
+    /// Observed nonces per candidate reaching `difficulty`; `None` if none
+    /// did. Divide by the attempt rate for the proving interval.
+    pub fn attempts_per_hit(&self, difficulty: u32) -> Option<f64> {
+        match self.candidates_at_least(difficulty) {
+            0 => None,
+            hits => Some(self.attempts as f64 / hits as f64),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// Persistent proof archive (feature = "store")
+// Proofs have to survive a crash until they land on-chain. The store is an
+// append-only log of put/submitted records replayed into memory on open;
+// pruning rewrites the log with only the surviving entries. A torn final
+// record from a crash mid-write is dropped on the next open. Puts are synced
+// before they return, and rewrites sync the directory after the rename, so
+// an accepted proof is on disk. Only a missing file opens as an empty store;
+// any other open error is returned rather than overwriting the archive.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{Challenge, Solution};
+
+const TAG_PUT: u8 = 0;
+const TAG_SUBMITTED: u8 = 1;
+
+/// Proofs are keyed by the challenge and the segment they prove
+pub type ProofKey = (Challenge, u64);
+
+/// A stored proof
+#[derive(Debug)]
+pub struct StoredProof {
+    /// Epoch the proof was produced in, used for pruning
+    pub epoch: u64,
+    pub solution: Solution,
+    /// Whether the proof has been accepted on-chain
+    pub submitted: bool,
+}
+
+/// File-backed proof archive
+pub struct ProofStore {
+    path: PathBuf,
+    log: BufWriter<File>,
+    proofs: BTreeMap<ProofKey, StoredProof>,
+}
+
+impl ProofStore {
+    /// Open the archive at `path`, creating it if missing
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut proofs = BTreeMap::new();
+
+        match File::open(&path) {
+            Ok(file) => {
+                let mut r = BufReader::new(file);
+                loop {
+                    match read_record(&mut r, &mut proofs) {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        // Rewrite on open so a torn tail never sits in front of new records
+        write_log(&path, &proofs)?;
+        let log = BufWriter::new(OpenOptions::new().append(true).open(&path)?);
+        Ok(Self { path, log, proofs })
+    }
+
+    /// Store `solution` for `(challenge, segment)`, replacing any earlier proof
+    pub fn put(
+        &mut self,
+        challenge: impl Into<Challenge>,
+        segment: u64,
+        epoch: u64,
+        solution: Solution,
+    ) -> io::Result<()> {
+        let key = (challenge.into(), segment);
+        write_put(&mut self.log, &key, epoch, &solution)?;
+        self.log.flush()?;
+        self.log.get_ref().sync_data()?;
+
+        self.proofs.insert(key, StoredProof { epoch, solution, submitted: false });
+        Ok(())
+    }
+
+    /// Record that the proof for `(challenge, segment)` was accepted
+    ///
+    /// Returns `false` if no such proof is stored.
+    pub fn mark_submitted(
+        &mut self,
+        challenge: impl Into<Challenge>,
+        segment: u64,
+    ) -> io::Result<bool> {
+        let key = (challenge.into(), segment);
+        let Some(proof) = self.proofs.get_mut(&key) else {
+            return Ok(false);
+        };
+
+        write_submitted(&mut self.log, &key)?;
+        self.log.flush()?;
+        proof.submitted = true;
+        Ok(true)
+    }
+
+    /// Proof stored for `(challenge, segment)`
+    pub fn get(&self, challenge: impl Into<Challenge>, segment: u64) -> Option<&StoredProof> {
+        self.proofs.get(&(challenge.into(), segment))
+    }
+
+    /// Proofs not yet accepted on-chain, for replay after a restart
+    pub fn unsubmitted(&self) -> impl Iterator<Item = (&ProofKey, &StoredProof)> {
+        self.proofs.iter().filter(|(_, p)| !p.submitted)
+    }
+
+    /// Number of stored proofs
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Whether the store holds no proofs
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Drop every proof from an epoch before `epoch` and compact the log
+    ///
+    /// Returns how many proofs were removed.
+    pub fn prune(&mut self, epoch: u64) -> io::Result<usize> {
+        let before = self.proofs.len();
+        self.proofs.retain(|_, p| p.epoch >= epoch);
+
+        write_log(&self.path, &self.proofs)?;
+        self.log = BufWriter::new(OpenOptions::new().append(true).open(&self.path)?);
+        Ok(before - self.proofs.len())
+    }
+}
+
+/// Atomically replace the log at `path` with one record per proof
+fn write_log(path: &Path, proofs: &BTreeMap<ProofKey, StoredProof>) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    for (key, proof) in proofs {
+        write_put(&mut w, key, proof.epoch, &proof.solution)?;
+        if proof.submitted {
+            write_submitted(&mut w, key)?;
+        }
+    }
+    w.into_inner()?.sync_all()?;
+    fs::rename(tmp, path)?;
+    sync_dir(path)
+}
+
+/// Sync the directory holding `path` so a rename into it is durable
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()
+}
+
+/// Directories can't be opened for syncing here; the rename is all there is
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+fn write_put(
+    w: &mut impl Write,
+    key: &ProofKey,
+    epoch: u64,
+    solution: &Solution,
+) -> io::Result<()> {
+    w.write_all(&[TAG_PUT])?;
+    write_key(w, key)?;
+    w.write_all(&epoch.to_le_bytes())?;
+    w.write_all(&solution.to_versioned_bytes())
+}
+
+fn write_submitted(w: &mut impl Write, key: &ProofKey) -> io::Result<()> {
+    w.write_all(&[TAG_SUBMITTED])?;
+    write_key(w, key)
+}
+
+fn write_key(w: &mut impl Write, (challenge, segment): &ProofKey) -> io::Result<()> {
+    w.write_all(challenge.as_bytes())?;
+    w.write_all(&segment.to_le_bytes())
+}
+
+/// Apply one record; `Ok(false)` at a clean end of file
+fn read_record(
+    r: &mut impl Read,
+    proofs: &mut BTreeMap<ProofKey, StoredProof>,
+) -> io::Result<bool> {
+    let mut tag = [0u8; 1];
+    if r.read(&mut tag)? == 0 {
+        return Ok(false);
+    }
+
+    let key = (Challenge(take(r)?), u64::from_le_bytes(take(r)?));
+    match tag[0] {
+        TAG_PUT => {
+            let epoch = u64::from_le_bytes(take(r)?);
+            let solution = Solution::from_versioned_bytes(&take::<26>(r)?)
+                .map_err(|_| malformed())?;
+            proofs.insert(key, StoredProof { epoch, solution, submitted: false });
+        }
+        TAG_SUBMITTED => {
+            if let Some(proof) = proofs.get_mut(&key) {
+                proof.submitted = true;
+            }
+        }
+        _ => return Err(malformed()),
+    }
+    Ok(true)
+}
+
+fn take<const L: usize>(r: &mut impl Read) -> io::Result<[u8; L]> {
+    let mut out = [0u8; L];
+    r.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "malformed proof store record")
+}
@@ -0,0 +1,9 @@
+// Tapes: segments laid out on disk
+// `format` fixes the file layout every prover and auditor reads; `writer`
+// builds tapes whose segments are proved as they are appended.
+
+pub mod format;
+pub mod writer;
+
+pub use format::{Header, IndexEntry, Reader, Seal, TapeView, Writer};
+pub use writer::TapeWriter;
@@ -0,0 +1,448 @@
+// On-disk tape layout
+// Provers and auditors read the same files, so the layout is fixed here:
+//
+//   header  := magic (8) version:u16 flags:u16 segment_size:u32 count:u64
+//              challenge (32) reserved (8)                       64 bytes
+//   segment := count * segment_size bytes, the last one zero-padded
+//   index   := count * entry
+//   entry   := checksum (32) sealed:u8 reserved (7) nonce (8) digest (16)
+//
+// Integers are little-endian. Segment n starts at `64 + n * segment_size`
+// and its index entry at `index_offset + n * 64`, so both are O(1) seeks.
+// The checksum is keccak256 of the padded segment. A sealed entry carries
+// the proof made over the segment under the header's challenge.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::{
+    build_seed, keccak, verify_seed, Challenge, CrankXError, SegmentProvider, Solution,
+    MAX_SEGMENT_SIZE,
+};
+
+/// First bytes of every tape file
+pub const MAGIC: [u8; 8] = *b"CRANKXTP";
+
+/// Layout version written by [`Writer`]
+pub const VERSION: u16 = 1;
+
+/// Bytes in the header
+pub const HEADER_LEN: usize = 64;
+
+/// Bytes in one index entry
+pub const ENTRY_LEN: usize = 64;
+
+/// Fixed part of a tape file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u16,
+    /// Size of every segment, in bytes
+    pub segment_size: u32,
+    /// Segments in the file
+    pub count: u64,
+    /// Challenge the sealed entries were proved under, if any
+    pub challenge: Option<Challenge>,
+}
+
+impl Header {
+    pub fn new(segment_size: u32, count: u64) -> Self {
+        Self { version: VERSION, segment_size, count, challenge: None }
+    }
+
+    /// File offset of segment `index`; in range for `index <= count` once
+    /// [`Header::file_len`] has succeeded
+    pub fn segment_offset(&self, index: u64) -> u64 {
+        HEADER_LEN as u64 + index * self.segment_size as u64
+    }
+
+    /// File offset of the first index entry
+    pub fn index_offset(&self) -> u64 {
+        self.segment_offset(self.count)
+    }
+
+    /// File offset of the entry for segment `index`
+    pub fn entry_offset(&self, index: u64) -> u64 {
+        self.index_offset() + index * ENTRY_LEN as u64
+    }
+
+    /// Length of a complete file with this header
+    ///
+    /// [`CrankXError::InvalidLength`] if it doesn't fit in a `u64`, as with
+    /// a corrupt or hostile `count`.
+    pub fn file_len(&self) -> Result<u64, CrankXError> {
+        let entry = self.segment_size as u64 + ENTRY_LEN as u64;
+        self.count
+            .checked_mul(entry)
+            .and_then(|body| body.checked_add(HEADER_LEN as u64))
+            .ok_or(CrankXError::InvalidLength)
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0; HEADER_LEN];
+        bytes[..8].copy_from_slice(&MAGIC);
+        bytes[8..10].copy_from_slice(&self.version.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.segment_size.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.count.to_le_bytes());
+        bytes[24..56].copy_from_slice(&self.challenge.unwrap_or_default().0);
+        bytes
+    }
+
+    /// Inverse of [`Header::to_bytes`]
+    ///
+    /// [`CrankXError::InvalidEncoding`] on a bad magic or a segment size of
+    /// zero or above [`MAX_SEGMENT_SIZE`], [`CrankXError::UnsupportedVersion`] on any version but
+    /// [`VERSION`].
+    pub fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Result<Self, CrankXError> {
+        if bytes[..8] != MAGIC {
+            return Err(CrankXError::InvalidEncoding);
+        }
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != VERSION {
+            return Err(CrankXError::UnsupportedVersion);
+        }
+        let segment_size = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        if segment_size == 0 || segment_size as usize > MAX_SEGMENT_SIZE {
+            return Err(CrankXError::InvalidEncoding);
+        }
+        let count = u64::from_le_bytes(bytes[16..24].try_into().unwrap());
+        let challenge: [u8; 32] = bytes[24..56].try_into().unwrap();
+        let challenge = (challenge != [0; 32]).then_some(Challenge(challenge));
+        Ok(Self { version, segment_size, count, challenge })
+    }
+}
+
+/// Proof recorded against a segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seal {
+    pub nonce: [u8; 8],
+    pub digest: [u8; 16],
+}
+
+impl Seal {
+    pub fn to_solution(&self) -> Solution {
+        Solution::new(self.digest, self.nonce)
+    }
+
+    /// Check the seal proves `segment` under `challenge`
+    pub fn verify(&self, challenge: &Challenge, segment: &[u8]) -> Result<(), CrankXError> {
+        verify_seed(&build_seed(challenge.as_bytes(), segment, &self.nonce)?, &self.digest)
+    }
+}
+
+impl From<&Solution> for Seal {
+    fn from(solution: &Solution) -> Self {
+        Self { nonce: solution.n, digest: solution.d }
+    }
+}
+
+/// What the index records about one segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// keccak256 of the padded segment
+    pub checksum: [u8; 32],
+    pub seal: Option<Seal>,
+}
+
+impl IndexEntry {
+    /// Unsealed entry for `segment`
+    pub fn for_segment(segment: &[u8]) -> Self {
+        Self { checksum: keccak(&[segment]), seal: None }
+    }
+
+    /// Whether `segment` hashes to the recorded checksum
+    pub fn matches(&self, segment: &[u8]) -> bool {
+        keccak(&[segment]) == self.checksum
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENTRY_LEN] {
+        let mut bytes = [0; ENTRY_LEN];
+        bytes[..32].copy_from_slice(&self.checksum);
+        if let Some(seal) = &self.seal {
+            bytes[32] = 1;
+            bytes[40..48].copy_from_slice(&seal.nonce);
+            bytes[48..].copy_from_slice(&seal.digest);
+        }
+        bytes
+    }
+
+    /// Inverse of [`IndexEntry::to_bytes`]
+    pub fn from_bytes(bytes: &[u8; ENTRY_LEN]) -> Result<Self, CrankXError> {
+        let checksum = bytes[..32].try_into().unwrap();
+        let seal = match bytes[32] {
+            0 => None,
+            1 => Some(Seal {
+                nonce: bytes[40..48].try_into().unwrap(),
+                digest: bytes[48..].try_into().unwrap(),
+            }),
+            _ => return Err(CrankXError::InvalidEncoding),
+        };
+        Ok(Self { checksum, seal })
+    }
+}
+
+/// Streams segments into a tape file
+///
+/// The header is rewritten with the final count by [`Writer::finish`]; a
+/// file that was never finished fails to open.
+pub struct Writer<W: Write + Seek> {
+    inner: W,
+    header: Header,
+    entries: Vec<IndexEntry>,
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// Start a tape of `segment_size`-byte segments at the start of `inner`
+    pub fn new(mut inner: W, segment_size: u32) -> io::Result<Self> {
+        if segment_size == 0 || segment_size as usize > MAX_SEGMENT_SIZE {
+            let message = format!("segment_size must be 1..={MAX_SEGMENT_SIZE}");
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        let header = Header::new(segment_size, 0);
+        inner.seek(SeekFrom::Start(0))?;
+        inner.write_all(&header.to_bytes())?;
+        Ok(Self { inner, header, entries: Vec::new() })
+    }
+
+    /// Record the challenge sealed entries are proved under
+    pub fn set_challenge(&mut self, challenge: impl Into<Challenge>) {
+        self.header.challenge = Some(challenge.into());
+    }
+
+    /// Segments appended so far
+    pub fn count(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    pub fn segment_size(&self) -> u32 {
+        self.header.segment_size
+    }
+
+    /// Append one segment, zero-padding it to the segment size; returns its
+    /// index
+    pub fn append(&mut self, segment: &[u8]) -> io::Result<u64> {
+        let size = self.header.segment_size as usize;
+        if segment.len() > size {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "segment exceeds segment_size"));
+        }
+        let mut padded = Cow::Borrowed(segment);
+        if segment.len() < size {
+            padded.to_mut().resize(size, 0);
+        }
+        self.inner.write_all(&padded)?;
+        self.entries.push(IndexEntry::for_segment(&padded));
+        Ok(self.count() - 1)
+    }
+
+    /// Record `seal` as the proof for segment `index`
+    pub fn seal(&mut self, index: u64, seal: Seal) -> io::Result<()> {
+        let entry = usize::try_from(index)
+            .ok()
+            .and_then(|i| self.entries.get_mut(i))
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "segment not written"))?;
+        entry.seal = Some(seal);
+        Ok(())
+    }
+
+    /// Write the index and the final header, handing back the sink
+    pub fn finish(mut self) -> io::Result<W> {
+        self.header.count = self.count();
+        for entry in &self.entries {
+            self.inner.write_all(&entry.to_bytes())?;
+        }
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.inner.write_all(&self.header.to_bytes())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Tape laid over bytes already in memory, such as a memory-mapped file
+///
+/// Segments are borrowed without copying and without checking their
+/// checksum; call [`TapeView::check`] where that matters.
+#[derive(Debug, Clone)]
+pub struct TapeView<B> {
+    bytes: B,
+    header: Header,
+}
+
+impl<B: AsRef<[u8]>> TapeView<B> {
+    /// [`CrankXError::InvalidLength`] unless `bytes` is exactly one
+    /// complete tape
+    pub fn new(bytes: B) -> Result<Self, CrankXError> {
+        let (head, _) =
+            bytes.as_ref().split_first_chunk::<HEADER_LEN>().ok_or(CrankXError::InvalidLength)?;
+        let header = Header::from_bytes(head)?;
+        if bytes.as_ref().len() as u64 != header.file_len()? {
+            return Err(CrankXError::InvalidLength);
+        }
+        Ok(Self { bytes, header })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Index entry for segment `index`
+    pub fn entry(&self, index: u64) -> Result<IndexEntry, CrankXError> {
+        self.check_index(index)?;
+        let at = self.header.entry_offset(index) as usize;
+        let (entry, _) = self.bytes.as_ref()[at..].split_first_chunk().unwrap();
+        IndexEntry::from_bytes(entry)
+    }
+
+    /// Bytes of segment `index`
+    pub fn segment_bytes(&self, index: u64) -> Result<&[u8], CrankXError> {
+        self.check_index(index)?;
+        let at = self.header.segment_offset(index) as usize;
+        Ok(&self.bytes.as_ref()[at..at + self.header.segment_size as usize])
+    }
+
+    /// [`CrankXError::InvalidEncoding`] if segment `index` doesn't match its
+    /// checksum
+    pub fn check(&self, index: u64) -> Result<(), CrankXError> {
+        if !self.entry(index)?.matches(self.segment_bytes(index)?) {
+            return Err(CrankXError::InvalidEncoding);
+        }
+        Ok(())
+    }
+
+    /// Segments whose seal is missing or doesn't prove them under the
+    /// header's challenge, in index order
+    pub fn unsealed(&self) -> Result<Vec<u64>, CrankXError> {
+        let challenge = self.header.challenge.unwrap_or_default();
+        let mut bad = Vec::new();
+        for index in 0..self.header.count {
+            let segment = self.segment_bytes(index)?;
+            let sealed = match self.entry(index)?.seal {
+                Some(seal) => seal.verify(&challenge, segment).is_ok(),
+                None => false,
+            };
+            if !sealed {
+                bad.push(index);
+            }
+        }
+        Ok(bad)
+    }
+
+    fn check_index(&self, index: u64) -> Result<(), CrankXError> {
+        if index >= self.header.count {
+            return Err(CrankXError::SegmentOutOfRange { index, count: self.header.count });
+        }
+        Ok(())
+    }
+}
+
+impl<B: AsRef<[u8]>> SegmentProvider for TapeView<B> {
+    type Error = CrankXError;
+
+    fn segment_count(&self) -> u64 {
+        self.header.count
+    }
+
+    fn segment(&self, index: u64) -> Result<Cow<'_, [u8]>, CrankXError> {
+        self.segment_bytes(index).map(Cow::Borrowed)
+    }
+}
+
+/// Tape read from a file a segment at a time
+///
+/// The header and index are loaded on open; every segment read is checked
+/// against its checksum.
+#[derive(Debug)]
+pub struct Reader {
+    file: Mutex<File>,
+    header: Header,
+    entries: Vec<IndexEntry>,
+}
+
+impl Reader {
+    /// Open the tape at `path`; `InvalidData` if it isn't a complete tape
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut head = [0; HEADER_LEN];
+        file.read_exact(&mut head)?;
+        let header = Header::from_bytes(&head).map_err(invalid_data)?;
+        if file.metadata()?.len() != header.file_len().map_err(invalid_data)? {
+            return Err(invalid_data(CrankXError::InvalidLength));
+        }
+
+        file.seek(SeekFrom::Start(header.index_offset()))?;
+        let mut entries = Vec::with_capacity(header.count.min(1 << 20) as usize);
+        let mut entry = [0; ENTRY_LEN];
+        for _ in 0..header.count {
+            file.read_exact(&mut entry)?;
+            entries.push(IndexEntry::from_bytes(&entry).map_err(invalid_data)?);
+        }
+        Ok(Self { file: Mutex::new(file), header, entries })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Index entry for segment `index`
+    pub fn entry(&self, index: u64) -> Option<&IndexEntry> {
+        usize::try_from(index).ok().and_then(|i| self.entries.get(i))
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Read segment `index`; `InvalidData` if it doesn't match its checksum
+    pub fn read_segment(&self, index: u64) -> io::Result<Vec<u8>> {
+        let entry = self.entry(index).ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::NotFound,
+                CrankXError::SegmentOutOfRange { index, count: self.header.count },
+            )
+        })?;
+        let mut segment = vec![0; self.header.segment_size as usize];
+        {
+            let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            file.seek(SeekFrom::Start(self.header.segment_offset(index)))?;
+            file.read_exact(&mut segment)?;
+        }
+        if !entry.matches(&segment) {
+            return Err(io::Error::new(ErrorKind::InvalidData, "segment checksum mismatch"));
+        }
+        Ok(segment)
+    }
+
+    /// Segments whose seal is missing or doesn't prove them under the
+    /// header's challenge, in index order
+    pub fn unsealed(&self) -> io::Result<Vec<u64>> {
+        let challenge = self.header.challenge.unwrap_or_default();
+        let mut bad = Vec::new();
+        for (index, entry) in (0..).zip(&self.entries) {
+            let sealed = match &entry.seal {
+                Some(seal) => seal.verify(&challenge, &self.read_segment(index)?).is_ok(),
+                None => false,
+            };
+            if !sealed {
+                bad.push(index);
+            }
+        }
+        Ok(bad)
+    }
+}
+
+impl SegmentProvider for Reader {
+    type Error = io::Error;
+
+    fn segment_count(&self) -> u64 {
+        self.header.count
+    }
+
+    fn segment(&self, index: u64) -> io::Result<Cow<'_, [u8]>> {
+        self.read_segment(index).map(Cow::Owned)
+    }
+}
+
+fn invalid_data(e: CrankXError) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,75 @@
+// Tapes sealed as they are written
+// Each appended segment is cranked against the tape's creation challenge
+// before it is written, and the proof goes into its index entry. A sealed
+// tape can later be checked segment by segment against those proofs, which
+// gives an archive an integrity baseline from the moment it was made.
+
+use std::io::{self, Seek, Write};
+
+use crate::miner::Miner;
+use crate::tape::format::{Seal, Writer};
+use crate::{Challenge, Solution};
+
+/// [`Writer`] that proves every segment it appends
+pub struct TapeWriter<W: Write + Seek> {
+    format: Writer<W>,
+    miner: Miner,
+    challenge: Challenge,
+    min_difficulty: u32,
+}
+
+impl<W: Write + Seek> TapeWriter<W> {
+    /// Start a tape sealed under `challenge`, cranked by `miner`
+    pub fn new(
+        inner: W,
+        segment_size: u32,
+        challenge: impl Into<Challenge>,
+        miner: Miner,
+    ) -> io::Result<Self> {
+        let challenge = challenge.into();
+        let mut format = Writer::new(inner, segment_size)?;
+        format.set_challenge(challenge);
+        Ok(Self { format, miner, challenge, min_difficulty: 0 })
+    }
+
+    /// Only seal with proofs of at least `min_difficulty` (default 0)
+    pub fn min_difficulty(mut self, min_difficulty: u32) -> Self {
+        self.min_difficulty = min_difficulty;
+        self
+    }
+
+    pub fn challenge(&self) -> &Challenge {
+        &self.challenge
+    }
+
+    /// Segments appended so far
+    pub fn count(&self) -> u64 {
+        self.format.count()
+    }
+
+    /// Prove `segment`, zero-padded to the segment size, then append it
+    /// sealed with the proof
+    ///
+    /// Nothing is written if proving fails; mining errors come back as
+    /// `io::ErrorKind::Other` wrapping the
+    /// [`CrankXError`](crate::CrankXError).
+    pub fn append(&mut self, segment: &[u8]) -> io::Result<Solution> {
+        let mut padded = segment.to_vec();
+        if padded.len() < self.format.segment_size() as usize {
+            padded.resize(self.format.segment_size() as usize, 0);
+        }
+        let report = self.miner.mine(self.challenge, &padded, self.min_difficulty);
+        let solution = report.map_err(io::Error::other)?.solution.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Interrupted, "miner stopped before sealing")
+        })?;
+
+        let index = self.format.append(&padded)?;
+        self.format.seal(index, Seal::from(&solution))?;
+        Ok(solution)
+    }
+
+    /// Write the index and header, handing back the sink
+    pub fn finish(self) -> io::Result<W> {
+        self.format.finish()
+    }
+}
@@ -0,0 +1,117 @@
+// 256-bit targets and the odds of hitting them
+// A hash with at least `d` leading zero bits is exactly a hash at or below
+// `2^(256 - d) - 1` read big-endian, so difficulty and target are two views
+// of the same threshold. Probabilities are per final hash: a seed that yields
+// no EquiX solution produces no hash, so pair these with a rate of solutions
+// (e.g. `BenchReport::solves_per_sec`), not of nonces.
+// Attempts until the first hit are geometric, so the mean hides a long tail:
+// the 99th percentile wait is about 4.6x the mean, which is what a deadline
+// has to be sized against.
+
+use std::time::Duration;
+
+use crate::difficulty_of;
+
+/// Percentile waits for a first solution, see [`Target::time_to_solution`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeToSolution {
+    pub median: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+}
+
+/// Inclusive upper bound on a final hash, big-endian
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Target(pub [u8; 32]);
+
+impl Target {
+    /// Target met by exactly the hashes with at least `difficulty` leading
+    /// zero bits (capped at 256)
+    pub fn from_difficulty(difficulty: u32) -> Self {
+        let zeros = difficulty.min(256) as usize;
+        let mut bytes = [0xff; 32];
+        bytes[..zeros / 8].fill(0);
+        if zeros < 256 {
+            bytes[zeros / 8] = 0xff >> (zeros % 8);
+        }
+        Self(bytes)
+    }
+
+    /// Leading zero bits every hash meeting this target has
+    pub fn difficulty(&self) -> u32 {
+        difficulty_of(&self.0)
+    }
+
+    /// Raw big-endian target bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Whether `hash` is at or below the target
+    pub fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        hash <= &self.0
+    }
+
+    /// Chance a single final hash meets the target
+    pub fn probability(&self) -> f64 {
+        // (target + 1) / 2^256, one byte at a time from the top
+        let scaled = self.0.iter().rev().fold(1.0, |acc, &b| (acc + b as f64) / 256.0);
+        scaled.min(1.0)
+    }
+
+    /// Chance at least one of `attempts` final hashes meets the target
+    pub fn probability_within(&self, attempts: u64) -> f64 {
+        // 1 - (1 - p)^n without losing tiny p to rounding
+        -(attempts as f64 * (-self.probability()).ln_1p()).exp_m1()
+    }
+
+    /// Mean number of final hashes needed to meet the target
+    pub fn expected_attempts(&self) -> f64 {
+        1.0 / self.probability()
+    }
+
+    /// Mean time to meet the target at `hashrate` final hashes per second
+    ///
+    /// `Duration::MAX` when the rate is zero or the wait doesn't fit.
+    pub fn eta(&self, hashrate: f64) -> Duration {
+        Duration::try_from_secs_f64(self.expected_attempts() / hashrate).unwrap_or(Duration::MAX)
+    }
+
+    /// Final hashes needed to meet the target with probability `confidence`
+    /// (in `0.0..1.0`), rounded up
+    pub fn attempts_for(&self, confidence: f64) -> f64 {
+        // Smallest n with 1 - (1 - p)^n >= confidence
+        ((-confidence).ln_1p() / (-self.probability()).ln_1p()).ceil()
+    }
+
+    /// Time within which the target is met with probability `confidence` at
+    /// `hashrate` final hashes per second
+    ///
+    /// `Duration::MAX` when the rate is zero or the wait doesn't fit.
+    pub fn eta_within(&self, hashrate: f64, confidence: f64) -> Duration {
+        Duration::try_from_secs_f64(self.attempts_for(confidence) / hashrate)
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Median, 90th and 99th percentile time to a first solution at
+    /// `hashrate` final hashes per second
+    pub fn time_to_solution(&self, hashrate: f64) -> TimeToSolution {
+        TimeToSolution {
+            median: self.eta_within(hashrate, 0.5),
+            p90: self.eta_within(hashrate, 0.9),
+            p99: self.eta_within(hashrate, 0.99),
+        }
+    }
+
+    /// Final hashes per second needed to meet the target within `deadline`
+    /// with probability `confidence`, for sizing a fleet
+    pub fn hashrate_for(&self, deadline: Duration, confidence: f64) -> f64 {
+        self.attempts_for(confidence) / deadline.as_secs_f64()
+    }
+}
+
+impl From<u32> for Target {
+    fn from(difficulty: u32) -> Self {
+        Self::from_difficulty(difficulty)
+    }
+}
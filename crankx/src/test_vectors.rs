@@ -0,0 +1,191 @@
+// Known-answer vectors for cross-implementation testing
+// Each one is the first nonce (counting up from zero) whose seed has an EquiX
+// solution, with the first solution the solver returns for it. The same set
+// ships as `tests/vectors.json` for non-Rust implementations.
+// `self_test` only verifies, so it is cheap enough for startup checks on
+// unusual targets (SBF, wasm, big-endian); `self_test_with_runtime` also
+// re-solves each vector to check the solver and HashX runtime in use.
+
+use equix::{EquiXBuilder, RuntimeOption, SolverMemory};
+
+use crate::{
+    build_seed, compute_hash, difficulty_of, solve_seed_with_builder, verify_seed, CrankXError,
+    SelectionPolicy,
+};
+
+/// One (challenge, data, nonce) → (digest, hash, difficulty) tuple
+#[derive(Debug, Clone, Copy)]
+pub struct TestVector {
+    /// Short label, matching the JSON fixture
+    pub name: &'static str,
+    pub challenge: [u8; 32],
+    pub data: &'static [u8],
+    pub nonce: [u8; 8],
+    /// Raw EquiX digest, as returned by the solver
+    pub digest: [u8; 16],
+    /// Final keccak(canonical digest || nonce) hash
+    pub hash: [u8; 32],
+    pub difficulty: u32,
+}
+
+/// Golden vectors, generated deterministically
+pub const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        name: "zeros",
+        challenge: [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        data: &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        nonce: [0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        digest: [
+            0xaf, 0x23, 0x4c, 0x28, 0x4a, 0x35, 0x3c, 0xe6, 0x5f, 0x3e, 0x85, 0x46, 0x42, 0x85, 0x6f, 0xf8,
+        ],
+        hash: [
+            0xcd, 0xe8, 0x02, 0x7e, 0xfd, 0x79, 0x1d, 0x91, 0xc6, 0x9b, 0x88, 0x0f, 0x69, 0xfc, 0xe0, 0x2b,
+            0x63, 0xb5, 0xa0, 0x13, 0xc0, 0x70, 0x0b, 0xd2, 0xf7, 0xe2, 0x52, 0xb5, 0x64, 0xd2, 0x43, 0xcc,
+        ],
+        difficulty: 0,
+    },
+    TestVector {
+        name: "ones",
+        challenge: [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ],
+        data: &[
+            0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+            0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        ],
+        nonce: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        digest: [
+            0x52, 0x1f, 0x0b, 0xac, 0x36, 0x3b, 0x3b, 0xad, 0xb1, 0x2b, 0xef, 0x55, 0x6a, 0x30, 0x76, 0xaf,
+        ],
+        hash: [
+            0x64, 0x02, 0xa5, 0xca, 0xcd, 0xf9, 0x91, 0x82, 0x94, 0xf0, 0xea, 0x07, 0xa4, 0xec, 0xb1, 0x23,
+            0x62, 0x4d, 0xc6, 0x35, 0xd7, 0x75, 0xa0, 0xc8, 0xda, 0x6c, 0x9a, 0x4c, 0x1a, 0x58, 0xe7, 0x83,
+        ],
+        difficulty: 1,
+    },
+    TestVector {
+        name: "counting",
+        challenge: [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+        ],
+        data: &[
+            0x00, 0x07, 0x0e, 0x15, 0x1c, 0x23, 0x2a, 0x31, 0x38, 0x3f, 0x46, 0x4d, 0x54, 0x5b, 0x62, 0x69,
+            0x70, 0x77, 0x7e, 0x85, 0x8c, 0x93, 0x9a, 0xa1, 0xa8, 0xaf, 0xb6, 0xbd, 0xc4, 0xcb, 0xd2, 0xd9,
+            0xe0, 0xe7, 0xee, 0xf5, 0xfc, 0x03, 0x0a, 0x11, 0x18, 0x1f, 0x26, 0x2d, 0x34, 0x3b, 0x42, 0x49,
+            0x50, 0x57, 0x5e, 0x65, 0x6c, 0x73, 0x7a, 0x81, 0x88, 0x8f, 0x96, 0x9d, 0xa4, 0xab, 0xb2, 0xb9,
+            0xc0, 0xc7, 0xce, 0xd5, 0xdc, 0xe3, 0xea, 0xf1, 0xf8, 0xff, 0x06, 0x0d, 0x14, 0x1b, 0x22, 0x29,
+            0x30, 0x37, 0x3e, 0x45, 0x4c, 0x53, 0x5a, 0x61, 0x68, 0x6f, 0x76, 0x7d, 0x84, 0x8b, 0x92, 0x99,
+            0xa0, 0xa7, 0xae, 0xb5, 0xbc, 0xc3, 0xca, 0xd1, 0xd8, 0xdf, 0xe6, 0xed, 0xf4, 0xfb, 0x02, 0x09,
+            0x10, 0x17, 0x1e, 0x25, 0x2c, 0x33, 0x3a, 0x41, 0x48, 0x4f, 0x56, 0x5d, 0x64, 0x6b, 0x72, 0x79,
+        ],
+        nonce: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        digest: [
+            0x1e, 0x15, 0x9f, 0x3d, 0x9f, 0x35, 0xf5, 0xb7, 0xb8, 0x51, 0xe1, 0x6f, 0xd9, 0x20, 0x1c, 0xd0,
+        ],
+        hash: [
+            0x8b, 0x27, 0x20, 0xa7, 0xfb, 0x17, 0xb9, 0x9e, 0xf4, 0x90, 0x50, 0x02, 0x18, 0xc1, 0xd7, 0xdc,
+            0xcf, 0xc4, 0x9c, 0x66, 0xe3, 0x40, 0x78, 0xc8, 0x10, 0x12, 0x50, 0x74, 0x8b, 0xc3, 0x39, 0xcb,
+        ],
+        difficulty: 0,
+    },
+    TestVector {
+        name: "single_byte",
+        challenge: [
+            0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a,
+            0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a, 0x5a,
+        ],
+        data: &[
+            0xab,
+        ],
+        nonce: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        digest: [
+            0x83, 0x46, 0xe0, 0xa9, 0x17, 0x15, 0x25, 0xb1, 0x10, 0xb3, 0x69, 0xe4, 0x19, 0xd3, 0xc5, 0xff,
+        ],
+        hash: [
+            0x26, 0x63, 0x1a, 0x0d, 0x01, 0x4a, 0xf7, 0xfc, 0xca, 0x62, 0xe8, 0x5b, 0xb1, 0xab, 0xc2, 0x2b,
+            0x81, 0xbc, 0x22, 0xcb, 0x31, 0xd9, 0xcb, 0xb6, 0x48, 0x50, 0xee, 0xb2, 0x3e, 0x7c, 0x88, 0xe5,
+        ],
+        difficulty: 2,
+    },
+    TestVector {
+        name: "descending",
+        challenge: [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+        ],
+        data: &[
+            0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8, 0xf7, 0xf6, 0xf5, 0xf4, 0xf3, 0xf2, 0xf1, 0xf0,
+            0xef, 0xee, 0xed, 0xec, 0xeb, 0xea, 0xe9, 0xe8, 0xe7, 0xe6, 0xe5, 0xe4, 0xe3, 0xe2, 0xe1, 0xe0,
+            0xdf, 0xde, 0xdd, 0xdc, 0xdb, 0xda, 0xd9, 0xd8, 0xd7, 0xd6, 0xd5, 0xd4, 0xd3, 0xd2, 0xd1, 0xd0,
+            0xcf, 0xce, 0xcd, 0xcc, 0xcb, 0xca, 0xc9, 0xc8, 0xc7, 0xc6, 0xc5, 0xc4, 0xc3, 0xc2, 0xc1, 0xc0,
+            0xbf, 0xbe, 0xbd, 0xbc, 0xbb, 0xba, 0xb9, 0xb8, 0xb7, 0xb6, 0xb5, 0xb4, 0xb3, 0xb2, 0xb1, 0xb0,
+            0xaf, 0xae, 0xad, 0xac, 0xab, 0xaa, 0xa9, 0xa8, 0xa7, 0xa6, 0xa5, 0xa4, 0xa3, 0xa2, 0xa1, 0xa0,
+            0x9f, 0x9e, 0x9d, 0x9c, 0x9b, 0x9a, 0x99, 0x98, 0x97, 0x96, 0x95, 0x94, 0x93, 0x92, 0x91, 0x90,
+            0x8f, 0x8e, 0x8d, 0x8c, 0x8b, 0x8a, 0x89, 0x88, 0x87, 0x86, 0x85, 0x84, 0x83, 0x82, 0x81, 0x80,
+            0x7f, 0x7e, 0x7d, 0x7c, 0x7b, 0x7a, 0x79, 0x78, 0x77, 0x76, 0x75, 0x74, 0x73, 0x72, 0x71, 0x70,
+            0x6f, 0x6e, 0x6d, 0x6c, 0x6b, 0x6a, 0x69, 0x68, 0x67, 0x66, 0x65, 0x64, 0x63, 0x62, 0x61, 0x60,
+            0x5f, 0x5e, 0x5d, 0x5c, 0x5b, 0x5a, 0x59, 0x58, 0x57, 0x56, 0x55, 0x54, 0x53, 0x52, 0x51, 0x50,
+            0x4f, 0x4e, 0x4d, 0x4c, 0x4b, 0x4a, 0x49, 0x48, 0x47, 0x46, 0x45, 0x44, 0x43, 0x42, 0x41, 0x40,
+            0x3f, 0x3e, 0x3d, 0x3c, 0x3b, 0x3a, 0x39, 0x38, 0x37, 0x36, 0x35, 0x34, 0x33, 0x32, 0x31, 0x30,
+            0x2f, 0x2e, 0x2d, 0x2c, 0x2b, 0x2a, 0x29, 0x28, 0x27, 0x26, 0x25, 0x24, 0x23, 0x22, 0x21, 0x20,
+            0x1f, 0x1e, 0x1d, 0x1c, 0x1b, 0x1a, 0x19, 0x18, 0x17, 0x16, 0x15, 0x14, 0x13, 0x12, 0x11, 0x10,
+            0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a, 0x09, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0x00,
+        ],
+        nonce: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        digest: [
+            0x71, 0x60, 0x57, 0x92, 0x2c, 0x7f, 0x21, 0xaa, 0x4a, 0x37, 0x79, 0xb4, 0x6c, 0xa6, 0xac, 0xfb,
+        ],
+        hash: [
+            0x63, 0x58, 0xd7, 0xa2, 0x66, 0x22, 0x5d, 0xda, 0x00, 0x7b, 0x38, 0x8c, 0xfb, 0x4b, 0x15, 0x3e,
+            0x9a, 0x5a, 0x77, 0xfe, 0x8d, 0xc4, 0xc0, 0x44, 0x27, 0x62, 0x12, 0x3a, 0x8e, 0x46, 0x25, 0x67,
+        ],
+        difficulty: 1,
+    },
+];
+
+/// Check that this build reproduces every golden vector
+pub fn self_test() -> Result<(), CrankXError> {
+    for v in TEST_VECTORS {
+        verify_seed(&build_seed(&v.challenge, v.data, &v.nonce)?, &v.digest)?;
+
+        let hash = compute_hash(&v.digest, &v.nonce);
+        if hash != v.hash || difficulty_of(&hash) != v.difficulty {
+            return Err(CrankXError::InvalidSolution);
+        }
+    }
+
+    Ok(())
+}
+
+/// [`self_test`], then solve every vector's seed with `runtime` and check the
+/// solver's first solution is the golden digest
+///
+/// Fails with [`CrankXError::CompilerUnavailable`] for
+/// `RuntimeOption::CompileOnly` where HashX can't compile.
+pub fn self_test_with_runtime(runtime: RuntimeOption) -> Result<(), CrankXError> {
+    self_test()?;
+
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(runtime);
+    let mut memory = SolverMemory::new();
+    for v in TEST_VECTORS {
+        let seed = build_seed(&v.challenge, v.data, &v.nonce)?;
+        let policy = SelectionPolicy::First;
+        let solution = solve_seed_with_builder(&builder, &mut memory, &seed, &v.nonce, policy)?;
+        if solution.d != v.digest || solution.to_hash() != v.hash {
+            return Err(CrankXError::InvalidSolution);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+// Fixtures for crates testing against crankx (feature = "testing")
+// Helpers here panic instead of returning errors: they build known-good
+// inputs, and a failure means the test setup itself is wrong.
+// Generated segments are SplitMix64 output from a seed, so they vary byte to
+// byte like real data yet come out identical on every platform and run.
+
+mod export;
+#[cfg(feature = "solana")]
+pub mod solana;
+
+pub use export::{export_vectors, vectors_to_csv, vectors_to_json, ExportedVector};
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::miner::solve_from_zero;
+use crate::rng::SplitMix64;
+use crate::{Challenge, Solution, DEFAULT_RUNTIME};
+
+/// `len` pseudorandom bytes determined by `seed`
+pub fn gen_segment(seed: u64, len: usize) -> Vec<u8> {
+    let mut segment = vec![0; len];
+    SplitMix64(seed).fill(&mut segment);
+    segment
+}
+
+/// [`gen_segment`] as an array, for the const-generic solve and verify
+/// functions
+pub fn gen_segment_array<const N: usize>(seed: u64) -> [u8; N] {
+    let mut segment = [0; N];
+    SplitMix64(seed).fill(&mut segment);
+    segment
+}
+
+/// `count` segments of `len` bytes; segment `i` is
+/// `gen_segment(tape_segment_seed(seed, i), len)`
+pub fn gen_tape(seed: u64, count: u64, len: usize) -> Vec<Vec<u8>> {
+    (0..count).map(|i| gen_segment(tape_segment_seed(seed, i), len)).collect()
+}
+
+/// Seed of segment `index` on the tape generated from `seed`, so one
+/// segment can be regenerated without the rest
+pub fn tape_segment_seed(seed: u64, index: u64) -> u64 {
+    SplitMix64(seed ^ index.rotate_left(32)).next_u64()
+}
+
+/// First proof over `data` of at least `min_difficulty`, scanning nonces
+/// from zero
+///
+/// Keep `min_difficulty` small (each bit doubles the expected work); panics
+/// if `data` is not a valid segment.
+pub fn solve_at_least(
+    challenge: impl Into<Challenge>,
+    data: &[u8],
+    min_difficulty: u32,
+) -> Solution {
+    let mut builder = EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let found = solve_from_zero(
+        &builder,
+        &mut SolverMemory::new(),
+        &challenge.into(),
+        data,
+        min_difficulty,
+        || false,
+    );
+    match found {
+        Ok(Some(solution)) => solution,
+        Ok(None) => panic!("nonce space exhausted below difficulty {min_difficulty}"),
+        Err(e) => panic!("cannot solve test segment: {e}"),
+    }
+}
@@ -0,0 +1,194 @@
+// Conformance vectors for other implementations
+// The golden vectors plus generated cases, rendered as JSON (the layout of
+// `tests/vectors.json`, with `canonical` and `valid` added) or CSV. Besides
+// random segments the set pins down the edges a port gets wrong: empty and
+// all-zero data, the longest seed, digests whose canonical word order differs
+// from the raw one, and a word-swapped digest that hashes the same yet must
+// be rejected. Rejected cases carry `valid: false`.
+
+use equix::{EquiXBuilder, SolverMemory};
+
+use crate::rng::SplitMix64;
+use crate::test_vectors::TEST_VECTORS;
+use crate::{
+    build_seed, compute_hash, difficulty_of, solve_seed_with_builder, to_canonical, verify_seed,
+    SelectionPolicy, DEFAULT_RUNTIME, MAX_DATA_LEN, MIN_SEGMENT_SIZE,
+};
+
+/// One exported case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedVector {
+    pub name: String,
+    pub challenge: [u8; 32],
+    pub data: Vec<u8>,
+    pub nonce: [u8; 8],
+    /// Raw EquiX digest
+    pub digest: [u8; 16],
+    /// Digest with its u16 words sorted, as hashed
+    pub canonical: [u8; 16],
+    /// keccak(canonical || nonce)
+    pub hash: [u8; 32],
+    pub difficulty: u32,
+    /// Whether a verifier must accept the case
+    pub valid: bool,
+}
+
+impl ExportedVector {
+    fn new(
+        name: String,
+        challenge: [u8; 32],
+        data: Vec<u8>,
+        nonce: [u8; 8],
+        digest: [u8; 16],
+    ) -> Self {
+        let mut canonical = digest;
+        to_canonical(&mut canonical);
+        let hash = compute_hash(&digest, &nonce);
+        let valid = build_seed(&challenge, &data, &nonce)
+            .and_then(|seed| verify_seed(&seed, &digest))
+            .is_ok();
+        Self {
+            name,
+            challenge,
+            data,
+            nonce,
+            digest,
+            canonical,
+            hash,
+            difficulty: difficulty_of(&hash),
+            valid,
+        }
+    }
+}
+
+/// The golden vectors, the edge cases, then `random` generated cases drawn
+/// from `seed`
+///
+/// Deterministic: the same arguments always give the same cases.
+pub fn export_vectors(random: usize, seed: u64) -> Vec<ExportedVector> {
+    let mut solver = Solver::new();
+    let mut rng = SplitMix64(seed);
+    let mut vectors: Vec<_> = TEST_VECTORS
+        .iter()
+        .map(|v| {
+            ExportedVector::new(v.name.into(), v.challenge, v.data.to_vec(), v.nonce, v.digest)
+        })
+        .collect();
+
+    vectors.push(ExportedVector::new("empty_data".into(), [7; 32], Vec::new(), [0; 8], [0; 16]));
+    vectors.push(solver.first("min_zeros".into(), [0; 32], vec![0; MIN_SEGMENT_SIZE]));
+    let challenge = random_bytes(&mut rng);
+    vectors.push(solver.first("max_seed".into(), challenge, random_data(&mut rng, MAX_DATA_LEN)));
+
+    // First case whose raw digest isn't already in canonical order, then the
+    // same digest with two differing words swapped: same hash, invalid proof
+    let reorder = (0u8..)
+        .map(|i| solver.first("canonical_reorder".into(), random_bytes(&mut rng), vec![i; 32]))
+        .find(|v| v.canonical != v.digest)
+        .unwrap();
+    let mut digest = reorder.digest;
+    let (words, _) = digest.as_chunks_mut::<2>();
+    let other = words.iter().position(|w| *w != words[0]).unwrap_or(1);
+    words.swap(0, other);
+    let (challenge, data, nonce) = (reorder.challenge, reorder.data.clone(), reorder.nonce);
+    let swapped = ExportedVector::new("swapped_words".into(), challenge, data, nonce, digest);
+    vectors.extend([reorder, swapped]);
+
+    for i in 0..random {
+        let challenge = random_bytes(&mut rng);
+        let spread = MAX_DATA_LEN + 1 - MIN_SEGMENT_SIZE;
+        let len = MIN_SEGMENT_SIZE + rng.next_u64() as usize % spread;
+        vectors.push(solver.first(format!("random_{i}"), challenge, random_data(&mut rng, len)));
+    }
+    vectors
+}
+
+/// `{"vectors": [...]}` with byte fields in lowercase hex
+pub fn vectors_to_json(vectors: &[ExportedVector]) -> String {
+    let entries: Vec<String> = vectors
+        .iter()
+        .map(|v| {
+            format!(
+                "    {{\n      \"name\": \"{}\",\n      \"challenge\": \"{}\",\n      \
+                 \"data\": \"{}\",\n      \"nonce\": \"{}\",\n      \"digest\": \"{}\",\n      \
+                 \"canonical\": \"{}\",\n      \"hash\": \"{}\",\n      \"difficulty\": {},\n      \
+                 \"valid\": {}\n    }}",
+                v.name,
+                hex(&v.challenge),
+                hex(&v.data),
+                hex(&v.nonce),
+                hex(&v.digest),
+                hex(&v.canonical),
+                hex(&v.hash),
+                v.difficulty,
+                v.valid,
+            )
+        })
+        .collect();
+    format!("{{\n  \"vectors\": [\n{}\n  ]\n}}\n", entries.join(",\n"))
+}
+
+/// One header line, then one line per case, byte fields in lowercase hex
+pub fn vectors_to_csv(vectors: &[ExportedVector]) -> String {
+    let mut csv = String::from("name,challenge,data,nonce,digest,canonical,hash,difficulty,valid");
+    csv.push('\n');
+    for v in vectors {
+        csv += &format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            v.name,
+            hex(&v.challenge),
+            hex(&v.data),
+            hex(&v.nonce),
+            hex(&v.digest),
+            hex(&v.canonical),
+            hex(&v.hash),
+            v.difficulty,
+            v.valid,
+        );
+    }
+    csv
+}
+
+/// Finds each case's first solvable nonce, as the golden vectors were made
+struct Solver {
+    builder: EquiXBuilder,
+    memory: SolverMemory,
+}
+
+impl Solver {
+    fn new() -> Self {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(DEFAULT_RUNTIME);
+        Self { builder, memory: SolverMemory::new() }
+    }
+
+    fn first(&mut self, name: String, challenge: [u8; 32], data: Vec<u8>) -> ExportedVector {
+        let (nonce, digest) = (0u64..)
+            .map(u64::to_le_bytes)
+            .find_map(|nonce| {
+                let seed = build_seed(&challenge, &data, &nonce).ok()?;
+                let policy = SelectionPolicy::First;
+                solve_seed_with_builder(&self.builder, &mut self.memory, &seed, &nonce, policy)
+                    .ok()
+                    .map(|s| (nonce, s.d))
+            })
+            .unwrap();
+        ExportedVector::new(name, challenge, data, nonce, digest)
+    }
+}
+
+fn random_bytes(rng: &mut SplitMix64) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    rng.fill(&mut bytes);
+    bytes
+}
+
+fn random_data(rng: &mut SplitMix64, len: usize) -> Vec<u8> {
+    let mut data = vec![0; len];
+    rng.fill(&mut data);
+    data
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
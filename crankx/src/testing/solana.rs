@@ -0,0 +1,93 @@
+// Fixtures for on-chain integration tests
+// Not tied to a test harness: instructions come out as plain `Instruction`s
+// for `solana-program-test`, LiteSVM or a hand-rolled `process_instruction`
+// call, and expected failures come out as the `ProgramError` or
+// `InstructionError` each of those reports.
+
+use solana_program::account_info::AccountInfo;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+use super::solve_at_least;
+use crate::solana::cpi::submit_proof_with_program;
+use crate::solana::SubmitProof;
+use crate::{Challenge, CrankXError};
+
+/// A valid `SubmitProof` for `segment` at `min_difficulty`, with that
+/// difficulty as its stated minimum
+pub fn valid_submission(
+    challenge: impl Into<Challenge>,
+    segment: &[u8],
+    min_difficulty: u32,
+) -> SubmitProof<'_> {
+    let challenge = challenge.into();
+    let solution = solve_at_least(challenge, segment, min_difficulty);
+    SubmitProof {
+        challenge: challenge.to_bytes(),
+        min_difficulty,
+        proof: solution.to_bytes(),
+        segment,
+    }
+}
+
+/// Instruction submitting a fresh valid proof to the verifier at
+/// `program_id`, signed by `miner`
+pub fn submit_ix(
+    program_id: &Pubkey,
+    miner: &Pubkey,
+    challenge: impl Into<Challenge>,
+    segment: &[u8],
+    min_difficulty: u32,
+) -> Instruction {
+    let proof = valid_submission(challenge, segment, min_difficulty);
+    submit_proof_with_program(program_id, miner, &proof)
+}
+
+/// The error a program reports for `e` (via crankx's `ProgramError` mapping)
+pub fn program_error(e: CrankXError) -> ProgramError {
+    e.into()
+}
+
+/// The `InstructionError` a transaction reports for `e`, for matching
+/// `TransactionError::InstructionError(index, ..)`
+pub fn instruction_error(e: CrankXError) -> InstructionError {
+    match program_error(e) {
+        ProgramError::Custom(code) => InstructionError::Custom(code),
+        other => InstructionError::from(u64::from(other)),
+    }
+}
+
+/// Panic unless `result` failed with `expected`'s error code
+#[track_caller]
+pub fn assert_rejected<T: core::fmt::Debug>(
+    result: Result<T, ProgramError>,
+    expected: CrankXError,
+) {
+    let expected = program_error(expected);
+    match result {
+        Err(e) => assert_eq!(e, expected, "wrong rejection"),
+        Ok(v) => panic!("expected {expected:?}, got Ok({v:?})"),
+    }
+}
+
+/// Call `process` directly with a single miner account, as the reference
+/// verifier expects, without a runtime
+pub fn process_with_miner(
+    process: impl FnOnce(&Pubkey, &[AccountInfo], &[u8]) -> Result<(), ProgramError>,
+    ix: &Instruction,
+) -> Result<(), ProgramError> {
+    let meta = ix.accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (owner, mut lamports, mut data) = (Pubkey::default(), 0, []);
+    let miner = AccountInfo::new(
+        &meta.pubkey,
+        meta.is_signer,
+        meta.is_writable,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+    process(&ix.program_id, &[miner], &ix.data)
+}
@@ -0,0 +1,154 @@
+// Terminal dashboard over a running daemon (feature = "tui")
+// `crankx tui --config <path>` runs the daemon on a thread of its own and
+// redraws its `Metrics` every half second: hashrate per miner thread, taken
+// from the change in that thread's attempts since the last frame, segments
+// proved and pending per tape, the best difficulty found and the last few
+// warnings and errors. `q`, Esc or Ctrl-C stops the daemon; the terminal is
+// restored on the way out, error or not.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::{Block, List, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use crate::metrics::{Metrics, RECENT_ERRORS};
+
+/// How often the dashboard redraws
+const FRAME: Duration = Duration::from_millis(500);
+
+/// What one frame shows
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// Hashes per second of each miner thread, indexed by thread
+    pub hashrates: Vec<f64>,
+    /// `(tape, proved, pending)` for every tape the daemon has seen
+    pub segments: Vec<(String, u64, u64)>,
+    pub best_difficulty: u32,
+    pub solutions: u64,
+    /// Last few warnings and errors, oldest first
+    pub errors: Vec<String>,
+}
+
+/// Turns successive reads of a [`Metrics`] into [`Snapshot`]s
+pub struct Dashboard {
+    metrics: Arc<Metrics>,
+    attempts: Vec<u64>,
+    at: Instant,
+}
+
+impl Dashboard {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        let attempts = metrics.worker_attempts();
+        Self { metrics, attempts, at: Instant::now() }
+    }
+
+    /// The metrics as they are now, with hashrates averaged since the last
+    /// snapshot (or since [`Dashboard::new`])
+    pub fn snapshot(&mut self) -> Snapshot {
+        let now = Instant::now();
+        let secs = now.duration_since(self.at).as_secs_f64();
+        let attempts = self.metrics.worker_attempts();
+        let hashrates = attempts
+            .iter()
+            .enumerate()
+            .map(|(worker, &n)| {
+                let delta = n.saturating_sub(self.attempts.get(worker).copied().unwrap_or(0));
+                if secs > 0.0 {
+                    delta as f64 / secs
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        (self.attempts, self.at) = (attempts, now);
+
+        Snapshot {
+            hashrates,
+            segments: self.metrics.segments(),
+            best_difficulty: self.metrics.best_difficulty(),
+            solutions: self.metrics.solutions(),
+            errors: self.metrics.recent_errors(),
+        }
+    }
+}
+
+/// Draw `snapshot` over the whole frame
+pub fn draw(frame: &mut Frame, snapshot: &Snapshot) {
+    let [summary, tables, errors] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(4),
+        Constraint::Length(RECENT_ERRORS as u16 + 2),
+    ])
+    .areas(frame.area());
+    let [workers, segments] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(tables);
+
+    let total: f64 = snapshot.hashrates.iter().sum();
+    let line = format!(
+        "{total:.1} H/s   best difficulty {}   {} solutions",
+        snapshot.best_difficulty, snapshot.solutions
+    );
+    frame.render_widget(Paragraph::new(line).block(Block::bordered().title("crankx")), summary);
+
+    let rows = snapshot
+        .hashrates
+        .iter()
+        .enumerate()
+        .map(|(worker, rate)| Row::new([worker.to_string(), format!("{rate:.1}")]));
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(8)])
+        .header(Row::new(["worker", "H/s"]))
+        .block(Block::bordered().title("Hashrate"));
+    frame.render_widget(table, workers);
+
+    let rows = snapshot.segments.iter().map(|(tape, proved, pending)| {
+        Row::new([tape.clone(), proved.to_string(), pending.to_string()])
+    });
+    let widths = [Constraint::Min(12), Constraint::Length(8), Constraint::Length(8)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(["tape", "proved", "pending"]))
+        .block(Block::bordered().title("Segments"));
+    frame.render_widget(table, segments);
+
+    let list = List::new(snapshot.errors.iter().map(String::as_str))
+        .block(Block::bordered().title("Recent errors"));
+    frame.render_widget(list, errors);
+}
+
+/// Take over the terminal and show `metrics` until `stop` is set or the
+/// user quits, which sets `stop`
+pub fn run(metrics: Arc<Metrics>, stop: &AtomicBool) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut dashboard = Dashboard::new(metrics);
+    let mut shown = || -> io::Result<()> {
+        while !stop.load(Relaxed) {
+            let snapshot = dashboard.snapshot();
+            terminal.draw(|frame| draw(frame, &snapshot))?;
+
+            let deadline = Instant::now() + FRAME;
+            while let Some(wait) = deadline.checked_duration_since(Instant::now()) {
+                if !event::poll(wait)? {
+                    break;
+                }
+                let Event::Key(key) = event::read()? else {
+                    continue;
+                };
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c;
+                if key.kind == KeyEventKind::Press && quit {
+                    stop.store(true, Relaxed);
+                    break;
+                }
+            }
+        }
+        Ok(())
+    };
+    let result = shown();
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,180 @@
+// Picking threads, batch size and HashX runtime for this machine
+// The best settings depend on core count, SMT, cache sizes and whether the
+// HashX compiler works here, so `autotune` measures instead of guessing:
+// every combination runs the real `Miner` over the same segment for a short
+// trial and the one with the most attempts per second wins. The batch size
+// is `Miner::batch_size`, how many consecutive nonces a thread takes between
+// looks at shared state. Rates within `TIE_TOLERANCE` of each other count as
+// a tie, which goes to fewer threads and then the smaller batch, the one
+// that stops soonest. With the `toml` feature the result is written into
+// the miner's settings file, so a fleet tunes once per machine and
+// `crankx daemon` picks it up.
+
+#[cfg(feature = "toml")]
+use std::fs;
+#[cfg(feature = "toml")]
+use std::io::{self, ErrorKind};
+#[cfg(feature = "toml")]
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use equix::RuntimeOption;
+
+#[cfg(feature = "toml")]
+use crate::config::{runtime_key, Config};
+use crate::miner::Miner;
+use crate::{build_seed, Challenge, CrankXError};
+
+/// Relative difference in rate below which two trials tie
+pub const TIE_TOLERANCE: f64 = 0.02;
+
+/// What [`autotune_with`] tries
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneOptions {
+    pub threads: Vec<usize>,
+    pub batch_sizes: Vec<u64>,
+    pub runtimes: Vec<RuntimeOption>,
+    /// How long each combination cranks
+    pub trial: Duration,
+    pub segment_size: usize,
+}
+
+impl Default for TuneOptions {
+    /// Powers of two up to every core plus the core count, batches of 1, 8
+    /// and 64, compiled and interpreted HashX, 250ms per trial over a
+    /// 128-byte segment
+    fn default() -> Self {
+        let cores = thread::available_parallelism().map_or(1, |n| n.get());
+        let mut threads: Vec<_> = (0..).map(|i| 1 << i).take_while(|&n| n < cores).collect();
+        threads.push(cores);
+        Self {
+            threads,
+            batch_sizes: vec![1, 8, 64],
+            runtimes: vec![RuntimeOption::TryCompile, RuntimeOption::InterpretOnly],
+            trial: Duration::from_millis(250),
+            segment_size: 128,
+        }
+    }
+}
+
+/// One combination and how it did
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tuning {
+    pub threads: usize,
+    pub batch_size: u64,
+    pub runtime: RuntimeOption,
+    /// Nonces per second the miner got through
+    pub attempts_per_sec: f64,
+}
+
+impl Tuning {
+    /// Miner with these threads, batch size and runtime
+    pub fn miner(&self) -> Miner {
+        Miner::new(self.threads).batch_size(self.batch_size).runtime(self.runtime)
+    }
+
+    /// Set `config`'s threads, batch size and runtime to these
+    #[cfg(feature = "toml")]
+    pub fn apply(&self, config: &mut Config) {
+        config.threads = self.threads;
+        config.batch_size = self.batch_size;
+        config.runtime = self.runtime;
+    }
+
+    /// Write `threads`, `batch_size` and `runtime` into the settings file at
+    /// `path`
+    ///
+    /// The file must already be a valid [`Config`]. Only those keys change:
+    /// everything else, comments and layout included, is kept. `InvalidData`
+    /// if it doesn't parse.
+    #[cfg(feature = "toml")]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        Config::from_toml(&text)?;
+        let mut file: toml_edit::DocumentMut = text
+            .parse()
+            .map_err(|e: toml_edit::TomlError| io::Error::new(ErrorKind::InvalidData, e))?;
+        set(&mut file, "threads", self.threads as i64);
+        set(&mut file, "batch_size", self.batch_size as i64);
+        set(&mut file, "runtime", runtime_key(self.runtime));
+        fs::write(path, file.to_string())
+    }
+}
+
+/// Set top-level `key` to `value`, keeping the comments around the old one
+#[cfg(feature = "toml")]
+fn set(file: &mut toml_edit::DocumentMut, key: &str, value: impl Into<toml_edit::Value>) {
+    let mut value = value.into();
+    if let Some(old) = file.get(key).and_then(toml_edit::Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    file[key] = toml_edit::Item::Value(value);
+}
+
+/// Every trial [`autotune_with`] ran, and the winner
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuneReport {
+    pub best: Tuning,
+    /// In the order tried: runtimes, then threads, then batch sizes
+    pub trials: Vec<Tuning>,
+}
+
+/// [`autotune_with`] the default options, a few seconds on most machines
+pub fn autotune() -> Result<TuneReport, CrankXError> {
+    autotune_with(&TuneOptions::default())
+}
+
+/// Mine with each combination in `options` for `options.trial` and keep the
+/// fastest
+///
+/// A runtime that can't run here (`CompileOnly` without a working compiler)
+/// scores zero rather than failing the run. [`CrankXError::InvalidLength`]
+/// if the segment size can't be cranked or any list in `options` is empty.
+pub fn autotune_with(options: &TuneOptions) -> Result<TuneReport, CrankXError> {
+    let data = vec![42u8; options.segment_size];
+    build_seed(&[0; 32], &data, &[0; 8])?;
+
+    let mut trials = Vec::new();
+    for &runtime in &options.runtimes {
+        for &threads in &options.threads {
+            for &batch_size in &options.batch_sizes {
+                let (threads, batch_size) = (threads.max(1), batch_size.max(1));
+                let tuning = Tuning { threads, batch_size, runtime, attempts_per_sec: 0.0 };
+                let attempts_per_sec = trial(&tuning.miner(), &data, options.trial);
+                trials.push(Tuning { attempts_per_sec, ..tuning });
+            }
+        }
+    }
+
+    let best = trials
+        .iter()
+        .copied()
+        .reduce(|best, t| {
+            let (rate, best_rate) = (t.attempts_per_sec, best.attempts_per_sec);
+            let tie = (rate - best_rate).abs() <= TIE_TOLERANCE * rate.max(best_rate);
+            let smaller = (t.threads, t.batch_size) < (best.threads, best.batch_size);
+            if (tie && smaller) || (!tie && rate > best_rate) {
+                t
+            } else {
+                best
+            }
+        })
+        .ok_or(CrankXError::InvalidLength)?;
+    Ok(TuneReport { best, trials })
+}
+
+/// Attempts per second `miner` manages on `data` over `duration`
+fn trial(miner: &Miner, data: &[u8], duration: Duration) -> f64 {
+    thread::scope(|s| {
+        // No solution reaches this, so only the shutdown below ends it
+        let mining = s.spawn(|| miner.mine(Challenge::default(), data, u32::MAX));
+        thread::sleep(duration);
+        miner.shutdown(Duration::from_secs(60));
+        match mining.join().unwrap() {
+            Ok(report) => report.attempts_per_sec(),
+            Err(_) => 0.0,
+        }
+    })
+}
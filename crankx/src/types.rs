@@ -0,0 +1,314 @@
+// Typed challenge and nonce
+// Raw `[u8; 32]` / `[u8; 8]` parameters are easy to swap or mis-endian; the API
+// accepts anything `Into<Challenge>` / `Into<Nonce>`, so existing byte-array
+// callers keep working.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::CrankXError;
+
+/// 32-byte challenge the seed starts with
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes), archive_attr(derive(Debug)))]
+pub struct Challenge(pub [u8; 32]);
+
+/// 8-byte nonce the seed ends with
+///
+/// Integer nonces are always encoded little-endian.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", archive(check_bytes), archive_attr(derive(Debug)))]
+pub struct Nonce(pub [u8; 8]);
+
+/// 16-byte nonce for [`crate::wide`], with room for a worker or epoch tag
+/// next to the counter
+///
+/// Integer nonces are always encoded little-endian.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WideNonce(pub [u8; 16]);
+
+impl Challenge {
+    /// Raw challenge bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Copy out the raw challenge bytes
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Parse 64 hex characters, with or without a `0x` prefix
+    pub fn from_hex(s: &str) -> Result<Self, CrankXError> {
+        decode_hex(s).map(Self)
+    }
+
+    /// Random challenge from the thread-local RNG
+    #[cfg(feature = "rand")]
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl Nonce {
+    /// Little-endian encoding of `n`
+    pub fn from_u64(n: u64) -> Self {
+        Self(n.to_le_bytes())
+    }
+
+    /// Little-endian decoding of the nonce
+    pub fn to_u64(self) -> u64 {
+        u64::from_le_bytes(self.0)
+    }
+
+    /// Raw nonce bytes
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+
+    /// Copy out the raw nonce bytes
+    pub fn to_bytes(self) -> [u8; 8] {
+        self.0
+    }
+
+    /// Parse 16 hex characters (raw byte order), with or without a `0x` prefix
+    pub fn from_hex(s: &str) -> Result<Self, CrankXError> {
+        decode_hex(s).map(Self)
+    }
+
+    /// Random nonce from the thread-local RNG
+    #[cfg(feature = "rand")]
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl WideNonce {
+    /// Little-endian encoding of `n`
+    pub fn from_u128(n: u128) -> Self {
+        Self(n.to_le_bytes())
+    }
+
+    /// Little-endian decoding of the nonce
+    pub fn to_u128(self) -> u128 {
+        u128::from_le_bytes(self.0)
+    }
+
+    /// `counter (8, LE) || tag (8, LE)`, e.g. a per-worker counter under a
+    /// worker id and epoch packed into `tag`
+    pub fn from_parts(tag: u64, counter: u64) -> Self {
+        Self::from_u128((tag as u128) << 64 | counter as u128)
+    }
+
+    /// `(tag, counter)`, the inverse of [`WideNonce::from_parts`]
+    pub fn to_parts(self) -> (u64, u64) {
+        let n = self.to_u128();
+        ((n >> 64) as u64, n as u64)
+    }
+
+    /// Raw nonce bytes
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Copy out the raw nonce bytes
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0
+    }
+
+    /// Parse 32 hex characters (raw byte order), with or without a `0x` prefix
+    pub fn from_hex(s: &str) -> Result<Self, CrankXError> {
+        decode_hex(s).map(Self)
+    }
+
+    /// Random nonce from the thread-local RNG
+    #[cfg(feature = "rand")]
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
+macro_rules! impl_bytes_newtype {
+    ($name:ident, $len:literal) => {
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<&[u8; $len]> for $name {
+            fn from(bytes: &[u8; $len]) -> Self {
+                Self(*bytes)
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(value: &$name) -> Self {
+                *value
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = CrankXError;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, CrankXError> {
+                bytes
+                    .try_into()
+                    .map(Self)
+                    .map_err(|_| CrankXError::InvalidLength)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = CrankXError;
+
+            fn from_str(s: &str) -> Result<Self, CrankXError> {
+                Self::from_hex(s)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+        }
+    };
+}
+
+impl_bytes_newtype!(Challenge, 32);
+impl_bytes_newtype!(Nonce, 8);
+impl_bytes_newtype!(WideNonce, 16);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Challenge {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hex(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Challenge {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_hex(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Nonce {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hex(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Nonce {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_hex(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WideNonce {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hex(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WideNonce {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_hex(deserializer).map(Self)
+    }
+}
+
+impl From<u64> for Nonce {
+    fn from(n: u64) -> Self {
+        Self::from_u64(n)
+    }
+}
+
+impl From<u128> for WideNonce {
+    fn from(n: u128) -> Self {
+        Self::from_u128(n)
+    }
+}
+
+/// Serialize `bytes` as a lowercase hex string
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_hex<S: serde::Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    s.serialize_str(&hex)
+}
+
+/// Deserialize a hex string of exactly `L` bytes
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_hex<'de, D, const L: usize>(d: D) -> Result<[u8; L], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(d)?;
+    decode_hex(&s).map_err(serde::de::Error::custom)
+}
+
+/// Deserialize a hex string of any even length
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_hex_vec<'de, D>(d: D) -> Result<Vec<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(d)?;
+    decode_hex_vec(&s).map_err(serde::de::Error::custom)
+}
+
+/// Decode hex of any even length, working on bytes so non-ASCII input is
+/// rejected rather than split mid-character
+#[cfg(feature = "serde")]
+pub(crate) fn decode_hex_vec(s: &str) -> Result<Vec<u8>, CrankXError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.len().is_multiple_of(2) {
+        return Err(CrankXError::InvalidHex);
+    }
+    s.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| Ok((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
+
+/// Decode exactly `L` bytes of hex
+fn decode_hex<const L: usize>(s: &str) -> Result<[u8; L], CrankXError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.len() != 2 * L {
+        return Err(CrankXError::InvalidHex);
+    }
+
+    let mut bytes = [0u8; L];
+    for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(bytes)
+}
+
+fn nibble(c: u8) -> Result<u8, CrankXError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(CrankXError::InvalidHex),
+    }
+}
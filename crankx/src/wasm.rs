@@ -0,0 +1,94 @@
+// Browser bindings for multi-core proving (feature = "wasm")
+// Thin wasm-bindgen wrappers over `workers`; every value crossing into JS is
+// a byte buffer, so jobs and reports go through postMessage as transferable
+// ArrayBuffers. The intended wiring:
+//
+//   main thread   jobs = splitJob(challenge, segment, difficulty, n)
+//                 post jobs[i] to worker i, along with a shared Int32Array
+//                 stop flag; feed every report to an Aggregator until it
+//                 says done, then set the flag
+//   worker i      w = new Worker(job); loop { r = w.step(k); post r;
+//                 stop if r says done or Atomics.load(flag, 0) != 0 }
+//
+// HashX can't compile in the browser, so workers always interpret.
+
+use equix::RuntimeOption;
+use wasm_bindgen::prelude::*;
+
+use crate::workers::{self, WorkerJob, WorkerReport};
+use crate::CrankXError;
+
+fn js_error(e: CrankXError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// Job buffers for `workers` workers, concatenated; job `i` starts at
+/// `i * jobLength(segment.length)`
+#[wasm_bindgen(js_name = splitJob)]
+pub fn split_job(
+    challenge: &[u8],
+    segment: &[u8],
+    min_difficulty: u32,
+    workers: u32,
+) -> Result<Vec<u8>, JsError> {
+    let challenge: [u8; 32] =
+        challenge.try_into().map_err(|_| js_error(CrankXError::InvalidLength))?;
+    if workers == 0 {
+        return Err(JsError::new("at least one worker is needed"));
+    }
+    let jobs = WorkerJob::split(challenge, segment, min_difficulty, workers);
+    Ok(jobs.iter().flat_map(WorkerJob::to_bytes).collect())
+}
+
+/// Bytes in one job buffer for a `segment_len`-byte segment
+#[wasm_bindgen(js_name = jobLength)]
+pub fn job_length(segment_len: usize) -> usize {
+    workers::JOB_HEADER_LEN + segment_len
+}
+
+/// One Web Worker's cranking state
+#[wasm_bindgen]
+pub struct Worker(workers::Worker);
+
+#[wasm_bindgen]
+impl Worker {
+    #[wasm_bindgen(constructor)]
+    pub fn new(job: &[u8]) -> Result<Worker, JsError> {
+        let job = WorkerJob::from_bytes(job).map_err(js_error)?;
+        Ok(Self(workers::Worker::new(job, RuntimeOption::InterpretOnly)))
+    }
+
+    /// Try up to `max_attempts` nonces; returns a report buffer
+    pub fn step(&mut self, max_attempts: u32) -> Result<Vec<u8>, JsError> {
+        let report = self.0.step(max_attempts as u64).map_err(js_error)?;
+        Ok(report.to_bytes().to_vec())
+    }
+}
+
+/// Main-thread merge of worker reports
+#[wasm_bindgen]
+pub struct Aggregator(workers::Aggregator);
+
+#[wasm_bindgen]
+impl Aggregator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(workers: u32) -> Aggregator {
+        Self(workers::Aggregator::new(workers))
+    }
+
+    /// Fold in one report buffer; returns whether the search is over
+    pub fn push(&mut self, report: &[u8]) -> Result<bool, JsError> {
+        let report = WorkerReport::from_bytes(report).map_err(js_error)?;
+        self.0.push(report).map_err(js_error)
+    }
+
+    /// Best solution so far as `digest (16) || nonce (8)`
+    pub fn best(&self) -> Option<Vec<u8>> {
+        self.0.best().map(|s| s.to_bytes().to_vec())
+    }
+
+    /// Nonces tried across all workers
+    pub fn attempts(&self) -> f64 {
+        self.0.attempts() as f64
+    }
+}
@@ -0,0 +1,171 @@
+// Proofs over a 16-byte nonce
+// A 64-bit nonce space per (challenge, segment) is plenty for one miner, but
+// runs short once worker ids and epoch counters are packed into the nonce.
+// Here the seed is `challenge || data || nonce (16)` and the final hash is
+// `hash(canonical digest || nonce (16))`, so a wide proof never verifies as
+// a regular one or the other way round. The versioned encoding carries its
+// own `WIDE_WIRE_VERSION`, so regular decoders reject it outright:
+//
+//   version:u8 = 2 || hash algorithm:u8 || digest:[u8; 16] || nonce:[u8; 16]
+
+use std::sync::OnceLock;
+
+use equix::SolverMemory;
+
+use crate::{
+    build_equix, build_seed, check_segment_size, difficulty_of, split_array, verify_seed,
+    Challenge, CrankXError, HashAlgorithm, Target, WideNonce, DEFAULT_RUNTIME, WIDE_WIRE_VERSION,
+};
+
+/// An EquiX digest and the 16-byte nonce it was found at
+///
+/// Same as [`crate::Solution`] apart from the nonce width; the final hash is
+/// cached on first use.
+#[derive(Debug, Default)]
+pub struct WideSolution {
+    /// Raw EquiX digest (16 bytes)
+    pub d: [u8; 16],
+    /// Nonce (16 bytes)
+    pub n: [u8; 16],
+    h: OnceLock<[u8; 32]>,
+    alg: HashAlgorithm,
+}
+
+impl WideSolution {
+    /// Create a new solution
+    pub fn new(digest: [u8; 16], nonce: [u8; 16]) -> Self {
+        Self::with_hash_algorithm(digest, nonce, HashAlgorithm::Keccak256)
+    }
+
+    /// Create a new solution whose final hash uses `alg` instead of Keccak256
+    pub fn with_hash_algorithm(digest: [u8; 16], nonce: [u8; 16], alg: HashAlgorithm) -> Self {
+        Self { d: digest, n: nonce, h: OnceLock::new(), alg }
+    }
+
+    /// Algorithm used for the final hash
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.alg
+    }
+
+    /// Verify the solution against the raw `challenge || data || nonce`
+    pub fn is_valid<const N: usize>(
+        &self,
+        challenge: impl Into<Challenge>,
+        data: &[u8; N],
+    ) -> Result<(), CrankXError> {
+        verify_wide(challenge, data, self.n, &self.d)
+    }
+
+    /// Final hash(digest || nonce) (32 bytes)
+    pub fn to_hash(&self) -> [u8; 32] {
+        *self.h.get_or_init(|| self.alg.hash(&self.d, &self.n))
+    }
+
+    /// Compute the difficulty of the solution
+    pub fn difficulty(&self) -> u32 {
+        difficulty_of(&self.to_hash())
+    }
+
+    /// Whether the final hash is at or below `target`
+    pub fn meets(&self, target: &Target) -> bool {
+        target.is_met_by(&self.to_hash())
+    }
+
+    /// Serialize as `digest (16) || nonce (16)`
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes[..16].copy_from_slice(&self.d);
+        bytes[16..].copy_from_slice(&self.n);
+        bytes
+    }
+
+    /// Deserialize `digest (16) || nonce (16)`
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let (d, n) = split_array(bytes);
+        Self::new(d, n)
+    }
+
+    /// Serialize to the self-describing wire format:
+    /// `version (1) || hash algorithm (1) || digest (16) || nonce (16)`
+    pub fn to_versioned_bytes(&self) -> [u8; 34] {
+        let mut bytes = [0; 34];
+        bytes[0] = WIDE_WIRE_VERSION;
+        bytes[1] = self.alg.id();
+        bytes[2..].copy_from_slice(&self.to_bytes());
+        bytes
+    }
+
+    /// Deserialize the self-describing wire format
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let bytes: &[u8; 34] = bytes
+            .try_into()
+            .map_err(|_| CrankXError::InvalidLength)?;
+
+        let (header, packed): ([u8; 2], [u8; 32]) = split_array(bytes);
+        if header[0] != WIDE_WIRE_VERSION {
+            return Err(CrankXError::UnsupportedVersion);
+        }
+
+        let alg = HashAlgorithm::from_id(header[1])?;
+        let (d, n) = split_array(&packed);
+        Ok(Self::with_hash_algorithm(d, n, alg))
+    }
+}
+
+/// Hex of the packed `digest || nonce` bytes
+#[cfg(feature = "serde")]
+impl serde::Serialize for WideSolution {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::types::serialize_hex(&self.to_bytes(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WideSolution {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::types::deserialize_hex(deserializer).map(|bytes| Self::from_bytes(&bytes))
+    }
+}
+
+/// Same canonical comparison as [`crate::Solution`]: equal final hashes
+impl PartialEq for WideSolution {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_hash() == other.to_hash()
+    }
+}
+
+impl Eq for WideSolution {}
+
+/// Solve PoW over raw `challenge || data || nonce (16)`, taking EquiX's first
+/// solution
+pub fn solve_wide<const N: usize>(
+    mem: &mut SolverMemory,
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<WideNonce>,
+) -> Result<WideSolution, CrankXError> {
+    check_segment_size::<N>();
+    let nonce = nonce.into();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.as_bytes())?;
+
+    let mut builder = equix::EquiXBuilder::new();
+    builder.runtime(DEFAULT_RUNTIME);
+    let eq = build_equix(&builder, &seed)?;
+
+    let first = eq.solve_with_memory(mem).first().map(|s| s.to_bytes());
+    first
+        .map(|d| WideSolution::new(d, nonce.to_bytes()))
+        .ok_or(CrankXError::NoSolution)
+}
+
+/// Verify a candidate digest against raw `challenge || data || nonce (16)`
+pub fn verify_wide<const N: usize>(
+    challenge: impl Into<Challenge>,
+    data: &[u8; N],
+    nonce: impl Into<WideNonce>,
+    digest: &[u8; 16],
+) -> Result<(), CrankXError> {
+    check_segment_size::<N>();
+    let seed = build_seed(challenge.into().as_bytes(), data, nonce.into().as_bytes())?;
+    verify_seed(&seed, digest)
+}
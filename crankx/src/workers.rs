@@ -0,0 +1,270 @@
+// Nonce search across message-passing workers
+// Hosts whose workers share nothing with the coordinator (browser Web
+// Workers, subprocesses) can't run `Miner`. Instead the coordinator splits a
+// segment's nonce space with `partition_nonces` into `WorkerJob`s, each a
+// flat buffer that can be transferred rather than copied. A worker cranks its
+// job in bounded steps, so between steps it can yield to its event loop and
+// poll a stop flag (an Int32Array over a SharedArrayBuffer, set by whoever
+// finishes first). Every step returns a `WorkerReport`, also a flat buffer,
+// and an `Aggregator` merges the reports.
+//
+//   job    := worker:u32 challenge (32) min_difficulty:u32 start:u64 end:u64
+//             segment
+//   report := worker:u32 found:u8 done:u8 attempts:u64 next:u64 solution (24)
+//
+// Integers are little-endian; `end` is inclusive.
+
+use equix::{EquiXBuilder, RuntimeOption, SolverMemory};
+
+use crate::nonces::partition_nonces;
+use crate::{
+    build_seed, check_seed_len, search_nonces, split_array, Challenge, CrankXError,
+    SelectionPolicy, Solution,
+};
+
+/// Bytes in a [`WorkerJob`] before the segment
+pub const JOB_HEADER_LEN: usize = 56;
+
+/// Bytes in a [`WorkerReport`]
+pub const REPORT_LEN: usize = 46;
+
+/// One worker's share of a segment's nonce space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerJob {
+    /// Index of the worker this job is for
+    pub worker: u32,
+    pub challenge: Challenge,
+    pub min_difficulty: u32,
+    /// First nonce to try
+    pub start: u64,
+    /// Last nonce to try, inclusive
+    pub end: u64,
+    pub segment: Vec<u8>,
+}
+
+impl WorkerJob {
+    /// Split the whole nonce space for `segment` between `workers` jobs
+    ///
+    /// Panics if `workers` is zero.
+    pub fn split(
+        challenge: impl Into<Challenge>,
+        segment: &[u8],
+        min_difficulty: u32,
+        workers: u32,
+    ) -> Vec<Self> {
+        let challenge = challenge.into();
+        (0..workers)
+            .map(|worker| {
+                let nonces = partition_nonces(worker as u64, workers as u64);
+                Self {
+                    worker,
+                    challenge,
+                    min_difficulty,
+                    start: *nonces.start(),
+                    end: *nonces.end(),
+                    segment: segment.to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(JOB_HEADER_LEN + self.segment.len());
+        bytes.extend_from_slice(&self.worker.to_le_bytes());
+        bytes.extend_from_slice(self.challenge.as_bytes());
+        bytes.extend_from_slice(&self.min_difficulty.to_le_bytes());
+        bytes.extend_from_slice(&self.start.to_le_bytes());
+        bytes.extend_from_slice(&self.end.to_le_bytes());
+        bytes.extend_from_slice(&self.segment);
+        bytes
+    }
+
+    /// Inverse of [`WorkerJob::to_bytes`]; also rejects segments that can't
+    /// be seeded, so a worker fails before it starts
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let Some((head, segment)) = bytes.split_first_chunk::<JOB_HEADER_LEN>() else {
+            return Err(CrankXError::InvalidLength);
+        };
+        check_seed_len(32, segment.len(), 8)?;
+        let (worker, rest): (_, [u8; 52]) = split_array(head);
+        let (challenge, rest): (_, [u8; 20]) = split_array(&rest);
+        let (min_difficulty, rest): (_, [u8; 16]) = split_array(&rest);
+        let (start, end) = split_array(&rest);
+        Ok(Self {
+            worker: u32::from_le_bytes(worker),
+            challenge: Challenge(challenge),
+            min_difficulty: u32::from_le_bytes(min_difficulty),
+            start: u64::from_le_bytes(start),
+            end: u64::from_le_bytes(end),
+            segment: segment.to_vec(),
+        })
+    }
+}
+
+/// What one [`Worker::step`] achieved
+#[derive(Debug, PartialEq, Eq)]
+pub struct WorkerReport {
+    pub worker: u32,
+    /// Qualifying solution, if this step found one
+    pub solution: Option<Solution>,
+    /// Whether the worker has nothing left to do: it found a solution or
+    /// ran out of nonces
+    pub done: bool,
+    /// Nonces tried during the step
+    pub attempts: u64,
+    /// Nonce the next step starts at
+    pub next: u64,
+}
+
+impl WorkerReport {
+    pub fn to_bytes(&self) -> [u8; REPORT_LEN] {
+        let mut bytes = [0; REPORT_LEN];
+        bytes[..4].copy_from_slice(&self.worker.to_le_bytes());
+        bytes[4] = self.solution.is_some() as u8;
+        bytes[5] = self.done as u8;
+        bytes[6..14].copy_from_slice(&self.attempts.to_le_bytes());
+        bytes[14..22].copy_from_slice(&self.next.to_le_bytes());
+        if let Some(solution) = &self.solution {
+            bytes[22..].copy_from_slice(&solution.to_bytes());
+        }
+        bytes
+    }
+
+    /// Inverse of [`WorkerReport::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CrankXError> {
+        let bytes: &[u8; REPORT_LEN] = bytes.try_into().map_err(|_| CrankXError::InvalidLength)?;
+        let flag = |b: u8| match b {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CrankXError::InvalidEncoding),
+        };
+        let (worker, rest): (_, [u8; 42]) = split_array(bytes);
+        let ([found, done], rest): (_, [u8; 40]) = split_array(&rest);
+        let (attempts, rest): (_, [u8; 32]) = split_array(&rest);
+        let (next, solution) = split_array(&rest);
+        Ok(Self {
+            worker: u32::from_le_bytes(worker),
+            solution: flag(found)?.then(|| Solution::from_bytes(&solution)),
+            done: flag(done)?,
+            attempts: u64::from_le_bytes(attempts),
+            next: u64::from_le_bytes(next),
+        })
+    }
+}
+
+/// Worker-side state for one [`WorkerJob`]
+pub struct Worker {
+    job: WorkerJob,
+    next: Option<u64>,
+    builder: EquiXBuilder,
+    memory: SolverMemory,
+}
+
+impl Worker {
+    /// Crank `job` with `runtime`; wasm has no compiler, so use
+    /// `RuntimeOption::InterpretOnly` there
+    pub fn new(job: WorkerJob, runtime: RuntimeOption) -> Self {
+        let mut builder = EquiXBuilder::new();
+        builder.runtime(runtime);
+        let next = (job.start <= job.end).then_some(job.start);
+        Self { job, next, builder, memory: SolverMemory::new() }
+    }
+
+    pub fn job(&self) -> &WorkerJob {
+        &self.job
+    }
+
+    /// Try up to `max_attempts` nonces, stopping early at the first solution
+    /// of at least the job's difficulty
+    pub fn step(&mut self, max_attempts: u64) -> Result<WorkerReport, CrankXError> {
+        let mut report = WorkerReport {
+            worker: self.job.worker,
+            solution: None,
+            done: false,
+            attempts: 0,
+            next: 0,
+        };
+        let mut seed = build_seed(self.job.challenge.as_bytes(), &self.job.segment, &[0; 8])?;
+        let (next, end) = (&mut self.next, self.job.end);
+        let nonces = std::iter::from_fn(|| {
+            let nonce = (*next)?;
+            *next = nonce.checked_add(1).filter(|&n| n <= end);
+            Some(nonce)
+        });
+
+        let min_difficulty = self.job.min_difficulty;
+        report.attempts = search_nonces(
+            &self.builder,
+            &mut self.memory,
+            &mut seed,
+            nonces.take(usize::try_from(max_attempts).unwrap_or(usize::MAX)),
+            SelectionPolicy::HighestDifficulty,
+            || false,
+            |solution| {
+                let qualifies = solution.difficulty() >= min_difficulty;
+                if qualifies {
+                    report.solution = Some(solution);
+                }
+                qualifies
+            },
+        )?;
+        if report.solution.is_some() {
+            self.next = None;
+        }
+
+        report.done = self.next.is_none();
+        report.next = self.next.unwrap_or(self.job.end);
+        Ok(report)
+    }
+}
+
+/// Coordinator-side merge of every worker's reports
+#[derive(Debug, Default)]
+pub struct Aggregator {
+    finished: Vec<bool>,
+    attempts: u64,
+    best: Option<Solution>,
+}
+
+impl Aggregator {
+    /// Expect reports from `workers` workers
+    pub fn new(workers: u32) -> Self {
+        Self { finished: vec![false; workers as usize], ..Default::default() }
+    }
+
+    /// Fold in one report; returns whether the search is over
+    ///
+    /// [`CrankXError::InvalidReport`] for a worker index past the count, or
+    /// attempts that would overflow the total; the report is then ignored.
+    pub fn push(&mut self, report: WorkerReport) -> Result<bool, CrankXError> {
+        let attempts = self.attempts.checked_add(report.attempts);
+        let (Some(finished), Some(attempts)) =
+            (self.finished.get_mut(report.worker as usize), attempts)
+        else {
+            return Err(CrankXError::InvalidReport);
+        };
+        *finished |= report.done;
+        self.attempts = attempts;
+        if let Some(solution) = report.solution {
+            if self.best.as_ref().is_none_or(|best| solution > *best) {
+                self.best = Some(solution);
+            }
+        }
+        Ok(self.is_done())
+    }
+
+    /// A solution has turned up, or every worker has run dry
+    pub fn is_done(&self) -> bool {
+        self.best.is_some() || self.finished.iter().all(|&f| f)
+    }
+
+    /// Best solution reported so far
+    pub fn best(&self) -> Option<&Solution> {
+        self.best.as_ref()
+    }
+
+    /// Nonces tried across all workers
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "accel")]
+
+use crankx::accel::{HashXProgram, Instruction, ENCODED_LEN, PROGRAM_LEN};
+use crankx::solve;
+use hashx::{HashXBuilder, RuntimeOption};
+
+const CHALLENGE: [u8; 32] = [7; 32];
+const DATA: [u8; 40] = [8; 40];
+
+fn seed(nonce: [u8; 8]) -> Vec<u8> {
+    [&CHALLENGE[..], &DATA, &nonce].concat()
+}
+
+#[test]
+fn exported_program_reproduces_equix_solutions() {
+    let mut checked = 0;
+    for nonce in (0u64..8).map(u64::to_le_bytes) {
+        let Ok(solution) = solve(CHALLENGE, &DATA, nonce) else {
+            continue;
+        };
+        let program = HashXProgram::export(&seed(nonce)).unwrap();
+        assert_eq!(program.instructions.len(), PROGRAM_LEN);
+
+        // An EquiX solution is 8 inputs whose hashes sum to zero mod 2^60
+        let sum = solution
+            .d
+            .chunks(2)
+            .map(|i| program.hash_to_u64(u16::from_le_bytes([i[0], i[1]]) as u64))
+            .fold(0u64, u64::wrapping_add);
+        assert_eq!(sum & ((1 << 60) - 1), 0);
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn binary_form_matches_the_documented_layout() {
+    let program = HashXProgram::export(&seed([0; 8])).unwrap();
+    let bytes = program.to_bytes();
+    assert_eq!(bytes.len(), ENCODED_LEN);
+    assert_eq!(bytes[..8], program.register_key[0].to_le_bytes());
+    assert_eq!(bytes[32..40], program.instructions[0].to_bytes());
+
+    let add = Instruction::AddConst { dst: 3, imm: -1 };
+    assert_eq!(add.to_bytes(), [4, 3, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+    let shift = Instruction::AddShift { dst: 1, src: 2, shift: 3 };
+    assert_eq!(shift.to_bytes(), [3, 1, 2, 0, 3, 0, 0, 0]);
+}
+
+#[test]
+fn hashx_listing_keeps_the_format_export_reads() {
+    let hashx =
+        HashXBuilder::new().runtime(RuntimeOption::InterpretOnly).build(&seed([0; 8])).unwrap();
+    let listing = format!("{hashx:?}");
+
+    // If these fail, hashx changed its Debug output: `export` needs updating
+    // before the pinned version can move
+    assert!(listing.contains("program: Interpret(Program {\n [  0]: "), "{listing}");
+    let lines = listing.lines().filter(|l| l.trim_start().starts_with('[')).collect::<Vec<_>>();
+    assert_eq!(lines.len(), PROGRAM_LEN, "{listing}");
+    assert!(lines[PROGRAM_LEN - 1].starts_with(" [511]: "), "{listing}");
+}
+
+#[test]
+fn exported_program_hashes_like_hashx() {
+    for nonce in (0u64..16).map(u64::to_le_bytes) {
+        let Ok(hashx) = HashXBuilder::new().build(&seed(nonce)) else {
+            continue;
+        };
+        let program = HashXProgram::export(&seed(nonce)).unwrap();
+        for input in (0..1 << 16).step_by(97) {
+            assert_eq!(program.hash_to_u64(input), hashx.hash_to_u64(input), "input {input}");
+        }
+    }
+}
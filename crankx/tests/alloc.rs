@@ -0,0 +1,57 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use crankx::{solve, verify, verify_in_buffer, CrankXError, MAX_SEED_LEN};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Relaxed);
+    f();
+    ALLOCATIONS.load(Relaxed) - before
+}
+
+#[test]
+fn buffer_verify_adds_no_allocations() {
+    let challenge = [6u8; 32];
+    let data = [7u8; 64];
+    let solution = (0u64..).find_map(|n| solve(challenge, &data, n).ok()).unwrap();
+
+    let mut seed = Vec::new();
+    seed.extend_from_slice(&challenge);
+    seed.extend_from_slice(&data);
+    seed.extend_from_slice(&solution.n);
+
+    // Whatever equix allocates for the HashX program is the floor
+    let equix = allocations(|| crankx::equix::verify_bytes(&seed, &solution.d).unwrap());
+
+    let mut buf = [0u8; MAX_SEED_LEN];
+    let buffered = allocations(|| {
+        verify_in_buffer(&mut buf, challenge, &data, solution.n, &solution.d).unwrap()
+    });
+    assert_eq!(buffered, equix);
+
+    let allocating = allocations(|| verify(challenge, &data, solution.n, &solution.d).unwrap());
+    assert_eq!(allocating, equix + 1);
+
+    assert!(matches!(
+        verify_in_buffer(&mut [0u8; 100], challenge, &data, solution.n, &solution.d),
+        Err(CrankXError::SeedTooLarge { max: 100, got: 104 })
+    ));
+}
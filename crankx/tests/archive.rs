@@ -0,0 +1,60 @@
+#![cfg(feature = "rkyv")]
+
+use crankx::batch::SolutionBatch;
+use crankx::{solve, Challenge, HashAlgorithm, Nonce, Solution};
+use rkyv::{check_archived_root, Deserialize, Infallible};
+
+fn solutions(count: usize) -> Vec<Solution> {
+    (0u64..).filter_map(|n| solve([1; 32], &[2u8; 32], n).ok()).take(count).collect()
+}
+
+#[test]
+fn solutions_are_read_in_place() {
+    let solutions = solutions(3);
+    let bytes = rkyv::to_bytes::<_, 256>(&solutions).unwrap();
+
+    let archived = check_archived_root::<Vec<Solution>>(&bytes).unwrap();
+    assert_eq!(archived.len(), 3);
+    for (archived, solution) in archived.iter().zip(&solutions) {
+        assert_eq!(archived.d, solution.d);
+        assert_eq!(archived.n, solution.n);
+        assert_eq!(archived.hash_algorithm().unwrap(), HashAlgorithm::Keccak256);
+        assert_eq!(archived.to_hash().unwrap(), solution.to_hash());
+        assert_eq!(archived.difficulty().unwrap(), solution.difficulty());
+    }
+
+    let owned: Vec<Solution> = archived.deserialize(&mut Infallible).unwrap();
+    assert_eq!(owned, solutions);
+}
+
+#[test]
+fn an_unknown_hash_algorithm_fails_validation() {
+    let bytes = rkyv::to_bytes::<_, 64>(&solutions(1)[0]).unwrap();
+    assert!(check_archived_root::<Solution>(&bytes).is_ok());
+
+    // The algorithm id is the archive's last byte
+    let mut bytes = bytes.to_vec();
+    *bytes.last_mut().unwrap() = 0xff;
+    assert!(check_archived_root::<Solution>(&bytes).is_err());
+}
+
+#[test]
+fn challenges_nonces_and_batches_round_trip() {
+    let bytes = rkyv::to_bytes::<_, 64>(&(Challenge([7; 32]), Nonce::from(9u64))).unwrap();
+    let archived = check_archived_root::<(Challenge, Nonce)>(&bytes).unwrap();
+    assert_eq!(archived.0 .0, [7; 32]);
+    assert_eq!(archived.1 .0, 9u64.to_le_bytes());
+
+    let mut batch = SolutionBatch::new([3; 32]);
+    for (segment, solution) in solutions(2).iter().enumerate() {
+        batch.push(segment as u64 * 1000, solution);
+    }
+    let bytes = rkyv::to_bytes::<_, 256>(&batch).unwrap();
+    let archived = check_archived_root::<SolutionBatch>(&bytes).unwrap();
+    assert_eq!(archived.challenge.0, [3; 32]);
+    assert_eq!(archived.proofs[1].segment, 1000);
+    assert_eq!(archived.proofs[1].nonce, batch.proofs[1].nonce);
+
+    let owned: SolutionBatch = archived.deserialize(&mut Infallible).unwrap();
+    assert_eq!(owned, batch);
+}
@@ -0,0 +1,35 @@
+use crankx::equix::SolverMemory;
+use crankx::{
+    check_aux_size, solve_with_aux, solve_with_memory, verify, verify_with_aux, MAX_AUX_LEN,
+};
+
+#[test]
+fn aux_is_bound_into_the_proof() {
+    let (challenge, data) = ([1u8; 32], [2u8; 64]);
+    let (alice, bob) = ([0xaa; 32], [0xbb; 32]);
+    let mut mem = SolverMemory::new();
+
+    let solution = (0u64..)
+        .find_map(|n| solve_with_aux(&mut mem, challenge, &data, &alice, n).ok())
+        .unwrap();
+
+    verify_with_aux(challenge, &data, &alice, solution.n, &solution.d).unwrap();
+    assert!(verify_with_aux(challenge, &data, &bob, solution.n, &solution.d).is_err());
+    assert!(verify(challenge, &data, solution.n, &solution.d).is_err());
+
+    check_aux_size::<MAX_AUX_LEN>();
+}
+
+#[test]
+fn empty_aux_is_the_plain_seed() {
+    let (challenge, data) = ([3u8; 32], [4u8; 64]);
+    let mut mem = SolverMemory::new();
+
+    let (nonce, plain) = (0u64..)
+        .find_map(|n| solve_with_memory(&mut mem, challenge, &data, n).ok().map(|s| (n, s)))
+        .unwrap();
+    let with_aux = solve_with_aux(&mut mem, challenge, &data, &[], nonce).unwrap();
+
+    assert_eq!(with_aux.d, plain.d);
+    verify_with_aux(challenge, &data, &[], plain.n, &plain.d).unwrap();
+}
@@ -0,0 +1,40 @@
+use crankx::backend::{self, EquiXBackend, PowBackend};
+use crankx::equix::RuntimeOption;
+
+#[test]
+fn equix_backend_matches_default_path() {
+    let challenge = [1u8; 32];
+    let data = [2u8; 48];
+    let backend = EquiXBackend::new(RuntimeOption::InterpretOnly);
+    let mut memory = backend.new_memory();
+
+    let (nonce, solution) = (0u64..)
+        .find_map(|n| {
+            let nonce = n.to_le_bytes();
+            backend::solve(&backend, &mut memory, challenge, &data, nonce)
+                .ok()
+                .map(|s| (nonce, s))
+        })
+        .unwrap();
+
+    let default = crankx::solve(challenge, &data, nonce).unwrap();
+    assert_eq!(solution.d, default.d);
+
+    backend::verify(&backend, challenge, &data, nonce, &solution.d).unwrap();
+    crankx::verify(challenge, &data, nonce, &solution.d).unwrap();
+}
+
+#[test]
+fn reused_builder_matches_default_path() {
+    let challenge = [3u8; 32];
+    let data = [4u8; 48];
+    let mut builder = crankx::equix::EquiXBuilder::new();
+    builder.runtime(RuntimeOption::InterpretOnly);
+    let mut memory = crankx::equix::SolverMemory::new();
+
+    for nonce in 0u64..8 {
+        let reused = crankx::solve_with_builder(&builder, &mut memory, challenge, &data, nonce);
+        let default = crankx::solve(challenge, &data, nonce);
+        assert_eq!(reused.map(|s| s.d).ok(), default.map(|s| s.d).ok());
+    }
+}
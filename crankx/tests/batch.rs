@@ -0,0 +1,211 @@
+use crankx::batch::{solve_batch, verify_batch, verify_nonces, BatchItem, SolutionBatch};
+use crankx::testing::{gen_segment_array, gen_tape};
+use crankx::{solve, CrankXError, MAX_DATA_LEN};
+
+const CHALLENGE: [u8; 32] = [11; 32];
+
+#[test]
+fn batch_of_mixed_segments() {
+    let small: [u8; 32] = gen_segment_array(1);
+    let large: [u8; 256] = gen_segment_array(2);
+
+    let a = (0u64..).find_map(|n| solve(CHALLENGE, &small, n).ok()).unwrap();
+    let b = (0u64..).find_map(|n| solve(CHALLENGE, &large, n).ok()).unwrap();
+
+    let items = [BatchItem::new(&small, &a), BatchItem::new(&large, &b)];
+    verify_batch(CHALLENGE, &items).unwrap();
+    verify_batch(CHALLENGE, &[]).unwrap();
+
+    // Swapping segments between proofs must fail
+    let swapped = [BatchItem::new(&large, &a), BatchItem::new(&small, &b)];
+    assert!(verify_batch(CHALLENGE, &swapped).is_err());
+
+    // Slices aren't size-checked at compile time
+    let oversized = [0u8; MAX_DATA_LEN + 1];
+    assert!(matches!(
+        verify_batch(CHALLENGE, &[BatchItem::new(&oversized, &a)]),
+        Err(CrankXError::SeedTooLarge { .. })
+    ));
+}
+
+#[test]
+fn batch_solve_matches_single_solves() {
+    let data = [3u8; 64];
+    let solutions = solve_batch(CHALLENGE, &data, 10, 16).unwrap();
+
+    let expected: Vec<_> = (10u64..26).filter_map(|n| solve(CHALLENGE, &data, n).ok()).collect();
+    assert_eq!(solutions.len(), expected.len());
+    for (a, b) in solutions.iter().zip(&expected) {
+        assert_eq!((a.n, a.d), (b.n, b.d));
+    }
+
+    assert!(solve_batch(CHALLENGE, &data, u64::MAX, 4).unwrap().len() <= 1);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_batch_matches_sequential() {
+    use crankx::batch::verify_batch_parallel;
+
+    let segments: Vec<[u8; 64]> = (0..32u8).map(|i| [i; 64]).collect();
+    let solutions: Vec<_> = segments
+        .iter()
+        .map(|data| (0u64..).find_map(|n| solve(CHALLENGE, data, n).ok()).unwrap())
+        .collect();
+
+    let mut items: Vec<_> =
+        segments.iter().zip(&solutions).map(|(d, s)| BatchItem::new(d, s)).collect();
+    verify_batch_parallel(CHALLENGE, &items).unwrap();
+    verify_batch_parallel(CHALLENGE, &[]).unwrap();
+
+    items[17].digest[0] ^= 1;
+    assert!(verify_batch_parallel(CHALLENGE, &items).is_err());
+    assert!(verify_batch(CHALLENGE, &items).is_err());
+}
+
+#[test]
+fn shared_segment_proofs() {
+    let data = [6u8; 96];
+    let solutions: Vec<_> =
+        (0u64..).filter_map(|n| solve(CHALLENGE, &data, n).ok()).take(4).collect();
+    let proofs: Vec<_> = solutions.iter().map(|s| s.to_bytes()).collect();
+
+    verify_nonces(CHALLENGE, &data, &proofs).unwrap();
+    verify_nonces(CHALLENGE, &data, &[]).unwrap();
+
+    // The same slice repeated takes the nonce-only path in verify_batch
+    let other = [7u8; 96];
+    let other_solution = (0u64..).find_map(|n| solve(CHALLENGE, &other, n).ok()).unwrap();
+    let mut items: Vec<_> = solutions.iter().map(|s| BatchItem::new(&data, s)).collect();
+    items.insert(2, BatchItem::new(&other, &other_solution));
+    verify_batch(CHALLENGE, &items).unwrap();
+
+    let mut bad = proofs.clone();
+    bad[3][20] ^= 1;
+    assert!(verify_nonces(CHALLENGE, &data, &bad).is_err());
+    items[4].nonce = bad[3][16..].try_into().unwrap();
+    assert!(verify_batch(CHALLENGE, &items).is_err());
+}
+
+fn solution_batch() -> (SolutionBatch, Vec<Vec<u8>>) {
+    let segments = gen_tape(4, 4, 64);
+    let mut batch = SolutionBatch::new(CHALLENGE);
+    for (i, data) in segments.iter().enumerate() {
+        let data: &[u8; 64] = data.as_slice().try_into().unwrap();
+        let solution = (0u64..).find_map(|n| solve(CHALLENGE, data, n).ok()).unwrap();
+        batch.push(i as u64, &solution);
+    }
+    (batch, segments)
+}
+
+#[test]
+fn solution_batch_round_trips_and_verifies() {
+    let (batch, segments) = solution_batch();
+
+    let bytes = batch.to_bytes();
+    assert_eq!(bytes.len(), batch.encoded_len());
+    assert_eq!(bytes.len(), 32 + 1 + 4 * (1 + 24));
+    assert_eq!(SolutionBatch::from_bytes(&bytes).unwrap(), batch);
+    assert!(SolutionBatch::from_bytes(&bytes[..31]).is_err());
+    assert!(SolutionBatch::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    batch.verify(&segments).unwrap();
+
+    let mut swapped = batch.clone();
+    (swapped.proofs[0].segment, swapped.proofs[1].segment) = (1, 0);
+    assert!(swapped.verify(&segments).is_err());
+
+    let mut missing = batch;
+    missing.proofs[0].segment = 9;
+    assert!(matches!(
+        missing.verify(&segments),
+        Err(CrankXError::SegmentOutOfRange { index: 9, count: 4 })
+    ));
+}
+
+#[test]
+fn report_classifies_every_item() {
+    use crankx::batch::verify_batch_report;
+    use crankx::report::Outcome;
+
+    let small: [u8; 32] = gen_segment_array(1);
+    let large: [u8; 256] = gen_segment_array(2);
+    let a = (0u64..).find_map(|n| solve(CHALLENGE, &small, n).ok()).unwrap();
+    let mut unordered = BatchItem::new(&small, &a);
+    unordered.digest[..4].rotate_left(2);
+    let oversized = [0u8; MAX_DATA_LEN + 1];
+
+    let items = [
+        BatchItem::new(&small, &a),
+        BatchItem::new(&large, &a),
+        unordered,
+        BatchItem::new(&oversized, &a),
+    ];
+    let report = verify_batch_report(CHALLENGE, &items, 0);
+    let outcomes: Vec<_> = report.items.iter().map(|i| &i.outcome).collect();
+    assert_eq!(outcomes[0], &Outcome::Valid { difficulty: a.difficulty() });
+    assert_eq!(outcomes[1], &Outcome::InvalidSolution);
+    assert!(matches!(outcomes[2], Outcome::Malformed { .. }));
+    let too_large = |r: &str| r.starts_with("Seed too large");
+    assert!(matches!(outcomes[3], Outcome::Malformed { reason } if too_large(reason)));
+    assert_eq!((report.counts.valid, report.counts.malformed), (1, 2));
+    assert_eq!(report.counts.total(), 4);
+    assert!(!report.all_valid());
+    assert_eq!(report.failures().map(|i| i.index).collect::<Vec<_>>(), [1, 2, 3]);
+
+    let harder = verify_batch_report(CHALLENGE, &items[..1], a.difficulty() + 1);
+    assert_eq!(
+        harder.items[0].outcome,
+        Outcome::BelowDifficulty { difficulty: a.difficulty(), required: a.difficulty() + 1 }
+    );
+    assert!(verify_batch_report(CHALLENGE, &[], 0).all_valid());
+
+    // Proofs whose segment is missing are malformed, the rest still checked
+    let (mut batch, segments) = solution_batch();
+    batch.push(9, &a);
+    let report = batch.verify_report(&segments, 0);
+    assert_eq!((report.counts.valid, report.counts.malformed), (4, 1));
+
+    #[cfg(feature = "rayon")]
+    {
+        let parallel = crankx::batch::verify_batch_parallel_report(CHALLENGE, &items, 0);
+        assert_eq!(parallel.items.len(), 4);
+        assert_eq!(parallel.counts, verify_batch_report(CHALLENGE, &items, 0).counts);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn report_serializes_to_json() {
+    use crankx::batch::verify_batch_report;
+
+    let (batch, segments) = solution_batch();
+    let data: &[u8] = &segments[0];
+    let solution = crankx::Solution::new(batch.proofs[0].digest, batch.proofs[0].nonce);
+    let items = [BatchItem::new(data, &solution), BatchItem::new(&segments[1], &solution)];
+    let report = verify_batch_report(CHALLENGE, &items, 0);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["items"][0]["outcome"], "valid");
+    assert_eq!(json["items"][1]["outcome"], "invalid_solution");
+    assert_eq!(json["items"][1]["index"], 1);
+    assert_eq!(json["counts"]["invalid_solution"], 1);
+    assert_eq!(serde_json::from_value::<crankx::report::VerificationReport>(json).unwrap(), report);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn solution_batch_serde() {
+    let (batch, _) = solution_batch();
+    let json = serde_json::to_string(&batch).unwrap();
+    assert_eq!(serde_json::from_str::<SolutionBatch>(&json).unwrap(), batch);
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn solution_batch_borsh() {
+    let (batch, _) = solution_batch();
+    let bytes = borsh::to_vec(&batch).unwrap();
+    assert_eq!(bytes.len(), 4 + batch.encoded_len());
+    assert_eq!(borsh::from_slice::<SolutionBatch>(&bytes).unwrap(), batch);
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use crankx::bench::{measure, measure_with};
+use crankx::equix::{Runtime, RuntimeOption};
+use crankx::MAX_DATA_LEN;
+
+#[test]
+fn rates_stay_finite_when_nothing_ran() {
+    for report in
+        [measure(32, Duration::ZERO), measure(MAX_DATA_LEN + 1, Duration::from_millis(10))]
+    {
+        assert_eq!(report.attempts_per_sec, 0.0);
+        assert_eq!(report.solves_per_sec, 0.0);
+        assert_eq!(report.verifies_per_sec, 0.0);
+        assert!(report.runtime_used.is_none());
+    }
+}
+
+#[test]
+fn verification_runs_on_the_requested_runtime() {
+    // Every solution found under the interpreter must also verify under it
+    let report = measure_with(32, Duration::from_millis(50), RuntimeOption::InterpretOnly);
+    assert_eq!(report.runtime_used, Some(Runtime::Interpret));
+    assert!(report.solves_per_sec > 0.0);
+    assert!(report.verifies_per_sec.is_finite() && report.verifies_per_sec > 0.0);
+}
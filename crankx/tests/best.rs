@@ -0,0 +1,36 @@
+use crankx::best::BestSolutions;
+use crankx::{solve, Solution};
+
+#[test]
+fn keeps_the_k_best() {
+    let mut all: Vec<_> =
+        (0u64..).filter_map(|n| solve([8; 32], &[9u8; 32], n).ok()).take(30).collect();
+
+    let mut best = BestSolutions::<5>::new();
+    assert!(best.threshold().is_none());
+    for (i, solution) in all.iter().enumerate() {
+        let copy = Solution::from_bytes(&solution.to_bytes());
+        best.offer(copy);
+        assert_eq!(best.len(), (i + 1).min(5));
+    }
+    assert!(best.is_full());
+
+    all.sort_by(|a, b| b.cmp(a));
+    assert_eq!(best.best(), all.first());
+    assert_eq!(best.threshold(), all.get(4));
+
+    // A solution no better than the threshold is turned away
+    let worst = all.pop().unwrap();
+    assert!(!best.offer(worst));
+
+    let kept = best.into_sorted_vec();
+    assert_eq!(kept, all[..5]);
+}
+
+#[test]
+fn zero_capacity_keeps_nothing() {
+    let mut none = BestSolutions::<0>::default();
+    let solution = (0u64..).find_map(|n| solve([8; 32], &[9u8; 32], n).ok()).unwrap();
+    assert!(!none.offer(solution));
+    assert!(none.is_empty() && none.best().is_none());
+}
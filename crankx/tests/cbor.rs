@@ -0,0 +1,71 @@
+#![cfg(all(feature = "cbor", feature = "envelope"))]
+
+use crankx::batch::SolutionBatch;
+use crankx::encoding::cbor::{from_cbor, to_cbor};
+use crankx::envelope::{ProofEnvelope, SigningKey};
+use crankx::job::Job;
+use crankx::{Challenge, CrankXError, Solution};
+
+fn job() -> Job {
+    Job {
+        id: 1,
+        challenge: Challenge([2; 32]),
+        segment: 3,
+        target: 4,
+        nonce_start: 0,
+        nonce_end: 1 << 20,
+        expires_at: None,
+    }
+}
+
+#[test]
+fn values_round_trip_through_their_serde_form() {
+    let solution = Solution::new([5; 16], [6; 8]);
+    let bytes = to_cbor(&solution).unwrap();
+    assert_eq!(from_cbor::<Solution>(&bytes).unwrap(), solution);
+    // The same hex string JSON carries, as a CBOR text string
+    let hex = serde_json::to_value(&solution).unwrap();
+    assert_eq!(bytes[0], 0x78);
+    assert_eq!(&bytes[2..], hex.as_str().unwrap().as_bytes());
+
+    let mut batch = SolutionBatch::new([7; 32]);
+    batch.push(9, &solution);
+    assert_eq!(from_cbor::<SolutionBatch>(&to_cbor(&batch).unwrap()).unwrap(), batch);
+
+    let envelope = ProofEnvelope::sign(&SigningKey::from_seed(&[1; 32]), [7; 32], 9, 10, solution);
+    let decoded: ProofEnvelope = from_cbor(&to_cbor(&envelope).unwrap()).unwrap();
+    decoded.verify_signature().unwrap();
+    assert_eq!(decoded, envelope);
+
+    assert_eq!(from_cbor::<Job>(&to_cbor(&job()).unwrap()).unwrap(), job());
+}
+
+#[test]
+fn map_keys_are_sorted_and_only_canonical_input_decodes() {
+    let bytes = to_cbor(&job()).unwrap();
+    let keys: Vec<Vec<u8>> = match ciborium::from_reader(&bytes[..]).unwrap() {
+        ciborium::Value::Map(entries) => entries
+            .iter()
+            .map(|(k, _)| {
+                let mut key = Vec::new();
+                ciborium::into_writer(k, &mut key).unwrap();
+                key
+            })
+            .collect(),
+        _ => unreachable!(),
+    };
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    // Shorter keys first: "id" before "target" before "challenge"
+    assert_eq!(&keys[0][1..], b"id");
+
+    // Plain ciborium writes fields in declaration order
+    let mut unsorted = Vec::new();
+    ciborium::into_writer(&job(), &mut unsorted).unwrap();
+    assert_ne!(unsorted, bytes);
+    assert!(matches!(from_cbor::<Job>(&unsorted), Err(CrankXError::InvalidEncoding)));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(from_cbor::<Job>(&trailing), Err(CrankXError::InvalidEncoding)));
+    assert!(matches!(from_cbor::<Job>(&bytes[..10]), Err(CrankXError::InvalidEncoding)));
+}
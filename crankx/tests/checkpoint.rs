@@ -0,0 +1,70 @@
+use crankx::checkpoint::MinerState;
+use crankx::nonces::NonceStrategy;
+use crankx::{solve, Challenge};
+
+#[test]
+fn state_round_trips() {
+    let challenge = Challenge([6; 32]);
+    let mut state = MinerState::new(challenge, 3);
+    state.cursors = vec![10, 20, u64::MAX];
+    state.epoch = 7;
+
+    let mut buf = Vec::new();
+    state.save(&mut buf).unwrap();
+    let restored = MinerState::restore(&mut buf.as_slice()).unwrap();
+    assert_eq!(restored.challenge, challenge);
+    assert_eq!(restored.cursors, state.cursors);
+    assert_eq!(restored.epoch, 7);
+    assert_eq!(restored.nonce_strategy, NonceStrategy::Sequential);
+    assert!(restored.best.is_none());
+
+    for strategy in [
+        NonceStrategy::Random { start: u64::MAX },
+        NonceStrategy::Identity { start: 5 },
+        NonceStrategy::Strided { index: 1, count: 3 },
+    ] {
+        buf.clear();
+        MinerState { nonce_strategy: strategy, ..MinerState::new(challenge, 1) }
+            .save(&mut buf)
+            .unwrap();
+        let restored = MinerState::restore(&mut buf.as_slice()).unwrap();
+        assert_eq!(restored.nonce_strategy, strategy);
+    }
+
+    let solution = (0u64..).find_map(|n| solve(challenge, &[1u8; 32], n).ok()).unwrap();
+    let bytes = solution.to_bytes();
+    assert!(state.offer(solution));
+
+    buf.clear();
+    state.save(&mut buf).unwrap();
+    let restored = MinerState::restore(&mut buf.as_slice()).unwrap();
+    assert_eq!(restored.best.unwrap().to_bytes(), bytes);
+
+    buf[0] = 0xff;
+    assert!(MinerState::restore(&mut buf.as_slice()).is_err());
+    assert!(MinerState::restore(&mut &buf[1..20]).is_err());
+}
+
+#[test]
+fn earlier_versions_restore_at_epoch_zero_walking_sequentially() {
+    let mut v1 = vec![1];
+    v1.extend_from_slice(&[6; 32]);
+    v1.extend_from_slice(&1u32.to_le_bytes());
+    v1.extend_from_slice(&42u64.to_le_bytes());
+    v1.push(0);
+
+    let restored = MinerState::restore(&mut v1.as_slice()).unwrap();
+    assert_eq!(restored.challenge, Challenge([6; 32]));
+    assert_eq!(restored.epoch, 0);
+    assert_eq!(restored.cursors, [42]);
+    assert_eq!(restored.nonce_strategy, NonceStrategy::Sequential);
+
+    // Version two added the epoch, not yet the strategy
+    let mut v2 = vec![2];
+    v2.extend_from_slice(&[6; 32]);
+    v2.extend_from_slice(&9u64.to_le_bytes());
+    v2.extend_from_slice(&v1[33..]);
+    let restored = MinerState::restore(&mut v2.as_slice()).unwrap();
+    assert_eq!((restored.epoch, restored.cursors), (9, vec![42]));
+    assert_eq!(restored.nonce_strategy, NonceStrategy::Sequential);
+}
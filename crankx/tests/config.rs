@@ -0,0 +1,109 @@
+use crankx::config::{RuntimeOption, SolverConfig, VerifyLevel};
+use crankx::equix::SolverMemory;
+use crankx::{solve, CrankXError, SelectionPolicy, Solution};
+
+const CHALLENGE: [u8; 32] = [9; 32];
+const DATA: [u8; 48] = [1; 48];
+
+#[test]
+fn default_config_matches_top_level_solve() {
+    let config = SolverConfig::default();
+    let mut mem = SolverMemory::new();
+    let nonce = (0u64..).find(|n| solve(CHALLENGE, &DATA, *n).is_ok()).unwrap();
+
+    let solution = config.solve(&mut mem, CHALLENGE, &DATA, nonce).unwrap();
+    assert_eq!(solution, solve(CHALLENGE, &DATA, nonce).unwrap());
+
+    let candidates = config.candidates(&mut mem, CHALLENGE, &DATA, nonce).unwrap();
+    assert_eq!(candidates[0], solution);
+
+    let best = config.policy(SelectionPolicy::HighestDifficulty);
+    let best = best.solve(&mut mem, CHALLENGE, &DATA, nonce).unwrap();
+    assert_eq!(Some(&best), candidates.iter().max());
+}
+
+#[test]
+fn candidate_types_round_trip() {
+    let config = SolverConfig::default().runtime(RuntimeOption::InterpretOnly);
+    let mut mem = SolverMemory::new();
+    let nonce = (0u64..).find(|n| solve(CHALLENGE, &DATA, *n).is_ok()).unwrap();
+    let solution = config.solve(&mut mem, CHALLENGE, &DATA, nonce).unwrap();
+
+    let candidate = solution.candidate().unwrap();
+    assert_eq!(Solution::from_candidate(&candidate, solution.n), solution);
+    let items = solution.items().unwrap();
+    assert_eq!(items[0].to_le_bytes(), solution.d[..2]);
+
+    // Swapping the first two items breaks canonical order
+    let mut d = solution.d;
+    d[..4].rotate_left(2);
+    let shuffled = Solution::new(d, solution.n);
+    assert!(matches!(shuffled.candidate(), Err(CrankXError::InvalidSolution)));
+}
+
+#[test]
+fn verify_levels() {
+    let config = SolverConfig::default();
+    let mut mem = SolverMemory::new();
+    let nonce = (0u64..).find(|n| solve(CHALLENGE, &DATA, *n).is_ok()).unwrap();
+    let solution = config.solve(&mut mem, CHALLENGE, &DATA, nonce).unwrap();
+    config.verify(CHALLENGE, &DATA, &solution).unwrap();
+
+    // Well-formed but for other data: only a full check notices
+    let other = [2u8; 48];
+    assert!(config.verify(CHALLENGE, &other, &solution).is_err());
+    let structural = config.verify_level(VerifyLevel::Structure);
+    structural.verify(CHALLENGE, &other, &solution).unwrap();
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn settings_file_fills_defaults_and_validates() {
+    use crankx::config::{Config, LogTarget};
+    use crankx::miner::Throttle;
+    use crankx::Challenge;
+
+    let config = Config::from_toml(
+        r#"
+        tape_dir = "/srv/tapes"
+        archive = "/srv/proofs"
+        threads = 3
+        batch_size = 16
+        affinity = [2, 3]
+        min_difficulty = 12
+        challenge = "0909090909090909090909090909090909090909090909090909090909090909"
+        duty_cycle = 0.5
+        submit_url = "https://rpc.example"
+        metrics_port = 9100
+        metrics_addr = "0.0.0.0"
+        log = "journal"
+        "#,
+    )
+    .unwrap();
+    assert_eq!((config.threads, config.batch_size, config.min_difficulty), (3, 16, 12));
+    assert_eq!(config.affinity, [2, 3]);
+    assert_eq!(config.challenge, Some(Challenge(CHALLENGE)));
+    assert_eq!(config.throttle, Throttle::DutyCycle(0.5));
+    assert_eq!(config.metrics_port, Some(9100));
+    assert_eq!(config.metrics_addr.to_string(), "0.0.0.0");
+    assert_eq!(config.log, LogTarget::Journal);
+
+    let minimal = Config::from_toml("tape_dir = \"t\"\narchive = \"p\"").unwrap();
+    assert_eq!(minimal, Config::new("t", "p"));
+    assert!(minimal.metrics_addr.is_loopback());
+
+    let err = |extra: &str| {
+        let text = format!("tape_dir = \"t\"\narchive = \"p\"\n{extra}");
+        Config::from_toml(&text).unwrap_err().to_string()
+    };
+    assert!(err("speed = 9").contains("unknown field `speed`"));
+    assert!(err("metrics_addr = \"localhost\"").contains("invalid IP address"));
+    assert!(Config::from_toml("tape_dir = \"t\"").is_err());
+    assert_eq!(err("threads = 0"), "threads must be at least 1");
+    assert_eq!(err("batch_size = 0"), "batch_size must be at least 1");
+    assert_eq!(err("affinity = [1, 1]"), "affinity lists core 1 twice");
+    assert_eq!(err("duty_cycle = 1.5"), "duty_cycle must be in (0, 1]");
+    assert_eq!(err("duty_cycle = 0.5\nmax_rate = 10.0"), "set duty_cycle or max_rate, not both");
+    assert!(err("submit_url = \"rpc.example\"").contains("isn't an http(s) URL"));
+    assert_eq!(err("poll_secs = 0"), "poll_secs must be at least 1");
+}
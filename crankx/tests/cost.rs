@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crankx::cost::{estimate_verify, CostModel, CuCost, MAX_TRANSACTION_CU};
+use crankx::MAX_DATA_LEN;
+
+#[test]
+fn cost_grows_with_segment_length() {
+    let small = estimate_verify(32);
+    let large = estimate_verify(MAX_DATA_LEN);
+    assert!(large > small);
+    assert!(small.wallclock_ns > 0);
+
+    let model = CostModel::DEFAULT.with_cu(100_000, 1);
+    assert_eq!(model.estimate_verify(64).cu, Some(100_064));
+    assert_eq!(model.estimate_batch([64, 64, 0]).cu, Some(300_128));
+    assert_eq!(model.estimate_batch([]).cu, Some(0));
+    assert_eq!(model.proofs_within(64, MAX_TRANSACTION_CU), Some(13));
+    assert_eq!(CostModel::DEFAULT.with_cu(0, 0).proofs_within(64, 1), Some(u64::MAX));
+}
+
+#[test]
+fn compute_units_are_only_what_callers_supply() {
+    assert_eq!(CostModel::DEFAULT.cu, None);
+    assert_eq!(estimate_verify(64).cu, None);
+    assert_eq!(CostModel::DEFAULT.estimate_batch([64, 64]).cu, None);
+    assert_eq!(CostModel::DEFAULT.proofs_within(64, MAX_TRANSACTION_CU), None);
+
+    // A batch mixing in an estimate without compute units has none either
+    let measured = CostModel::DEFAULT.with_cu(100_000, 1).estimate_verify(64);
+    assert_eq!((measured + estimate_verify(64)).cu, None);
+}
+
+#[test]
+fn compute_units_fit_measurements_from_above() {
+    let model = CostModel::from_cu_measurements([(64, 100_064), (128, 100_130), (256, 100_256)])
+        .expect("three lengths");
+    assert_eq!(model.cu, Some(CuCost { per_proof: 100_002, per_byte: 1 }));
+    assert_eq!(model.per_proof_ns, CostModel::DEFAULT.per_proof_ns);
+    for (len, units) in [(64, 100_064), (128, 100_130), (256, 100_256)] {
+        assert!(model.estimate_verify(len).cu.unwrap() >= units);
+    }
+
+    // A slope needs two lengths
+    assert_eq!(CostModel::from_cu_measurements([]), None);
+    assert_eq!(CostModel::from_cu_measurements([(64, 1), (64, 2)]), None);
+    // Costs that fall with length don't make later bytes free of the fixed part
+    let falling = CostModel::from_cu_measurements([(64, 200), (128, 100)]).unwrap();
+    assert_eq!(falling.cu, Some(CuCost { per_proof: 200, per_byte: 0 }));
+}
+
+#[test]
+fn calibration_measures_this_machine() {
+    let model = CostModel::calibrate(Duration::from_millis(200));
+    assert_eq!(model.cu, None);
+    assert!(model.per_byte_ns >= 0.0);
+    assert!(model.estimate_verify(MAX_DATA_LEN).wallclock_ns > 0);
+}
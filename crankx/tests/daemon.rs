@@ -0,0 +1,191 @@
+#![cfg(feature = "daemon")]
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use crankx::checkpoint::MinerState;
+use crankx::daemon::Daemon;
+use crankx::store::ProofStore;
+use crankx::tape::Writer;
+use crankx::{verify, Challenge};
+
+#[test]
+fn proves_new_tapes_once_and_reloads() {
+    let root = std::env::temp_dir().join(format!("crankx-daemon-{}", std::process::id()));
+    let (tapes, archive) = (root.join("tapes"), root.join("proofs"));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&tapes).unwrap();
+
+    let challenge = Challenge([4; 32]);
+    let mut tape = Writer::new(File::create(tapes.join("a.tape")).unwrap(), 32).unwrap();
+    tape.set_challenge(challenge);
+    for segment in [[1u8; 32], [2u8; 32]] {
+        tape.append(&segment).unwrap();
+    }
+    tape.finish().unwrap();
+    // Nothing to prove it under: no challenge in the header or the config
+    let mut bare = Writer::new(File::create(tapes.join("bare.tape")).unwrap(), 32).unwrap();
+    bare.append(&[3u8; 32]).unwrap();
+    bare.finish().unwrap();
+    // Not a tape, and not mistaken for one
+    fs::write(tapes.join("notes.txt"), "hello").unwrap();
+    fs::write(tapes.join("torn.tape"), [0u8; 10]).unwrap();
+
+    let config_path = root.join("crankx.toml");
+    let write_config = |threads| {
+        let text = format!(
+            "tape_dir = {:?}\narchive = {:?}\nmin_difficulty = 2\nthreads = {threads}\n\
+             poll_secs = 60\nlog = {:?}\n",
+            tapes,
+            archive,
+            root.join("crankx.log")
+        );
+        fs::write(&config_path, text).unwrap();
+    };
+    write_config(2);
+
+    let mut daemon = Daemon::load(&config_path).unwrap();
+    assert_eq!(daemon.run_once().unwrap(), 2);
+    assert_eq!(daemon.run_once().unwrap(), 0);
+
+    let store = ProofStore::open(archive.join("a.tape.proofs")).unwrap();
+    for (index, segment) in [[1u8; 32], [2u8; 32]].iter().enumerate() {
+        let proof = store.get(challenge, index as u64).unwrap();
+        assert!(proof.solution.difficulty() >= 2);
+        verify(challenge, segment, proof.solution.n, &proof.solution.d).unwrap();
+    }
+
+    // A reload picks up the new config; a stop ends the idle wait
+    write_config(1);
+    daemon.reload_flag().store(true, Ordering::Relaxed);
+    let stop = daemon.stop_flag();
+    let running = thread::spawn(move || {
+        daemon.run().unwrap();
+        daemon
+    });
+    thread::sleep(Duration::from_millis(300));
+    stop.store(true, Ordering::Relaxed);
+    let daemon = running.join().unwrap();
+    assert_eq!(daemon.config().threads, 1);
+
+    let log = fs::read_to_string(root.join("crankx.log")).unwrap();
+    assert!(log.contains("a.tape segment 1"));
+    assert!(log.contains("skipping"));
+    assert!(log.contains("skipping bare.tape: no challenge"));
+    assert!(!archive.join("bare.tape.proofs").exists());
+    assert!(log.contains("config reloaded"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn serves_metrics_with_segment_staleness() {
+    let root = std::env::temp_dir().join(format!("crankx-daemon-metrics-{}", std::process::id()));
+    let tapes = root.join("tapes");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&tapes).unwrap();
+
+    let mut tape = Writer::new(File::create(tapes.join("b.tape")).unwrap(), 32).unwrap();
+    tape.set_challenge(Challenge([5; 32]));
+    for segment in [[5u8; 32], [6u8; 32]] {
+        tape.append(&segment).unwrap();
+    }
+    tape.finish().unwrap();
+
+    // metrics_port can't be 0, so borrow a free port
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let text = format!(
+        "tape_dir = {:?}\narchive = {:?}\nmin_difficulty = 1\nthreads = 1\n\
+         metrics_port = {port}\nlog = {:?}\n",
+        tapes,
+        root.join("proofs"),
+        root.join("crankx.log")
+    );
+    let config_path = root.join("crankx.toml");
+    fs::write(&config_path, text).unwrap();
+
+    let mut daemon = Daemon::load(&config_path).unwrap();
+    assert_eq!(daemon.run_once().unwrap(), 2);
+    // The last segment proved; the first may have aged a second since
+    assert_eq!(daemon.metrics().staleness("b.tape", 1), Some(0));
+    assert!(daemon.metrics().staleness("b.tape", 0).is_some());
+    assert!(daemon.metrics().solutions() >= 2);
+    assert_eq!(daemon.metrics().segments(), [("b.tape".to_string(), 2, 0)]);
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let request = "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("crankx_solutions_total 2\n"));
+    assert!(response.contains("crankx_segment_staleness{source=\"b.tape\",segment=\"1\"} 0\n"));
+
+    // Gone tapes stop being reported
+    fs::remove_file(tapes.join("b.tape")).unwrap();
+    assert_eq!(daemon.run_once().unwrap(), 0);
+    assert_eq!(daemon.metrics().staleness("b.tape", 0), None);
+
+    drop(daemon);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn a_stopped_segment_resumes_from_its_checkpoint() {
+    let root = std::env::temp_dir().join(format!("crankx-daemon-resume-{}", std::process::id()));
+    let (tapes, archive) = (root.join("tapes"), root.join("proofs"));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&tapes).unwrap();
+
+    let challenge = Challenge([8; 32]);
+    let mut tape = Writer::new(File::create(tapes.join("c.tape")).unwrap(), 32).unwrap();
+    tape.set_challenge(challenge);
+    tape.append(&[9u8; 32]).unwrap();
+    tape.finish().unwrap();
+
+    let config_path = root.join("crankx.toml");
+    let write_config = |min_difficulty| {
+        let text = format!(
+            "tape_dir = {:?}\narchive = {:?}\nmin_difficulty = {min_difficulty}\nthreads = 1\n\
+             log = {:?}\n",
+            tapes,
+            archive,
+            root.join("crankx.log")
+        );
+        fs::write(&config_path, text).unwrap();
+    };
+
+    // Stopped partway through a search it can't finish
+    write_config(64);
+    let mut daemon = Daemon::load(&config_path).unwrap();
+    let stop = daemon.stop_flag();
+    let stopper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(300));
+        stop.store(true, Ordering::Relaxed);
+    });
+    assert_eq!(daemon.run_once().unwrap(), 0);
+    stopper.join().unwrap();
+    drop(daemon);
+
+    let checkpoint = fs::read(archive.join("c.tape.checkpoint")).unwrap();
+    let (index, mut state) = checkpoint.split_first_chunk::<8>().unwrap();
+    assert_eq!(u64::from_le_bytes(*index), 0);
+    let state = MinerState::restore(&mut state).unwrap();
+    assert_eq!(state.challenge, challenge);
+    let cursor = state.cursors[0];
+    assert!(cursor > 0);
+
+    // A restarted daemon carries on past the nonces already tried
+    write_config(1);
+    let mut daemon = Daemon::load(&config_path).unwrap();
+    assert_eq!(daemon.run_once().unwrap(), 1);
+    let store = ProofStore::open(archive.join("c.tape.proofs")).unwrap();
+    let proof = store.get(challenge, 0).unwrap();
+    assert!(u64::from_le_bytes(proof.solution.n) >= cursor);
+    assert!(!archive.join("c.tape.checkpoint").exists());
+
+    let _ = fs::remove_dir_all(&root);
+}
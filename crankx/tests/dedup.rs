@@ -0,0 +1,50 @@
+use crankx::dedup::{DedupTracker, Seen};
+use crankx::solve;
+
+fn key(i: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[..4].copy_from_slice(&i.to_le_bytes());
+    key[8..12].copy_from_slice(&i.wrapping_mul(0x9e37_79b9).to_le_bytes());
+    key
+}
+
+#[test]
+fn rejects_replays_within_an_epoch() {
+    let mut tracker = DedupTracker::new(4, 4096);
+    assert_eq!(tracker.check(1, key(1)), Seen::New);
+    assert_eq!(tracker.check(1, key(1)), Seen::Duplicate);
+    assert_eq!(tracker.check(1, key(2)), Seen::New);
+
+    // Older epochs are stale, newer ones start fresh
+    assert_eq!(tracker.check(0, key(3)), Seen::Stale);
+    assert_eq!(tracker.check(2, key(1)), Seen::New);
+    assert_eq!(tracker.epoch(), 2);
+    assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn evicted_keys_fall_back_to_the_filter() {
+    let mut tracker = DedupTracker::new(2, 1 << 16);
+    for i in 0..100 {
+        assert_eq!(tracker.check(0, key(i)), Seen::New);
+    }
+    assert_eq!(tracker.len(), 2);
+
+    assert_eq!(tracker.check(0, key(99)), Seen::Duplicate);
+    for i in 0..98 {
+        assert_eq!(tracker.check(0, key(i)), Seen::Probable);
+    }
+
+    tracker.reset(5);
+    assert!(tracker.is_empty());
+    assert_eq!(tracker.check(5, key(0)), Seen::New);
+}
+
+#[test]
+fn keyed_by_solution_hash() {
+    let solution = (0u64..).find_map(|n| solve([1; 32], &[2u8; 32], n).ok()).unwrap();
+
+    let mut tracker = DedupTracker::new(0, 1024);
+    assert_eq!(tracker.check_solution(0, &solution), Seen::New);
+    assert_eq!(tracker.check_solution(0, &solution), Seen::Probable);
+}
@@ -0,0 +1,71 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use crankx::devices::{CpuDevice, Device, DeviceResult, DeviceScheduler, DeviceWork};
+use crankx::equix::RuntimeOption;
+use crankx::{verify, CrankXError};
+
+const CHALLENGE: [u8; 32] = [4; 32];
+const DATA: [u8; 64] = [5; 64];
+
+/// A CPU device slowed down by a fixed delay per nonce
+struct Slow(CpuDevice);
+
+impl Device for Slow {
+    fn name(&self) -> String {
+        "slow".into()
+    }
+
+    fn search(
+        &mut self,
+        work: &DeviceWork,
+        nonces: Range<u64>,
+    ) -> Result<DeviceResult, CrankXError> {
+        std::thread::sleep(Duration::from_millis(50) * (nonces.end - nonces.start) as u32);
+        self.0.search(work, nonces)
+    }
+}
+
+struct Broken;
+
+impl Device for Broken {
+    fn name(&self) -> String {
+        "broken".into()
+    }
+
+    fn rate_hint(&self) -> Option<f64> {
+        Some(1e9)
+    }
+
+    fn search(&mut self, _: &DeviceWork, _: Range<u64>) -> Result<DeviceResult, CrankXError> {
+        Err(CrankXError::CompilerUnavailable)
+    }
+}
+
+#[test]
+fn faster_devices_take_larger_chunks() {
+    let mut fleet = DeviceScheduler::new(Duration::from_millis(20)).with_cpus(1);
+    fleet.add(Slow(CpuDevice::new(1, RuntimeOption::TryCompile)));
+    assert_eq!(fleet.len(), 2);
+
+    let report = fleet.mine(CHALLENGE, &DATA, 6).unwrap();
+    let solution = report.solution.as_ref().unwrap();
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+
+    let (cpu, slow) = (&report.devices[0], &report.devices[1]);
+    assert_eq!((cpu.name.as_str(), slow.name.as_str()), ("cpu0", "slow"));
+    assert!(cpu.rate > slow.rate);
+    assert!(cpu.attempts > slow.attempts);
+    assert_eq!(report.attempts(), cpu.attempts + slow.attempts);
+}
+
+#[test]
+fn device_errors_end_the_run() {
+    let mut fleet = DeviceScheduler::new(Duration::from_millis(20)).with_cpus(1);
+    fleet.add(Broken);
+    assert!(matches!(fleet.mine(CHALLENGE, &DATA, 30), Err(CrankXError::CompilerUnavailable)));
+
+    let mut empty = DeviceScheduler::new(Duration::from_millis(20));
+    assert!(empty.is_empty());
+    assert!(empty.mine(CHALLENGE, &DATA, 1).unwrap().solution.is_none());
+}
@@ -0,0 +1,39 @@
+use crankx::compat::drillx::{self, DrillxSolution};
+use crankx::Solution;
+
+#[test]
+fn data_less_round_trip() {
+    let challenge = [3u8; 32];
+    let solution = (0u64..)
+        .find_map(|n| drillx::solve(challenge, n.to_le_bytes()).ok())
+        .unwrap();
+
+    drillx::verify(challenge, solution.n, &solution.d).unwrap();
+
+    // The core API refuses the same seed as empty data
+    assert!(matches!(
+        crankx::batch::verify_nonces(challenge, &[], &[solution.to_bytes()]),
+        Err(crankx::CrankXError::SegmentTooSmall { min: 1, got: 0 })
+    ));
+
+    let legacy = DrillxSolution::from(&solution);
+    assert_eq!(legacy.to_bytes(), solution.to_bytes());
+    assert_eq!(DrillxSolution::from_bytes(&legacy.to_bytes()), legacy);
+    assert!(legacy.matches(&solution));
+}
+
+#[test]
+fn matches_ignores_digest_word_order() {
+    let mut d = [0u8; 16];
+    for (i, b) in d.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let mut swapped = d;
+    swapped[..2].copy_from_slice(&d[2..4]);
+    swapped[2..4].copy_from_slice(&d[..2]);
+
+    let nonce = [9u8; 8];
+    let legacy = DrillxSolution { d: swapped, n: nonce };
+    assert!(legacy.matches(&Solution::new(d, nonce)));
+    assert!(!legacy.matches(&Solution::new(d, [0u8; 8])));
+}
@@ -0,0 +1,38 @@
+use crankx::economics::Rig;
+use crankx::Target;
+
+fn rig() -> Rig {
+    Rig {
+        hashrate: 100.0,
+        target: Target::from_difficulty(10),
+        reward_per_proof: 0.5,
+        power_watts: 250.0,
+        power_cost_per_kwh: 0.2,
+    }
+}
+
+#[test]
+fn daily_estimate() {
+    let estimate = rig().estimate();
+
+    // 100 H/s at 1-in-1024 odds
+    assert!((estimate.proofs_per_day - 100.0 * 86_400.0 / 1024.0).abs() < 1e-6);
+    assert!((estimate.revenue_per_day - estimate.proofs_per_day * 0.5).abs() < 1e-9);
+    assert!((estimate.cost_per_day - 1.2).abs() < 1e-9);
+    assert!(estimate.margin_per_day > 0.0);
+
+    // One bit harder halves the proofs
+    let harder = Rig { target: Target::from_difficulty(11), ..rig() }.estimate();
+    assert!((harder.proofs_per_day * 2.0 - estimate.proofs_per_day).abs() < 1e-6);
+}
+
+#[test]
+fn break_even_points() {
+    let rig = rig();
+
+    let at_reward = Rig { reward_per_proof: rig.break_even_reward(), ..rig };
+    assert!(at_reward.estimate().margin_per_day.abs() < 1e-9);
+
+    let at_power = Rig { power_cost_per_kwh: rig.break_even_power_cost(), ..rig };
+    assert!(at_power.estimate().margin_per_day.abs() < 1e-9);
+}
@@ -0,0 +1,53 @@
+// Heap use of the minimal verify configuration, counted by a wrapping
+// allocator; the numbers documented on `SolverConfig::EMBEDDED` come from here
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use crankx::config::SolverConfig;
+use crankx::test_vectors::TEST_VECTORS;
+use crankx::{Solution, MAX_DATA_LEN};
+
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Relaxed) + layout.size();
+        PEAK.fetch_max(live, Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+#[test]
+fn interpreted_verify_stays_small() {
+    let v = TEST_VECTORS.iter().find(|v| v.name == "ones").unwrap();
+    let data: &[u8; 64] = v.data.try_into().unwrap();
+    let solution = Solution::new(v.digest, v.nonce);
+    let config = SolverConfig::EMBEDDED;
+
+    let before = LIVE.load(Relaxed);
+    PEAK.store(before, Relaxed);
+    config.verify(v.challenge, data, &solution).unwrap();
+    let small = PEAK.load(Relaxed) - before;
+
+    let big = [7u8; MAX_DATA_LEN];
+    let before = LIVE.load(Relaxed);
+    PEAK.store(before, Relaxed);
+    let _ = config.verify(v.challenge, &big, &solution);
+    let large = PEAK.load(Relaxed) - before;
+
+    // One 4 KiB HashX program plus the seed
+    assert!(small < 4096 + 256, "{small}");
+    assert!(large < 4096 + MAX_DATA_LEN + 256, "{large}");
+}
@@ -0,0 +1,66 @@
+use proptest::prelude::*;
+
+use crankx::encoding::compact::{decode, encode, encoded_len, CompactProof};
+use crankx::{solve, CrankXError};
+
+fn proof() -> impl Strategy<Value = CompactProof> {
+    (any::<u64>(), any::<[u8; 8]>(), any::<[u8; 16]>())
+        .prop_map(|(segment, nonce, digest)| CompactProof { segment, nonce, digest })
+}
+
+proptest! {
+    #[test]
+    fn compact_round_trip(proofs in prop::collection::vec(proof(), 0..40)) {
+        let bytes = encode(&proofs);
+        prop_assert_eq!(bytes.len(), encoded_len(&proofs));
+        prop_assert_eq!(decode(&bytes).unwrap(), proofs);
+    }
+
+    #[test]
+    fn compact_rejects_truncation_and_trailing_bytes(
+        proofs in prop::collection::vec(proof(), 1..8),
+        cut in 1usize..24,
+    ) {
+        let bytes = encode(&proofs);
+        prop_assert!(decode(&bytes[..bytes.len() - cut]).is_err());
+
+        let mut longer = bytes;
+        longer.push(0);
+        prop_assert!(decode(&longer).is_err());
+    }
+}
+
+#[test]
+fn compact_layout() {
+    let solution = (0u64..).find_map(|n| solve([1; 32], &[2u8; 32], n).ok()).unwrap();
+    let proofs = [CompactProof::new(3, &solution), CompactProof::new(300, &solution)];
+    let bytes = encode(&proofs);
+
+    // count, then segment 3 (1 byte) and 300 (2 bytes) each before 24 fixed bytes
+    assert_eq!(bytes.len(), 1 + 1 + 24 + 2 + 24);
+    assert_eq!(&bytes[..2], &[2, 3]);
+    assert_eq!(&bytes[2..10], &solution.n);
+    assert_eq!(&bytes[26..28], &[0xac, 0x02]);
+    assert_eq!(decode(&bytes).unwrap()[1].solution().to_hash(), solution.to_hash());
+
+    assert_eq!(encode(&[]), [0]);
+    assert_eq!(encoded_len(&[CompactProof { segment: u64::MAX, ..proofs[0] }]), 1 + 10 + 24);
+}
+
+#[test]
+fn compact_rejects_non_canonical_varints() {
+    let invalid = |bytes: &[u8]| matches!(decode(bytes), Err(CrankXError::InvalidEncoding));
+
+    assert!(invalid(&[]));
+    // Zero proofs written with a redundant continuation byte
+    assert!(invalid(&[0x80, 0x00]));
+    // Count larger than the remaining bytes can hold
+    assert!(invalid(&[0xff, 0xff, 0xff, 0xff, 0x0f]));
+
+    // Segment index overflowing u64
+    let mut overflow = vec![1];
+    overflow.extend_from_slice(&[0xff; 9]);
+    overflow.push(0x02);
+    overflow.extend_from_slice(&[0; 24]);
+    assert!(invalid(&overflow));
+}
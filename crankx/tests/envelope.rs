@@ -0,0 +1,100 @@
+#![cfg(feature = "envelope")]
+
+use crankx::envelope::{verify_signature, ProofEnvelope, SigningKey, ENVELOPE_LEN};
+use crankx::{solve, Challenge, CrankXError};
+
+fn unhex<const N: usize>(s: &str) -> [u8; N] {
+    let bytes: Vec<u8> =
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect();
+    bytes.try_into().unwrap()
+}
+
+#[test]
+fn signatures_match_rfc_8032() {
+    // RFC 8032 section 7.1, tests 1 and 2
+    let cases = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            &[][..],
+            concat!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155",
+                "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            &[0x72][..],
+            concat!(
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da",
+                "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+        ),
+    ];
+    for (seed, public, message, signature) in cases {
+        let key = SigningKey::from_seed(&unhex(seed));
+        assert_eq!(key.public_key(), unhex(public));
+        let signature = unhex(signature);
+        assert_eq!(key.sign(&[message]), signature);
+        verify_signature(&key.public_key(), &[message], &signature).unwrap();
+        assert!(verify_signature(&key.public_key(), &[b"x"], &signature).is_err());
+    }
+}
+
+#[test]
+fn envelope_round_trips_and_rejects_tampering() {
+    let key = SigningKey::from_seed(&[9; 32]);
+    let mut keypair = [9; 64];
+    keypair[32..].copy_from_slice(&key.public_key());
+    assert_eq!(SigningKey::from_keypair_bytes(&keypair).unwrap().public_key(), key.public_key());
+    keypair[63] ^= 1;
+    assert!(SigningKey::from_keypair_bytes(&keypair).is_err());
+
+    let (challenge, data) = (Challenge([1; 32]), [2u8; 64]);
+    let solution = (0u64..).find_map(|n| solve(challenge, &data, n.to_le_bytes()).ok()).unwrap();
+    let envelope = ProofEnvelope::sign(&key, challenge, 7, 1_700_000_000, solution);
+    envelope.verify(&data).unwrap();
+
+    let bytes = envelope.to_bytes();
+    assert_eq!(ProofEnvelope::from_bytes(&bytes).unwrap(), envelope);
+    assert!(matches!(ProofEnvelope::from_bytes(&bytes[1..]), Err(CrankXError::InvalidLength)));
+
+    // Any changed byte breaks the signature
+    for i in [0, 32, 40, 72, 80, 100, ENVELOPE_LEN - 1] {
+        let mut tampered = bytes;
+        tampered[i] ^= 1;
+        let tampered = ProofEnvelope::from_bytes(&tampered).unwrap();
+        assert!(matches!(tampered.verify_signature(), Err(CrankXError::InvalidSignature)));
+    }
+    // A good signature over the wrong segment bytes still fails the proof
+    assert!(envelope.verify(&[3u8; 64]).is_err());
+}
+
+#[test]
+fn weak_keys_and_malleated_signatures_are_rejected() {
+    // The identity as key and as `R` with `S = 0` satisfies the verification
+    // equation for any message; small order rules it out
+    let mut identity = [0; 32];
+    identity[0] = 1;
+    let mut forged = [0; 64];
+    forged[..32].copy_from_slice(&identity);
+    assert!(matches!(
+        verify_signature(&identity, &[b"anything"], &forged),
+        Err(CrankXError::InvalidSignature)
+    ));
+
+    // `S` plus the group order satisfies the same equation: a second encoding
+    let order: [u8; 32] = unhex("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010");
+    let key = SigningKey::from_seed(&[5; 32]);
+    let signature = key.sign(&[b"m"]);
+    let mut malleated = signature;
+    let mut carry = 0u16;
+    for (s, l) in malleated[32..].iter_mut().zip(order) {
+        let sum = *s as u16 + l as u16 + carry;
+        *s = sum as u8;
+        carry = sum >> 8;
+    }
+    verify_signature(&key.public_key(), &[b"m"], &signature).unwrap();
+    assert!(verify_signature(&key.public_key(), &[b"m"], &malleated).is_err());
+}
@@ -0,0 +1,37 @@
+use crankx::equix::SolverMemory;
+use crankx::fresh::{solve_fresh, verify_fresh, Anchor};
+use crankx::{verify, CrankXError};
+
+#[test]
+fn anchor_round_trips() {
+    for anchor in [Anchor::Slot(42), Anchor::Timestamp(1_700_000_000)] {
+        assert_eq!(Anchor::from_bytes(&anchor.to_bytes()).unwrap(), anchor);
+    }
+    let mut bytes = Anchor::Slot(1).to_bytes();
+    bytes[0] = 9;
+    assert!(matches!(Anchor::from_bytes(&bytes), Err(CrankXError::InvalidEncoding)));
+}
+
+#[test]
+fn verify_fresh_enforces_max_age() {
+    let (challenge, data, anchor) = ([1u8; 32], [2u8; 64], Anchor::Slot(1_000));
+    let mut mem = SolverMemory::new();
+    let solution = (0u64..)
+        .find_map(|n| solve_fresh(&mut mem, challenge, &data, anchor, n).ok())
+        .unwrap();
+    let check =
+        |anchor, now| verify_fresh(challenge, &data, anchor, solution.n, &solution.d, now, 150);
+
+    check(anchor, Anchor::Slot(1_000)).unwrap();
+    check(anchor, Anchor::Slot(1_150)).unwrap();
+    assert!(matches!(
+        check(anchor, Anchor::Slot(1_151)),
+        Err(CrankXError::StaleProof { age: 151, max_age: 150 })
+    ));
+    assert!(matches!(check(anchor, Anchor::Slot(999)), Err(CrankXError::InvalidAnchor)));
+    assert!(matches!(check(anchor, Anchor::Timestamp(1_000)), Err(CrankXError::InvalidAnchor)));
+
+    // Claiming a newer anchor breaks the proof itself
+    assert!(check(Anchor::Slot(1_100), Anchor::Slot(1_100)).is_err());
+    assert!(verify(challenge, &data, solution.n, &solution.d).is_err());
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use crankx::fuzzing::CanonicalSolution;
+use crankx::{Challenge, HashAlgorithm, Nonce, Solution};
+
+/// Deterministic filler bytes, different for every `seed`
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+#[test]
+fn challenge_nonce_and_solution_take_their_bytes_in_order() {
+    let data: Vec<u8> = (0..=255).collect();
+    let mut u = Unstructured::new(&data);
+
+    assert_eq!(Challenge::arbitrary(&mut u).unwrap(), Challenge(core::array::from_fn(|i| i as u8)));
+    assert_eq!(Nonce::arbitrary(&mut u).unwrap(), Nonce(core::array::from_fn(|i| 32 + i as u8)));
+    let solution = Solution::arbitrary(&mut u).unwrap();
+    assert_eq!(solution.d, core::array::from_fn(|i| 40 + i as u8));
+    assert_eq!(solution.n, core::array::from_fn(|i| 56 + i as u8));
+    assert_eq!(Solution::size_hint(0), (24, Some(25)));
+}
+
+#[test]
+fn an_exhausted_input_still_gives_a_keccak_solution() {
+    let solution = Solution::arbitrary(&mut Unstructured::new(&[])).unwrap();
+    assert_eq!(solution.d, [0; 16]);
+    assert_eq!(solution.hash_algorithm(), HashAlgorithm::Keccak256);
+}
+
+#[test]
+fn canonical_solutions_pass_the_structure_check() {
+    let mut shapeless = 0;
+    for seed in 0..256 {
+        let data = bytes(seed, 32);
+        let solution = Solution::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        shapeless += solution.candidate().is_err() as u32;
+
+        let canonical = CanonicalSolution::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let canonical = Solution::from(canonical);
+        assert!(canonical.candidate().is_ok(), "seed {seed}");
+        assert_eq!(canonical.n, solution.n);
+
+        // Same items, only reordered
+        let mut items = canonical.d.chunks(2).collect::<Vec<_>>();
+        let mut original = solution.d.chunks(2).collect::<Vec<_>>();
+        items.sort();
+        original.sort();
+        assert_eq!(items, original);
+    }
+    assert!(shapeless > 200);
+}
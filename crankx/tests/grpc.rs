@@ -0,0 +1,81 @@
+#![cfg(feature = "grpc")]
+
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+use tokio_stream::StreamExt;
+use tonic::transport::Server;
+
+use crankx::grpc::pb::{GetStatsRequest, StreamSharesRequest, SubmitJobRequest};
+use crankx::grpc::{ProverClient, ProverService};
+use crankx::miner::Miner;
+use crankx::verify;
+
+#[test]
+fn submitted_jobs_stream_shares_back() {
+    let segments = vec![[3u8; 64], [4u8; 64]];
+    let service = ProverService::new(segments.clone(), Miner::new(2));
+    let stop = service.stop_flag();
+
+    let runtime = Runtime::new().unwrap();
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+        tokio::spawn(
+            Server::builder().add_service(service.into_server()).serve_with_incoming(incoming),
+        );
+
+        let mut client = ProverClient::connect(format!("http://{addr}")).await.unwrap();
+        let mut shares = client.stream_shares(StreamSharesRequest {}).await.unwrap().into_inner();
+
+        let bad = SubmitJobRequest {
+            job_id: 1,
+            challenge: vec![5; 31],
+            segments: vec![],
+            min_difficulty: 0,
+        };
+        assert!(client.submit_job(bad).await.is_err());
+        let missing = SubmitJobRequest {
+            job_id: 1,
+            challenge: vec![5; 32],
+            segments: vec![2],
+            min_difficulty: 0,
+        };
+        assert!(client.submit_job(missing).await.is_err());
+
+        // Every segment when none are named
+        let job = SubmitJobRequest {
+            job_id: 7,
+            challenge: vec![5; 32],
+            segments: vec![],
+            min_difficulty: 2,
+        };
+        assert_eq!(client.submit_job(job).await.unwrap().into_inner().queued, 2);
+
+        let mut proved = Vec::new();
+        while proved.len() < 2 {
+            let share = tokio::time::timeout(Duration::from_secs(60), shares.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(share.job_id, 7);
+            assert!(share.difficulty >= 2);
+            let nonce: [u8; 8] = share.nonce.try_into().unwrap();
+            let digest: [u8; 16] = share.digest.try_into().unwrap();
+            let challenge: [u8; 32] = share.challenge.try_into().unwrap();
+            verify(challenge, &segments[share.segment as usize], nonce, &digest).unwrap();
+            proved.push(share.segment);
+        }
+        proved.sort();
+        assert_eq!(proved, [0, 1]);
+
+        let stats = client.get_stats(GetStatsRequest {}).await.unwrap().into_inner();
+        assert_eq!(stats.solutions, 2);
+        assert!(stats.attempts >= 2);
+        assert_eq!(stats.segments_pending, 0);
+        assert_eq!(stats.errors, 0);
+    });
+    stop.store(true, std::sync::atomic::Ordering::Relaxed);
+}
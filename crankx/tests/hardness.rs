@@ -0,0 +1,50 @@
+use crankx::equix::SolverMemory;
+use crankx::hardness::{solve_hard, verify_hard, HardSolution, Hardness, MAX_ROUNDS};
+use crankx::{solve_with_memory, CrankXError};
+
+const CHALLENGE: [u8; 32] = [7; 32];
+const DATA: [u8; 64] = [8; 64];
+
+fn solved(hardness: Hardness) -> HardSolution {
+    let mut mem = SolverMemory::new();
+    (0u64..).find_map(|n| solve_hard(&mut mem, hardness, CHALLENGE, &DATA, n).ok()).unwrap()
+}
+
+#[test]
+fn rounds_are_clamped() {
+    assert_eq!(Hardness::new(0), Hardness::BASE);
+    assert_eq!(Hardness::new(200).rounds(), MAX_ROUNDS);
+    assert_eq!(Hardness::default().rounds(), 1);
+}
+
+#[test]
+fn one_round_is_the_plain_proof() {
+    let hard = solved(Hardness::BASE);
+    let mut mem = SolverMemory::new();
+    let plain = solve_with_memory(&mut mem, CHALLENGE, &DATA, hard.n).unwrap();
+
+    assert_eq!(hard.d, [plain.d]);
+    assert_eq!(hard.to_hash(), plain.to_hash());
+}
+
+#[test]
+fn chained_rounds_verify_only_under_their_hardness() {
+    let hardness = Hardness::new(3);
+    let solution = solved(hardness);
+    assert_eq!(solution.d.len(), 3);
+
+    verify_hard(hardness, CHALLENGE, &DATA, &solution).unwrap();
+    assert!(matches!(
+        verify_hard(Hardness::new(2), CHALLENGE, &DATA, &solution),
+        Err(CrankXError::ProofCount { expected: 2, got: 3 })
+    ));
+
+    let mut reordered = solution.clone();
+    reordered.d.swap(1, 2);
+    assert!(verify_hard(hardness, CHALLENGE, &DATA, &reordered).is_err());
+
+    let bytes = solution.to_bytes();
+    assert_eq!(bytes.len(), 8 + 3 * 16);
+    assert_eq!(HardSolution::from_bytes(&bytes, hardness).unwrap(), solution);
+    assert!(HardSolution::from_bytes(&bytes, Hardness::new(2)).is_err());
+}
@@ -0,0 +1,128 @@
+#[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+use crankx::keccak::sha3_256;
+use crankx::keccak::{keccak256, BACKEND};
+use crankx::{solve, verify_with_hash, CrankXError, HashAlgorithm, Solution, WIRE_VERSION};
+
+const DIGEST: [u8; 16] = [
+    0x52, 0x1f, 0x0b, 0xac, 0x36, 0x3b, 0x3b, 0xad, 0xb1, 0x2b, 0xef, 0x55, 0x6a, 0x30, 0x76, 0xaf,
+];
+const NONCE: [u8; 8] = [0; 8];
+
+#[test]
+fn keccak_is_default() {
+    let solution = Solution::new(DIGEST, NONCE);
+    let explicit = Solution::with_hash_algorithm(DIGEST, NONCE, HashAlgorithm::Keccak256);
+
+    assert_eq!(solution.hash_algorithm(), HashAlgorithm::Keccak256);
+    assert_eq!(solution.to_hash(), explicit.to_hash());
+    assert_eq!(solution.to_hash(), HashAlgorithm::default().hash(&DIGEST, &NONCE));
+}
+
+#[test]
+#[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+fn algorithms_differ() {
+    let keccak = Solution::new(DIGEST, NONCE);
+    let sha3 = Solution::with_hash_algorithm(DIGEST, NONCE, HashAlgorithm::Sha3_256);
+    assert_ne!(keccak.to_hash(), sha3.to_hash());
+}
+
+#[test]
+#[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+fn versioned_round_trip() {
+    let sha3 = Solution::with_hash_algorithm(DIGEST, NONCE, HashAlgorithm::Sha3_256);
+    let bytes = sha3.to_versioned_bytes();
+    assert_eq!(bytes[0], WIRE_VERSION);
+    assert_eq!(bytes[1], HashAlgorithm::Sha3_256.id());
+
+    let decoded = Solution::from_versioned_bytes(&bytes).unwrap();
+    assert_eq!(decoded.hash_algorithm(), HashAlgorithm::Sha3_256);
+    assert_eq!(decoded.to_hash(), sha3.to_hash());
+    assert_eq!(decoded.to_bytes(), sha3.to_bytes());
+}
+
+#[test]
+fn versioned_rejects_unknown_headers() {
+    let mut bytes = Solution::new(DIGEST, NONCE).to_versioned_bytes();
+
+    bytes[1] = 0xff;
+    assert!(matches!(
+        Solution::from_versioned_bytes(&bytes),
+        Err(CrankXError::UnsupportedHash)
+    ));
+
+    bytes[0] = WIRE_VERSION + 1;
+    assert!(matches!(
+        Solution::from_versioned_bytes(&bytes),
+        Err(CrankXError::UnsupportedVersion)
+    ));
+
+    assert!(matches!(
+        Solution::from_versioned_bytes(&bytes[..25]),
+        Err(CrankXError::InvalidLength)
+    ));
+}
+
+#[test]
+fn keccak_backend_known_answers() {
+    let hex = |h: [u8; 32]| h.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    let long: Vec<u8> = (0..200u8).collect();
+    let split: &[&[u8]] = &[&long[..7], &long[7..]];
+
+    // Empty and multi-block inputs, the latter split mid-block across parts,
+    // through whichever backend the build has
+    assert_eq!(
+        hex(keccak256(&[])),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        "backend {BACKEND}"
+    );
+    assert_eq!(
+        hex(keccak256(split)),
+        "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890",
+        "backend {BACKEND}"
+    );
+    // No SHA3 with `solana-hash`
+    #[cfg(any(feature = "sha3", feature = "keccak-f1600"))]
+    {
+        assert_eq!(
+            hex(sha3_256(&[])),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        assert_eq!(
+            hex(sha3_256(split)),
+            "5f728f63bf5ee48c77f453c0490398fa645b8d4c4e56be9a41cfec344d6ca899"
+        );
+    }
+}
+
+#[test]
+fn verify_with_hash_trusts_the_hash_unless_strict() {
+    let (challenge, data) = ([6u8; 32], [7u8; 64]);
+    let solution = (0u64..).find_map(|n| solve(challenge, &data, n.to_le_bytes()).ok()).unwrap();
+    let (nonce, digest, hash) = (solution.n, solution.d, solution.to_hash());
+
+    for strict in [false, true] {
+        let difficulty = verify_with_hash(challenge, &data, nonce, &digest, &hash, strict);
+        assert_eq!(difficulty.unwrap(), solution.difficulty());
+    }
+
+    let forged = [0u8; 32];
+    assert_eq!(verify_with_hash(challenge, &data, nonce, &digest, &forged, false).unwrap(), 256);
+    assert!(matches!(
+        verify_with_hash(challenge, &data, nonce, &digest, &forged, true),
+        Err(CrankXError::InvalidSolution)
+    ));
+    // The EquiX proof is checked either way
+    assert!(verify_with_hash(challenge, &[8u8; 64], nonce, &digest, &hash, false).is_err());
+}
+
+#[test]
+fn backend_follows_precedence() {
+    let expected = if cfg!(feature = "solana-hash") {
+        "solana"
+    } else if cfg!(feature = "keccak-f1600") {
+        "keccak-f1600"
+    } else {
+        "sha3"
+    };
+    assert_eq!(BACKEND, expected);
+}
@@ -0,0 +1,26 @@
+use crankx::equix::SolverMemory;
+use crankx::identity::{bind_challenge, solve_for, verify_for};
+use crankx::{keccak::keccak256, verify, Challenge};
+
+#[test]
+fn proofs_only_verify_for_their_miner() {
+    let (challenge, data) = ([1u8; 32], [2u8; 64]);
+    let (alice, mallory) = ([0xaa; 32], [0x66; 32]);
+    let mut mem = SolverMemory::new();
+
+    let solution = (0u64..)
+        .find_map(|n| solve_for(&mut mem, &alice, challenge, &data, n).ok())
+        .unwrap();
+
+    verify_for(&alice, challenge, &data, solution.n, &solution.d).unwrap();
+    assert!(verify_for(&mallory, challenge, &data, solution.n, &solution.d).is_err());
+    assert!(verify(challenge, &data, solution.n, &solution.d).is_err());
+    verify(bind_challenge(challenge, &alice), &data, solution.n, &solution.d).unwrap();
+}
+
+#[test]
+fn binding_is_the_specified_hash() {
+    let (challenge, miner) = ([3u8; 32], [4u8; 32]);
+    let expected = keccak256(&[b"crankx:miner:v1", &challenge, &miner]);
+    assert_eq!(bind_challenge(challenge, &miner), Challenge(expected));
+}
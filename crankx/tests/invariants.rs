@@ -0,0 +1,175 @@
+use proptest::prelude::*;
+
+use crankx::batch::{verify_batch, verify_nonces, BatchItem};
+use crankx::encoding::compact::decode;
+use crankx::{solve, verify, verify_in_buffer, verify_raw, Solution, MAX_DATA_LEN, MAX_SEED_LEN};
+
+const DATA_LEN: usize = 64;
+
+/// Solving runs a full EquiX search per case, so keep the case count modest.
+fn solve_config() -> ProptestConfig {
+    ProptestConfig::with_cases(16)
+}
+
+/// First solution found at or after `nonce`
+fn find_solution(
+    challenge: &[u8; 32],
+    data: &[u8; DATA_LEN],
+    nonce: u64,
+) -> Solution {
+    (nonce..)
+        .find_map(|n| solve(challenge, data, n.to_le_bytes()).ok())
+        .unwrap()
+}
+
+proptest! {
+    #![proptest_config(solve_config())]
+
+    #[test]
+    fn solutions_verify(
+        challenge in any::<[u8; 32]>(),
+        data in any::<[u8; DATA_LEN]>(),
+        nonce in any::<[u8; 8]>(),
+    ) {
+        if let Ok(solution) = solve(challenge, &data, nonce) {
+            prop_assert_eq!(solution.n, nonce);
+            prop_assert!(verify(challenge, &data, solution.n, &solution.d).is_ok());
+            prop_assert!(solution.is_valid(challenge, &data).is_ok());
+            prop_assert!(verify_raw(challenge, &data, &solution.to_bytes()).is_ok());
+        }
+    }
+
+    #[test]
+    fn tampering_breaks_verification(
+        challenge in any::<[u8; 32]>(),
+        data in any::<[u8; DATA_LEN]>(),
+        nonce in any::<u64>(),
+        index in 0..32 + DATA_LEN + 8,
+        flip in 1..=u8::MAX,
+    ) {
+        let solution = find_solution(&challenge, &data, nonce);
+
+        let mut challenge = challenge;
+        let mut data = data;
+        let mut nonce = solution.n;
+
+        match index {
+            i if i < 32 => challenge[i] ^= flip,
+            i if i < 32 + DATA_LEN => data[i - 32] ^= flip,
+            i => nonce[i - 32 - DATA_LEN] ^= flip,
+        }
+
+        prop_assert!(verify(challenge, &data, nonce, &solution.d).is_err());
+
+        let mut raw = solution.to_bytes();
+        raw[16..].copy_from_slice(&nonce);
+        prop_assert!(verify_raw(challenge, &data, &raw).is_err());
+    }
+}
+
+proptest! {
+    #[test]
+    fn bytes_round_trip(digest in any::<[u8; 16]>(), nonce in any::<[u8; 8]>()) {
+        let solution = Solution::new(digest, nonce);
+        let bytes = solution.to_bytes();
+
+        let decoded = Solution::from_bytes(&bytes);
+        prop_assert_eq!(decoded.d, digest);
+        prop_assert_eq!(decoded.n, nonce);
+        prop_assert_eq!(decoded.to_hash(), solution.to_hash());
+
+        let sliced = Solution::try_from_slice(&bytes).unwrap();
+        prop_assert_eq!(sliced.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn full_bytes_round_trip(
+        digest in any::<[u8; 16]>(),
+        nonce in any::<[u8; 8]>(),
+        index in 0usize..56,
+        flip in 1..=u8::MAX,
+    ) {
+        let solution = Solution::new(digest, nonce);
+        let full = solution.to_bytes_full();
+        prop_assert_eq!(&full[..24], &solution.to_bytes()[..]);
+
+        let checked = Solution::from_bytes_full(&full).unwrap();
+        let trusted = Solution::from_bytes_full_unchecked(&full);
+        prop_assert_eq!(checked.to_hash(), solution.to_hash());
+        prop_assert_eq!(trusted.to_hash(), solution.to_hash());
+        prop_assert_eq!(trusted.difficulty(), solution.difficulty());
+
+        // Any flipped bit breaks the digest/nonce/hash binding
+        let mut tampered = full;
+        tampered[index] ^= flip;
+        prop_assert!(Solution::from_bytes_full(&tampered).is_err());
+    }
+
+    #[test]
+    fn wrong_length_slices_rejected(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+        prop_assume!(bytes.len() != 24);
+        prop_assert!(Solution::try_from_slice(&bytes).is_err());
+    }
+
+    #[test]
+    fn hash_ignores_digest_word_order(
+        words in any::<[u16; 8]>().prop_map(Vec::from).prop_shuffle(),
+        nonce in any::<[u8; 8]>(),
+    ) {
+        let mut sorted = words.clone();
+        sorted.sort_unstable();
+
+        let solution = Solution::new(to_digest(&words), nonce);
+        let canonical = Solution::new(to_digest(&sorted), nonce);
+
+        prop_assert_eq!(solution.to_hash(), canonical.to_hash());
+        prop_assert_eq!(solution.difficulty(), canonical.difficulty());
+    }
+}
+
+proptest! {
+    // Verification runs on untrusted bytes (on Solana a panic aborts the
+    // transaction), so every verify-side entry point must turn arbitrary
+    // input into an error rather than a panic.
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn verify_paths_never_panic(
+        challenge in any::<[u8; 32]>(),
+        data in prop::collection::vec(any::<u8>(), 0..MAX_DATA_LEN + 64),
+        proofs in prop::collection::vec(any::<[u8; 24]>(), 0..3),
+        full in prop::collection::vec(any::<u8>(), 56),
+        encoded in prop::collection::vec(any::<u8>(), 0..128),
+        buf_len in 0..MAX_SEED_LEN + 8,
+    ) {
+        let proof = proofs.first().copied().unwrap_or_default();
+        let solution = Solution::from_bytes(&proof);
+
+        let _ = verify_raw(challenge, &[0u8; DATA_LEN], &proof);
+        let _ = verify_nonces(challenge, &data, &proofs);
+        let items: Vec<_> =
+            proofs.iter().map(|p| BatchItem::new(&data, &Solution::from_bytes(p))).collect();
+        let _ = verify_batch(challenge, &items);
+        let mut buf = vec![0u8; buf_len];
+        let _ = verify_in_buffer(&mut buf, challenge, &[0u8; DATA_LEN], solution.n, &solution.d);
+
+        let _ = Solution::from_bytes_full(full.as_slice().try_into().unwrap());
+        let _ = Solution::try_from_slice(&encoded);
+        let _ = decode(&encoded);
+
+        #[cfg(feature = "solana")]
+        {
+            use crankx::solana::{verify_with_difficulty, SubmitProof};
+            let _ = verify_with_difficulty(challenge, &data, &proof, 0);
+            let _ = SubmitProof::unpack(&encoded);
+        }
+    }
+}
+
+fn to_digest(words: &[u16]) -> [u8; 16] {
+    let mut digest = [0u8; 16];
+    for (chunk, word) in digest.chunks_exact_mut(2).zip(words) {
+        chunk.copy_from_slice(&word.to_ne_bytes());
+    }
+    digest
+}
@@ -0,0 +1,88 @@
+use crankx::job::{Job, Rejection, Share};
+use crankx::{solve, Challenge, CrankXError};
+
+const CHALLENGE: [u8; 32] = [2; 32];
+
+fn job() -> Job {
+    Job {
+        id: 9,
+        challenge: Challenge(CHALLENGE),
+        segment: 4,
+        target: 0,
+        nonce_start: 0,
+        nonce_end: 999,
+        expires_at: Some(1_700_000_000),
+    }
+}
+
+fn share(job_id: u64) -> Share {
+    let solution = (0u64..).find_map(|n| solve(CHALLENGE, &[1u8; 32], n).ok()).unwrap();
+    Share { job_id, worker_id: 5, solution }
+}
+
+#[test]
+fn share_checks() {
+    let job = job();
+    let now = 1_600_000_000;
+    let ok = share(9);
+    assert_eq!(ok.check(&job, now), Ok(()));
+
+    assert_eq!(share(8).check(&job, now), Err(Rejection::WrongJob));
+    assert_eq!(ok.check(&job, 1_700_000_001), Err(Rejection::Expired));
+    assert_eq!(ok.check(&Job { expires_at: None, ..job }, u64::MAX), Ok(()));
+
+    let above = Job { target: ok.solution.difficulty() + 1, ..job };
+    assert_eq!(ok.check(&above, now), Err(Rejection::BelowTarget));
+    let elsewhere = Job { nonce_start: 1000, nonce_end: 1999, ..job };
+    assert_eq!(ok.check(&elsewhere, now), Err(Rejection::NonceOutOfRange));
+}
+
+#[test]
+fn nonce_ranges_must_not_be_empty() {
+    assert!(job().validate().is_ok());
+    assert!(Job { nonce_start: 999, ..job() }.validate().is_ok());
+    assert!(matches!(
+        Job { nonce_start: 1000, ..job() }.validate(),
+        Err(CrankXError::InvalidNonceRange { start: 1000, end: 999 })
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_round_trip() {
+    let job = job();
+    let json = serde_json::to_value(job).unwrap();
+    assert_eq!(json["challenge"], "02".repeat(32));
+    assert_eq!(serde_json::from_value::<Job>(json).unwrap(), job);
+
+    // expires_at may be omitted
+    let mut json = serde_json::to_value(job).unwrap();
+    json.as_object_mut().unwrap().remove("expires_at");
+    assert_eq!(serde_json::from_value::<Job>(json).unwrap().expires_at, None);
+
+    let share = share(9);
+    let json = serde_json::to_string(&share).unwrap();
+    let decoded: Share = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, share);
+    assert_eq!(decoded.solution.to_bytes(), share.solution.to_bytes());
+
+    let bad = json.replace(&hex(&share.solution.to_bytes()), "zz");
+    assert!(serde_json::from_str::<Share>(&bad).is_err());
+}
+
+#[cfg(feature = "serde")]
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(feature = "pool")]
+#[test]
+fn pool_frames_carry_expiry() {
+    use crankx::pool::Message;
+
+    for expires_at in [None, Some(1_700_000_000)] {
+        let job = Job { expires_at, ..job() };
+        let decoded = Message::decode(&Message::Job(job).encode()).unwrap();
+        assert!(matches!(decoded, Message::Job(j) if j == job));
+    }
+}
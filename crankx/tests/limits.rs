@@ -0,0 +1,168 @@
+use std::thread;
+
+use crankx::batch::{verify_batch, verify_batch_limited, BatchItem, SolutionBatch};
+use crankx::equix::SolverMemory;
+use crankx::limits::{Rate, VerifyLimits};
+use crankx::{
+    check_challenge_size, check_segment_size, solve, solve_with_challenge, solve_with_memory,
+    verify, verify_with_challenge, CrankXError, MAX_CHALLENGE_LEN, MAX_DATA_LEN, MAX_SEED_LEN,
+    MAX_SEGMENT_SIZE, MIN_SEGMENT_SIZE,
+};
+
+#[test]
+fn oversized_segments_rejected() {
+    // Const-generic entry points reject this at compile time (see the
+    // `check_segment_size` doctest); slice-based ones at runtime
+    let data = [0u8; MAX_DATA_LEN + 1];
+    let item = BatchItem { data: &data, nonce: [0u8; 8], digest: [0u8; 16] };
+
+    assert!(matches!(
+        verify_batch([0u8; 32], &[item]),
+        Err(CrankXError::SeedTooLarge { max: MAX_SEED_LEN, got }) if got == MAX_SEED_LEN + 1
+    ));
+    assert_eq!(MAX_SEGMENT_SIZE, MAX_DATA_LEN);
+    check_segment_size::<MAX_SEGMENT_SIZE>();
+}
+
+#[test]
+fn empty_segments_rejected_and_tiny_ones_supported() {
+    let item = BatchItem { data: &[], nonce: [0u8; 8], digest: [0u8; 16] };
+    assert!(matches!(
+        verify_batch([0u8; 32], &[item]),
+        Err(CrankXError::SegmentTooSmall { min: MIN_SEGMENT_SIZE, got: 0 })
+    ));
+    check_segment_size::<MIN_SEGMENT_SIZE>();
+
+    let challenge = [0u8; 32];
+    let data = [7u8; MIN_SEGMENT_SIZE];
+    let solution = (0u64..)
+        .find_map(|n| solve(challenge, &data, n.to_le_bytes()).ok())
+        .unwrap();
+    verify(challenge, &data, solution.n, &solution.d).unwrap();
+    assert!(verify(challenge, &[8u8; MIN_SEGMENT_SIZE], solution.n, &solution.d).is_err());
+}
+
+#[test]
+fn largest_segment_accepted() {
+    let challenge = [0u8; 32];
+    let data = [1u8; MAX_DATA_LEN];
+
+    let solution = (0u64..)
+        .find_map(|n| solve(challenge, &data, n.to_le_bytes()).ok())
+        .unwrap();
+    verify(challenge, &data, solution.n, &solution.d).unwrap();
+}
+
+#[test]
+fn solver_memory_stays_off_the_stack() {
+    // SolverMemory is a handle to equix's ~1.8 MB heap buffer, so solving
+    // fits on a thread stack far smaller than the scratch space
+    assert_eq!(size_of::<SolverMemory>(), size_of::<usize>());
+
+    let solved = thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(|| {
+            let mut memory = SolverMemory::new();
+            (0u64..).find_map(|n| solve_with_memory(&mut memory, [3; 32], &[4u8; 64], n).ok())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(solved.is_some());
+}
+
+#[test]
+fn long_challenges_are_bound_as_is() {
+    let mut mem = SolverMemory::new();
+    let data = [5u8; 64];
+
+    // At 32 bytes the generic functions are the plain ones
+    let short = [9u8; 32];
+    let solution = (0u64..)
+        .find_map(|n| solve_with_challenge(&mut mem, &short, &data, n).ok())
+        .unwrap();
+    assert_eq!(solution, solve(short, &data, solution.n).unwrap());
+    verify(short, &data, solution.n, &solution.d).unwrap();
+
+    let mut long = [9u8; 48];
+    let solution = (0u64..)
+        .find_map(|n| solve_with_challenge(&mut mem, &long, &data, n).ok())
+        .unwrap();
+    verify_with_challenge(&long, &data, solution.n, &solution.d).unwrap();
+    long[47] ^= 1;
+    assert!(verify_with_challenge(&long, &data, solution.n, &solution.d).is_err());
+
+    check_challenge_size::<MAX_CHALLENGE_LEN>();
+}
+
+#[test]
+fn verify_limits_reject_before_verifying() {
+    let limits = VerifyLimits::new().max_data_len(64).max_batch(2);
+    let big = [0u8; 65];
+    let small = [0u8; 64];
+    let item = |data| BatchItem { data, nonce: [0u8; 8], digest: [0u8; 16] };
+
+    assert!(matches!(
+        verify_batch_limited([0u8; 32], &[item(&big)], &limits, "a"),
+        Err(CrankXError::DataTooLarge { max: 64, got: 65 })
+    ));
+    assert!(matches!(
+        verify_batch_limited([0u8; 32], &[item(&small); 3], &limits, "a"),
+        Err(CrankXError::BatchTooLarge { max: 2, got: 3 })
+    ));
+    // Within limits the proofs themselves are checked
+    assert!(matches!(
+        verify_batch_limited([0u8; 32], &[item(&small)], &limits, "a"),
+        Err(CrankXError::InvalidSolution | CrankXError::EquiXFailure)
+    ));
+}
+
+#[test]
+fn per_key_rate_charges_each_proof() {
+    let limits = VerifyLimits::new().per_key_rate(Rate::new(0.0, 3).unwrap());
+    limits.admit("a", 2).unwrap();
+    assert!(matches!(limits.admit("a", 2), Err(CrankXError::RateLimited)));
+    limits.admit("a", 1).unwrap();
+    assert!(matches!(limits.admit("a", 1), Err(CrankXError::RateLimited)));
+    limits.admit("b", 3).unwrap();
+
+    let refilling = VerifyLimits::new().per_key_rate(Rate::new(1e6, 1).unwrap());
+    refilling.admit("a", 1).unwrap();
+    thread::sleep(std::time::Duration::from_millis(1));
+    refilling.admit("a", 1).unwrap();
+
+    let challenge = [1u8; 32];
+    let segments = vec![vec![2u8; 64]];
+    let mut batch = SolutionBatch::new(challenge);
+    let solution = (0u64..).find_map(|n| solve(challenge, &[2u8; 64], n.to_le_bytes()).ok());
+    batch.push(0, &solution.unwrap());
+    batch.verify_limited(&segments, &limits, "c").unwrap();
+    assert!(matches!(batch.verify_limited(&segments, &limits, "b"), Err(CrankXError::RateLimited)));
+}
+
+#[test]
+fn rates_that_break_the_bucket_are_rejected() {
+    for (per_sec, burst) in [(f64::NAN, 1), (-1.0, 1), (f64::INFINITY, 1), (1.0, 0)] {
+        assert!(matches!(Rate::new(per_sec, burst), Err(CrankXError::InvalidRate)));
+    }
+    let rate = Rate::new(0.0, 1).unwrap();
+    assert_eq!((rate.per_sec(), rate.burst()), (0.0, 1));
+}
+
+#[test]
+fn flooding_fresh_keys_leaves_existing_limits_in_place() {
+    let limits = VerifyLimits::new().per_key_rate(Rate::new(0.0, 1).unwrap()).max_keys(4);
+    limits.admit("client", 1).unwrap();
+    assert!(matches!(limits.admit("client", 1), Err(CrankXError::RateLimited)));
+
+    // Three fresh keys fill the table; the rest are turned away
+    let admitted = (0..1000).filter(|i| limits.admit(&format!("flood{i}"), 1).is_ok()).count();
+    assert_eq!(admitted, 3);
+    assert!(matches!(limits.admit("client", 1), Err(CrankXError::RateLimited)));
+
+    // Refilled buckets make room again
+    let refilling = VerifyLimits::new().per_key_rate(Rate::new(1e6, 1).unwrap()).max_keys(1);
+    refilling.admit("a", 1).unwrap();
+    thread::sleep(std::time::Duration::from_millis(1));
+    refilling.admit("b", 1).unwrap();
+}
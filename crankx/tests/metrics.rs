@@ -0,0 +1,91 @@
+#![cfg(feature = "metrics")]
+
+use std::sync::Arc;
+
+use crankx::metrics::{Metrics, RECENT_ERRORS};
+use crankx::miner::Miner;
+use crankx::scheduler::{Scheduler, StalestFirst};
+
+#[test]
+fn counters_accumulate() {
+    let metrics = Metrics::default();
+    metrics.add_attempts(10);
+    metrics.add_attempts(5);
+    metrics.record_solution(7);
+    metrics.record_solution(3);
+    metrics.record_verification(true);
+    metrics.record_verification(false);
+
+    assert_eq!(metrics.attempts(), 15);
+    assert_eq!(metrics.solutions(), 2);
+    assert_eq!(metrics.best_difficulty(), 7);
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE crankx_attempts_total counter\ncrankx_attempts_total 15\n"));
+    assert!(text.contains("crankx_best_difficulty 7\n"));
+    assert!(text.contains("crankx_verify_failures_total 1\n"));
+}
+
+#[test]
+fn workers_segments_and_errors_are_reported() {
+    let metrics = Metrics::default();
+    metrics.add_worker_attempts(1, 4);
+    metrics.add_worker_attempts(1, 2);
+    metrics.set_segments("t.tape", 3, 5);
+    for i in 0..RECENT_ERRORS + 2 {
+        metrics.record_error(format!("error {i}"));
+    }
+
+    assert_eq!(metrics.worker_attempts(), [0, 6]);
+    assert_eq!(metrics.segments(), [("t.tape".to_string(), 3, 5)]);
+    assert_eq!(metrics.errors(), RECENT_ERRORS as u64 + 2);
+    let recent = metrics.recent_errors();
+    assert_eq!(recent.len(), RECENT_ERRORS);
+    assert_eq!(recent[0], "error 2");
+
+    let text = metrics.render();
+    assert!(text.contains("crankx_worker_attempts_total{worker=\"1\"} 6\n"));
+    assert!(text.contains("crankx_segments_proved{source=\"t.tape\"} 3\n"));
+    assert!(text.contains("crankx_segments_pending{source=\"t.tape\"} 5\n"));
+    assert!(text.contains(&format!("crankx_errors_total {}\n", RECENT_ERRORS + 2)));
+
+    metrics.clear_source("t.tape");
+    assert!(metrics.segments().is_empty());
+}
+
+#[test]
+fn scheduler_reports_staleness() {
+    let metrics = Arc::new(Metrics::default());
+    let mut scheduler =
+        Scheduler::new(2, StalestFirst).metrics(metrics.clone(), "a \"quoted\" tape");
+    assert!(!metrics.render().contains("crankx_segment_staleness"));
+
+    let first = scheduler.next(5).unwrap();
+    scheduler.proved(first, 5);
+    assert_eq!(metrics.staleness("a \"quoted\" tape", first), Some(0));
+    scheduler.next(9);
+    assert_eq!(metrics.staleness("a \"quoted\" tape", first), Some(4));
+    assert_eq!(metrics.staleness("a \"quoted\" tape", 1 - first), Some(10));
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE crankx_segment_staleness gauge\n"));
+    assert!(
+        text.contains("crankx_segment_staleness{source=\"a \\\"quoted\\\" tape\",segment=\"1\"}")
+    );
+
+    metrics.clear_source("a \"quoted\" tape");
+    assert_eq!(metrics.staleness("a \"quoted\" tape", first), None);
+}
+
+#[test]
+fn miner_counts_its_work() {
+    let metrics = Arc::new(Metrics::default());
+    let miner = Miner::new(2).metrics(metrics.clone());
+    let report = miner.mine([3; 32], &[7; 64], 2).unwrap();
+    let solution = report.solution.unwrap();
+
+    assert_eq!(metrics.attempts(), report.attempts);
+    assert_eq!(metrics.solutions(), 1);
+    assert_eq!(metrics.best_difficulty(), solution.difficulty());
+    assert_eq!(metrics.worker_attempts().iter().sum::<u64>(), report.attempts);
+}
@@ -0,0 +1,293 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crankx::bench::measure_with;
+use crankx::checkpoint::MinerState;
+use crankx::equix::{Runtime, RuntimeOption, SolverMemory};
+use crankx::miner::{AutoScale, MineReport, Miner, Throttle};
+use crankx::nonces::NonceStrategy;
+use crankx::{solve_with_policy, verify, Challenge, CrankXError, SelectionPolicy};
+
+const CHALLENGE: [u8; 32] = [4; 32];
+const DATA: [u8; 64] = [5; 64];
+
+#[test]
+fn threads_find_a_qualifying_solution() {
+    let miner = Miner::new(2);
+    let report = miner.mine(CHALLENGE, &DATA, 3).unwrap();
+
+    let solution = report.solution.unwrap();
+    assert!(solution.difficulty() >= 3);
+    assert!(report.attempts > 0);
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+
+    miner.stop_flag().store(true, Ordering::Relaxed);
+    let report = miner.mine(CHALLENGE, &DATA, 3).unwrap();
+    assert!(report.solution.is_none());
+    assert_eq!(report.attempts, 0);
+
+    assert!(matches!(
+        miner.mine(CHALLENGE, &[0u8; 5000], 0),
+        Err(CrankXError::SeedTooLarge { .. })
+    ));
+}
+
+#[test]
+fn rates_are_zero_without_measured_time() {
+    let report = MineReport { attempts: 10, ..MineReport::default() };
+    assert_eq!(report.attempts_per_sec(), 0.0);
+    assert_eq!(report.busy_attempts_per_sec(), 0.0);
+}
+
+#[test]
+fn batches_hand_threads_runs_of_consecutive_nonces() {
+    // Both threads have a solution in their first batch of three: thread 0
+    // tries nonces 0..3 and thread 1 tries 3..6 before either moves on
+    let solvable = |nonces: std::ops::Range<u64>| {
+        nonces.into_iter().any(|n| crankx::count_solutions(CHALLENGE, &DATA, n).unwrap_or(0) > 0)
+    };
+    assert!(solvable(0..3) && solvable(3..6));
+
+    let report = Miner::new(2).batch_size(3).mine(CHALLENGE, &DATA, 0).unwrap();
+    let solution = report.solution.unwrap();
+    assert!(u64::from_le_bytes(solution.n) < 6);
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+}
+
+#[test]
+fn reports_the_runtime_used() {
+    let miner = Miner::new(1).runtime(RuntimeOption::InterpretOnly);
+    let report = miner.mine(CHALLENGE, &DATA, 0).unwrap();
+    assert_eq!(report.runtime_used, Some(Runtime::Interpret));
+
+    let report = measure_with(32, Duration::from_millis(50), RuntimeOption::InterpretOnly);
+    assert_eq!(report.runtime_used, Some(Runtime::Interpret));
+    assert_eq!(report.segment_size, 32);
+    assert!(report.verifies_per_sec > 0.0);
+}
+
+#[test]
+fn throttle_slows_wall_clock_but_not_busy_rate() {
+    let stopper = |miner: &Miner, after| {
+        let stop = miner.stop_flag();
+        std::thread::spawn(move || {
+            std::thread::sleep(after);
+            stop.store(true, Ordering::Relaxed);
+        })
+    };
+
+    // Capped at 20 nonces/s: ~0.3s allows at most a handful
+    let miner = Miner::new(2).throttle(Throttle::MaxRate(20.0)).unwrap();
+    let handle = stopper(&miner, Duration::from_millis(300));
+    let report = miner.mine(CHALLENGE, &DATA, 256).unwrap();
+    handle.join().unwrap();
+    assert!(report.attempts <= 10, "{} attempts", report.attempts);
+
+    // At a 25% duty cycle threads are busy about a quarter of the time
+    let miner = Miner::new(1).throttle(Throttle::DutyCycle(0.25)).unwrap();
+    let handle = stopper(&miner, Duration::from_millis(400));
+    let report = miner.mine(CHALLENGE, &DATA, 256).unwrap();
+    handle.join().unwrap();
+    let busy = report.busy.as_secs_f64() / report.elapsed.as_secs_f64();
+    assert!(busy < 0.5, "busy {busy:.2}");
+    assert!(report.busy_attempts_per_sec() > report.attempts_per_sec());
+}
+
+#[test]
+fn throttles_are_checked_and_waits_interruptible() {
+    for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(Miner::new(1).throttle(Throttle::MaxRate(bad)).is_err());
+    }
+    for bad in [0.0, 1.5, f64::NAN] {
+        assert!(Miner::new(1).throttle(Throttle::DutyCycle(bad)).is_err());
+    }
+
+    // One nonce a day: the first attempt is followed by a day-long wait
+    // that shutdown cuts short
+    let miner = Miner::new(1).throttle(Throttle::MaxRate(1.0 / 86_400.0)).unwrap();
+    std::thread::scope(|s| {
+        let mining = s.spawn(|| miner.mine(CHALLENGE, &DATA, 256).unwrap());
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(miner.shutdown(Duration::from_secs(5)));
+        assert_eq!(mining.join().unwrap().attempts, 1);
+    });
+}
+
+#[test]
+fn auto_scale_parks_threads_under_load() {
+    let run = |load: fn() -> Option<f64>| {
+        let auto_scale = AutoScale {
+            min_threads: 1,
+            cpu_budget: 1.0,
+            interval: Duration::from_millis(20),
+            load,
+        };
+        let miner = Miner::new(3).auto_scale(auto_scale);
+        let stop = miner.stop_flag();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            stop.store(true, Ordering::Relaxed);
+        });
+        let report = miner.mine(CHALLENGE, &DATA, 256).unwrap();
+        handle.join().unwrap();
+        report
+    };
+
+    // A saturated machine leaves room for the minimum only
+    assert_eq!(run(|| Some(1e6)).active_threads, 1);
+    // Unknown load keeps every thread
+    assert_eq!(run(|| None).active_threads, 3);
+}
+
+#[test]
+fn solve_many_proves_each_segment() {
+    let segments: Vec<[u8; 48]> = (0..5u8).map(|i| [i; 48]).collect();
+    let miner = Miner::new(2);
+    let solutions = miner.solve_many(CHALLENGE, &segments, 2).unwrap();
+
+    assert_eq!(solutions.len(), segments.len());
+    for (segment, solution) in segments.iter().zip(&solutions) {
+        let solution = solution.as_ref().unwrap();
+        assert!(solution.difficulty() >= 2);
+        verify(CHALLENGE, segment, solution.n, &solution.d).unwrap();
+    }
+
+    assert!(matches!(
+        miner.solve_many(CHALLENGE, &[vec![0u8; 5000]], 0),
+        Err(CrankXError::SeedTooLarge { .. })
+    ));
+
+    miner.stop_flag().store(true, Ordering::Relaxed);
+    let solutions = miner.solve_many(CHALLENGE, &segments, 2).unwrap();
+    assert!(solutions.iter().all(Option::is_none));
+}
+
+#[test]
+fn update_challenge_rotates_a_running_mine() {
+    let miner = Miner::new(2);
+    let rotated = [6; 32];
+    let report = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            miner.update_challenge(rotated);
+        });
+        miner.mine(CHALLENGE, &DATA, 10).unwrap()
+    });
+
+    assert_eq!(report.challenge.0, rotated);
+    let solution = report.solution.unwrap();
+    verify(rotated, &DATA, solution.n, &solution.d).unwrap();
+    assert!(verify(CHALLENGE, &DATA, solution.n, &solution.d).is_err());
+}
+
+#[test]
+fn concurrent_mines_keep_their_own_challenge() {
+    let miner = Miner::new(1);
+    let other = Challenge([7; 32]);
+    let (first, second) = std::thread::scope(|s| {
+        let first = s.spawn(|| miner.mine(CHALLENGE, &DATA, 6).unwrap());
+        let second = s.spawn(|| miner.mine(other, &DATA, 6).unwrap());
+        (first.join().unwrap(), second.join().unwrap())
+    });
+
+    assert_eq!(first.challenge, Challenge(CHALLENGE));
+    assert_eq!(second.challenge, other);
+    let (a, b) = (first.solution.unwrap(), second.solution.unwrap());
+    verify(CHALLENGE, &DATA, a.n, &a.d).unwrap();
+    verify(other, &DATA, b.n, &b.d).unwrap();
+}
+
+#[test]
+fn pause_holds_threads_until_resumed() {
+    let miner = Miner::new(2);
+    miner.pause();
+    let report = std::thread::scope(|s| {
+        let mining = s.spawn(|| miner.mine(CHALLENGE, &DATA, 3).unwrap());
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!mining.is_finished());
+        miner.resume();
+        mining.join().unwrap()
+    });
+    assert!(!miner.is_paused());
+    let solution = report.solution.unwrap();
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+}
+
+#[test]
+fn shutdown_wakes_paused_threads() {
+    let miner = Miner::new(2);
+    assert!(miner.shutdown(Duration::ZERO));
+    miner.stop_flag().store(false, Ordering::Relaxed);
+
+    miner.pause();
+    std::thread::scope(|s| {
+        let mining = s.spawn(|| miner.mine(CHALLENGE, &DATA, 30).unwrap());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(miner.shutdown(Duration::from_secs(5)));
+        let report = mining.join().unwrap();
+        assert!(report.solution.is_none());
+        assert_eq!(report.attempts, 0);
+    });
+}
+
+#[test]
+fn mines_segments_from_a_provider() {
+    let segments = vec![[1u8; 64], DATA];
+    let miner = Miner::new(2);
+    let report = miner.mine_segment(CHALLENGE, &segments, 1, 3).unwrap();
+    let solution = report.solution.unwrap();
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+
+    let missing = miner.mine_segment(CHALLENGE, &segments, 2, 3).unwrap_err();
+    assert!(matches!(missing, CrankXError::SegmentOutOfRange { index: 2, count: 2 }));
+}
+
+#[test]
+fn stopped_mine_resumes_from_its_cursor() {
+    // Stop partway through an impossible search
+    let miner = Miner::new(1);
+    let mut state = MinerState::new(CHALLENGE, 1);
+    let stop = miner.stop_flag();
+    let stopper = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        stop.store(true, Ordering::Relaxed);
+    });
+    let first = miner.mine_from(&mut state, &DATA, 256).unwrap();
+    stopper.join().unwrap();
+    assert!(first.solution.is_none());
+    assert!(first.attempts > 0);
+    assert_eq!(state.cursors, [first.attempts]);
+
+    // Through a restart, and on to the first qualifying nonce past the cursor
+    let mut saved = Vec::new();
+    state.save(&mut saved).unwrap();
+    let mut state = MinerState::restore(&mut saved.as_slice()).unwrap();
+    miner.stop_flag().store(false, Ordering::Relaxed);
+    let second = miner.mine_from(&mut state, &DATA, 2).unwrap();
+    let solution = second.solution.unwrap();
+
+    let mut memory = SolverMemory::new();
+    let mut qualifies = |n: u64| {
+        let policy = SelectionPolicy::HighestDifficulty;
+        solve_with_policy(&mut memory, CHALLENGE, &DATA, n, policy)
+            .is_ok_and(|s| s.difficulty() >= 2)
+    };
+    let expected = (first.attempts..).find(|&n| qualifies(n)).unwrap();
+    assert_eq!(u64::from_le_bytes(solution.n), expected);
+    assert_eq!(second.attempts, expected - first.attempts + 1);
+    assert_eq!(state.cursors, [expected + 1]);
+    assert_eq!(state.best.as_ref().unwrap().n, solution.n);
+
+    // Cursors are per thread, and count positions in one nonce strategy;
+    // a random one starts somewhere new every time
+    assert!(matches!(
+        Miner::new(2).mine_from(&mut state, &DATA, 2),
+        Err(CrankXError::CheckpointMismatch { threads: 2, cursors: 1, .. })
+    ));
+    let random = Miner::new(1).nonce_strategy(NonceStrategy::random());
+    assert!(matches!(
+        random.mine_from(&mut state, &DATA, 2),
+        Err(CrankXError::CheckpointMismatch { saved_strategy: NonceStrategy::Sequential, .. })
+    ));
+    assert!(random.mine_from(&mut random.state(CHALLENGE), &DATA, 2).is_ok());
+}
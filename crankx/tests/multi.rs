@@ -0,0 +1,30 @@
+use crankx::{solve_k, verify_k, CrankXError, Solution};
+
+const CHALLENGE: [u8; 32] = [8; 32];
+const DATA: [u8; 64] = [9; 64];
+
+#[test]
+fn k_proofs_round_trip() {
+    let solutions = solve_k(CHALLENGE, &DATA, 3, 1).unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert!(solutions.iter().all(|s| s.difficulty() >= 1));
+    verify_k(CHALLENGE, &DATA, &solutions, 3, 1).unwrap();
+
+    assert!(matches!(
+        verify_k(CHALLENGE, &DATA, &solutions[..2], 3, 1),
+        Err(CrankXError::ProofCount { expected: 3, got: 2 })
+    ));
+
+    let bytes = solutions[0].to_bytes();
+    let replayed = [Solution::from_bytes(&bytes), Solution::from_bytes(&bytes)];
+    assert!(matches!(
+        verify_k(CHALLENGE, &DATA, &replayed, 2, 0),
+        Err(CrankXError::DuplicateNonce)
+    ));
+
+    let hardest = solutions.iter().map(|s| s.difficulty()).max().unwrap();
+    assert!(matches!(
+        verify_k(CHALLENGE, &DATA, &solutions, 3, hardest + 1),
+        Err(CrankXError::InsufficientDifficulty { .. })
+    ));
+}
@@ -0,0 +1,100 @@
+use crankx::nonces::{partition_nonces, solve_range, strided_nonces, NonceStrategy, Nonces};
+use crankx::{solve, verify, SelectionPolicy};
+
+const CHALLENGE: [u8; 32] = [3; 32];
+const DATA: [u8; 64] = [4; 64];
+
+#[test]
+fn yields_qualifying_solutions_in_nonce_order() {
+    let found: Vec<_> = Nonces::new(CHALLENGE, &DATA).filter_difficulty(2).take(3).collect();
+    assert_eq!(found.len(), 3);
+    assert!(found.windows(2).all(|w| w[0].0.to_u64() < w[1].0.to_u64()));
+
+    for (nonce, solution) in &found {
+        assert!(solution.difficulty() >= 2);
+        assert_eq!(solution.d, solve(CHALLENGE, &DATA, *nonce).unwrap().d);
+        verify(CHALLENGE, &DATA, *nonce, &solution.d).unwrap();
+    }
+
+    let (first, _) = Nonces::new(CHALLENGE, &DATA).next().unwrap();
+    let (later, _) = Nonces::new(CHALLENGE, &DATA).starting_at(first.to_u64() + 1).next().unwrap();
+    assert!(later.to_u64() > first.to_u64());
+
+    let (_, best) = Nonces::new(CHALLENGE, &DATA)
+        .starting_at(first)
+        .policy(SelectionPolicy::HighestDifficulty)
+        .next()
+        .unwrap();
+    verify(CHALLENGE, &DATA, first, &best.d).unwrap();
+
+    assert!(Nonces::new(CHALLENGE, &DATA).starting_at(u64::MAX).nth(1).is_none());
+}
+
+#[test]
+fn partitions_cover_the_nonce_space() {
+    assert_eq!(partition_nonces(0, 1), 0..=u64::MAX);
+
+    for count in [2u64, 3, 7, 1000] {
+        let slices: Vec<_> = (0..count).map(|i| partition_nonces(i, count)).collect();
+        assert_eq!(*slices[0].start(), 0);
+        assert_eq!(*slices.last().unwrap().end(), u64::MAX);
+        for pair in slices.windows(2) {
+            assert_eq!(pair[0].end() + 1, *pair[1].start());
+        }
+
+        let sizes: Vec<_> = slices.iter().map(|r| r.end() - r.start()).collect();
+        assert!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1);
+    }
+
+    let strided: Vec<_> = strided_nonces(2, 5).take(3).collect();
+    assert_eq!(strided, [2, 7, 12]);
+    assert_eq!(strided_nonces(0, 1).nth(5), Some(5));
+}
+
+#[test]
+#[should_panic]
+fn partition_rejects_out_of_range_worker() {
+    partition_nonces(3, 3);
+}
+
+#[test]
+fn strategies_offset_the_search() {
+    let seq: Vec<_> = NonceStrategy::Sequential.walk(1, 3).take(3).collect();
+    assert_eq!(seq, strided_nonces(1, 3).take(3).collect::<Vec<_>>());
+
+    let random = NonceStrategy::Random { start: u64::MAX - 1 };
+    assert_eq!(random.walk(0, 2).take(3).collect::<Vec<_>>(), [u64::MAX - 1, 0, 2]);
+
+    let strided = NonceStrategy::Strided { index: 2, count: 5 };
+    assert_eq!(strided.walk(0, 1).take(3).collect::<Vec<_>>(), [2, 7, 12]);
+    assert_eq!(strided.nonce(u64::MAX / 5 + 1), None);
+
+    // Same identity, same offset; different identities land apart
+    let a = NonceStrategy::for_identity(&[1; 32]);
+    assert_eq!(a, NonceStrategy::for_identity(&[1; 32]));
+    assert_ne!(a, NonceStrategy::for_identity(&[2; 32]));
+    assert!(matches!(NonceStrategy::random(), NonceStrategy::Random { .. }));
+
+    let (nonce, solution) = solve_range(CHALLENGE, &DATA, a, 1_000, 1).unwrap();
+    let NonceStrategy::Identity { start } = a else { unreachable!() };
+    assert!(nonce.to_u64().wrapping_sub(start) < 1_000);
+    verify(CHALLENGE, &DATA, nonce, &solution.d).unwrap();
+    assert!(solve_range(CHALLENGE, &DATA, a, 0, 0).is_none());
+}
+
+#[test]
+fn miner_records_its_strategy() {
+    use crankx::miner::Miner;
+
+    let strategy = NonceStrategy::Random { start: 1 << 40 };
+    let report = Miner::new(2)
+        .nonce_strategy(strategy)
+        .collect_stats(true)
+        .mine(CHALLENGE, &DATA, 2)
+        .unwrap();
+    let solution = report.solution.unwrap();
+    assert!(u64::from_le_bytes(solution.n) >= 1 << 40);
+    verify(CHALLENGE, &DATA, solution.n, &solution.d).unwrap();
+    assert_eq!(report.nonce_strategy, strategy);
+    assert_eq!(report.stats.unwrap().nonce_strategy, strategy);
+}
@@ -0,0 +1,60 @@
+use crankx::pipeline::{self, bounded, PipelineConfig, PipelineError, Proof};
+use crankx::verify;
+
+const CHALLENGE: [u8; 32] = [6; 32];
+
+#[test]
+fn run_proves_every_segment() {
+    let segments: Vec<[u8; 32]> = (0..6u8).map(|i| [i; 32]).collect();
+    let config = PipelineConfig { solvers: 2, capacity: 1 };
+
+    let mut proofs = Vec::new();
+    let submitted = pipeline::run(&segments, 0..6, CHALLENGE, 2, config, |proof: Proof| {
+        proofs.push(proof);
+        Ok::<_, ()>(())
+    })
+    .unwrap();
+
+    assert_eq!(submitted, 6);
+    proofs.sort_by_key(|p| p.index);
+    for (i, proof) in proofs.iter().enumerate() {
+        assert_eq!(proof.index, i as u64);
+        assert!(proof.solution.difficulty() >= 2);
+        verify(CHALLENGE, &segments[i], proof.solution.n, &proof.solution.d).unwrap();
+    }
+}
+
+#[test]
+fn failing_stages_end_the_run() {
+    let segments: Vec<[u8; 32]> = (0..20u8).map(|i| [i; 32]).collect();
+    let config = PipelineConfig { solvers: 2, capacity: 1 };
+
+    // The submitter gives up after two proofs; the rest of the run unwinds
+    let mut budget = 2;
+    let result = pipeline::run(&segments, 0..20, CHALLENGE, 0, config, |_| {
+        budget -= 1;
+        if budget < 0 {
+            return Err("rpc down");
+        }
+        Ok(())
+    });
+    assert!(matches!(result, Err(PipelineError::Submit("rpc down"))));
+
+    // Segment 20 doesn't exist
+    let result = pipeline::run(&segments, 18..22, CHALLENGE, 0, config, |_| Ok::<_, ()>(()));
+    assert!(matches!(result, Err(PipelineError::Read(_))));
+}
+
+#[test]
+fn bounded_channels_apply_backpressure() {
+    let (tx, rx) = bounded::<u32>(2);
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+    assert!(tx.try_send(3).is_err());
+
+    let other = rx.clone();
+    assert_eq!(other.recv(), Ok(1));
+    drop(tx);
+    assert_eq!(rx.recv(), Ok(2));
+    assert!(rx.recv().is_err());
+}
@@ -0,0 +1,64 @@
+use crankx::equix::{self, SolverMemory};
+use crankx::{count_solutions, solve, solve_with_memory, solve_with_policy, verify, SelectionPolicy};
+
+const CHALLENGE: [u8; 32] = [4; 32];
+const DATA: [u8; 32] = [5; 32];
+
+/// A nonce whose seed has more than one EquiX solution
+fn multi_solution_nonce() -> [u8; 8] {
+    (0u64..)
+        .map(u64::to_le_bytes)
+        .find(|n| {
+            let seed = [&CHALLENGE[..], &DATA[..], &n[..]].concat();
+            equix::solve(&seed).map(|s| s.len() > 1).unwrap_or(false)
+        })
+        .unwrap()
+}
+
+#[test]
+fn policies_pick_valid_solutions() {
+    let nonce = multi_solution_nonce();
+    let mut memory = SolverMemory::new();
+
+    let first = solve_with_memory(&mut memory, CHALLENGE, &DATA, nonce).unwrap();
+    let canonical =
+        solve_with_policy(&mut memory, CHALLENGE, &DATA, nonce, SelectionPolicy::default())
+            .unwrap();
+    assert_eq!(first.d, canonical.d);
+
+    let lowest =
+        solve_with_policy(&mut memory, CHALLENGE, &DATA, nonce, SelectionPolicy::LowestDigest)
+            .unwrap();
+    let best = solve_with_policy(
+        &mut memory,
+        CHALLENGE,
+        &DATA,
+        nonce,
+        SelectionPolicy::HighestDifficulty,
+    )
+    .unwrap();
+
+    assert!(lowest.d <= first.d);
+    assert!(best.difficulty() >= first.difficulty());
+    assert!(best.difficulty() >= lowest.difficulty());
+
+    for s in [&first, &lowest, &best] {
+        verify(CHALLENGE, &DATA, nonce, &s.d).unwrap();
+    }
+}
+
+#[test]
+fn counts_candidates_per_seed() {
+    let nonce = multi_solution_nonce();
+    assert!(count_solutions(CHALLENGE, &DATA, nonce).unwrap() > 1);
+
+    for n in 0u64..8 {
+        match count_solutions(CHALLENGE, &DATA, n) {
+            Ok(0) | Err(_) => assert!(solve(CHALLENGE, &DATA, n).is_err()),
+            Ok(count) => {
+                assert!(count <= 8);
+                assert!(solve(CHALLENGE, &DATA, n).is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,375 @@
+#![cfg(feature = "pool")]
+
+use std::thread;
+use std::time::Duration;
+
+use crankx::pool::{Coordinator, Message, Worker};
+use crankx::{verify, Challenge};
+
+#[test]
+fn worker_submits_shares() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let segments = vec![[3u8; 64], [4u8; 64]];
+
+    // A client that never says hello doesn't keep the worker out
+    let _silent = std::net::TcpStream::connect(coordinator.local_addr().unwrap()).unwrap();
+    let worker = Worker::connect(coordinator.local_addr().unwrap(), 7, segments.clone()).unwrap();
+    thread::spawn(move || worker.run());
+
+    while coordinator.worker_count() == 0 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // A segment the worker doesn't have skips that job, not the worker
+    let challenge = Challenge([5; 32]);
+    assert_eq!(coordinator.dispatch(0, challenge, 9, 2), 1);
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(coordinator.dispatch(1, challenge, 1, 2), 1);
+
+    let share = coordinator.shares().recv_timeout(Duration::from_secs(60)).unwrap();
+    assert_eq!(share.job_id, 1);
+    assert_eq!(share.worker_id, 7);
+    assert!(share.solution.difficulty() >= 2);
+    verify(challenge, &segments[1], share.solution.n, &share.solution.d).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn local_workers_split_one_job_over_a_unix_socket() {
+    use std::collections::HashMap;
+    use std::time::Instant;
+
+    let path = std::env::temp_dir().join(format!("crankx-pool-{}.sock", std::process::id()));
+    let coordinator = Coordinator::bind_unix(&path).unwrap();
+    assert!(coordinator.local_addr().is_none());
+    // A live coordinator keeps its socket
+    assert!(Coordinator::bind_unix(&path).is_err());
+
+    let segments = vec![[3u8; 64]];
+    for id in [1, 2] {
+        let worker = Worker::connect_unix(&path, id, segments.clone()).unwrap();
+        thread::spawn(move || worker.run());
+    }
+    while coordinator.worker_count() < 2 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let challenge = Challenge([5; 32]);
+    assert_eq!(coordinator.dispatch(1, challenge, 0, 0), 2);
+
+    // Both workers report to the one receiver, each from its own half
+    let mut halves = HashMap::new();
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while halves.len() < 2 && Instant::now() < deadline {
+        let share = coordinator.shares().recv_timeout(Duration::from_secs(60)).unwrap();
+        verify(challenge, &segments[0], share.solution.n, &share.solution.d).unwrap();
+        let half = u64::from_le_bytes(share.solution.n) >> 63;
+        assert_eq!(*halves.entry(share.worker_id).or_insert(half), half);
+    }
+    assert_eq!(halves.len(), 2);
+    assert_ne!(halves[&1], halves[&2]);
+
+    let _ = std::fs::remove_file(&path);
+
+    // An ordinary file at the socket path is left alone
+    std::fs::write(&path, "not a socket").unwrap();
+    let err = Coordinator::bind_unix(&path).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn frames_round_trip() {
+    let msg = Message::Hello { worker_id: 42 };
+    let mut buf = Vec::new();
+    crankx::pool::write_message(&mut buf, &msg).unwrap();
+
+    let decoded = crankx::pool::read_message(&mut buf.as_slice()).unwrap();
+    assert!(matches!(decoded, Message::Hello { worker_id: 42 }));
+
+    let oversized = (u32::MAX).to_le_bytes();
+    assert!(crankx::pool::read_message(&mut oversized.as_slice()).is_err());
+
+    // An expiry of zero is still an expiry
+    for expires_at in [None, Some(0), Some(1_700_000_000)] {
+        let job = crankx::pool::Job {
+            id: 1,
+            challenge: Challenge([5; 32]),
+            segment: 0,
+            target: 0,
+            nonce_start: 0,
+            nonce_end: u64::MAX,
+            expires_at,
+        };
+        let mut buf = Vec::new();
+        crankx::pool::write_message(&mut buf, &Message::Job(job)).unwrap();
+        let Message::Job(decoded) = crankx::pool::read_message(&mut buf.as_slice()).unwrap() else {
+            panic!("not a job");
+        };
+        assert_eq!(decoded, job);
+    }
+
+    // A job with nothing to search is refused
+    let empty = crankx::pool::Job {
+        id: 1,
+        challenge: Challenge([5; 32]),
+        segment: 0,
+        target: 0,
+        nonce_start: 2,
+        nonce_end: 1,
+        expires_at: None,
+    };
+    let mut buf = Vec::new();
+    crankx::pool::write_message(&mut buf, &Message::Job(empty)).unwrap();
+    let err = crankx::pool::read_message(&mut buf.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // So is any expiry but a set one or a zeroed absent one
+    let job = crankx::pool::Job { nonce_start: 0, ..empty };
+    let mut canonical = Vec::new();
+    crankx::pool::write_message(&mut canonical, &Message::Job(job)).unwrap();
+    let flag = canonical.len() - 9;
+    for (at, byte) in [(flag, 2), (flag + 1, 1), (canonical.len() - 1, 0x80)] {
+        let mut buf = canonical.clone();
+        buf[at] = byte;
+        assert!(crankx::pool::read_message(&mut buf.as_slice()).is_err());
+    }
+}
+
+#[test]
+fn shares_count_for_the_worker_that_said_hello() {
+    use crankx::pool::{write_message, Share};
+    use crankx::Solution;
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let mut stream = std::net::TcpStream::connect(coordinator.local_addr().unwrap()).unwrap();
+    write_message(&mut stream, &Message::Hello { worker_id: 1 }).unwrap();
+
+    let solution = || Solution::from_bytes(&[0; 24]);
+    let share = |worker_id| Share { job_id: 1, worker_id, solution: solution() };
+    write_message(&mut stream, &Message::Share(share(2))).unwrap();
+    write_message(&mut stream, &Message::Share(share(1))).unwrap();
+
+    let received = coordinator.shares().recv_timeout(Duration::from_secs(60)).unwrap();
+    assert_eq!(received.worker_id, 1);
+    assert!(coordinator.shares().recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn a_stalled_worker_does_not_hold_up_dispatch() {
+    use crankx::pool::{read_message, write_message};
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let addr = coordinator.local_addr().unwrap();
+    let mut clients: Vec<_> = (0..2)
+        .map(|id| {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            write_message(&mut stream, &Message::Hello { worker_id: id }).unwrap();
+            stream
+        })
+        .collect();
+    while coordinator.worker_count() < 2 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Worker 0 never reads; far more jobs than its socket buffers can hold
+    // still go out to worker 1
+    let last = 200_000;
+    for id in 1..=last {
+        assert_eq!(coordinator.dispatch(id, Challenge([5; 32]), 0, 0), 2);
+    }
+    let live = &mut clients[1];
+    loop {
+        match read_message(live).unwrap() {
+            Message::Job(job) if job.id == last => break,
+            Message::Job(_) => {}
+            other => panic!("expected a job, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn dispatch_covers_the_whole_nonce_space() {
+    use crankx::pool::{read_message, write_message};
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let addr = coordinator.local_addr().unwrap();
+    let mut clients: Vec<_> = (0..3)
+        .map(|id| {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            write_message(&mut stream, &Message::Hello { worker_id: id }).unwrap();
+            stream
+        })
+        .collect();
+    while coordinator.worker_count() < 3 {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(coordinator.dispatch(1, Challenge([5; 32]), 0, 0), 3);
+
+    let mut ranges: Vec<_> = clients
+        .iter_mut()
+        .map(|stream| match read_message(stream).unwrap() {
+            Message::Job(job) => job.nonces(),
+            other => panic!("expected a job, got {other:?}"),
+        })
+        .collect();
+    ranges.sort_by_key(|r| *r.start());
+    assert_eq!(*ranges[0].start(), 0);
+    assert_eq!(*ranges[2].end(), u64::MAX);
+    for pair in ranges.windows(2) {
+        assert_eq!(*pair[0].end() + 1, *pair[1].start());
+    }
+}
+
+#[test]
+fn dispatched_jobs_expire_after_the_ttl() {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use crankx::pool::{read_message, write_message};
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().job_ttl(Duration::from_secs(3600));
+    let mut stream = std::net::TcpStream::connect(coordinator.local_addr().unwrap()).unwrap();
+    write_message(&mut stream, &Message::Hello { worker_id: 1 }).unwrap();
+    while coordinator.worker_count() == 0 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    assert_eq!(coordinator.dispatch(1, Challenge([5; 32]), 0, 0), 1);
+    let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let Message::Job(job) = read_message(&mut stream).unwrap() else {
+        panic!("expected a job");
+    };
+    let expires_at = job.expires_at.unwrap();
+    assert!((before + 3600..=after + 3600).contains(&expires_at));
+    assert!(!job.is_expired(after) && job.is_expired(after + 3601));
+}
+
+#[test]
+fn shares_are_classified_and_credited() {
+    use crankx::pool::shares::{
+        validate_new_share, validate_share, Rejection, SeenShares, ShareClass, WorkLedger,
+    };
+    use crankx::pool::{Job, Share};
+    use crankx::{solve, Solution, Target};
+
+    let challenge = Challenge([6; 32]);
+    let data = [8u8; 64];
+    let job = Job {
+        id: 3,
+        challenge,
+        segment: 0,
+        target: 0,
+        nonce_start: 0,
+        nonce_end: 1 << 20,
+        expires_at: None,
+    };
+    let solution = (0u64..).find_map(|n| solve(challenge, &data, n).ok()).unwrap();
+    let d = solution.difficulty();
+
+    let share = |job_id, solution: &Solution| Share {
+        job_id,
+        worker_id: 1,
+        solution: Solution::from_bytes(&solution.to_bytes()),
+    };
+    let (easy, at, hard) = (Target::from(0), Target::from(d), Target::from(d + 1));
+
+    let good = share(3, &solution);
+    assert_eq!(validate_share(&job, &good, &data, &at, &hard), ShareClass::Share);
+    assert_eq!(validate_share(&job, &good, &data, &easy, &at), ShareClass::Block);
+
+    let invalid = |share: &Share, data: &[u8], target| {
+        match validate_share(&job, share, data, target, &hard) {
+            ShareClass::Invalid(reason) => reason,
+            other => panic!("accepted: {other:?}"),
+        }
+    };
+    assert_eq!(invalid(&share(4, &solution), &data, &easy), Rejection::WrongJob);
+    assert_eq!(invalid(&good, &data, &hard), Rejection::BelowTarget);
+    assert_eq!(invalid(&good, &[9u8; 64], &easy), Rejection::BadProof);
+    let late = Job { nonce_start: 1 << 20, nonce_end: u64::MAX, ..job };
+    assert!(matches!(
+        validate_share(&late, &good, &data, &easy, &hard),
+        ShareClass::Invalid(Rejection::NonceOutOfRange)
+    ));
+
+    // Shares at a harder target earn proportionally more work
+    let mut ledger = WorkLedger::new();
+    ledger.record(1, ShareClass::Share, &Target::from(2));
+    ledger.record(2, ShareClass::Block, &Target::from(4));
+    ledger.record(2, ShareClass::Invalid(Rejection::BadProof), &Target::from(4));
+    assert_eq!(ledger.worker(1).unwrap().work, 4.0);
+    assert_eq!(ledger.worker(2).unwrap().blocks, 1);
+    assert_eq!(ledger.worker(2).unwrap().invalid, 1);
+    assert_eq!(ledger.work_share(2), 0.8);
+
+    assert_eq!(ledger.drain().len(), 2);
+    assert_eq!(ledger.total_work(), 0.0);
+
+    // A share resubmitted to the same job is refused and earns nothing more
+    let mut seen = SeenShares::new(16, 1024);
+    for _ in 0..3 {
+        let class = validate_new_share(&job, &good, &data, &at, &hard, &mut seen);
+        ledger.record(1, class, &at);
+    }
+    assert_eq!(
+        validate_new_share(&job, &good, &data, &at, &hard, &mut seen),
+        ShareClass::Invalid(Rejection::Duplicate)
+    );
+    let credited = ledger.worker(1).unwrap();
+    assert_eq!((credited.shares, credited.invalid), (1, 2));
+    assert_eq!(credited.work, at.expected_attempts());
+
+    // Forgetting the job starts it over
+    seen.forget(3);
+    let class = validate_new_share(&job, &good, &data, &at, &hard, &mut seen);
+    assert_eq!(class, ShareClass::Share);
+}
+
+#[test]
+fn disconnected_workers_give_up_their_slice() {
+    use crankx::pool::{read_message, write_message};
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let addr = coordinator.local_addr().unwrap();
+    let connect = |id| {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        write_message(&mut stream, &Message::Hello { worker_id: id }).unwrap();
+        stream
+    };
+    let mut live = connect(1);
+    let gone = connect(2);
+    while coordinator.worker_count() < 2 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Without ever being sent a job, the closed connection is reaped and
+    // the survivor gets the whole nonce space
+    drop(gone);
+    while coordinator.worker_count() > 1 {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(coordinator.dispatch(1, Challenge([5; 32]), 0, 0), 1);
+    let Message::Job(job) = read_message(&mut live).unwrap() else {
+        panic!("expected a job");
+    };
+    assert_eq!(job.nonces(), 0..=u64::MAX);
+}
+
+#[test]
+fn shutdown_closes_workers_and_stops_accepting() {
+    use crankx::pool::{read_message, write_message};
+
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+    let addr = coordinator.local_addr().unwrap();
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write_message(&mut stream, &Message::Hello { worker_id: 1 }).unwrap();
+    while coordinator.worker_count() == 0 {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    coordinator.shutdown();
+    assert!(read_message(&mut stream).is_err());
+    assert!(std::net::TcpStream::connect(addr).is_err());
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "proto")]
+
+use crankx::batch::SolutionBatch;
+use crankx::encoding::compact::CompactProof;
+use crankx::encoding::proto::{pb, Proto, SCHEMA};
+use crankx::job::{Job, Share};
+use crankx::{Challenge, CrankXError, Solution};
+
+fn job() -> Job {
+    Job {
+        id: 3,
+        challenge: Challenge([4; 32]),
+        segment: 5,
+        target: 6,
+        nonce_start: 7,
+        nonce_end: 1 << 40,
+        expires_at: Some(0),
+    }
+}
+
+fn share() -> Share {
+    Share { job_id: 1, worker_id: 300, solution: Solution::new([9; 16], [8; 8]) }
+}
+
+fn batch() -> SolutionBatch {
+    let mut batch = SolutionBatch::new([2; 32]);
+    batch.push(0, &Solution::new([1; 16], [2; 8])).push(u64::MAX, &Solution::new([3; 16], [4; 8]));
+    batch
+}
+
+/// Field numbers of `message` as declared in the schema
+fn schema_fields(message: &str) -> Vec<u64> {
+    let body = SCHEMA.split(&format!("message {message} {{")).nth(1).unwrap();
+    let body = &body[..body.find('}').unwrap()];
+    body.lines()
+        .filter_map(|l| l.split_once('=')?.1.split(';').next()?.trim().parse().ok())
+        .collect()
+}
+
+fn varint(bytes: &mut &[u8]) -> u64 {
+    let len = bytes.iter().position(|b| b & 0x80 == 0).unwrap() + 1;
+    let value = bytes[..len].iter().rev().fold(0u64, |v, b| v << 7 | (b & 0x7f) as u64);
+    *bytes = &bytes[len..];
+    value
+}
+
+/// Field numbers present in an encoding, repeats collapsed
+fn encoded_fields(mut bytes: &[u8]) -> Vec<u64> {
+    let mut fields = Vec::new();
+    while !bytes.is_empty() {
+        let tag = varint(&mut bytes);
+        if tag & 7 == 2 {
+            let len = varint(&mut bytes) as usize;
+            bytes = &bytes[len..];
+        } else {
+            varint(&mut bytes);
+        }
+        if fields.last() != Some(&(tag >> 3)) {
+            fields.push(tag >> 3);
+        }
+    }
+    fields
+}
+
+#[test]
+fn encoders_follow_the_schema() {
+    let solution = Solution::new([1; 16], [1; 8]);
+    assert_eq!(encoded_fields(&solution.to_proto()), schema_fields("Solution"));
+    assert_eq!(encoded_fields(&job().to_proto()), schema_fields("Job"));
+    assert_eq!(encoded_fields(&share().to_proto()), schema_fields("Share"));
+    assert_eq!(encoded_fields(&batch().proofs[1].to_proto()), schema_fields("Proof"));
+    assert_eq!(encoded_fields(&batch().to_proto()), schema_fields("Batch"));
+}
+
+#[test]
+fn messages_round_trip() {
+    assert_eq!(Job::decode_proto(&job().to_proto()).unwrap(), job());
+    let unset = Job { expires_at: None, ..job() };
+    assert_eq!(Job::decode_proto(&unset.to_proto()).unwrap(), unset);
+    assert_eq!(Share::decode_proto(&share().to_proto()).unwrap(), share());
+    assert_eq!(SolutionBatch::decode_proto(&batch().to_proto()).unwrap(), batch());
+
+    // Share { job_id: 1, worker_id: 300, solution } as protoc encodes it
+    let mut expected = vec![0x08, 0x01, 0x10, 0xac, 0x02, 0x1a, 28, 0x0a, 16];
+    expected.extend([9; 16]);
+    expected.extend([0x12, 8]);
+    expected.extend([8; 8]);
+    assert_eq!(share().to_proto(), expected);
+}
+
+#[test]
+fn decoding_is_lenient_about_order_and_unknowns_but_not_lengths() {
+    // Unknown varint, fixed64, fixed32 and bytes fields, then the known ones
+    // in reverse order
+    let mut bytes = vec![0x48, 0x01, 0x51, 0, 0, 0, 0, 0, 0, 0, 0, 0x5d, 0, 0, 0, 0, 0x62, 1, 0];
+    bytes.extend([0x12, 8]);
+    bytes.extend([8; 8]);
+    bytes.extend([0x0a, 16]);
+    bytes.extend([9; 16]);
+    assert_eq!(Solution::decode_proto(&bytes).unwrap(), Solution::new([9; 16], [8; 8]));
+
+    assert!(matches!(Solution::decode_proto(&[0x0a, 1, 0]), Err(CrankXError::InvalidLength)));
+    assert!(matches!(Solution::decode_proto(&[0x08, 1]), Err(CrankXError::InvalidEncoding)));
+    assert!(matches!(Share::decode_proto(&[0x08, 1]), Err(CrankXError::InvalidEncoding)));
+    let truncated = &job().to_proto()[..10];
+    assert!(matches!(Job::decode_proto(truncated), Err(CrankXError::InvalidEncoding)));
+}
+
+#[test]
+fn decoding_accepts_any_varint_length() {
+    // job_id 1 padded to three bytes, worker_id 300 to ten, as protobuf allows
+    let mut bytes = vec![0x08, 0x81, 0x80, 0x00, 0x10, 0xac, 0x82];
+    bytes.extend([0x80; 7]);
+    bytes.push(0x00);
+    bytes.extend(&share().to_proto()[5..]);
+    assert_eq!(Share::decode_proto(&bytes).unwrap(), share());
+}
+
+#[test]
+fn generated_types_convert_both_ways() {
+    let message = pb::Job::from(&job());
+    assert_eq!(message.expires_at, Some(0));
+    assert_eq!(Job::try_from(message).unwrap(), job());
+    let backwards = pb::Job { nonce_start: 2, nonce_end: 1, ..pb::Job::from(&job()) };
+    assert!(matches!(Job::try_from(backwards), Err(CrankXError::InvalidNonceRange { .. })));
+
+    // Unset bytes fields are zeros; a missing solution is an error
+    let empty = pb::Share { job_id: 1, worker_id: 2, solution: Some(pb::Solution::default()) };
+    assert_eq!(Share::try_from(empty).unwrap().solution, Solution::new([0; 16], [0; 8]));
+    let missing = pb::Share { solution: None, ..Default::default() };
+    assert!(matches!(Share::try_from(missing), Err(CrankXError::InvalidEncoding)));
+    let short = pb::Proof { segment: 0, nonce: vec![1; 7], digest: vec![] };
+    assert!(matches!(CompactProof::try_from(short), Err(CrankXError::InvalidLength)));
+}
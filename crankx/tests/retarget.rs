@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crankx::retarget::{adjust, Retarget, RetargetParams};
+
+const PARAMS: RetargetParams = RetargetParams {
+    target_interval: Duration::from_secs(60),
+    window: 4,
+    max_step: 3,
+    min_difficulty: 2,
+    max_difficulty: 40,
+};
+
+#[test]
+fn adjusts_by_rounded_doublings() {
+    let secs = Duration::from_secs;
+    assert_eq!(adjust(10, secs(60), &PARAMS), 10);
+    assert_eq!(adjust(10, secs(30), &PARAMS), 11);
+    assert_eq!(adjust(10, secs(120), &PARAMS), 9);
+
+    // 60/45 is under sqrt(2), so it rounds to no change
+    assert_eq!(adjust(10, secs(45), &PARAMS), 10);
+    assert_eq!(adjust(10, secs(40), &PARAMS), 11);
+
+    // Capped per adjustment and clamped to the bounds
+    assert_eq!(adjust(10, Duration::ZERO, &PARAMS), 13);
+    assert_eq!(adjust(10, Duration::MAX, &PARAMS), 7);
+    assert_eq!(adjust(39, secs(1), &PARAMS), 40);
+    assert_eq!(adjust(3, secs(3600), &PARAMS), 2);
+}
+
+#[test]
+fn retargets_once_per_window() {
+    let mut retarget = Retarget::new(PARAMS, 10);
+    for _ in 0..3 {
+        assert_eq!(retarget.record(Duration::from_secs(15)), 10);
+    }
+    assert_eq!(retarget.record(Duration::from_secs(15)), 12);
+    assert_eq!(retarget.difficulty(), 12);
+}
@@ -0,0 +1,65 @@
+use crankx::sampled::{
+    sample_offsets, solve_sampled, verify_sampled, verify_sampled_openings, SampleParams,
+};
+use crankx::{CrankXError, Nonce};
+
+const CHALLENGE: [u8; 32] = [10; 32];
+const PARAMS: SampleParams = SampleParams { count: 4, len: 32 };
+
+#[test]
+fn sampled_proof_round_trip() {
+    let data: Vec<u8> = (0..1 << 16).map(|i| (i * 31 % 251) as u8).collect();
+
+    let (nonce, solution) = (0u64..)
+        .find_map(|n| solve_sampled(CHALLENGE, &data, n, PARAMS).ok().map(|s| (n, s)))
+        .unwrap();
+
+    verify_sampled(CHALLENGE, &data, nonce, &solution.d, PARAMS).unwrap();
+
+    // A verifier holding only the sampled ranges reaches the same answer
+    let offsets = sample_offsets(CHALLENGE, nonce, data.len() as u64, PARAMS).unwrap();
+    let samples: Vec<&[u8]> = offsets
+        .iter()
+        .map(|&o| &data[o as usize..o as usize + PARAMS.len])
+        .collect();
+    verify_sampled_openings(CHALLENGE, data.len() as u64, &samples, nonce, &solution.d, PARAMS)
+        .unwrap();
+
+    // Changing a sampled byte breaks the proof
+    let mut tampered = data.clone();
+    tampered[offsets[0] as usize] ^= 1;
+    assert!(verify_sampled(CHALLENGE, &tampered, nonce, &solution.d, PARAMS).is_err());
+}
+
+#[test]
+fn offsets_depend_on_nonce() {
+    let a = sample_offsets(CHALLENGE, Nonce::from_u64(1), 1 << 20, PARAMS).unwrap();
+    let b = sample_offsets(CHALLENGE, Nonce::from_u64(2), 1 << 20, PARAMS).unwrap();
+    assert_ne!(a, b);
+    assert!(a.iter().all(|&o| o + PARAMS.len as u64 <= 1 << 20));
+}
+
+#[test]
+fn rejects_bad_params() {
+    assert!(matches!(
+        sample_offsets(CHALLENGE, 0u64, 16, PARAMS),
+        Err(CrankXError::InvalidSampling)
+    ));
+    assert!(matches!(
+        sample_offsets(CHALLENGE, 0u64, 1 << 20, SampleParams { count: 1024, len: 32 }),
+        Err(CrankXError::SeedTooLarge { .. })
+    ));
+
+    // Sizes whose seed length overflows are rejected rather than wrapping
+    for params in [
+        SampleParams { count: usize::MAX, len: 32 },
+        SampleParams { count: 2, len: usize::MAX },
+        SampleParams { count: 1, len: usize::MAX - 40 },
+    ] {
+        assert!(matches!(params.seed_len(), Err(CrankXError::InvalidSampling)));
+        assert!(matches!(
+            sample_offsets(CHALLENGE, 0u64, u64::MAX, params),
+            Err(CrankXError::InvalidSampling)
+        ));
+    }
+}
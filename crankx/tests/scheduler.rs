@@ -0,0 +1,54 @@
+use crankx::scheduler::{
+    RecallPriority, RewardWeighted, Scheduler, SegmentInfo, SelectionStrategy, StalestFirst,
+};
+
+#[test]
+fn stalest_first_cycles_through_segments() {
+    let mut scheduler = Scheduler::new(3, StalestFirst);
+    let mut order = Vec::new();
+    for now in 0..6 {
+        let index = scheduler.next(now).unwrap();
+        scheduler.proved(index, now);
+        order.push(index);
+    }
+    assert_eq!(order, [0, 1, 2, 0, 1, 2]);
+
+    assert_eq!(Scheduler::new(0, StalestFirst).next(0), None);
+}
+
+#[test]
+fn reward_weighted_favours_paying_segments() {
+    let mut scheduler = Scheduler::new(3, RewardWeighted);
+    scheduler.set_reward(1, 10.0);
+    scheduler.set_reward(2, 0.0);
+
+    let mut counts = [0; 3];
+    for now in 0..30 {
+        let index = scheduler.next(now).unwrap();
+        scheduler.proved(index, now);
+        counts[index as usize] += 1;
+    }
+    assert!(counts[1] > 3 * counts[0], "{counts:?}");
+    assert!(counts[0] > 0);
+    assert_eq!(counts[2], 0);
+}
+
+#[test]
+fn recalled_segments_jump_the_queue() {
+    let mut scheduler = Scheduler::new(4, RecallPriority::<StalestFirst>::default());
+    scheduler.recall(3);
+    assert_eq!(scheduler.next(0), Some(3));
+    scheduler.proved(3, 0);
+    assert!(!scheduler.segments()[3].recalled);
+    assert_eq!(scheduler.next(1), Some(0));
+}
+
+#[test]
+fn closures_are_strategies() {
+    let highest = |segments: &[SegmentInfo], _now| segments.len().checked_sub(1);
+    let mut scheduler = Scheduler::new(5, highest);
+    assert_eq!(scheduler.next(0), Some(4));
+
+    let mut boxed: Box<dyn SelectionStrategy + Send> = Box::new(StalestFirst);
+    assert_eq!(boxed.select(Scheduler::new(2, StalestFirst).segments(), 0), Some(0));
+}
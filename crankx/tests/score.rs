@@ -0,0 +1,49 @@
+use crankx::score::{CumulativeWork, Score};
+use crankx::{solve, Solution};
+
+fn hash_with(prefix: &[u8]) -> [u8; 32] {
+    let mut hash = [0xff; 32];
+    hash[..prefix.len()].copy_from_slice(prefix);
+    hash
+}
+
+#[test]
+fn score_refines_difficulty() {
+    let a = Score::from_hash(hash_with(&[0x00, 0x10]));
+    let b = Score::from_hash(hash_with(&[0x00, 0x1f]));
+    assert_eq!((a.difficulty(), b.difficulty()), (11, 11));
+    assert!(a > b);
+    assert!(a.work() > b.work());
+
+    for score in [a, b] {
+        let f = score.fractional_difficulty();
+        assert!((11.0..12.0).contains(&f), "{f}");
+    }
+
+    let floor = Score::from_difficulty(11);
+    assert_eq!(floor.difficulty(), 11);
+    assert!((floor.fractional_difficulty() - 11.0).abs() < 1e-9);
+    assert!(a >= floor && b >= floor);
+}
+
+#[test]
+fn score_order_matches_solution_order() {
+    let mut solutions: Vec<Solution> =
+        (0u64..40).filter_map(|n| solve([1; 32], &[2u8; 32], n).ok()).collect();
+    solutions.sort();
+    let scores: Vec<Score> = solutions.iter().map(Score::from).collect();
+    assert!(scores.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn cumulative_work_adds_up() {
+    let scores = [Score::from_difficulty(10), Score::from_difficulty(10)];
+    let mut work = CumulativeWork::default();
+    work.extend(&scores);
+    assert_eq!(work.proofs, 2);
+    assert!((work.equivalent_difficulty() - 11.0).abs() < 1e-9);
+
+    let mut total = work;
+    total.merge(&work);
+    assert!((total.equivalent_difficulty() - 12.0).abs() < 1e-9);
+}
@@ -0,0 +1,78 @@
+use std::io::Write;
+
+use crankx::equix::SolverMemory;
+use crankx::seed::{gather_seed, solve_vectored, verify_vectored, SeedBuilder};
+use crankx::{solve, verify, CrankXError, MAX_DATA_LEN, MAX_SEED_LEN};
+
+const CHALLENGE: [u8; 32] = [3; 32];
+
+#[test]
+fn parts_solve_like_one_segment() {
+    let mut data = [0u8; 48];
+    data[..8].copy_from_slice(b"header:1");
+    data[8..40].fill(0xab);
+
+    let mut builder = SeedBuilder::new(CHALLENGE);
+    builder.push(b"header:1");
+    builder.write_all(&[0xab; 32]).unwrap();
+    builder.extend([0u8; 8]);
+    assert_eq!(builder.data(), data);
+
+    let mut mem = SolverMemory::new();
+    let (nonce, solution) = (0u64..)
+        .find_map(|n| builder.solve(&mut mem, n).ok().map(|s| (n, s)))
+        .unwrap();
+    assert_eq!(solution, solve(CHALLENGE, &data, nonce).unwrap());
+    verify(CHALLENGE, &data, solution.n, &solution.d).unwrap();
+    builder.verify(solution.n, &solution.d).unwrap();
+
+    // Solving and verifying leave the builder as it was
+    assert_eq!(builder.data(), data);
+    builder.push(&[1]);
+    assert!(builder.verify(solution.n, &solution.d).is_err());
+}
+
+#[test]
+fn segment_size_checked_on_use() {
+    let mut mem = SolverMemory::new();
+    let mut builder = SeedBuilder::new(CHALLENGE);
+    assert!(matches!(builder.solve(&mut mem, 0), Err(CrankXError::SegmentTooSmall { .. })));
+
+    builder.extend(&[0u8; MAX_DATA_LEN + 1]);
+    assert!(matches!(builder.verify(0, &[0; 16]), Err(CrankXError::SeedTooLarge { .. })));
+
+    builder.clear();
+    assert!(builder.data().is_empty());
+}
+
+#[test]
+fn vectored_parts_match_contiguous_data() {
+    let data: Vec<u8> = (0..100).collect();
+    // A ring buffer's two halves, wrapped at 70
+    let parts: [&[u8]; 2] = [&data[..70], &data[70..]];
+    let mut buf = [0u8; MAX_SEED_LEN];
+    let mut mem = SolverMemory::new();
+
+    let seed = gather_seed(&mut buf, CHALLENGE, &parts, 5).unwrap();
+    assert_eq!(&seed[32..132], data);
+
+    let (nonce, solution) = (0u64..)
+        .find_map(|n| {
+            let solved = solve_vectored(&mut buf, &mut mem, CHALLENGE, &parts, n);
+            solved.ok().map(|s| (n, s))
+        })
+        .unwrap();
+    let contiguous: &[u8; 100] = data.as_slice().try_into().unwrap();
+    assert_eq!(solution, solve(CHALLENGE, contiguous, nonce).unwrap());
+    verify_vectored(&mut buf, CHALLENGE, &parts, solution.n, &solution.d).unwrap();
+    verify_vectored(&mut buf, CHALLENGE, &[&data], solution.n, &solution.d).unwrap();
+
+    assert!(matches!(
+        gather_seed(&mut buf[..64], CHALLENGE, &parts, 0),
+        Err(CrankXError::SeedTooLarge { max: 64, got: 140 })
+    ));
+    assert!(matches!(
+        gather_seed(&mut buf, CHALLENGE, &[&[], &[]], 0),
+        Err(CrankXError::SegmentTooSmall { .. })
+    ));
+}
@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+use crankx::segment::{ChunkedSegments, SegmentProvider};
+use crankx::CrankXError;
+
+#[test]
+fn vec_provider_borrows() {
+    let segments = vec![vec![1u8; 4], vec![2u8; 4]];
+    assert_eq!(segments.segment_count(), 2);
+    assert!(matches!(segments.segment(1), Ok(Cow::Borrowed(s)) if s == [2u8; 4]));
+    assert!(matches!(
+        segments.segment(2),
+        Err(CrankXError::SegmentOutOfRange { index: 2, count: 2 })
+    ));
+}
+
+#[test]
+fn chunked_pads_last_segment() {
+    let tape = ChunkedSegments::new((0u8..10).collect::<Vec<_>>(), 4);
+    assert_eq!(tape.segment_count(), 3);
+    assert!(matches!(tape.segment(0), Ok(Cow::Borrowed(s)) if s == [0, 1, 2, 3]));
+    assert_eq!(&*tape.segment(2).unwrap(), &[8, 9, 0, 0]);
+    assert!(tape.segment(3).is_err());
+}
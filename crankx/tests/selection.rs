@@ -0,0 +1,53 @@
+use crankx::selection::{rank, select_winners, Submission, Weighting};
+use crankx::{solve, Solution};
+
+fn solutions(count: usize) -> Vec<Solution> {
+    let mut all: Vec<Solution> =
+        (0u64..).filter_map(|n| solve([1; 32], &[2u8; 32], n).ok()).take(count).collect();
+    all.sort_by(|a, b| b.cmp(a));
+    all
+}
+
+fn submit(miner: u8, solution: &Solution, segments: u64) -> Submission {
+    Submission { miner: [miner; 32], solution: Solution::new(solution.d, solution.n), segments }
+}
+
+#[test]
+fn best_difficulty_wins_and_copies_are_dropped() {
+    let s = solutions(3);
+    let submissions =
+        [submit(1, &s[2], 1), submit(2, &s[0], 1), submit(3, &s[0], 1), submit(4, &s[1], 1)];
+
+    let ranking = rank(&submissions, Weighting::None);
+    let order: Vec<usize> = ranking.iter().map(|r| r.index).collect();
+    assert_eq!(order, [1, 3, 0]);
+    assert!(ranking.iter().enumerate().all(|(i, r)| r.rank == i));
+    assert_eq!(ranking[0].hash, s[0].to_hash());
+
+    let winners = select_winners(&submissions, 1, Weighting::None);
+    assert_eq!(winners[0].miner, [2; 32]);
+
+    // Same inputs in another order pick the same winners
+    let reversed: Vec<Submission> =
+        submissions.iter().rev().map(|x| submit(x.miner[0], &x.solution, 1)).collect();
+    let winner = select_winners(&reversed, 1, Weighting::None)[0];
+    assert_eq!(winner.miner, [3; 32]);
+    assert_eq!(winner.hash, winners[0].hash);
+}
+
+#[test]
+fn segment_weighting_adds_log2_bits() {
+    let s = solutions(2);
+    let gap = s[0].difficulty() - s[1].difficulty();
+    let submissions = [
+        submit(1, &s[0], 1),
+        submit(2, &s[1], 1 << (gap + 1)),
+        submit(3, &s[0], 0),
+    ];
+
+    let ranking = rank(&submissions, Weighting::SegmentCount);
+    assert_eq!(ranking.len(), 2);
+    assert_eq!(ranking[0].miner, [2; 32]);
+    assert_eq!(ranking[0].bonus, gap + 1);
+    assert_eq!(ranking[0].key(), s[0].difficulty() + 1);
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "service")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use crankx::limits::{Rate, VerifyLimits};
+use crankx::service::Service;
+use crankx::MAX_DATA_LEN;
+
+fn post(addr: SocketAddr, path: &str, body: Value) -> (u16, Value) {
+    let body = body.to_string();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let status = response[9..12].parse().unwrap();
+    let json = response.split("\r\n\r\n").nth(1).unwrap();
+    (status, serde_json::from_str(json).unwrap())
+}
+
+#[test]
+fn solve_then_verify_over_http() {
+    let service = Service::bind("127.0.0.1:0").unwrap();
+    let addr = service.local_addr().unwrap();
+    thread::spawn(move || service.run(2));
+
+    let challenge = "11".repeat(32);
+    let data = "22".repeat(64);
+
+    let (status, solved) = post(
+        addr,
+        "/solve",
+        json!({ "challenge": challenge, "data": data, "min_difficulty": 1 }),
+    );
+    assert_eq!(status, 200);
+    assert!(solved["difficulty"].as_u64().unwrap() >= 1);
+
+    let verify = json!({
+        "challenge": challenge,
+        "data": data,
+        "nonce": solved["nonce"],
+        "digest": solved["digest"],
+    });
+    let (status, verified) = post(addr, "/verify", verify);
+    assert_eq!(status, 200);
+    assert_eq!(verified["valid"], true);
+
+    let (status, _) = post(addr, "/solve", json!({ "challenge": "zz", "data": data }));
+    assert_eq!(status, 400);
+    // Multibyte characters are bad hex, not a panic in the handler
+    let (status, _) = post(addr, "/solve", json!({ "challenge": challenge, "data": "é1" }));
+    assert_eq!(status, 400);
+    let unbounded = json!({ "challenge": challenge, "data": data, "max_attempts": u64::MAX });
+    let (status, _) = post(addr, "/solve", unbounded);
+    assert_eq!(status, 400);
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").unwrap();
+    let mut metrics = String::new();
+    stream.read_to_string(&mut metrics).unwrap();
+
+    assert!(metrics.contains("crankx_solutions_total 1\n"));
+    assert!(metrics.contains("crankx_verifications_total 1\n"));
+    assert!(metrics.contains("crankx_memory_pool_size 1\n"));
+}
+
+#[test]
+fn bench_returns_a_machine_report() {
+    let service = Service::bind("127.0.0.1:0").unwrap();
+    let addr = service.local_addr().unwrap();
+    thread::spawn(move || service.run(1));
+
+    let (status, report) = post(addr, "/bench", json!({ "segment_size": 64, "millis": 100 }));
+    assert_eq!(status, 200);
+    assert_eq!(report["segment_size"], 64);
+    assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["host"]["os"], std::env::consts::OS);
+    assert!(report["host"]["cpus"].as_u64().unwrap() >= 1);
+    assert!(report["solves_per_sec"].as_f64().unwrap() > 0.0);
+    assert!(report["verifies_per_sec"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn verify_is_guarded_by_limits() {
+    let rate = Rate::new(0.0, 2).unwrap();
+    let limits = VerifyLimits::new().max_data_len(64).per_key_rate(rate);
+    let service = Service::bind("127.0.0.1:0").unwrap().limits(limits);
+    let addr = service.local_addr().unwrap();
+    thread::spawn(move || service.run(1));
+
+    let request = |data: String| {
+        json!({ "challenge": "11".repeat(32), "data": data, "nonce": "00".repeat(8),
+                "digest": "00".repeat(16) })
+    };
+    let (status, _) = post(addr, "/verify", request("22".repeat(65)));
+    assert_eq!(status, 413);
+    assert_eq!(post(addr, "/verify", request("22".repeat(64))).0, 200);
+    assert_eq!(post(addr, "/verify", request("22".repeat(64))).0, 200);
+    let (status, refused) = post(addr, "/verify", request("22".repeat(64)));
+    assert_eq!(status, 429);
+    assert_eq!(refused["error"], "Verification rate limit exceeded");
+}
+
+#[test]
+fn oversized_data_is_refused_before_any_work() {
+    let service = Service::bind("127.0.0.1:0").unwrap();
+    let addr = service.local_addr().unwrap();
+    thread::spawn(move || service.run(1));
+
+    let data = "22".repeat(MAX_DATA_LEN + 1);
+    let solve = json!({ "challenge": "11".repeat(32), "data": data, "max_attempts": 1 });
+    assert_eq!(post(addr, "/solve", solve).0, 413);
+    let verify = json!({ "challenge": "11".repeat(32), "data": data, "nonce": "00".repeat(8),
+                         "digest": "00".repeat(16) });
+    assert_eq!(post(addr, "/verify", verify).0, 413);
+}
+
+#[test]
+fn solve_and_bench_are_charged_for_their_work() {
+    // Enough for one small solve, never for a default-sized one
+    let rate = Rate::new(0.0, 2_000).unwrap();
+    let limits = VerifyLimits::new().per_key_rate(rate);
+    let service = Service::bind("127.0.0.1:0").unwrap().limits(limits);
+    let addr = service.local_addr().unwrap();
+    thread::spawn(move || service.run(1));
+
+    let solve = |max_attempts: u64| {
+        json!({ "challenge": "11".repeat(32), "data": "22".repeat(64),
+                "max_attempts": max_attempts })
+    };
+    assert_eq!(post(addr, "/solve", solve(1 << 16)).0, 429);
+    assert_ne!(post(addr, "/solve", solve(10)).0, 429);
+    // 10 attempts cost 1,000 of the 2,000 tokens; 100ms of bench another 1,000
+    assert_eq!(post(addr, "/bench", json!({ "segment_size": 64, "millis": 100 })).0, 200);
+    assert_eq!(post(addr, "/bench", json!({ "segment_size": 64, "millis": 1 })).0, 429);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "sim")]
+
+use std::time::Duration;
+
+use crankx::retarget::{Retarget, RetargetParams};
+use crankx::sim::{run, Fixed, SimConfig, SimMiner, SimReport};
+
+#[test]
+fn fixed_difficulty_matches_the_expected_latency() {
+    let config = SimConfig {
+        miners: vec![SimMiner::new(768.0), SimMiner::new(256.0)],
+        rounds: 20_000,
+        seed: 1,
+    };
+    let report = run(&config, &mut Fixed(10));
+
+    assert_eq!(report.proofs(), 20_000);
+    assert!((report.mean_latency().as_secs_f64() - 1.0).abs() < 0.05);
+    let median = report.latency_percentile(0.5).unwrap().as_secs_f64();
+    assert!((median - 2f64.ln()).abs() < 0.05);
+
+    let share = report.wins[0] as f64 / report.proofs() as f64;
+    assert!((share - 0.75).abs() < 0.02);
+    assert_eq!(report.difficulty_drift(), 0);
+
+    assert_eq!(run(&config, &mut Fixed(10)), report);
+}
+
+#[test]
+fn retarget_converges_on_the_hashrate() {
+    let params = RetargetParams { target_interval: Duration::from_secs(1), ..Default::default() };
+    let config = SimConfig { miners: vec![SimMiner::new(4096.0)], rounds: 2_000, seed: 7 };
+    let report = run(&config, &mut Retarget::new(params, 4));
+
+    assert_eq!(report.difficulty[0], 4);
+    assert!(report.difficulty_drift() >= 7);
+    let settled = &report.difficulty[1_000..];
+    assert!(settled.iter().all(|d| (10..=14).contains(d)));
+}
+
+#[test]
+fn uncovered_segments_go_unproved() {
+    let config = SimConfig {
+        miners: vec![
+            SimMiner { hashrate: 1.0e6, coverage: 0.0 },
+            SimMiner { hashrate: 1.0, coverage: 0.5 },
+        ],
+        rounds: 1_000,
+        seed: 3,
+    };
+    let report = run(&config, &mut Fixed(0));
+
+    assert_eq!(report.wins[0], 0);
+    assert_eq!(report.wins[1] as usize, report.proofs());
+    assert_eq!(report.proofs() as u64 + report.unprovable, 1_000);
+    assert!((400..600).contains(&report.unprovable));
+}
+
+#[test]
+fn means_of_an_empty_or_huge_report_stay_finite() {
+    let empty = SimReport::default();
+    assert_eq!(empty.mean_latency(), Duration::ZERO);
+    assert_eq!(empty.mean_difficulty(), 0.0);
+
+    let report = SimReport { latencies: vec![Duration::MAX; 4], ..Default::default() };
+    assert_eq!(report.mean_latency(), Duration::MAX / 4);
+}
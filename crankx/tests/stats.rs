@@ -0,0 +1,75 @@
+use crankx::miner::Miner;
+use crankx::stats::Stats;
+
+#[test]
+fn records_candidates_and_difficulties() {
+    let mut stats = Stats::default();
+    stats.record(&[]);
+    stats.record(&[3, 0]);
+    stats.record(&[5]);
+
+    assert_eq!(stats.attempts, 3);
+    assert_eq!(stats.no_solution, 1);
+    assert_eq!(stats.candidates[..3], [1, 1, 1]);
+    assert_eq!(stats.difficulty, [1, 0, 0, 1, 0, 1]);
+    assert_eq!(stats.total_candidates(), 3);
+    assert_eq!(stats.max_difficulty(), Some(5));
+    assert!((stats.no_solution_rate() - 1.0 / 3.0).abs() < 1e-12);
+
+    let mut merged = Stats::default();
+    merged.merge(&stats);
+    merged.merge(&stats);
+    assert_eq!(merged.attempts, 6);
+    assert_eq!(merged.difficulty, [2, 0, 0, 2, 0, 2]);
+}
+
+#[test]
+fn miner_collects_stats_on_request() {
+    let report = Miner::new(2).mine([1; 32], &[2u8; 32], 0).unwrap();
+    assert!(report.stats.is_none());
+
+    let report = Miner::new(2).collect_stats(true).mine([1; 32], &[2u8; 32], 4).unwrap();
+    let stats = report.stats.unwrap();
+    assert_eq!(stats.attempts, report.attempts);
+    assert!(stats.max_difficulty().unwrap() >= 4);
+    assert_eq!(stats.candidates.iter().sum::<u64>(), stats.attempts);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn stats_serialize_to_json() {
+    let mut stats = Stats::default();
+    stats.record(&[2]);
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["attempts"], 1);
+    assert_eq!(json["difficulty"], serde_json::json!([0, 0, 1]));
+    assert_eq!(serde_json::from_value::<Stats>(json).unwrap(), stats);
+}
+
+#[test]
+fn percentiles_read_off_the_histogram() {
+    let mut stats = Stats::default();
+    assert_eq!(stats.percentile(0.5), None);
+    assert_eq!(stats.percentiles(), None);
+
+    // 100 candidates: 50 at 0, 40 at 1, 9 at 2, 1 at 7
+    for d in (0..50).map(|_| 0).chain((0..40).map(|_| 1)).chain((0..9).map(|_| 2)) {
+        stats.record(&[d]);
+    }
+    stats.record(&[7]);
+
+    assert_eq!(stats.percentile(0.0), Some(0));
+    assert_eq!(stats.percentile(0.5), Some(0));
+    assert_eq!(stats.percentile(0.51), Some(1));
+    assert_eq!(stats.percentile(0.99), Some(2));
+    assert_eq!(stats.percentile(1.0), Some(7));
+    assert_eq!(stats.percentile(1.5), None);
+
+    let p = stats.percentiles().unwrap();
+    assert_eq!((p.p50, p.p90, p.p99, p.p999, p.max), (0, 1, 2, 7, 7));
+
+    assert_eq!(stats.candidates_at_least(2), 10);
+    assert_eq!(stats.attempts_per_hit(2), Some(10.0));
+    assert_eq!(stats.attempts_per_hit(8), None);
+}
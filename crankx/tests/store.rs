@@ -0,0 +1,57 @@
+#![cfg(feature = "store")]
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use crankx::store::ProofStore;
+use crankx::{solve, Challenge, Solution};
+
+#[test]
+fn proofs_survive_reopen_and_prune() {
+    let path = std::env::temp_dir().join(format!("crankx-store-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let challenge = Challenge([2; 32]);
+    let solution = (0u64..).find_map(|n| solve(challenge, &[7u8; 32], n).ok()).unwrap();
+    let bytes = solution.to_bytes();
+
+    let mut store = ProofStore::open(&path).unwrap();
+    store.put(challenge, 0, 1, Solution::from_bytes(&bytes)).unwrap();
+    store.put(challenge, 1, 2, Solution::from_bytes(&bytes)).unwrap();
+    store.put(challenge, 2, 3, solution).unwrap();
+    assert!(store.mark_submitted(challenge, 1).unwrap());
+    assert!(!store.mark_submitted(challenge, 9).unwrap());
+    drop(store);
+
+    // A crash mid-write leaves a torn record behind
+    OpenOptions::new().append(true).open(&path).unwrap().write_all(&[0, 1, 2]).unwrap();
+
+    let mut store = ProofStore::open(&path).unwrap();
+    assert_eq!(store.len(), 3);
+    assert_eq!(store.get(challenge, 2).unwrap().solution.to_bytes(), bytes);
+    let pending: Vec<_> = store.unsubmitted().map(|(&(_, segment), _)| segment).collect();
+    assert_eq!(pending, [0, 2]);
+
+    assert_eq!(store.prune(2).unwrap(), 1);
+    drop(store);
+
+    let store = ProofStore::open(&path).unwrap();
+    assert_eq!(store.len(), 2);
+    assert!(store.get(challenge, 0).is_none());
+    assert!(store.get(challenge, 1).unwrap().submitted);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_archive_is_an_error_not_an_empty_store() {
+    // A symlink loop fails to open with something other than NotFound
+    let path = std::env::temp_dir().join(format!("crankx-store-loop-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    std::os::unix::fs::symlink(&path, &path).unwrap();
+
+    assert!(ProofStore::open(&path).is_err());
+    assert!(fs::symlink_metadata(&path).unwrap().file_type().is_symlink());
+    let _ = fs::remove_file(&path);
+}
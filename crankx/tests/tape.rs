@@ -0,0 +1,125 @@
+use std::fs;
+use std::io::Cursor;
+
+use crankx::miner::Miner;
+use crankx::scheduler::{Scheduler, StalestFirst};
+use crankx::tape::format::{Header, ENTRY_LEN, HEADER_LEN};
+use crankx::tape::{Reader, Seal, TapeView, TapeWriter, Writer};
+use crankx::testing::gen_tape;
+use crankx::{Challenge, CrankXError, SegmentProvider, MAX_SEGMENT_SIZE};
+
+fn write_tape(segments: &[Vec<u8>], size: u32) -> Vec<u8> {
+    let mut w = Writer::new(Cursor::new(Vec::new()), size).unwrap();
+    w.set_challenge(Challenge([3; 32]));
+    for segment in segments {
+        w.append(segment).unwrap();
+    }
+    w.seal(1, Seal { nonce: [1; 8], digest: [2; 16] }).unwrap();
+    w.finish().unwrap().into_inner()
+}
+
+#[test]
+fn view_seeks_segments_and_index() {
+    let mut segments = gen_tape(5, 4, 32);
+    segments[3].truncate(10);
+    let bytes = write_tape(&segments, 32);
+    assert_eq!(bytes.len(), HEADER_LEN + 4 * 32 + 4 * ENTRY_LEN);
+
+    let view = TapeView::new(&bytes[..]).unwrap();
+    assert_eq!(view.header().count, 4);
+    assert_eq!(view.header().challenge, Some(Challenge([3; 32])));
+    assert_eq!(view.segment_count(), 4);
+    assert_eq!(&*view.segment(2).unwrap(), &segments[2][..]);
+    assert_eq!(&view.segment(3).unwrap()[..10], &segments[3][..]);
+    assert_eq!(&view.segment(3).unwrap()[10..], &[0; 22]);
+    assert_eq!(view.entry(1).unwrap().seal, Some(Seal { nonce: [1; 8], digest: [2; 16] }));
+    assert_eq!(view.entry(0).unwrap().seal, None);
+    assert!((0..4).all(|i| view.check(i).is_ok()));
+    assert!(matches!(view.segment(4), Err(CrankXError::SegmentOutOfRange { .. })));
+
+    let mut corrupt = bytes.clone();
+    corrupt[HEADER_LEN + 40] ^= 1;
+    assert!(matches!(
+        TapeView::new(&corrupt[..]).unwrap().check(1),
+        Err(CrankXError::InvalidEncoding)
+    ));
+    assert!(matches!(
+        TapeView::new(&bytes[..bytes.len() - 1]),
+        Err(CrankXError::InvalidLength)
+    ));
+    corrupt[0] ^= 1;
+    assert!(matches!(TapeView::new(&corrupt[..]), Err(CrankXError::InvalidEncoding)));
+}
+
+#[test]
+fn hostile_headers_are_rejected() {
+    // 2^57 entries of 64 + 64 bytes wrap the file length back to the header
+    let mut header = Header::new(64, 1 << 57);
+    assert!(matches!(header.file_len(), Err(CrankXError::InvalidLength)));
+    assert!(matches!(TapeView::new(header.to_bytes()), Err(CrankXError::InvalidLength)));
+
+    header = Header::new(MAX_SEGMENT_SIZE as u32 + 1, 0);
+    assert!(matches!(TapeView::new(header.to_bytes()), Err(CrankXError::InvalidEncoding)));
+    assert!(Writer::new(Cursor::new(Vec::new()), header.segment_size).is_err());
+}
+
+#[test]
+fn reader_checks_segments_and_feeds_the_scheduler() {
+    let path = std::env::temp_dir().join(format!("crankx-tape-{}.tape", std::process::id()));
+    let segments = gen_tape(9, 3, 64);
+    let mut bytes = write_tape(&segments, 64);
+    fs::write(&path, &bytes).unwrap();
+
+    let reader = Reader::open(&path).unwrap();
+    assert_eq!(reader.entries().len(), 3);
+    assert!(reader.entry(1).unwrap().seal.is_some());
+    assert_eq!(reader.read_segment(0).unwrap(), segments[0]);
+    assert!(reader.segment(3).is_err());
+    let mut scheduler = Scheduler::for_provider(&reader, StalestFirst);
+    assert_eq!(scheduler.segments().len(), 3);
+    assert!(scheduler.next(0).is_some());
+
+    bytes[HEADER_LEN + 64] ^= 1;
+    fs::write(&path, &bytes).unwrap();
+    let reader = Reader::open(&path).unwrap();
+    assert!(reader.read_segment(0).is_ok());
+    assert_eq!(reader.read_segment(1).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    // An unfinished tape doesn't open
+    bytes.truncate(HEADER_LEN + 64);
+    fs::write(&path, &bytes).unwrap();
+    assert!(Reader::open(&path).is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn tape_writer_seals_every_segment() {
+    let segments = gen_tape(11, 3, 48);
+    let challenge = Challenge([8; 32]);
+    let mut w = TapeWriter::new(Cursor::new(Vec::new()), 64, challenge, Miner::new(2)).unwrap();
+    let proofs: Vec<_> = segments.iter().map(|s| w.append(s).unwrap()).collect();
+    assert_eq!(w.count(), 3);
+    let mut bytes = w.finish().unwrap().into_inner();
+
+    let view = TapeView::new(&bytes[..]).unwrap();
+    assert_eq!(view.header().challenge, Some(challenge));
+    assert!(view.unsealed().unwrap().is_empty());
+    for (i, proof) in (0..).zip(&proofs) {
+        let seal = view.entry(i).unwrap().seal.unwrap();
+        assert_eq!(seal.to_solution(), *proof);
+        assert!(seal.verify(&challenge, view.segment_bytes(i).unwrap()).is_ok());
+    }
+
+    // A tampered segment no longer matches its seal
+    bytes[HEADER_LEN + 64 + 5] ^= 1;
+    assert_eq!(TapeView::new(&bytes[..]).unwrap().unsealed().unwrap(), vec![1]);
+
+    let path = std::env::temp_dir().join(format!("crankx-sealed-{}.tape", std::process::id()));
+    let mut w = TapeWriter::new(fs::File::create(&path).unwrap(), 64, challenge, Miner::new(1))
+        .unwrap()
+        .min_difficulty(1);
+    assert!(w.append(&segments[0]).unwrap().difficulty() >= 1);
+    w.finish().unwrap();
+    assert!(Reader::open(&path).unwrap().unsealed().unwrap().is_empty());
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use crankx::{difficulty_of, meets_difficulty, solve, Solution, Target};
+
+#[test]
+fn difficulty_round_trips_through_target() {
+    for d in [0, 1, 7, 8, 9, 31, 255, 256] {
+        assert_eq!(Target::from_difficulty(d).difficulty(), d);
+    }
+    assert_eq!(Target::from_difficulty(300), Target([0; 32]));
+
+    let target = Target::from_difficulty(12);
+    assert_eq!(target.as_bytes()[..2], [0x00, 0x0f]);
+    assert!(target.as_bytes()[2..].iter().all(|&b| b == 0xff));
+
+    let mut hash = [0xff; 32];
+    hash[..2].copy_from_slice(&[0x00, 0x0f]);
+    assert!(target.is_met_by(&hash));
+    hash[1] = 0x10;
+    assert!(!target.is_met_by(&hash));
+
+    let solution = (0u64..).find_map(|n| solve([1; 32], &[2u8; 32], n).ok()).unwrap();
+    assert!(solution.meets(&Target::from_difficulty(solution.difficulty())));
+    assert!(!solution.meets(&Target::from_difficulty(solution.difficulty() + 1)));
+}
+
+#[test]
+fn odds_and_eta() {
+    assert_eq!(Target::from_difficulty(0).probability(), 1.0);
+    assert_eq!(Target::from_difficulty(10).probability(), 1.0 / 1024.0);
+    assert_eq!(Target::from_difficulty(10).expected_attempts(), 1024.0);
+    assert!(Target::from_difficulty(256).probability() > 0.0);
+
+    let target = Target::from_difficulty(1);
+    assert_eq!(target.probability_within(0), 0.0);
+    assert!((target.probability_within(2) - 0.75).abs() < 1e-12);
+
+    let hard = Target::from_difficulty(64);
+    assert!((hard.probability_within(1 << 20) - 2f64.powi(-44)).abs() < 2f64.powi(-60));
+
+    assert_eq!(Target::from_difficulty(10).eta(512.0), Duration::from_secs(2));
+    assert_eq!(Target::from_difficulty(10).eta(0.0), Duration::MAX);
+}
+
+#[test]
+fn difficulty_of_matches_targets() {
+    let mut hash = [0xffu8; 32];
+    assert_eq!(difficulty_of(&hash), 0);
+    hash[0] = 0;
+    hash[1] = 0x1f;
+    assert_eq!(difficulty_of(&hash), 11);
+    assert_eq!(difficulty_of(&[0; 32]), 256);
+
+    for d in [0, 1, 7, 8, 11, 12, 64, 256] {
+        let target = Target::from_difficulty(d);
+        assert_eq!(meets_difficulty(&hash, d), target.is_met_by(&hash), "difficulty {d}");
+    }
+    assert!(meets_difficulty(&hash, 11) && !meets_difficulty(&hash, 12));
+
+    let solution = Solution::new([1; 16], [2; 8]);
+    assert_eq!(difficulty_of(&solution.to_hash()), solution.difficulty());
+}
+
+#[test]
+fn time_to_solution_follows_the_geometric_tail() {
+    let coin = Target::from_difficulty(1);
+    assert_eq!(coin.attempts_for(0.5), 1.0);
+    assert_eq!(coin.attempts_for(0.9), 4.0);
+    assert_eq!(coin.attempts_for(0.99), 7.0);
+
+    let target = Target::from_difficulty(20);
+    let mean = target.expected_attempts();
+    assert!((target.attempts_for(0.5) / mean - 2f64.ln()).abs() < 1e-5);
+    assert!((target.attempts_for(0.99) / mean - 100f64.ln()).abs() < 1e-5);
+
+    let tts = target.time_to_solution(mean);
+    assert!(tts.median < tts.p90 && tts.p90 < tts.p99);
+    assert!((tts.p99.as_secs_f64() - 100f64.ln()).abs() < 1e-5);
+    assert_eq!(target.time_to_solution(0.0).median, Duration::MAX);
+
+    let rate = target.hashrate_for(Duration::from_secs(10), 0.99);
+    assert!((target.eta_within(rate, 0.99).as_secs_f64() - 10.0).abs() < 1e-6);
+}
@@ -0,0 +1,80 @@
+use crankx::testing::{gen_segment, gen_segment_array, gen_tape, tape_segment_seed};
+
+#[test]
+fn generated_segments_are_reproducible() {
+    assert_eq!(gen_segment(1, 100), gen_segment(1, 100));
+    assert_ne!(gen_segment(1, 100), gen_segment(2, 100));
+    assert_eq!(gen_segment(1, 37), gen_segment(1, 100)[..37]);
+    assert_eq!(gen_segment_array::<64>(5), gen_segment(5, 64)[..]);
+
+    // Pinned so the stream never silently changes under downstream tests
+    assert_eq!(gen_segment(0, 8), 0xe220_a839_7b1d_cdafu64.to_le_bytes());
+
+    let segment = gen_segment(3, 4096);
+    let distinct = (0..=255u8).filter(|b| segment.contains(b)).count();
+    assert!(distinct > 240);
+}
+
+#[test]
+fn tape_segments_regenerate_individually() {
+    let tape = gen_tape(9, 4, 128);
+    assert_eq!(tape.len(), 4);
+    assert_eq!(tape[2], gen_segment(tape_segment_seed(9, 2), 128));
+    assert_ne!(tape[0], tape[1]);
+    assert_ne!(gen_tape(10, 1, 128)[0], tape[0]);
+}
+
+#[cfg(feature = "solana")]
+mod solana {
+    use crankx::solana::{verify_with_difficulty, SubmitProof};
+    use crankx::testing::solana::{
+        assert_rejected, instruction_error, process_with_miner, submit_ix, valid_submission,
+    };
+    use crankx::testing::solve_at_least;
+    use crankx::CrankXError;
+    use solana_program::account_info::AccountInfo;
+    use solana_program::instruction::InstructionError;
+    use solana_program::program_error::ProgramError;
+    use solana_program::pubkey::Pubkey;
+
+    const CHALLENGE: [u8; 32] = [9; 32];
+    const SEGMENT: [u8; 100] = [4; 100];
+
+    /// Minimal stand-in for the reference verifier
+    fn process(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Result<(), ProgramError> {
+        if !accounts.first().is_some_and(|a| a.is_signer) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let ix = SubmitProof::unpack(data)?;
+        verify_with_difficulty(ix.challenge, ix.segment, &ix.proof, ix.min_difficulty).map(|_| ())
+    }
+
+    #[test]
+    fn generated_proofs_meet_their_difficulty() {
+        let solution = solve_at_least(CHALLENGE, &SEGMENT, 6);
+        assert!(solution.difficulty() >= 6);
+
+        let submission = valid_submission(CHALLENGE, &SEGMENT, 6);
+        verify_with_difficulty(CHALLENGE, &SEGMENT, &submission.proof, 6).unwrap();
+    }
+
+    #[test]
+    fn fixtures_drive_a_processor() {
+        let (program, miner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = submit_ix(&program, &miner, CHALLENGE, &SEGMENT, 4);
+        assert_eq!(ix.program_id, program);
+        process_with_miner(process, &ix).unwrap();
+
+        let mut harder = SubmitProof::unpack(&ix.data).unwrap();
+        harder.min_difficulty = 64;
+        let ix = solana_program::instruction::Instruction { data: harder.pack(), ..ix };
+        assert_rejected(
+            process_with_miner(process, &ix),
+            CrankXError::InsufficientDifficulty { required: 0, actual: 0 },
+        );
+        assert_eq!(
+            instruction_error(CrankXError::InsufficientDifficulty { required: 0, actual: 0 }),
+            InstructionError::Custom(11)
+        );
+    }
+}
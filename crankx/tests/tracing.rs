@@ -0,0 +1,115 @@
+#![cfg(feature = "tracing")]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use crankx::miner::Miner;
+use crankx::multi::solve_k;
+use crankx::{verify, CrankXError};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+type Fields = HashMap<&'static str, String>;
+
+/// Every span opened, in order, with its fields as they ended up
+#[derive(Clone, Default)]
+struct Spans(Arc<Mutex<Vec<(&'static Metadata<'static>, Fields)>>>);
+
+thread_local! {
+    /// Spans entered on this thread, innermost last
+    static ENTERED: RefCell<Vec<Id>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Spans {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.0.lock().unwrap();
+        let mut fields = HashMap::new();
+        span.record(&mut Visitor(&mut fields));
+        spans.push((span.metadata(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.0.lock().unwrap();
+        values.record(&mut Visitor(&mut spans[span.into_u64() as usize - 1].1));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        ENTERED.with(|e| e.borrow_mut().push(span.clone()));
+    }
+
+    fn exit(&self, _: &Id) {
+        ENTERED.with(|e| e.borrow_mut().pop());
+    }
+
+    fn current_span(&self) -> Current {
+        match ENTERED.with(|e| e.borrow().last().cloned()) {
+            Some(id) => {
+                Current::new(id.clone(), self.0.lock().unwrap()[id.into_u64() as usize - 1].0)
+            }
+            None => Current::none(),
+        }
+    }
+}
+
+impl Spans {
+    fn named(&self, name: &str) -> Vec<Fields> {
+        let spans = self.0.lock().unwrap();
+        spans.iter().filter(|(m, _)| m.name() == name).map(|(_, f)| f.clone()).collect()
+    }
+}
+
+// One test: the miner's threads only see a global subscriber
+#[test]
+fn solving_verifying_and_mining_are_traced() {
+    let spans = Spans::default();
+    tracing::subscriber::set_global_default(spans.clone()).unwrap();
+
+    let solutions = solve_k([1; 32], &[2; 64], 1, 3).unwrap();
+    let search = &spans.named("search")[0];
+    let attempts: u64 = search["attempts"].parse().unwrap();
+    assert_eq!(search["nonce_start"], "0");
+    assert_eq!(search["nonce_end"], (attempts - 1).to_string());
+    assert_eq!(search["difficulty"], solutions[0].difficulty().to_string());
+    let solves = spans.named("solve");
+    assert_eq!(solves.len() as u64, attempts);
+    // Seeds whose puzzle can't be built never get as far as a runtime
+    let solved: Vec<_> = solves.iter().filter(|s| s.contains_key("difficulty")).collect();
+    assert!(!solved.is_empty() && solved.iter().all(|s| s.contains_key("runtime")));
+
+    let solution = &solutions[0];
+    verify([1; 32], &[2; 64], solution.n, &solution.d).unwrap();
+    let wrong = verify([1; 32], &[3; 64], solution.n, &solution.d);
+    assert!(matches!(wrong, Err(CrankXError::EquiXFailure)));
+    assert_eq!(spans.named("verify").len(), 2);
+
+    let report = Miner::new(2).mine([4; 32], &[5; 64], 2).unwrap();
+    let mine = &spans.named("mine")[0];
+    assert_eq!(mine["threads"], "2");
+    assert_eq!(mine["attempts"], report.attempts.to_string());
+    assert_eq!(mine["difficulty"], report.solution.unwrap().difficulty().to_string());
+    assert_eq!(mine["runtime"], format!("{:?}", report.runtime_used.unwrap()));
+    let cranks = spans.named("crank");
+    assert_eq!(cranks.len(), 2);
+    let cranked: u64 = cranks.iter().map(|c| c["attempts"].parse::<u64>().unwrap()).sum();
+    assert_eq!(cranked, report.attempts);
+}
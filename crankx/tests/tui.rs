@@ -0,0 +1,78 @@
+#![cfg(feature = "tui")]
+
+use std::fs;
+use std::sync::Arc;
+
+use crankx::config::Config;
+use crankx::daemon::Daemon;
+use crankx::metrics::Metrics;
+use crankx::tui::{draw, Dashboard, Snapshot};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+fn screen(snapshot: &Snapshot) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| draw(frame, snapshot)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content
+        .chunks(80)
+        .map(|row| row.iter().map(|c| c.symbol()).collect::<String>())
+        .fold(String::new(), |screen, row| screen + &row + "\n")
+}
+
+#[test]
+fn draws_every_panel() {
+    let snapshot = Snapshot {
+        hashrates: vec![120.0, 80.5],
+        segments: vec![("a.tape".to_string(), 3, 4)],
+        best_difficulty: 17,
+        solutions: 3,
+        errors: vec!["b.tape segment 2: bad".to_string()],
+    };
+    let screen = screen(&snapshot);
+
+    assert!(screen.contains("200.5 H/s   best difficulty 17   3 solutions"));
+    assert!(screen.contains("120.0"));
+    assert!(screen.contains("80.5"));
+    assert!(screen.contains("a.tape"));
+    assert!(screen.contains("b.tape segment 2: bad"));
+}
+
+#[test]
+fn snapshots_follow_the_metrics() {
+    let metrics = Arc::new(Metrics::default());
+    let mut dashboard = Dashboard::new(metrics.clone());
+    metrics.add_worker_attempts(2, 50);
+    metrics.set_segments("a.tape", 1, 2);
+    metrics.record_solution(9);
+
+    let snapshot = dashboard.snapshot();
+    assert_eq!(snapshot.hashrates.len(), 3);
+    assert!(snapshot.hashrates[2] > 0.0);
+    assert_eq!(snapshot.hashrates[0], 0.0);
+    assert_eq!(snapshot.segments, [("a.tape".to_string(), 1, 2)]);
+    assert_eq!(snapshot.best_difficulty, 9);
+
+    // Nothing tried since the last frame is a hashrate of zero
+    assert_eq!(dashboard.snapshot().hashrates[2], 0.0);
+}
+
+#[test]
+fn a_quiet_daemon_still_records_its_warnings() {
+    let root = std::env::temp_dir().join(format!("crankx-tui-{}", std::process::id()));
+    let tapes = root.join("tapes");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&tapes).unwrap();
+    fs::write(tapes.join("broken.tape"), b"not a tape").unwrap();
+
+    let config = Config::new(&tapes, root.join("proofs"));
+    let mut daemon = Daemon::new(config).unwrap().quiet();
+    assert_eq!(daemon.run_once().unwrap(), 0);
+
+    let errors = daemon.metrics().recent_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("skipping "));
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use crankx::config::RuntimeOption;
+use crankx::tune::{autotune_with, TuneOptions, TIE_TOLERANCE};
+use crankx::CrankXError;
+
+fn options() -> TuneOptions {
+    TuneOptions {
+        threads: vec![1, 2],
+        batch_sizes: vec![1, 4],
+        runtimes: vec![RuntimeOption::InterpretOnly],
+        trial: Duration::from_millis(60),
+        segment_size: 64,
+    }
+}
+
+#[test]
+fn picks_the_fastest_trial() {
+    let report = autotune_with(&options()).unwrap();
+    assert_eq!(report.trials.len(), 4);
+    assert!(report.trials.iter().all(|t| t.attempts_per_sec > 0.0));
+    // A slower trial can only win on a tie, with fewer threads
+    let best = report.best.attempts_per_sec;
+    assert!(report.trials.iter().all(|t| t.attempts_per_sec <= best / (1.0 - TIE_TOLERANCE)));
+    assert!(report.trials.contains(&report.best));
+    assert_eq!(report.best.miner().threads(), report.best.threads);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn tuning_is_saved_into_the_settings_file() {
+    use crankx::config::Config;
+    use crankx::tune::Tuning;
+
+    let path = std::env::temp_dir().join(format!("crankx-tune-{}.toml", std::process::id()));
+    let file = "# prover box\ntape_dir = \"/tapes\"\narchive = \"/proofs\"\nthreads = 1 # was 1\n";
+    std::fs::write(&path, file).unwrap();
+    let tuning = Tuning {
+        threads: 3,
+        batch_size: 8,
+        runtime: RuntimeOption::InterpretOnly,
+        attempts_per_sec: 1.0,
+    };
+    tuning.save(&path).unwrap();
+
+    // Only the tuned keys change; comments stay
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("# prover box\ntape_dir = \"/tapes\"\n"));
+    assert!(text.contains("threads = 3 # was 1\n"));
+    let config = Config::from_path(&path).unwrap();
+    assert_eq!((config.threads, config.batch_size), (3, 8));
+    assert_eq!(config.runtime, RuntimeOption::InterpretOnly);
+    assert_eq!(config.tape_dir, std::path::Path::new("/tapes"));
+    let mut expected = Config::new("/tapes", "/proofs");
+    tuning.apply(&mut expected);
+    assert_eq!(config, expected);
+
+    std::fs::write(&path, "threads = 4\n").unwrap();
+    assert!(tuning.save(&path).is_err());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn rejects_empty_options_and_oversized_segments() {
+    let empty = TuneOptions { threads: vec![], ..options() };
+    assert!(matches!(autotune_with(&empty), Err(CrankXError::InvalidLength)));
+
+    let huge = TuneOptions { segment_size: crankx::MAX_DATA_LEN + 1, ..options() };
+    assert!(autotune_with(&huge).is_err());
+}
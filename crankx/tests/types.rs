@@ -0,0 +1,74 @@
+use crankx::{solve, verify, Challenge, CrankXError, Nonce, Solution};
+
+#[test]
+fn nonce_is_little_endian() {
+    let nonce = Nonce::from_u64(0x0102);
+    assert_eq!(nonce.to_bytes(), [2, 1, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(nonce.to_u64(), 0x0102);
+    assert_eq!(Nonce::from(0x0102u64), nonce);
+}
+
+#[test]
+fn hex_round_trip() {
+    let challenge = Challenge([0xab; 32]);
+    let hex = challenge.to_string();
+    assert_eq!(hex.len(), 64);
+    assert_eq!(hex.parse::<Challenge>().unwrap(), challenge);
+    assert_eq!(Challenge::from_hex(&format!("0x{hex}")).unwrap(), challenge);
+
+    assert_eq!("0100000000000000".parse::<Nonce>().unwrap(), Nonce::from_u64(1));
+    assert!(matches!("0100".parse::<Nonce>(), Err(CrankXError::InvalidHex)));
+    assert!(matches!("zz00000000000000".parse::<Nonce>(), Err(CrankXError::InvalidHex)));
+}
+
+#[test]
+fn newtypes_and_arrays_are_interchangeable() {
+    let challenge = Challenge([6; 32]);
+    let data = [7u8; 32];
+
+    let (nonce, solution) = (0u64..)
+        .find_map(|n| solve(challenge, &data, n).ok().map(|s| (Nonce::from(n), s)))
+        .unwrap();
+
+    assert_eq!(solution.n, nonce.to_bytes());
+    verify(challenge, &data, nonce, &solution.d).unwrap();
+    verify([6u8; 32], &data, solution.n, &solution.d).unwrap();
+    solution.is_valid(challenge, &data).unwrap();
+}
+
+#[test]
+fn try_new_checks_the_seed() {
+    let challenge = [6u8; 32];
+    let data = [7u8; 32];
+    let solution = (0u64..).find_map(|n| solve(challenge, &data, n).ok()).unwrap();
+
+    let built = Solution::try_new(solution.d, solution.n, challenge, &data).unwrap();
+    assert_eq!(built.to_hash(), solution.to_hash());
+
+    assert!(matches!(
+        Solution::try_new(solution.d, solution.n, challenge, &[8u8; 32]),
+        Err(CrankXError::InvalidSolution)
+    ));
+}
+
+#[test]
+fn solutions_rank_by_difficulty_then_hash() {
+    let mut solutions: Vec<_> =
+        (0u64..).filter_map(|n| solve([5; 32], &[1u8; 32], n).ok()).take(24).collect();
+    solutions.sort();
+
+    for pair in solutions.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        assert!(a.difficulty() <= b.difficulty());
+        if a.difficulty() == b.difficulty() {
+            assert!(a.to_hash() > b.to_hash());
+        }
+    }
+
+    // Reordering a digest's words is the same proof
+    let best = solutions.last().unwrap();
+    let mut digest = best.d;
+    digest.rotate_left(2);
+    assert_eq!(Solution::new(digest, best.n), *best);
+    assert_ne!(solutions[0], *best);
+}
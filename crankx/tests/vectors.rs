@@ -0,0 +1,81 @@
+use serde_json::Value;
+
+use crankx::equix::RuntimeOption;
+use crankx::test_vectors::TEST_VECTORS;
+use crankx::testing::{export_vectors, vectors_to_csv, vectors_to_json};
+use crankx::{self_test, self_test_with_runtime, solve, verify, Solution, MAX_DATA_LEN};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn self_test_passes() {
+    self_test().unwrap();
+    self_test_with_runtime(RuntimeOption::InterpretOnly).unwrap();
+    self_test_with_runtime(RuntimeOption::TryCompile).unwrap();
+}
+
+#[test]
+fn json_fixture_matches_module() {
+    let fixture: Value = serde_json::from_str(include_str!("vectors.json")).unwrap();
+    let vectors = fixture["vectors"].as_array().unwrap();
+
+    assert_eq!(vectors.len(), TEST_VECTORS.len());
+    for (json, v) in vectors.iter().zip(TEST_VECTORS) {
+        assert_eq!(json["name"], v.name);
+        assert_eq!(json["challenge"], hex(&v.challenge));
+        assert_eq!(json["data"], hex(v.data));
+        assert_eq!(json["nonce"], hex(&v.nonce));
+        assert_eq!(json["digest"], hex(&v.digest));
+        assert_eq!(json["hash"], hex(&v.hash));
+        assert_eq!(json["difficulty"], v.difficulty);
+    }
+}
+
+#[test]
+fn vectors_match_solver() {
+    // Spot-check one vector end to end through the const-generic API
+    let v = TEST_VECTORS.iter().find(|v| v.name == "ones").unwrap();
+    let data: &[u8; 64] = v.data.try_into().unwrap();
+
+    let solution = solve(v.challenge, data, v.nonce).unwrap();
+    assert_eq!(solution.d, v.digest);
+    assert_eq!(solution.to_hash(), v.hash);
+    assert_eq!(solution.difficulty(), v.difficulty);
+
+    verify(v.challenge, data, v.nonce, &v.digest).unwrap();
+    assert_eq!(Solution::new(v.digest, v.nonce).to_hash(), v.hash);
+}
+
+#[test]
+fn export_covers_golden_and_edge_cases() {
+    let vectors = export_vectors(3, 7);
+    assert_eq!(vectors, export_vectors(3, 7));
+    assert_eq!(vectors.len(), TEST_VECTORS.len() + 5 + 3);
+
+    let json: Value = serde_json::from_str(&vectors_to_json(&vectors)).unwrap();
+    let fixture: Value = serde_json::from_str(include_str!("vectors.json")).unwrap();
+    for (exported, golden) in json["vectors"].as_array().unwrap().iter().zip(
+        fixture["vectors"].as_array().unwrap(),
+    ) {
+        for key in ["name", "challenge", "data", "nonce", "digest", "hash", "difficulty"] {
+            assert_eq!(exported[key], golden[key]);
+        }
+        assert_eq!(exported["valid"], true);
+    }
+
+    let case = |name: &str| vectors.iter().find(|v| v.name == name).unwrap();
+    assert!(!case("empty_data").valid);
+    assert!(case("min_zeros").valid && case("min_zeros").data == [0]);
+    assert_eq!(case("max_seed").data.len(), MAX_DATA_LEN);
+    let (reorder, swapped) = (case("canonical_reorder"), case("swapped_words"));
+    assert!(reorder.valid && reorder.canonical != reorder.digest);
+    assert!(!swapped.valid);
+    assert_eq!(swapped.hash, reorder.hash);
+    assert!(vectors.iter().filter(|v| v.name.starts_with("random_")).all(|v| v.valid));
+
+    let csv = vectors_to_csv(&vectors);
+    assert_eq!(csv.lines().count(), vectors.len() + 1);
+    assert!(csv.lines().nth(1).unwrap().starts_with("zeros,0000"));
+}
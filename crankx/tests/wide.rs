@@ -0,0 +1,58 @@
+use crankx::equix::SolverMemory;
+use crankx::wide::{solve_wide, verify_wide, WideSolution};
+use crankx::{verify, CrankXError, Solution, WideNonce, WIDE_WIRE_VERSION};
+
+fn solved(challenge: [u8; 32], data: &[u8; 64], tag: u64) -> WideSolution {
+    let mut mem = SolverMemory::new();
+    (0u64..)
+        .find_map(|n| solve_wide(&mut mem, challenge, data, WideNonce::from_parts(tag, n)).ok())
+        .unwrap()
+}
+
+#[test]
+fn wide_nonce_packs_tag_above_counter() {
+    let nonce = WideNonce::from_parts(7, 0x0102);
+    assert_eq!(nonce.to_parts(), (7, 0x0102));
+    assert_eq!(&nonce.to_bytes()[..8], &0x0102u64.to_le_bytes());
+    assert_eq!(&nonce.to_bytes()[8..], &7u64.to_le_bytes());
+    assert_eq!(WideNonce::from(nonce.to_u128()), nonce);
+    assert_eq!(nonce.to_string().parse::<WideNonce>().unwrap(), nonce);
+}
+
+#[test]
+fn wide_solutions_verify_and_bind_the_whole_nonce() {
+    let (challenge, data) = ([3u8; 32], [4u8; 64]);
+    let solution = solved(challenge, &data, 9);
+
+    solution.is_valid(challenge, &data).unwrap();
+    verify_wide(challenge, &data, solution.n, &solution.d).unwrap();
+
+    let mut retagged = solution.n;
+    retagged[15] ^= 1;
+    assert!(verify_wide(challenge, &data, retagged, &solution.d).is_err());
+
+    // The low half alone is not a regular proof of the same digest
+    let low: [u8; 8] = solution.n[..8].try_into().unwrap();
+    assert!(verify(challenge, &data, low, &solution.d).is_err());
+    assert_ne!(solution.to_hash(), Solution::new(solution.d, low).to_hash());
+}
+
+#[test]
+fn wide_wire_format_has_its_own_version() {
+    let solution = solved([5; 32], &[6; 64], 1);
+
+    let bytes = solution.to_versioned_bytes();
+    assert_eq!(bytes[0], WIDE_WIRE_VERSION);
+    assert_eq!(WideSolution::from_versioned_bytes(&bytes).unwrap(), solution);
+    assert_eq!(WideSolution::from_bytes(&solution.to_bytes()), solution);
+
+    let regular = Solution::new(solution.d, [0; 8]).to_versioned_bytes();
+    assert!(matches!(
+        WideSolution::from_versioned_bytes(&[&regular[..], &[0; 8]].concat()),
+        Err(CrankXError::UnsupportedVersion)
+    ));
+    assert!(matches!(
+        WideSolution::from_versioned_bytes(&regular),
+        Err(CrankXError::InvalidLength)
+    ));
+}
@@ -0,0 +1,80 @@
+use crankx::batch::{verify_batch, BatchItem};
+use crankx::equix::RuntimeOption;
+use crankx::testing::gen_segment;
+use crankx::workers::{Aggregator, Worker, WorkerJob, WorkerReport, JOB_HEADER_LEN};
+use crankx::{Challenge, CrankXError};
+
+#[test]
+fn jobs_cover_the_nonce_space_and_round_trip() {
+    let segment = gen_segment(4, 100);
+    let jobs = WorkerJob::split(Challenge([1; 32]), &segment, 3, 4);
+    assert_eq!(jobs.len(), 4);
+    assert_eq!(jobs[0].start, 0);
+    assert_eq!(jobs[3].end, u64::MAX);
+    assert!(jobs.windows(2).all(|w| w[0].end + 1 == w[1].start));
+
+    for job in &jobs {
+        let bytes = job.to_bytes();
+        assert_eq!(bytes.len(), JOB_HEADER_LEN + 100);
+        assert_eq!(&WorkerJob::from_bytes(&bytes).unwrap(), job);
+    }
+    assert!(matches!(WorkerJob::from_bytes(&[0; 10]), Err(CrankXError::InvalidLength)));
+    assert!(matches!(
+        WorkerJob::from_bytes(&[0; JOB_HEADER_LEN]),
+        Err(CrankXError::SegmentTooSmall { .. })
+    ));
+}
+
+#[test]
+fn workers_step_and_aggregate() {
+    let challenge = Challenge([6; 32]);
+    let segment = gen_segment(8, 64);
+    let jobs = WorkerJob::split(challenge, &segment, 4, 2);
+    let mut workers: Vec<_> =
+        jobs.into_iter().map(|job| Worker::new(job, RuntimeOption::TryCompile)).collect();
+
+    let mut aggregator = Aggregator::new(2);
+    'search: loop {
+        for worker in &mut workers {
+            let report = worker.step(8).unwrap();
+            assert!(report.attempts <= 8);
+            // Reports cross the worker boundary as bytes
+            let report = WorkerReport::from_bytes(&report.to_bytes()).unwrap();
+            if aggregator.push(report).unwrap() {
+                break 'search;
+            }
+        }
+    }
+
+    let best = aggregator.best().unwrap();
+    assert!(best.difficulty() >= 4);
+    assert!(aggregator.attempts() > 0);
+    verify_batch(challenge, &[BatchItem::new(&segment, best)]).unwrap();
+
+    let stray = WorkerReport { worker: 5, solution: None, done: true, attempts: 1, next: 0 };
+    assert!(matches!(aggregator.push(stray), Err(CrankXError::InvalidReport)));
+    let attempts = aggregator.attempts();
+    let greedy = WorkerReport { worker: 0, solution: None, done: true, attempts: u64::MAX, next: 0 };
+    assert!(matches!(aggregator.push(greedy), Err(CrankXError::InvalidReport)));
+    assert_eq!(aggregator.attempts(), attempts);
+}
+
+#[test]
+fn exhausted_range_finishes_the_search() {
+    let job = WorkerJob {
+        worker: 0,
+        challenge: Challenge([0; 32]),
+        min_difficulty: 64,
+        start: 10,
+        end: 12,
+        segment: gen_segment(1, 32),
+    };
+    let mut worker = Worker::new(job, RuntimeOption::TryCompile);
+    let report = worker.step(100).unwrap();
+    assert_eq!(report.attempts, 3);
+    assert!(report.done && report.solution.is_none());
+
+    let mut aggregator = Aggregator::new(1);
+    assert!(aggregator.push(report).unwrap());
+    assert!(aggregator.best().is_none());
+}
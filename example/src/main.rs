@@ -41,7 +41,7 @@ fn do_work<const N: usize>(
 
     loop {
         if let Ok(solution) = solve_with_memory(
-            &mut memory, &challenge, data, &nonce.to_le_bytes()) {
+            &mut memory, challenge, data, nonce.to_le_bytes()) {
 
             if solution.difficulty() >= DIFFICULTY {
                 return Ok(solution);
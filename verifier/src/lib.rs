@@ -0,0 +1,53 @@
+// Reference on-chain verifier for CrankX proofs
+// Accepts a `SubmitProof` instruction signed by the miner, checks the proof
+// and its difficulty, and logs a `ProofAccepted` event. Kept minimal so it
+// doubles as the compute-unit regression target for verification.
+
+use crankx::solana::{verify_with_difficulty, SubmitProof};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::log::sol_log_data;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+// Must match `crankx::solana::cpi::VERIFIER_ID`
+solana_program::declare_id!("BwR6tPD811KikQzCR9rXEWBMvRy9w2m7aisf748QGXzi");
+
+/// Discriminator of the event logged for each accepted proof
+pub const PROOF_ACCEPTED: &[u8] = b"crankx:proof_accepted";
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Accounts: `[signer] miner`
+///
+/// On success logs, via `sol_log_data`, the fields
+/// `PROOF_ACCEPTED, miner, challenge, proof, difficulty (4, LE)`.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if program_id != &ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let accounts = &mut accounts.iter();
+    let miner = next_account_info(accounts)?;
+    if !miner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix = SubmitProof::unpack(data)?;
+    let difficulty =
+        verify_with_difficulty(ix.challenge, ix.segment, &ix.proof, ix.min_difficulty)?;
+
+    sol_log_data(&[
+        PROOF_ACCEPTED,
+        miner.key.as_ref(),
+        &ix.challenge,
+        &ix.proof,
+        &difficulty.to_le_bytes(),
+    ]);
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use crankx::solana::cpi::{submit_proof, VERIFIER_ID};
+use crankx::solana::SubmitProof;
+use crankx::solve;
+use crankx_verifier::{process_instruction, ID};
+use solana_program::account_info::AccountInfo;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+const CHALLENGE: [u8; 32] = [9; 32];
+const SEGMENT: [u8; 128] = [4; 128];
+
+fn run(signer: bool, data: &[u8]) -> Result<(), ProgramError> {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::default();
+    let mut lamports = 0;
+    let mut account_data = [];
+    let miner =
+        AccountInfo::new(&key, signer, false, &mut lamports, &mut account_data, &owner, false, 0);
+    process_instruction(&ID, &[miner], data)
+}
+
+#[test]
+fn accepts_valid_proofs() {
+    let solution = (0u64..).find_map(|n| solve(CHALLENGE, &SEGMENT, n).ok()).unwrap();
+    let ix = SubmitProof {
+        challenge: CHALLENGE,
+        min_difficulty: solution.difficulty(),
+        proof: solution.to_bytes(),
+        segment: &SEGMENT,
+    };
+    run(true, &ix.pack()).unwrap();
+
+    // Unsigned, too hard, wrong segment, truncated
+    assert_eq!(run(false, &ix.pack()), Err(ProgramError::MissingRequiredSignature));
+    let hard = SubmitProof { min_difficulty: solution.difficulty() + 1, ..ix };
+    assert!(run(true, &hard.pack()).is_err());
+    let wrong = SubmitProof { segment: &[5; 128], ..ix };
+    assert!(run(true, &wrong.pack()).is_err());
+    assert_eq!(
+        run(true, &ix.pack()[..SubmitProof::HEADER_LEN - 1]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn cpi_builder_targets_this_program() {
+    assert_eq!(VERIFIER_ID, ID);
+
+    let miner = Pubkey::new_unique();
+    let proof =
+        SubmitProof { challenge: CHALLENGE, min_difficulty: 3, proof: [1; 24], segment: &SEGMENT };
+    let ix = submit_proof(&miner, &proof);
+
+    assert_eq!(ix.program_id, ID);
+    assert_eq!(ix.accounts.len(), 1);
+    assert!(ix.accounts[0].is_signer && !ix.accounts[0].is_writable);
+    assert_eq!(ix.accounts[0].pubkey, miner);
+    assert_eq!(SubmitProof::unpack(&ix.data).unwrap(), proof);
+}